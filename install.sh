@@ -152,10 +152,12 @@ After=network.target
 Wants=network.target
 
 [Service]
-Type=simple
+Type=notify
 User=calendar-monitor
 Group=calendar-monitor
 ExecStart=$INSTALL_DIR/calendar-monitor
+# Restarted when its update loop stops answering
+WatchdogSec=60
 Restart=always
 RestartSec=5
 StandardOutput=journal
@@ -0,0 +1,3029 @@
+use async_trait::async_trait;
+use base64::Engine;
+use handlebars::html_escape;
+use axum::{
+    extract::{Path as UrlPath, Query, Request, ws::{Message, WebSocket, WebSocketUpgrade}, State},
+    middleware::{self, Next},
+    response::{sse::{Event, KeepAlive, Sse}, Html, IntoResponse, Response},
+    routing::{get, post},
+    http::{StatusCode, HeaderMap, header},
+    Json, Router,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use futures_util::{stream, Stream, StreamExt};
+use local_ip_address::local_ip;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, Mutex, RwLock};
+use tokio::time::interval;
+use tower_http::cors::CorsLayer;
+use tracing::{info, warn};
+
+use crate::cli::Cli;
+use crate::config::{env_var, AuthFlow, Config, CountdownConfig, DisplayConfig, IcsSourceConfig, PrivacyConfig, ServerConfig, MAX_UPDATE_INTERVAL_SECONDS};
+use crate::calendar::{CalendarService, SourceError, SourceHealth};
+use crate::meeting::{highest_priority, local_midnight, Meeting, MeetingSource};
+use crate::recurrence::Window;
+use crate::free_slots::{find_free_slots, parse_duration, FreeSlot};
+use crate::badge::{render_badge, BUSY_COLOR, FREE_COLOR};
+use crate::source::{Aggregator, CalendarSource, IcsSource};
+use crate::google_calendar::{GoogleCalendarListEntry, GoogleCalendarService, GoogleTokens};
+use crate::holidays::HolidaySource;
+use crate::issues::IssueSource;
+use crate::local_meetings::LocalMeetingStore;
+use crate::hidden_meetings::{HiddenMeeting, HiddenMeetings};
+use crate::graphql::{build_schema, CalendarSchema};
+use crate::stats::{meeting_stats, StatsPeriod};
+use crate::payload::PayloadFormat;
+use crate::oidc::OidcSessions;
+use crate::profile::Profile;
+use crate::telegram::TelegramBot;
+use crate::scripting::Scripts;
+use crate::notify::{notifiers_from_config, MeetingTracker, Notifier, NotifierBackend, Schedules};
+use crate::events::{self, backends_from_config, EventBus, NotificationBackend, SourceWatch};
+
+// Embed static files into the binary
+const STYLE_CSS: &str = include_str!("../static/style.css");
+const APP_JS: &str = include_str!("../static/app.js");
+
+/// Longest range /api/meetings/range answers, as other days are read from the sources
+pub(crate) const MAX_RANGE_DAYS: i64 = 31;
+
+/// How many days ahead /api/free-slots looks
+pub(crate) const FREE_SLOT_DAYS: i64 = 7;
+
+/// Largest width or height /render/eink.png draws
+const MAX_EINK_SIZE: u32 = 2048;
+
+/// Version of the JSON API served under `/api/v1` and carried in every
+/// `MeetingUpdate`. Within a version fields are only ever added; removing or
+/// renaming one means a new version, with the old one kept alongside.
+pub const API_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeetingUpdate {
+    /// `API_VERSION` of the server that built the update
+    #[serde(default)]
+    pub version: u32,
+    pub current_meeting: Option<Meeting>,
+    pub next_meeting: Option<Meeting>,
+    /// Seconds until the current meeting ends
+    pub countdown_seconds: Option<i64>,
+    /// Seconds until the next meeting starts
+    #[serde(default)]
+    pub next_meeting_starts_in_seconds: Option<i64>,
+    pub active_time_blocks: Vec<ActiveTimeBlock>,
+    /// Time blocks starting later today or tomorrow, soonest first
+    #[serde(default)]
+    pub upcoming_time_blocks: Vec<Meeting>,
+    /// All-day events (vacations, holidays) happening now; never shown as the current meeting
+    #[serde(default)]
+    pub all_day_events: Vec<Meeting>,
+    /// Name of today's public holiday, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub holiday: Option<String>,
+    /// Every visible event of today, including ended ones, in start order
+    #[serde(default)]
+    pub agenda_today: Vec<Meeting>,
+    /// True when every ICS source failed and the last saved snapshot is shown instead
+    #[serde(default)]
+    pub stale: bool,
+    /// When the stale data was originally fetched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stale_since: Option<DateTime<Utc>>,
+    /// IANA time zone the client subscribed with, to show times in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// When an ICS or CalDAV source was last fetched successfully
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_updated: Option<DateTime<Utc>>,
+    /// Sources whose latest fetch failed; their meetings may be missing or outdated
+    #[serde(default)]
+    pub source_errors: Vec<SourceError>,
+    /// True during the configured quiet hours, for displays to dim
+    #[serde(default)]
+    pub quiet: bool,
+    /// When displays turn the countdowns yellow and red
+    #[serde(default)]
+    pub countdown: CountdownConfig,
+}
+
+impl MeetingUpdate {
+    /// Whether both show the same thing, ignoring the countdowns and progress
+    /// that change as time passes (clients work those out from the meeting times)
+    pub fn same_state(&self, other: &MeetingUpdate) -> bool {
+        let without_countdowns = |update: &MeetingUpdate| {
+            serde_json::to_value(MeetingUpdate {
+                countdown_seconds: None,
+                next_meeting_starts_in_seconds: None,
+                active_time_blocks: update.active_time_blocks
+                    .iter()
+                    .map(|block| ActiveTimeBlock { progress_percent: 0, ..block.clone() })
+                    .collect(),
+                ..update.clone()
+            })
+            .ok()
+        };
+        without_countdowns(self) == without_countdowns(other)
+    }
+
+    /// Show times in `timezone`: sets `timezone` and the `local` times of
+    /// every meeting
+    pub fn localize(&mut self, timezone: chrono_tz::Tz) {
+        self.for_each_meeting(|meeting| *meeting = meeting.clone().with_local_times(timezone));
+        self.timezone = Some(timezone.name().to_string());
+    }
+
+    /// Change every meeting the update shows
+    pub fn for_each_meeting(&mut self, mut f: impl FnMut(&mut Meeting)) {
+        self.current_meeting.iter_mut().for_each(&mut f);
+        self.next_meeting.iter_mut().for_each(&mut f);
+        self.active_time_blocks.iter_mut().map(|block| &mut block.meeting).for_each(&mut f);
+        self.upcoming_time_blocks.iter_mut().for_each(&mut f);
+        self.all_day_events.iter_mut().for_each(&mut f);
+        self.agenda_today.iter_mut().for_each(&mut f);
+    }
+}
+
+/// A time block in progress
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveTimeBlock {
+    #[serde(flatten)]
+    pub meeting: Meeting,
+    /// How much of the block has passed, 0 to 100
+    #[serde(default)]
+    pub progress_percent: u8,
+}
+
+/// What an agenda entry is, so clients don't have to re-derive it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, async_graphql::Enum)]
+#[serde(rename_all = "snake_case")]
+#[graphql(name = "EventKind")]
+pub enum AgendaEntryKind {
+    Meeting,
+    TimeBlock,
+    AllDay,
+}
+
+impl AgendaEntryKind {
+    pub fn of(meeting: &Meeting) -> Self {
+        if meeting.all_day {
+            AgendaEntryKind::AllDay
+        } else if meeting.is_time_block() {
+            AgendaEntryKind::TimeBlock
+        } else {
+            AgendaEntryKind::Meeting
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgendaEntry {
+    pub kind: AgendaEntryKind,
+    #[serde(flatten)]
+    pub meeting: Meeting,
+}
+
+/// Every event of a day, as returned by /api/agenda
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgendaResponse {
+    pub date: NaiveDate,
+    /// Meetings, time blocks and all-day events in chronological order
+    pub events: Vec<AgendaEntry>,
+}
+
+/// The current or else the next meeting, flattened for small embedded
+/// clients (/api/next). Every field is null when nothing is coming up.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NextMeeting {
+    pub title: Option<String>,
+    /// 0 once the meeting has started
+    pub starts_in_seconds: Option<i64>,
+    pub ends_in_seconds: Option<i64>,
+    pub location: Option<String>,
+    /// Seconds until the meeting after the current one starts, or until
+    /// this one starts when nothing is in progress
+    #[serde(default)]
+    pub next_meeting_starts_in_seconds: Option<i64>,
+}
+
+impl From<&Meeting> for NextMeeting {
+    fn from(meeting: &Meeting) -> Self {
+        Self {
+            title: Some(meeting.title.clone()),
+            starts_in_seconds: Some(meeting.time_until_start().max(0)),
+            ends_in_seconds: Some(meeting.time_until_end()),
+            location: meeting.location.clone(),
+            next_meeting_starts_in_seconds: None,
+        }
+    }
+}
+
+/// Longest title `DeckState` carries, in characters; Stream Deck keys fit little more
+const DECK_TITLE_CHARS: usize = 16;
+
+/// Fixed, small state for Stream Deck and companion plugins, as returned by /api/deck
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeckState {
+    /// `busy`, `soon` (the next meeting starts within the countdown's
+    /// warning threshold) or `free`
+    pub state: String,
+    /// The current or next meeting's title, shortened to fit a key
+    pub title: Option<String>,
+    /// Minutes until the current meeting ends or the next one starts, rounded up
+    pub minutes: Option<i64>,
+    /// Which icon to show: `video` for a meeting with a join link, `meeting`,
+    /// `clock` shortly before one, or `free`
+    pub icon: String,
+    /// The link to join the current or next meeting, for a key press to open
+    pub join_url: Option<String>,
+}
+
+impl From<&MeetingUpdate> for DeckState {
+    fn from(update: &MeetingUpdate) -> Self {
+        let soon = update.next_meeting.as_ref().filter(|meeting| {
+            meeting.time_until_start() <= update.countdown.warning_threshold_seconds as i64
+        });
+        let (state, meeting, seconds) = match (&update.current_meeting, soon) {
+            (Some(current), _) => ("busy", Some(current), current.time_until_end()),
+            (None, Some(next)) => ("soon", Some(next), next.time_until_start()),
+            (None, None) => ("free", update.next_meeting.as_ref(), update.next_meeting.as_ref().map_or(0, Meeting::time_until_start)),
+        };
+        let join_url = meeting.filter(|_| state != "free").and_then(Meeting::url);
+        let icon = match state {
+            _ if join_url.is_some() => "video",
+            "busy" => "meeting",
+            "soon" => "clock",
+            _ => "free",
+        };
+        let title = meeting.map(|meeting| {
+            if meeting.title.chars().count() <= DECK_TITLE_CHARS {
+                return meeting.title.clone();
+            }
+            let cut: String = meeting.title.chars().take(DECK_TITLE_CHARS - 1).collect();
+            format!("{}…", cut.trim_end())
+        });
+        Self {
+            state: state.to_string(),
+            title,
+            minutes: meeting.map(|_| (seconds.max(0) + 59) / 60),
+            icon: icon.to_string(),
+            join_url,
+        }
+    }
+}
+
+/// The one line the OBS overlay shows, as returned by /api/overlay
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverlayLine {
+    /// e.g. "Next: Standup in 04:12"; empty when nothing is coming up
+    pub text: String,
+}
+
+/// Upcoming gaps in the agenda, as returned by /api/free-slots
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FreeSlotsResponse {
+    pub duration_minutes: i64,
+    pub slots: Vec<FreeSlot>,
+}
+
+/// Meetings matching a query, as returned by /api/search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResponse {
+    pub query: String,
+    pub meetings: Vec<Meeting>,
+}
+
+/// Meetings overlapping a time range, as returned by /api/meetings/range
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RangeResponse {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub meetings: Vec<Meeting>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerInfo {
+    pub local_ip: String,
+    pub server_port: u16,
+    /// /ws and /events clients currently connected
+    #[serde(default)]
+    pub connected_clients: usize,
+}
+
+/// The effective configuration, as returned by /api/config
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigResponse {
+    /// Config file that was loaded, if any
+    pub config_file: Option<PathBuf>,
+    /// Environment variables overriding the config file
+    pub env_overrides: Vec<String>,
+    /// Configuration in effect, with secrets redacted
+    pub config: Config,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverrideStatus {
+    Busy,
+    Free,
+}
+
+/// Body of POST /api/override
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverrideRequest {
+    pub status: OverrideStatus,
+    pub until: DateTime<Utc>,
+    /// Title of the meeting shown while busy (default "Busy")
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+/// A busy or free status set by hand, taking precedence over the calendars
+/// until it runs out
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusOverride {
+    pub status: OverrideStatus,
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+impl StatusOverride {
+    pub fn is_active(&self) -> bool {
+        Utc::now() < self.until
+    }
+
+    /// The meeting standing in for a busy status
+    pub fn meeting(&self) -> Meeting {
+        let title = self.title.clone().unwrap_or_else(|| "Busy".to_string());
+        Meeting::new(title, self.since, self.until).with_source(MeetingSource::manual_override())
+    }
+}
+
+/// Sent by a WebSocket client after connecting to tailor its updates,
+/// e.g. `{"sources": ["work"], "include_time_blocks": false}`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Subscription {
+    /// Only meetings from these source ids (`google`, an ICS or CalDAV
+    /// alias, `local`); every source when empty
+    pub sources: Vec<String>,
+    pub include_time_blocks: bool,
+    /// IANA time zone (e.g. "Europe/Istanbul") the display shows times in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// Name of a `[profile.NAME]` whose filters and privacy level apply
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+}
+
+impl Default for Subscription {
+    fn default() -> Self {
+        Self {
+            sources: Vec::new(),
+            include_time_blocks: true,
+            timezone: None,
+            profile: None,
+        }
+    }
+}
+
+impl Subscription {
+    /// The subscription of `?timezone=` and `?profile=` on /ws, /events and
+    /// /api/meetings
+    pub fn from_query(query: &HashMap<String, String>, profiles: &HashMap<String, Profile>) -> Result<Self, String> {
+        let subscription = Subscription {
+            timezone: query.get("timezone").cloned(),
+            profile: query.get("profile").cloned(),
+            ..Subscription::default()
+        };
+        subscription.validate()?;
+        subscription.validate_profile(profiles)?;
+        Ok(subscription)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        self.parsed_timezone().map(|_| ())
+    }
+
+    fn parsed_timezone(&self) -> Result<Option<chrono_tz::Tz>, String> {
+        self.timezone
+            .as_ref()
+            .map(|timezone| {
+                timezone
+                    .parse::<chrono_tz::Tz>()
+                    .map_err(|_| format!("Unknown time zone '{}', expected e.g. Europe/Istanbul", timezone))
+            })
+            .transpose()
+    }
+
+    /// Fails for a profile that is not configured
+    pub fn validate_profile(&self, profiles: &HashMap<String, Profile>) -> Result<(), String> {
+        match &self.profile {
+            Some(name) if !profiles.contains_key(name) => Err(format!("Unknown profile '{}'", name)),
+            _ => Ok(()),
+        }
+    }
+
+    fn includes(&self, meeting: &Meeting) -> bool {
+        self.sources.is_empty()
+            || meeting.source.as_ref().is_some_and(|source| self.sources.contains(&source.id))
+    }
+}
+
+/// Body of POST /api/meetings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewMeeting {
+    pub title: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    #[serde(default)]
+    pub location: Option<String>,
+}
+
+/// Optional body of POST /api/meetings/{id}/hide
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HideRequest {
+    /// How long to hide the meeting, e.g. "2h"; for good when missing
+    #[serde(default)]
+    pub duration: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HiddenResponse {
+    pub hidden: Vec<HiddenMeeting>,
+}
+
+/// Outcome of POST /api/admin/reload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReloadResponse {
+    pub config_file: PathBuf,
+    /// Health of the rebuilt ICS and CalDAV sources
+    pub sources: Vec<SourceHealth>,
+    /// Changed sections that only take effect after a restart
+    pub restart_required: Vec<String>,
+}
+
+// Global state for Google Calendar tokens
+pub type GoogleTokensStore = Arc<RwLock<Option<GoogleTokens>>>;
+
+/// Google Calendar client built once at startup and shared by all requests
+pub type SharedGoogleService = Arc<RwLock<GoogleCalendarService>>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourcesResponse {
+    pub sources: Vec<SourceHealth>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceChangeResponse {
+    pub sources: Vec<SourceHealth>,
+    /// Whether the change was written back to the configuration file
+    pub persisted: bool,
+}
+
+/// A calendar of the connected Google account, as listed by /api/google/calendars
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoogleCalendarInfo {
+    /// Calendar ID to put in `[[google.calendars]]`
+    pub id: String,
+    pub name: String,
+    pub color: Option<String>,
+    pub primary: bool,
+    pub access_role: Option<String>,
+    /// Whether the calendar is currently monitored
+    pub monitored: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoogleCalendarsResponse {
+    pub calendars: Vec<GoogleCalendarInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiError {
+    pub error: String,
+}
+
+/// Version of the typed WebSocket protocol spoken on `/ws?protocol=2`.
+/// Protocol 1, the default, sends bare `MeetingUpdate`s.
+pub const WS_PROTOCOL_VERSION: u32 = 2;
+
+/// Every message of the typed protocol, tagged with the protocol version:
+/// `{"protocol": 2, "type": "update", "data": {...}}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Envelope<T> {
+    pub protocol: u32,
+    #[serde(flatten)]
+    pub message: T,
+}
+
+/// Messages the server sends over the typed protocol
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// Sent on connect and whenever the meetings change
+    Update(Box<MeetingUpdate>),
+    /// A client message that could not be handled
+    Error { message: String },
+    /// Fetch health of every calendar source, sent on connect and on change
+    SourceStatus(Vec<SourceHealth>),
+    /// The configuration was reloaded; settings read from /api/v1/config may be outdated
+    ConfigChanged { loaded_at: DateTime<Utc> },
+    /// Answer to a `ping`
+    Pong,
+    /// The `[display]` settings, sent first on connect and again after a reload
+    Display(DisplayConfig),
+}
+
+/// Messages a client sends over the typed protocol
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// Tailor the updates of this connection
+    Subscribe(Subscription),
+    Ping,
+}
+
+/// Progress of the Google device authorization (`google.auth_flow = "device"`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum DeviceAuthorizationStatus {
+    #[default]
+    Idle,
+    /// Waiting for the user to enter `user_code` at `verification_url`
+    Pending {
+        user_code: String,
+        verification_url: String,
+        expires_at: DateTime<Utc>,
+    },
+    Connected,
+    Failed { error: String },
+}
+
+/// A redirect sign-in waiting for its callback, keyed by the OAuth `state`
+#[derive(Debug, Clone)]
+pub struct PendingAuthorization {
+    pub pkce_verifier: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Last Google Calendar results, shared by every client and tick
+#[derive(Debug, Default)]
+pub struct GoogleMeetingsCache {
+    /// Bumped after each completed fetch, so callers that waited for a fetch
+    /// in progress can reuse its result instead of starting another
+    generation: u64,
+    meetings: Vec<Meeting>,
+    /// When Google was last asked; the meetings are reused until the TTL passes
+    fetched_at: Option<Instant>,
+    /// Latest failed fetch, until one succeeds; the meetings are the last ones fetched
+    failure: Option<SourceError>,
+}
+
+impl GoogleMeetingsCache {
+    /// Fetch again on the next tick, e.g. after signing in
+    pub fn invalidate(&mut self) {
+        self.fetched_at = None;
+    }
+}
+
+/// Updates a slow client may fall behind by before it skips ahead
+const LIVE_UPDATE_BACKLOG: usize = 16;
+
+/// An unchanged update is sent again this often, so clients can tell the
+/// connection is still alive
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// What the live update task sends to every client: the update, and the
+/// calendar state it was built from so that connections with a
+/// `Subscription` can build their own
+pub struct LiveSnapshot {
+    pub update: MeetingUpdate,
+    pub sources: Vec<SourceHealth>,
+    /// When the configuration in effect was loaded
+    pub config_loaded_at: DateTime<Utc>,
+    /// `[display]` of the configuration in effect
+    pub display: DisplayConfig,
+    /// `[profile.NAME]`s of the configuration in effect
+    profiles: Arc<HashMap<String, Profile>>,
+    /// `[privacy]` of the configuration in effect, for profiles a reload removed
+    privacy: PrivacyConfig,
+    /// Today's and tomorrow's visible meetings
+    meetings: Vec<Meeting>,
+    status_override: Option<StatusOverride>,
+    stale_since: Option<DateTime<Utc>>,
+}
+
+impl LiveSnapshot {
+    /// Whether both show the same thing, ignoring the countdowns
+    fn same_state(&self, other: &LiveSnapshot) -> bool {
+        self.update.same_state(&other.update)
+            && self.sources == other.sources
+            && self.config_loaded_at == other.config_loaded_at
+            && serde_json::to_value(&self.meetings).ok() == serde_json::to_value(&other.meetings).ok()
+    }
+
+    /// The update as a client with this subscription sees it
+    pub fn update_for(&self, subscription: &Subscription) -> MeetingUpdate {
+        if *subscription == Subscription::default() {
+            return self.update.clone();
+        }
+        let profile = subscription.profile.as_ref().map(|name| {
+            self.profiles.get(name).cloned().unwrap_or_else(|| Profile::fallback(&self.privacy))
+        });
+        let meetings: Vec<Meeting> = self.meetings.iter()
+            .filter(|m| subscription.includes(m) && profile.as_ref().is_none_or(|profile| profile.includes(m)))
+            .cloned()
+            .collect();
+        let mut update = MeetingUpdate {
+            last_updated: self.update.last_updated,
+            source_errors: self.update.source_errors.clone(),
+            quiet: self.update.quiet,
+            countdown: self.update.countdown,
+            ..compose_update(&meetings, self.status_override.as_ref(), self.stale_since)
+        };
+        let include_time_blocks = subscription.include_time_blocks
+            && profile.as_ref().is_none_or(Profile::include_time_blocks);
+        if !include_time_blocks {
+            update.active_time_blocks.clear();
+            update.upcoming_time_blocks.clear();
+            update.agenda_today.retain(|m| m.all_day || !m.is_time_block());
+        }
+        if let Some(profile) = &profile {
+            profile.apply(&mut update);
+        }
+        if let Ok(Some(timezone)) = subscription.parsed_timezone() {
+            update.localize(timezone);
+        }
+        update
+    }
+}
+
+/// The `MeetingUpdate`s pushed to /ws and /events clients. One background
+/// task builds an update every second and broadcasts it to every client when
+/// it changed; it runs while anyone is connected and is started again by the
+/// next client.
+#[derive(Clone)]
+pub struct LiveUpdates {
+    sender: broadcast::Sender<Arc<LiveSnapshot>>,
+    status: Arc<std::sync::Mutex<LiveStatus>>,
+}
+
+#[derive(Default)]
+struct LiveStatus {
+    running: bool,
+    /// The last snapshot built, handed to clients as they connect
+    latest: Option<Arc<LiveSnapshot>>,
+}
+
+impl Default for LiveUpdates {
+    fn default() -> Self {
+        Self {
+            sender: broadcast::channel(LIVE_UPDATE_BACKLOG).0,
+            status: Arc::new(std::sync::Mutex::new(LiveStatus::default())),
+        }
+    }
+}
+
+impl LiveUpdates {
+    /// Number of clients currently receiving updates
+    pub fn clients(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// The latest update, if the task is running, and the ones to come
+    fn subscribe(&self, state: &AppState) -> (Option<Arc<LiveSnapshot>>, broadcast::Receiver<Arc<LiveSnapshot>>) {
+        let mut status = self.status.lock().unwrap_or_else(|e| e.into_inner());
+        let receiver = self.sender.subscribe();
+        if !status.running {
+            status.running = true;
+            status.latest = None;
+            tokio::spawn(broadcast_updates(state.clone()));
+        }
+        (status.latest.clone(), receiver)
+    }
+}
+
+/// Build a `MeetingUpdate` every second and send it to all live clients when
+/// it differs from the previous one (or the heartbeat is due), until the last
+/// client disconnects
+async fn broadcast_updates(state: AppState) {
+    let live = state.live_updates.clone();
+    let mut every = update_interval(&state);
+    let mut interval = interval(every);
+    let mut last_sent = Instant::now();
+    loop {
+        interval.tick().await;
+        // Follow a reloaded server.update_interval_seconds
+        if update_interval(&state) != every {
+            every = update_interval(&state);
+            interval = tokio::time::interval_at(tokio::time::Instant::now() + every, every);
+        }
+        {
+            // Checked under the lock so a client subscribing now sees the task stopped
+            let mut status = live.status.lock().unwrap_or_else(|e| e.into_inner());
+            if live.clients() == 0 {
+                status.running = false;
+                return;
+            }
+        }
+
+        let snapshot = Arc::new(live_snapshot(&state).await);
+        let mut status = live.status.lock().unwrap_or_else(|e| e.into_inner());
+        let changed = status.latest.as_ref().is_none_or(|latest| !latest.same_state(&snapshot));
+        status.latest = Some(snapshot.clone());
+        if changed || last_sent.elapsed() >= HEARTBEAT_INTERVAL {
+            // Sent under the lock so a client subscribing now gets it either as
+            // `latest` or from the channel, never both or neither. Every client
+            // may have left since the check; the next tick stops the task.
+            let _ = live.sender.send(snapshot);
+            last_sent = Instant::now();
+        }
+    }
+}
+
+/// Publish meeting updates, meeting starts and ends and source failures on
+/// the event bus every `update_interval_seconds`, for as long as the server
+/// runs. The `[notifications]` integrations and the other backends are
+/// subscribed to it here, again whenever the configuration is reloaded.
+pub async fn run_notifications(state: AppState) {
+    let mut tracker = MeetingTracker::default();
+    let mut source_watch = SourceWatch::default();
+    let mut subscribed: Option<(Arc<Configured>, Vec<events::Subscription>)> = None;
+    let mut every = update_interval(&state);
+    let mut interval = interval(every);
+    loop {
+        interval.tick().await;
+        *state.last_tick.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+        if update_interval(&state) != every {
+            every = update_interval(&state);
+            interval = tokio::time::interval_at(tokio::time::Instant::now() + every, every);
+        }
+        // Taken before building the update so a reload in between is picked up next time
+        let configured = state.configured();
+        if !subscribed.as_ref().is_some_and(|(previous, _)| Arc::ptr_eq(previous, &configured)) {
+            // The previous notifiers are unsubscribed as their subscriptions drop
+            let subscriptions = configured.notifiers.iter()
+                .map(|notifier| Arc::new(NotifierBackend::new(notifier.clone(), state.notification_schedules.clone())) as Arc<dyn NotificationBackend>)
+                .chain(configured.backends.iter().cloned())
+                .map(|backend| state.events.subscribe(backend))
+                .collect();
+            subscribed = Some((configured, subscriptions));
+        }
+        if !state.events.has_subscribers() {
+            tracker = MeetingTracker::default();
+            continue;
+        }
+
+        let update = Arc::new(build_meeting_update(&state).await);
+        for event in tracker.observe(update.current_meeting.as_ref()) {
+            state.events.publish(events::Event::Meeting { event: Box::new(event), quiet: update.quiet });
+        }
+        for event in source_watch.observe(&update.source_errors) {
+            state.events.publish(event);
+        }
+        state.events.publish(events::Event::Update(update));
+    }
+}
+
+/// Ping the systemd watchdog every `every` for as long as `run_notifications`
+/// keeps going round; once it hangs, the pings stop and systemd restarts the
+/// service
+pub async fn run_watchdog(state: AppState, every: Duration) {
+    let mut interval = interval(every);
+    let mut hung = false;
+    loop {
+        interval.tick().await;
+        // A tick may take a while building the update, or be a long interval away
+        if state.ticked_within(update_interval(&state) + every * 2) {
+            crate::systemd::watchdog();
+            hung = false;
+        } else if !hung {
+            warn!("The update loop has not run for a while; no longer pinging the systemd watchdog");
+            hung = true;
+        }
+    }
+}
+
+/// Answer the commands sent to the `[notifications.telegram]` bot, for as
+/// long as the server runs
+pub async fn run_telegram_bot(state: AppState) {
+    let mut offset = None;
+    loop {
+        let config = state.config();
+        let Some(telegram) = config.notifications.telegram.clone().filter(|telegram| telegram.commands) else {
+            tokio::time::sleep(update_interval(&state)).await;
+            continue;
+        };
+
+        let bot = TelegramBot::new(state.http_client.clone(), telegram, config.display.clock_24h);
+        match bot.commands(&mut offset).await {
+            Ok(commands) => {
+                for command in commands {
+                    let update = build_meeting_update(&state).await;
+                    if let Err(e) = bot.send(&bot.answer(&command, &update)).await {
+                        warn!("Failed to answer Telegram command {}: {}", command, e);
+                    }
+                }
+            }
+            Err(e) => {
+                warn!("Failed to fetch Telegram commands: {}", e);
+                tokio::time::sleep(TELEGRAM_RETRY_DELAY).await;
+            }
+        }
+    }
+}
+
+/// Wait before polling a failing Telegram API again
+const TELEGRAM_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+fn update_interval(state: &AppState) -> Duration {
+    Duration::from_secs(state.config().server.update_interval_seconds())
+}
+
+/// Sign-ins not completed within this time are forgotten
+const PENDING_AUTHORIZATION_TTL_MINUTES: i64 = 10;
+
+/// Cookie carrying the OAuth `state` of a Google sign-in to its callback, so
+/// only the browser that started it (with the access token) can finish it
+const GOOGLE_SIGN_IN_COOKIE: &str = "calendar_monitor_google_sign_in";
+
+fn api_error(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(ApiError { error: message.into() })).into_response()
+}
+
+/// Everything built from the configuration, replaced as a whole when the
+/// configuration is reloaded
+pub struct Configured {
+    pub config: Arc<Config>,
+    pub calendar_service: Arc<CalendarService>,
+    /// None when Google is not configured; the error if it could not be set up
+    pub google_service: Result<Option<SharedGoogleService>, String>,
+    /// None when no holiday country is configured
+    pub holidays: Option<HolidaySource>,
+    /// Repositories whose due dates are shown
+    pub issue_sources: Vec<IssueSource>,
+    /// `[profile.NAME]`s, by name
+    pub profiles: Arc<HashMap<String, Profile>>,
+    /// `[notifications]` integrations told when meetings start and end
+    pub notifiers: Vec<Arc<dyn Notifier>>,
+    /// Other subscribers of the event bus, such as the `[busylight]`
+    pub backends: Vec<Arc<dyn NotificationBackend>>,
+    /// `[scripting]` scripts; None when none are configured or they failed to load
+    pub scripts: Option<Arc<Scripts>>,
+    /// When this configuration took effect
+    pub loaded_at: DateTime<Utc>,
+}
+
+impl Configured {
+    fn new(
+        config: Arc<Config>,
+        http_client: &reqwest::Client,
+        oauth_http_client: &reqwest::Client,
+        snapshot_file: Option<&Path>,
+    ) -> Self {
+        let mut calendar_service = CalendarService::new_from_config(&config, http_client.clone());
+        if let Some(path) = snapshot_file {
+            calendar_service = calendar_service.with_snapshot_file(path.to_path_buf());
+        }
+        let google_service = GoogleCalendarService::new_from_config(&config)
+            .map(|service| {
+                service.map(|service| {
+                    Arc::new(RwLock::new(
+                        service
+                            .with_http_client(http_client.clone())
+                            .with_oauth_http_client(oauth_http_client.clone()),
+                    ))
+                })
+            })
+            .map_err(|e| {
+                warn!("Failed to create Google Calendar service: {}", e);
+                e.to_string()
+            });
+        let holidays = HolidaySource::new_from_config(&config, http_client.clone());
+        let issue_sources = config.issues.sources.iter()
+            .map(|source| IssueSource::new(http_client.clone(), source.clone()))
+            .collect();
+        let profiles = Arc::new(Profile::from_config(&config));
+        let mut notifiers = notifiers_from_config(&config, http_client);
+        let backends = backends_from_config(&config, http_client);
+        let scripts = config.scripting.directory.as_ref().and_then(|directory| {
+            match Scripts::load(Path::new(directory)) {
+                Ok(scripts) => {
+                    info!("Loaded scripts: {:?}", scripts.names());
+                    Some(Arc::new(scripts))
+                }
+                Err(e) => {
+                    warn!("Scripts not loaded: {}", e);
+                    None
+                }
+            }
+        });
+        if let Some(scripts) = scripts.as_ref().filter(|scripts| scripts.reacts_to_events()) {
+            notifiers.push(scripts.clone());
+        }
+        Self {
+            config,
+            calendar_service: Arc::new(calendar_service),
+            google_service,
+            holidays,
+            issue_sources,
+            profiles,
+            notifiers,
+            backends,
+            scripts,
+            loaded_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AppState {
+    configured: Arc<std::sync::RwLock<Arc<Configured>>>,
+    pub google_tokens: GoogleTokensStore,
+    pub http_client: reqwest::Client,
+    pub oauth_http_client: reqwest::Client,
+    /// Config file that runtime source changes are written back to
+    pub config_file: Option<Arc<Mutex<PathBuf>>>,
+    /// Command-line flags, applied again whenever the config file is reloaded
+    cli: Cli,
+    /// Where the last good ICS fetch is kept, see `with_snapshot_file`
+    snapshot_file: Option<PathBuf>,
+    pub device_authorization: Arc<RwLock<DeviceAuthorizationStatus>>,
+    pub pending_authorizations: Arc<Mutex<HashMap<String, PendingAuthorization>>>,
+    pub google_meetings: Arc<Mutex<GoogleMeetingsCache>>,
+    /// Meetings created through POST /api/meetings
+    pub local_meetings: LocalMeetingStore,
+    /// Meetings kept off the display through POST /api/meetings/{id}/hide
+    pub hidden_meetings: HiddenMeetings,
+    /// Busy/free status set through /api/override, shared by every display
+    pub status_override: Arc<RwLock<Option<StatusOverride>>>,
+    pub graphql_schema: CalendarSchema,
+    pub live_updates: LiveUpdates,
+    /// Meeting, source and configuration events for the notification backends
+    pub events: EventBus,
+    /// Agendas and reminders sent, kept across configuration reloads
+    notification_schedules: Arc<std::sync::Mutex<Schedules>>,
+    /// When `run_notifications` last went round (or the state was created),
+    /// for the systemd watchdog
+    last_tick: Arc<std::sync::Mutex<Instant>>,
+    /// OpenID Connect sign-ins and sessions, see `[oidc]`
+    pub oidc_sessions: OidcSessions,
+    /// Put in the server's own forms and checked when they are posted, so
+    /// other sites can't submit them; new on every start
+    form_token: Arc<String>,
+}
+
+impl AppState {
+    pub fn new(config: Arc<Config>, google_tokens: Option<GoogleTokens>) -> Self {
+        let http_client = crate::http::build_client(&config.http).unwrap_or_else(|e| {
+            warn!("{}; falling back to default HTTP client", e);
+            reqwest::Client::new()
+        });
+        let oauth_http_client = crate::http::build_oauth_client(&config.http).unwrap_or_else(|e| {
+            warn!("{}; falling back to default OAuth HTTP client", e);
+            reqwest::Client::builder()
+                .redirect(reqwest::redirect::Policy::none())
+                .build()
+                .unwrap_or_default()
+        });
+        let configured = Configured::new(config, &http_client, &oauth_http_client, None);
+        Self {
+            configured: Arc::new(std::sync::RwLock::new(Arc::new(configured))),
+            google_tokens: Arc::new(RwLock::new(google_tokens)),
+            http_client,
+            oauth_http_client,
+            config_file: None,
+            cli: Cli::default(),
+            snapshot_file: None,
+            device_authorization: Arc::new(RwLock::new(DeviceAuthorizationStatus::default())),
+            pending_authorizations: Arc::new(Mutex::new(HashMap::new())),
+            google_meetings: Arc::new(Mutex::new(GoogleMeetingsCache::default())),
+            local_meetings: LocalMeetingStore::in_memory(),
+            hidden_meetings: HiddenMeetings::in_memory(),
+            status_override: Arc::new(RwLock::new(None)),
+            graphql_schema: build_schema(),
+            live_updates: LiveUpdates::default(),
+            events: EventBus::default(),
+            notification_schedules: Arc::default(),
+            last_tick: Arc::new(std::sync::Mutex::new(Instant::now())),
+            oidc_sessions: OidcSessions::default(),
+            form_token: Arc::new(oauth2::CsrfToken::new_random().secret().clone()),
+        }
+    }
+
+    /// Whether `run_notifications` went round within `max`
+    pub fn ticked_within(&self, max: Duration) -> bool {
+        self.last_tick.lock().unwrap_or_else(|e| e.into_inner()).elapsed() <= max
+    }
+
+    /// Persist the last good ICS fetch to this file and fall back on it when
+    /// every source is unreachable (e.g. no network at startup)
+    pub fn with_snapshot_file(mut self, path: Option<PathBuf>) -> Self {
+        if let Some(path) = path {
+            let config = self.config();
+            self.swap_configured(Configured::new(config, &self.http_client, &self.oauth_http_client, Some(&path)));
+            self.snapshot_file = Some(path);
+        }
+        self
+    }
+
+    /// Keep meetings created through POST /api/meetings in this file
+    pub fn with_local_meetings_file(mut self, path: Option<PathBuf>) -> Self {
+        if let Some(path) = path {
+            self.local_meetings = LocalMeetingStore::open(path);
+        }
+        self
+    }
+
+    /// Keep the meetings hidden through POST /api/meetings/{id}/hide in this file
+    pub fn with_hidden_meetings_file(mut self, path: Option<PathBuf>) -> Self {
+        if let Some(path) = path {
+            self.hidden_meetings = HiddenMeetings::open(path);
+        }
+        self
+    }
+
+    /// Persist runtime source changes to this configuration file
+    pub fn with_config_file(mut self, path: Option<PathBuf>) -> Self {
+        self.config_file = path.map(|path| Arc::new(Mutex::new(path)));
+        self
+    }
+
+    /// Keep these command-line overrides across config reloads
+    pub fn with_cli(mut self, cli: Cli) -> Self {
+        self.cli = cli;
+        self
+    }
+
+    /// The configuration and the services built from it. Holding on to the
+    /// result keeps a consistent view across a concurrent reload.
+    pub fn configured(&self) -> Arc<Configured> {
+        self.configured.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub fn config(&self) -> Arc<Config> {
+        self.configured().config.clone()
+    }
+
+    pub fn calendar_service(&self) -> Arc<CalendarService> {
+        self.configured().calendar_service.clone()
+    }
+
+    fn swap_configured(&self, configured: Configured) {
+        *self.configured.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(configured);
+    }
+
+    /// Switch to a new configuration, rebuilding every calendar source.
+    /// Requests in flight finish with the previous one; the HTTP clients and
+    /// the listening address stay as they were at startup.
+    pub async fn apply_config(&self, config: Config) {
+        let configured = Configured::new(Arc::new(config), &self.http_client, &self.oauth_http_client, self.snapshot_file.as_deref());
+        let loaded_at = configured.loaded_at;
+        self.swap_configured(configured);
+        self.google_meetings.lock().await.invalidate();
+        self.events.publish(events::Event::ConfigChanged { loaded_at });
+    }
+}
+
+/// The shared Google Calendar service, if Google is configured
+fn google_service(state: &AppState) -> anyhow::Result<Option<SharedGoogleService>> {
+    state.configured().google_service.clone().map_err(|e| anyhow::anyhow!(e))
+}
+
+/// A copy of the shared Google service for sign-in flows, which must not
+/// hold its lock while waiting on the user or Google
+async fn google_service_copy(state: &AppState) -> anyhow::Result<Option<GoogleCalendarService>> {
+    match google_service(state)? {
+        Some(google_service) => Ok(Some(google_service.read().await.clone())),
+        None => Ok(None),
+    }
+}
+
+/// Build the application router with all routes and shared state.
+///
+/// Used by `main` and by integration tests or embedders that want to serve
+/// the dashboard without going through the binary's startup sequence.
+pub fn build_router(state: AppState) -> Router {
+    // Everything showing meetings, behind `server.auth_token` when one is set
+    let protected = Router::new()
+        .route("/ws", get(websocket_handler))
+        .route("/events", get(sse_handler))
+        .nest("/api/v1", api_routes())
+        // Unversioned aliases, kept for clients written before /api/v1
+        .nest("/api", api_routes())
+        .route("/badge.svg", get(get_badge))
+        .route("/render/eink.png", get(get_eink_png))
+        .route("/graphql", post(graphql))
+        // Signing in, or starting a device flow, replaces the connected Google account
+        .route("/auth/google/login", get(google_auth_login))
+        .route("/auth/google/device", get(get_device_authorization).post(post_device_authorization))
+        .route("/auth/google/logout", get(google_logout_page).post(google_auth_logout))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_auth));
+
+    // Public unless `server.protect_dashboard` or basic authentication is set
+    let pages = Router::new()
+        .route("/", get(index))
+        // Fetches /api/overlay with the page's ?token=, like the dashboard
+        .route("/overlay", get(overlay))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_page_auth));
+
+    Router::new()
+        .merge(pages)
+        .merge(protected)
+        // Subscribers authenticate with `export.token` instead
+        .route("/calendar.ics", get(get_calendar_ics))
+        .route("/auth/google/callback", get(google_auth_callback))
+        .route("/auth/google/status", get(google_auth_status))
+        .route("/auth/oidc/login", get(oidc_login))
+        .route("/auth/oidc/callback", get(oidc_callback))
+        .route("/auth/oidc/logout", get(oidc_logout_page).post(oidc_logout))
+        .route("/static/style.css", get(serve_css))
+        .route("/static/app.js", get(serve_js))
+        .layer(middleware::from_fn_with_state(state.clone(), require_login))
+        .layer(CorsLayer::permissive())
+        .with_state(state)
+}
+
+/// Serve `router` on `listener`, over HTTPS when `[server]` names a
+/// certificate and key
+pub async fn serve(listener: tokio::net::TcpListener, router: Router, server: &ServerConfig) -> anyhow::Result<()> {
+    let Some((cert_file, key_file)) = server.tls() else {
+        crate::systemd::ready();
+        axum::serve(listener, router).await?;
+        return Ok(());
+    };
+    // More than one provider can be compiled in through other dependencies;
+    // an error only means one was installed already
+    let _ = rustls::crypto::ring::default_provider().install_default();
+    let tls = axum_server::tls_rustls::RustlsConfig::from_pem_file(cert_file, key_file)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to load the TLS certificate '{}' and key '{}': {}", cert_file, key_file, e))?;
+    crate::systemd::ready();
+    axum_server::from_tcp_rustls(listener.into_std()?, tls)
+        .serve(router.into_make_service())
+        .await?;
+    Ok(())
+}
+
+/// The JSON API, served under /api/v1 and /api
+fn api_routes() -> Router<AppState> {
+    Router::new()
+        .route("/meetings", get(get_meetings).post(create_meeting))
+        .route("/meetings/range", get(get_meetings_range))
+        .route("/meetings/:id", get(get_meeting).delete(delete_meeting))
+        .route("/meetings/:id/hide", post(hide_meeting).delete(unhide_meeting))
+        .route("/hidden", get(get_hidden_meetings))
+        .route("/export.csv", get(get_export_csv))
+        .route("/search", get(search_meetings))
+        .route("/next", get(get_next_meeting))
+        .route("/status.txt", get(get_status_text))
+        .route("/deck", get(get_deck))
+        .route("/overlay", get(get_overlay_line))
+        .route("/free-slots", get(get_free_slots))
+        .route("/agenda", get(get_agenda))
+        .route("/stats", get(get_stats))
+        .route("/sources", get(get_sources).post(add_ics_source).delete(remove_ics_source))
+        .route("/server-info", get(get_server_info))
+        .route("/config", get(get_config))
+        .route("/display-config", get(get_display_config))
+        .route("/override", get(get_override).post(set_override).delete(clear_override))
+        .route("/admin/reload", post(reload_config))
+        .route("/google/calendars", get(get_google_calendars))
+        .route_layer(middleware::from_fn(negotiate_format))
+}
+
+/// Largest JSON response `negotiate_format` re-encodes
+const MAX_NEGOTIATED_BODY: usize = 16 * 1024 * 1024;
+
+/// Re-encode JSON responses as MessagePack or CBOR when the `Accept` header
+/// asks for one of them first
+async fn negotiate_format(request: Request, next: Next) -> Response {
+    let format = request
+        .headers()
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .and_then(PayloadFormat::from_accept);
+    let mut response = next.run(request).await;
+    response.headers_mut().append(header::VARY, header::HeaderValue::from_static("accept"));
+
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|value| value.as_bytes().starts_with(b"application/json"));
+    let Some(format) = format.filter(|format| *format != PayloadFormat::Json && is_json) else {
+        return response;
+    };
+
+    let (mut parts, body) = response.into_parts();
+    let encoded = match axum::body::to_bytes(body, MAX_NEGOTIATED_BODY).await {
+        Ok(bytes) => serde_json::from_slice::<serde_json::Value>(&bytes)
+            .map_err(anyhow::Error::from)
+            .and_then(|value| format.encode(&value)),
+        Err(e) => Err(anyhow::anyhow!("Failed to read response: {}", e)),
+    };
+    match encoded {
+        Ok(encoded) => {
+            parts.headers.insert(header::CONTENT_TYPE, header::HeaderValue::from_static(format.content_type()));
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Response::from_parts(parts, axum::body::Body::from(encoded))
+        }
+        Err(e) => api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+/// Reject requests without `server.auth_token`, sent as a bearer token or,
+/// for clients that can't set headers (WebSocket, EventSource), as `?token=`
+async fn require_auth(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let config = state.config();
+    if config.server.auth_token.is_none() && config.server.basic_auth().is_none() {
+        return next.run(request).await;
+    }
+    match authorized(&state, &config, &request) {
+        true => next.run(request).await,
+        false => unauthorized(&config.server),
+    }
+}
+
+async fn require_page_auth(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let config = state.config();
+    match !config.server.dashboard_protected() || authorized(&state, &config, &request) {
+        true => next.run(request).await,
+        false => unauthorized(&config.server),
+    }
+}
+
+/// With `[oidc]`, everything but the sign-in itself, static assets and
+/// `/calendar.ics` (which has `export.token`) needs a session, or the
+/// credentials `require_auth` takes. Pages send the browser to sign in.
+async fn require_login(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let config = state.config();
+    let path = request.uri().path();
+    let exempt = path.starts_with("/auth/oidc/") || path.starts_with("/static/") || path == "/calendar.ics";
+    if config.oidc.is_none() || exempt || authorized(&state, &config, &request) {
+        return next.run(request).await;
+    }
+
+    let page = request.method() == axum::http::Method::GET
+        && (path == "/" || path == "/overlay" || path.starts_with("/auth/google/"));
+    if !page {
+        return unauthorized(&config.server);
+    }
+    let return_to = request.uri().path_and_query().map_or("/", |path| path.as_str());
+    let login = format!("/auth/oidc/login?return_to={}", urlencoding::encode(return_to));
+    axum::response::Redirect::temporary(&login).into_response()
+}
+
+/// Whether `request` carries `server.auth_token`, as a bearer header or
+/// `?token=`, the `server.auth_username` basic credentials or an `[oidc]`
+/// session
+fn authorized(state: &AppState, config: &Config, request: &Request) -> bool {
+    if config.oidc.is_some() {
+        let session = crate::oidc::session_cookie(request.headers());
+        if session.is_some_and(|session| state.oidc_sessions.user(&session).is_some()) {
+            return true;
+        }
+    }
+
+    let server = &config.server;
+    let authorization = request.headers().get(header::AUTHORIZATION).and_then(|value| value.to_str().ok());
+
+    if let Some(expected) = &server.auth_token {
+        let bearer = authorization.and_then(|value| value.strip_prefix("Bearer ")).map(|token| token.trim().to_string());
+        let query = request.uri().query().and_then(|query| {
+            url::form_urlencoded::parse(query.as_bytes())
+                .find(|(name, _)| name == "token")
+                .map(|(_, token)| token.into_owned())
+        });
+        if bearer.or(query).is_some_and(|token| secrets_match(&token, expected)) {
+            return true;
+        }
+    }
+
+    let Some((username, password)) = server.basic_auth() else {
+        return false;
+    };
+    let credentials = authorization
+        .and_then(|value| value.strip_prefix("Basic "))
+        .and_then(|encoded| base64::engine::general_purpose::STANDARD.decode(encoded.trim()).ok())
+        .and_then(|decoded| String::from_utf8(decoded).ok());
+    // Compared as a whole, so neither half can be guessed on its own
+    credentials.is_some_and(|credentials| secrets_match(&credentials, &format!("{}:{}", username, password)))
+}
+
+/// Compare a secret in constant time, so response times don't tell how much
+/// of a guess was right
+fn secrets_match(given: &str, expected: &str) -> bool {
+    use subtle::ConstantTimeEq;
+    given.as_bytes().ct_eq(expected.as_bytes()).into()
+}
+
+/// 401 asking for basic credentials when they are configured, so browsers
+/// prompt for them, and for the token otherwise
+fn unauthorized(server: &ServerConfig) -> Response {
+    let (challenge, message) = match server.basic_auth() {
+        Some(_) => ("Basic realm=\"Calendar Monitor\", charset=\"UTF-8\"", "Missing or invalid credentials"),
+        None => ("Bearer", "Missing or invalid token (server.auth_token)"),
+    };
+    let mut response = api_error(StatusCode::UNAUTHORIZED, message);
+    response.headers_mut().insert(header::WWW_AUTHENTICATE, challenge.parse().unwrap());
+    response
+}
+
+async fn index() -> impl IntoResponse {
+    Html(include_str!("../templates/index.html"))
+}
+
+/// Chroma-key friendly page for OBS browser sources
+async fn overlay() -> impl IntoResponse {
+    Html(include_str!("../templates/overlay.html"))
+}
+
+async fn websocket_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    query: Query<HashMap<String, String>>,
+) -> Response {
+    let every = match client_interval(&query) {
+        Ok(every) => every,
+        Err(message) => return api_error(StatusCode::BAD_REQUEST, message),
+    };
+    let subscription = match Subscription::from_query(&query, &state.configured().profiles) {
+        Ok(subscription) => subscription,
+        Err(message) => return api_error(StatusCode::BAD_REQUEST, message),
+    };
+    let protocol = match query.get("protocol").map(|value| value.parse::<u32>()) {
+        None => 1,
+        Some(Ok(protocol)) if (1..=WS_PROTOCOL_VERSION).contains(&protocol) => protocol,
+        Some(_) => return api_error(
+            StatusCode::BAD_REQUEST,
+            format!("Unsupported protocol, expected 1 to {}", WS_PROTOCOL_VERSION),
+        ),
+    };
+    let format = match query.get("format").map(|value| PayloadFormat::parse(value)) {
+        None => PayloadFormat::Json,
+        Some(Some(format)) => format,
+        Some(None) => return api_error(StatusCode::BAD_REQUEST, "Unsupported format, expected json, msgpack or cbor"),
+    };
+    ws.on_upgrade(move |socket| handle_socket(socket, state, every, protocol, format, subscription))
+}
+
+/// `?interval=<seconds>`: the least time a client wants between updates,
+/// e.g. 30 for a low-power display
+fn client_interval(query: &HashMap<String, String>) -> Result<Option<Duration>, String> {
+    let Some(value) = query.get("interval") else {
+        return Ok(None);
+    };
+    match value.parse::<u64>() {
+        Ok(seconds) if (1..=MAX_UPDATE_INTERVAL_SECONDS).contains(&seconds) => Ok(Some(Duration::from_secs(seconds))),
+        _ => Err(format!("'interval' must be between 1 and {} seconds", MAX_UPDATE_INTERVAL_SECONDS)),
+    }
+}
+
+/// The `MeetingUpdate`s pushed to live clients: the latest one, then every
+/// change, at most one per `every` when given (changes in between are
+/// collapsed into the newest). Both the WebSocket and the Server-Sent Events
+/// stream are fed from here.
+fn meeting_updates(state: AppState, every: Option<Duration>) -> impl Stream<Item = Arc<LiveSnapshot>> {
+    let (latest, receiver) = state.live_updates.subscribe(&state);
+    stream::unfold((latest, receiver, None), move |(latest, mut receiver, last_sent)| async move {
+        let mut update = match latest {
+            Some(update) => update,
+            None => next_update(&mut receiver).await?,
+        };
+        if let (Some(every), Some(last_sent)) = (every, last_sent) {
+            tokio::time::sleep_until(last_sent + every).await;
+            loop {
+                match receiver.try_recv() {
+                    Ok(newer) => update = newer,
+                    Err(broadcast::error::TryRecvError::Lagged(_)) => continue,
+                    Err(_) => break,
+                }
+            }
+        }
+        Some((update, (None, receiver, Some(tokio::time::Instant::now()))))
+    })
+}
+
+async fn next_update(receiver: &mut broadcast::Receiver<Arc<LiveSnapshot>>) -> Option<Arc<LiveSnapshot>> {
+    loop {
+        match receiver.recv().await {
+            Ok(update) => return Some(update),
+            // Too slow to keep up; carry on with the latest update
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => return None,
+        }
+    }
+}
+
+async fn handle_socket(
+    mut socket: WebSocket,
+    state: AppState,
+    every: Option<Duration>,
+    protocol: u32,
+    format: PayloadFormat,
+    mut subscription: Subscription,
+) {
+    let mut updates = std::pin::pin!(meeting_updates(state.clone(), every));
+    let mut last_snapshot: Option<Arc<LiveSnapshot>> = None;
+
+    loop {
+        let messages = tokio::select! {
+            snapshot = updates.next() => {
+                let Some(snapshot) = snapshot else { break };
+                let messages = snapshot_messages(&snapshot, last_snapshot.as_deref(), &subscription);
+                last_snapshot = Some(snapshot);
+                messages
+            }
+            // Updates are only sent on change, so also watch for the client leaving
+            message = socket.recv() => match message {
+                Some(Ok(message @ (Message::Text(_) | Message::Binary(_)))) => match client_message(protocol, format, message) {
+                    Ok(ClientMessage::Subscribe(new_subscription)) => {
+                        match new_subscription.validate_profile(&state.configured().profiles) {
+                            Ok(()) => {
+                                subscription = new_subscription;
+                                // Resend what the client has with the new subscription applied
+                                last_snapshot.iter()
+                                    .map(|snapshot| ServerMessage::Update(Box::new(snapshot.update_for(&subscription))))
+                                    .collect()
+                            }
+                            Err(message) => vec![ServerMessage::Error { message: format!("Invalid subscription: {}", message) }],
+                        }
+                    }
+                    Ok(ClientMessage::Ping) => vec![ServerMessage::Pong],
+                    Err(message) => vec![ServerMessage::Error { message }],
+                },
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+        };
+
+        for message in messages.into_iter().filter_map(|message| encode_message(protocol, format, message)) {
+            if socket.send(message).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// What a client is sent for a new snapshot: the update, and the display
+/// settings, source health and configuration when they changed since the
+/// previous one. The display settings come first on connect, so dashboards
+/// show the first update the way they are configured to.
+fn snapshot_messages(snapshot: &LiveSnapshot, previous: Option<&LiveSnapshot>, subscription: &Subscription) -> Vec<ServerMessage> {
+    let mut messages = Vec::new();
+    if previous.is_none_or(|previous| previous.display != snapshot.display) {
+        messages.push(ServerMessage::Display(snapshot.display.clone()));
+    }
+    messages.push(ServerMessage::Update(Box::new(snapshot.update_for(subscription))));
+    if previous.is_none_or(|previous| previous.sources != snapshot.sources) {
+        messages.push(ServerMessage::SourceStatus(snapshot.sources.clone()));
+    }
+    if previous.is_some_and(|previous| previous.config_loaded_at != snapshot.config_loaded_at) {
+        messages.push(ServerMessage::ConfigChanged { loaded_at: snapshot.config_loaded_at });
+    }
+    messages
+}
+
+/// Read a message from a client: JSON text, or binary in the connection's
+/// format. Protocol 1 clients can only send a bare `Subscription`.
+fn client_message(protocol: u32, format: PayloadFormat, message: Message) -> Result<ClientMessage, String> {
+    let (format, data) = match message {
+        Message::Text(text) => (PayloadFormat::Json, text.into_bytes()),
+        Message::Binary(data) => (format, data),
+        _ => return Err("Unexpected message".to_string()),
+    };
+    if protocol == 1 {
+        let subscription: Subscription = format.decode(&data)
+            .map_err(|e| format!("Invalid subscription: {}", e))?;
+        subscription.validate().map_err(|e| format!("Invalid subscription: {}", e))?;
+        return Ok(ClientMessage::Subscribe(subscription));
+    }
+
+    let message: ClientMessage = format.decode(&data).map_err(|e| format!("Invalid message: {}", e))?;
+    if let ClientMessage::Subscribe(subscription) = &message {
+        subscription.validate().map_err(|e| format!("Invalid subscription: {}", e))?;
+    }
+    Ok(message)
+}
+
+/// A message in the client's protocol and format: JSON as text, MessagePack
+/// and CBOR as binary. Protocol 1 only carries updates and errors.
+fn encode_message(protocol: u32, format: PayloadFormat, message: ServerMessage) -> Option<Message> {
+    let encoded = if protocol == WS_PROTOCOL_VERSION {
+        format.encode(&Envelope { protocol, message })
+    } else {
+        match message {
+            ServerMessage::Update(update) => format.encode(&update),
+            ServerMessage::Error { message } => format.encode(&ApiError { error: message }),
+            _ => return None,
+        }
+    };
+    match encoded {
+        Ok(data) if format == PayloadFormat::Json => String::from_utf8(data).ok().map(Message::Text),
+        Ok(data) => Some(Message::Binary(data)),
+        Err(e) => {
+            warn!("{}", e);
+            None
+        }
+    }
+}
+
+/// The WebSocket feed as Server-Sent Events, for networks whose proxies
+/// drop WebSocket connections
+async fn sse_handler(
+    State(state): State<AppState>,
+    query: Query<HashMap<String, String>>,
+) -> Response {
+    let every = match client_interval(&query) {
+        Ok(every) => every,
+        Err(message) => return api_error(StatusCode::BAD_REQUEST, message),
+    };
+    let subscription = match Subscription::from_query(&query, &state.configured().profiles) {
+        Ok(subscription) => subscription,
+        Err(message) => return api_error(StatusCode::BAD_REQUEST, message),
+    };
+    let retry = Event::default().retry(Duration::from_secs(state.config().server.sse_retry_seconds()));
+    let updates = meeting_updates(state, every)
+        .map(move |snapshot| Event::default().json_data(snapshot.update_for(&subscription)));
+    Sse::new(stream::once(async { Ok(retry) }).chain(updates))
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+/// Execute a GraphQL query against the schema in `crate::graphql`
+async fn graphql(
+    State(state): State<AppState>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    let schema = state.graphql_schema.clone();
+    Json(schema.execute(request.data(state)).await)
+}
+
+/// The live update, with times shown in `?timezone=` when given
+async fn get_meetings(
+    State(state): State<AppState>,
+    query: Query<HashMap<String, String>>,
+) -> Response {
+    let subscription = match Subscription::from_query(&query, &state.configured().profiles) {
+        Ok(subscription) => subscription,
+        Err(message) => return api_error(StatusCode::BAD_REQUEST, message),
+    };
+    Json(live_snapshot(&state).await.update_for(&subscription)).into_response()
+}
+
+async fn get_next_meeting(State(state): State<AppState>) -> Json<NextMeeting> {
+    let update = build_meeting_update(&state).await;
+    let meeting = update.current_meeting.as_ref().or(update.next_meeting.as_ref());
+    Json(NextMeeting {
+        next_meeting_starts_in_seconds: update.next_meeting_starts_in_seconds,
+        ..meeting.map(NextMeeting::from).unwrap_or_default()
+    })
+}
+
+/// The current state in `DeckState`'s fixed schema, for Stream Deck buttons
+async fn get_deck(State(state): State<AppState>) -> Json<DeckState> {
+    Json(DeckState::from(&build_meeting_update(&state).await))
+}
+
+/// The overlay's line, with the countdown as of now
+async fn get_overlay_line(State(state): State<AppState>) -> Json<OverlayLine> {
+    Json(OverlayLine { text: overlay_line(&build_meeting_update(&state).await) })
+}
+
+/// "Now: Standup, 12:34 left" during a meeting, "Next: Standup in 04:12"
+/// before one, or nothing
+pub fn overlay_line(update: &MeetingUpdate) -> String {
+    match (&update.current_meeting, &update.next_meeting) {
+        (Some(meeting), _) => format!("Now: {}, {} left", meeting.title, meeting.format_time_remaining()),
+        (None, Some(meeting)) => format!("Next: {} in {}", meeting.title, meeting.format_time_remaining()),
+        (None, None) => String::new(),
+    }
+}
+
+/// One human-readable line for tickers and status bars
+async fn get_status_text(State(state): State<AppState>) -> impl IntoResponse {
+    let update = build_meeting_update(&state).await;
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, "text/plain; charset=utf-8".parse().unwrap());
+    (StatusCode::OK, headers, format!("{}\n", status_line(&update)))
+}
+
+/// Summarize an update as e.g. "In: Sprint Review (ends 14:30) — Next: 1:1 at 15:00",
+/// with times in the server's local time zone
+pub fn status_line(update: &MeetingUpdate) -> String {
+    let now = chrono::Local::now();
+    let local_time = |time: DateTime<Utc>| {
+        let time = time.with_timezone(&chrono::Local);
+        if time.date_naive() == now.date_naive() {
+            time.format("%H:%M").to_string()
+        } else {
+            time.format("%a %H:%M").to_string()
+        }
+    };
+
+    let current = match &update.current_meeting {
+        Some(meeting) => format!("In: {} (ends {})", meeting.title, local_time(meeting.end_time)),
+        None => "Free".to_string(),
+    };
+    match &update.next_meeting {
+        Some(meeting) => format!("{} — Next: {} at {}", current, meeting.title, local_time(meeting.start_time)),
+        None => current,
+    }
+}
+
+/// shields.io-style "busy until 15:00" / "free" badge; `?label=` replaces the
+/// default "calendar" label
+async fn get_badge(
+    State(state): State<AppState>,
+    query: Query<HashMap<String, String>>,
+) -> impl IntoResponse {
+    let update = build_meeting_update(&state).await;
+    let (message, color) = match busy_until(&update) {
+        Some(until) => (format!("busy until {}", until.with_timezone(&chrono::Local).format("%H:%M")), BUSY_COLOR),
+        None => ("free".to_string(), FREE_COLOR),
+    };
+    let label = query.get("label").map(String::as_str).unwrap_or("calendar");
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, "image/svg+xml".parse().unwrap());
+    // Wikis and image proxies would otherwise keep showing a stale status
+    headers.insert(header::CACHE_CONTROL, "no-cache, max-age=0".parse().unwrap());
+    (StatusCode::OK, headers, render_badge(label, &message, color))
+}
+
+/// Monochrome PNG of the current/next meeting for e-ink displays;
+/// `?width=&height=` set the resolution (default 800x480)
+async fn get_eink_png(
+    State(state): State<AppState>,
+    query: Query<HashMap<String, String>>,
+) -> Response {
+    let mut size = [800, 480];
+    for (value, name) in size.iter_mut().zip(["width", "height"]) {
+        if let Some(param) = query.get(name) {
+            match param.parse::<u32>() {
+                Ok(parsed) if (100..=MAX_EINK_SIZE).contains(&parsed) => *value = parsed,
+                _ => return api_error(StatusCode::BAD_REQUEST, format!("'{}' must be between 100 and {}", name, MAX_EINK_SIZE)),
+            }
+        }
+    }
+
+    let update = build_meeting_update(&state).await;
+    match crate::eink::render(&update, size[0], size[1]).to_png() {
+        Ok(png) => {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::CONTENT_TYPE, "image/png".parse().unwrap());
+            headers.insert(header::CACHE_CONTROL, "no-cache, max-age=0".parse().unwrap());
+            (StatusCode::OK, headers, png).into_response()
+        }
+        Err(e) => api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+/// The merged, deduplicated calendar as an ICS feed (`?token=` must match `export.token`)
+async fn get_calendar_ics(
+    State(state): State<AppState>,
+    query: Query<HashMap<String, String>>,
+) -> Response {
+    let config = state.config();
+    let Some(token) = &config.export.token else {
+        return api_error(StatusCode::NOT_FOUND, "ICS export is disabled (export.token)");
+    };
+    if !query.get("token").is_some_and(|given| secrets_match(given, token)) {
+        return api_error(StatusCode::UNAUTHORIZED, "Missing or invalid 'token' query parameter");
+    }
+
+    let today = chrono::Local::now().date_naive();
+    let start = local_midnight(today);
+    let end = local_midnight(today + chrono::Days::new(config.export.days.into()));
+    let meetings = meetings_between(&state, start, end).await;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, "text/calendar; charset=utf-8".parse().unwrap());
+    (StatusCode::OK, headers, crate::ics_export::to_ics(&meetings, "Calendar Monitor")).into_response()
+}
+
+/// End of the current meeting, or of the next one when it follows back to back
+pub fn busy_until(update: &MeetingUpdate) -> Option<DateTime<Utc>> {
+    let current = update.current_meeting.as_ref()?;
+    match &update.next_meeting {
+        Some(next) if next.start_time <= current.end_time => Some(current.end_time.max(next.end_time)),
+        _ => Some(current.end_time),
+    }
+}
+
+/// The next gaps of at least `?duration=` (default 30m) within working hours,
+/// at most `?limit=` (default 5) of them
+async fn get_free_slots(
+    State(state): State<AppState>,
+    query: Query<HashMap<String, String>>,
+) -> Response {
+    let duration = match query.get("duration") {
+        Some(value) => match parse_duration(value) {
+            Some(duration) => duration,
+            None => return api_error(StatusCode::BAD_REQUEST, format!("Invalid duration '{}', expected e.g. 30m or 1h30m", value)),
+        },
+        None => chrono::Duration::minutes(30),
+    };
+    let limit = match query.get("limit").map(|value| value.parse::<usize>()) {
+        Some(Ok(limit)) if limit > 0 => limit,
+        Some(_) => return api_error(StatusCode::BAD_REQUEST, "'limit' must be a positive number"),
+        None => 5,
+    };
+
+    let now = Utc::now();
+    let meetings = meetings_between(&state, now, now + chrono::Duration::days(FREE_SLOT_DAYS)).await;
+    let slots = find_free_slots(&meetings, now, &state.config().working_hours, duration, FREE_SLOT_DAYS, limit);
+
+    Json(FreeSlotsResponse {
+        duration_minutes: duration.num_minutes(),
+        slots,
+    })
+    .into_response()
+}
+
+/// Meetings overlapping `?from=...&to=...` (RFC 3339)
+async fn get_meetings_range(
+    State(state): State<AppState>,
+    query: Query<HashMap<String, String>>,
+) -> Response {
+    let (from, to) = match range_params(&query) {
+        Ok(range) => range,
+        Err(message) => return api_error(StatusCode::BAD_REQUEST, message),
+    };
+
+    let meetings = meetings_between(&state, from, to).await;
+    Json(RangeResponse { from, to, meetings }).into_response()
+}
+
+/// The manual busy/free status, or null when the calendars decide
+async fn get_override(State(state): State<AppState>) -> Json<Option<StatusOverride>> {
+    let status_override = state.status_override.read().await;
+    Json(status_override.clone().filter(StatusOverride::is_active))
+}
+
+/// Mark yourself busy or free until the given time, whatever the calendars say
+async fn set_override(
+    State(state): State<AppState>,
+    Json(request): Json<OverrideRequest>,
+) -> Response {
+    let now = Utc::now();
+    if request.until <= now {
+        return api_error(StatusCode::BAD_REQUEST, "'until' must be in the future");
+    }
+
+    let status_override = StatusOverride {
+        status: request.status,
+        since: now,
+        until: request.until,
+        title: request.title.filter(|title| !title.trim().is_empty()),
+    };
+    info!("Status overridden as {:?} until {}", status_override.status, status_override.until);
+    *state.status_override.write().await = Some(status_override.clone());
+    Json(status_override).into_response()
+}
+
+/// Hand the status back to the calendars
+async fn clear_override(State(state): State<AppState>) -> StatusCode {
+    if state.status_override.write().await.take().is_some() {
+        info!("Status override cleared");
+    }
+    StatusCode::NO_CONTENT
+}
+
+/// Full details of one of today's or tomorrow's meetings, by its `id`
+async fn get_meeting(State(state): State<AppState>, UrlPath(id): UrlPath<String>) -> Response {
+    let meeting = calendar_sources(&state)
+        .fetch_meetings(&Window::today_and_tomorrow())
+        .await
+        .into_iter()
+        .find(|m| m.id == id);
+    match meeting {
+        Some(meeting) => Json(meeting).into_response(),
+        None => api_error(StatusCode::NOT_FOUND, format!("No meeting with id '{}' today or tomorrow", id)),
+    }
+}
+
+/// Create a meeting on the monitor itself, e.g. a walk-in booking on a room
+/// display. It counts for current/next like calendar meetings.
+async fn create_meeting(
+    State(state): State<AppState>,
+    Json(request): Json<NewMeeting>,
+) -> Response {
+    let title = request.title.trim();
+    if title.is_empty() {
+        return api_error(StatusCode::BAD_REQUEST, "'title' must not be empty");
+    }
+    if request.start >= request.end {
+        return api_error(StatusCode::BAD_REQUEST, "'start' must be before 'end'");
+    }
+    if request.end - request.start > chrono::Duration::days(1) {
+        return api_error(StatusCode::BAD_REQUEST, "Meetings created here can last at most a day");
+    }
+
+    let mut meeting = Meeting::new(title.to_string(), request.start, request.end)
+        .with_source(LocalMeetingStore::source());
+    if let Some(location) = request.location.filter(|location| !location.trim().is_empty()) {
+        meeting = meeting.with_location(location);
+    }
+    if state.local_meetings.meetings().await.iter().any(|m| m.id == meeting.id) {
+        return api_error(StatusCode::CONFLICT, format!("'{}' already exists at that time", meeting.title));
+    }
+
+    match state.local_meetings.add(meeting).await {
+        Ok(meeting) => {
+            info!("Created local meeting '{}' at {}", meeting.title, meeting.start_time);
+            (StatusCode::CREATED, Json(meeting)).into_response()
+        }
+        Err(e) => api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+/// Keep one of today's or tomorrow's meetings off the display, for good or
+/// for the `duration` in the body
+async fn hide_meeting(
+    State(state): State<AppState>,
+    UrlPath(id): UrlPath<String>,
+    request: Option<Json<HideRequest>>,
+) -> Response {
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    let until = match request.duration.as_deref() {
+        Some(value) => match parse_duration(value) {
+            Some(duration) => Some(Utc::now() + duration),
+            None => return api_error(StatusCode::BAD_REQUEST, format!("Invalid duration '{}', expected e.g. 30m or 1h30m", value)),
+        },
+        None => None,
+    };
+
+    let meeting = calendar_sources(&state)
+        .fetch_meetings(&Window::today_and_tomorrow())
+        .await
+        .into_iter()
+        .find(|m| m.id == id);
+    let Some(meeting) = meeting else {
+        return api_error(StatusCode::NOT_FOUND, format!("No meeting with id '{}' today or tomorrow", id));
+    };
+
+    match state.hidden_meetings.hide(&meeting, until).await {
+        Ok(hidden) => {
+            match hidden.until {
+                Some(until) => info!("Hiding '{}' until {}", hidden.title, until),
+                None => info!("Hiding '{}'", hidden.title),
+            }
+            Json(hidden).into_response()
+        }
+        Err(e) => api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+/// Show a hidden meeting again
+async fn unhide_meeting(State(state): State<AppState>, UrlPath(id): UrlPath<String>) -> Response {
+    match state.hidden_meetings.unhide(&id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => api_error(StatusCode::NOT_FOUND, format!("Meeting '{}' is not hidden", id)),
+        Err(e) => api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+async fn get_hidden_meetings(State(state): State<AppState>) -> Json<HiddenResponse> {
+    Json(HiddenResponse {
+        hidden: state.hidden_meetings.list().await,
+    })
+}
+
+/// Delete a meeting created through POST /api/meetings
+async fn delete_meeting(State(state): State<AppState>, UrlPath(id): UrlPath<String>) -> Response {
+    match state.local_meetings.remove(&id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => api_error(StatusCode::NOT_FOUND, format!("No local meeting with id '{}'", id)),
+        Err(e) => api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+/// Today's and tomorrow's meetings whose title, description or location
+/// contains `?q=` (case-insensitive), sorted by start time
+async fn search_meetings(
+    State(state): State<AppState>,
+    query: Query<HashMap<String, String>>,
+) -> Response {
+    let Some(search) = query.get("q").map(|q| q.trim()).filter(|q| !q.is_empty()) else {
+        return api_error(StatusCode::BAD_REQUEST, "Missing 'q' query parameter");
+    };
+
+    let meetings = visible_meetings(&state, &Window::today_and_tomorrow())
+        .await
+        .into_iter()
+        .filter(|m| m.matches(search))
+        .collect();
+    Json(SearchResponse { query: search.to_string(), meetings }).into_response()
+}
+
+/// Meetings between `?from=` and `?to=` as CSV rows, for spreadsheets
+async fn get_export_csv(
+    State(state): State<AppState>,
+    query: Query<HashMap<String, String>>,
+) -> Response {
+    let (from, to) = match range_params(&query) {
+        Ok(range) => range,
+        Err(message) => return api_error(StatusCode::BAD_REQUEST, message),
+    };
+
+    let meetings = meetings_between(&state, from, to).await;
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, "text/csv; charset=utf-8".parse().unwrap());
+    headers.insert(header::CONTENT_DISPOSITION, "attachment; filename=\"agenda.csv\"".parse().unwrap());
+    (StatusCode::OK, headers, crate::csv_export::to_csv(&meetings)).into_response()
+}
+
+/// The `?from=` / `?to=` range of a request, at most `MAX_RANGE_DAYS` long
+fn range_params(query: &HashMap<String, String>) -> Result<(DateTime<Utc>, DateTime<Utc>), String> {
+    let from = time_param(query, "from", false)?;
+    let to = time_param(query, "to", true)?;
+    if from >= to {
+        return Err("'from' must be before 'to'".to_string());
+    }
+    if to - from > chrono::Duration::days(MAX_RANGE_DAYS) {
+        return Err(format!("Ranges are limited to {} days", MAX_RANGE_DAYS));
+    }
+    Ok((from, to))
+}
+
+/// An RFC 3339 or YYYY-MM-DD query parameter, or why it can't be used. A date
+/// stands for the start of that local day, or its end for `end_of_day`.
+fn time_param(query: &HashMap<String, String>, name: &str, end_of_day: bool) -> Result<DateTime<Utc>, String> {
+    let Some(value) = query.get(name) else {
+        return Err(format!("Missing '{}' query parameter", name));
+    };
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let date = if end_of_day { date.succ_opt().unwrap_or(date) } else { date };
+        return Ok(local_midnight(date));
+    }
+    // An unencoded '+' in the offset arrives as a space
+    DateTime::parse_from_rfc3339(&value.replace(' ', "+"))
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|_| format!("Invalid '{}' time '{}', expected RFC 3339 or YYYY-MM-DD", name, value))
+}
+
+/// Meetings of all sources overlapping `start` to `end`, merged and filtered
+/// like the live update
+pub(crate) async fn meetings_between(state: &AppState, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<Meeting> {
+    // Days the monitor shows anyway are served from the source caches
+    let cached = Window::today_and_tomorrow();
+    let window = if cached.start() <= start && end <= cached.end() {
+        cached
+    } else {
+        Window::new(start.date_naive(), (end - chrono::Duration::seconds(1)).date_naive())
+    };
+
+    visible_meetings(state, &window)
+        .await
+        .into_iter()
+        .filter(|m| m.start_time < end && m.end_time > start)
+        .collect()
+}
+
+/// All events of a day (`?date=YYYY-MM-DD`, today by default) in the
+/// server's local time zone
+async fn get_agenda(
+    State(state): State<AppState>,
+    query: Query<HashMap<String, String>>,
+) -> Response {
+    let date = match query.get("date") {
+        Some(date) => match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+            Ok(date) => date,
+            Err(_) => return api_error(StatusCode::BAD_REQUEST, format!("Invalid date '{}', expected YYYY-MM-DD", date)),
+        },
+        None => chrono::Local::now().date_naive(),
+    };
+    let Some(next_day) = date.succ_opt() else {
+        return api_error(StatusCode::BAD_REQUEST, format!("Date out of range: {}", date));
+    };
+
+    let events = meetings_between(&state, local_midnight(date), local_midnight(next_day))
+        .await
+        .into_iter()
+        .map(|meeting| AgendaEntry { kind: AgendaEntryKind::of(&meeting), meeting })
+        .collect();
+
+    Json(AgendaResponse { date, events }).into_response()
+}
+
+/// Meeting time, count, longest gap and back-to-back meetings of today
+/// (`?period=day`, the default) or this week (`?period=week`)
+async fn get_stats(
+    State(state): State<AppState>,
+    query: Query<HashMap<String, String>>,
+) -> Response {
+    let period = match query.get("period") {
+        Some(value) => match StatsPeriod::parse(value) {
+            Some(period) => period,
+            None => return api_error(StatusCode::BAD_REQUEST, format!("Invalid period '{}', expected day or week", value)),
+        },
+        None => StatsPeriod::Day,
+    };
+
+    let (from, to) = period.range(chrono::Local::now().date_naive());
+    let meetings = meetings_between(&state, from, to).await;
+    Json(meeting_stats(&meetings, period, from, to)).into_response()
+}
+
+/// Google Calendar events of the connected account (or service account)
+struct GoogleSource {
+    state: AppState,
+}
+
+#[async_trait]
+impl CalendarSource for GoogleSource {
+    fn name(&self) -> &str {
+        "Google Calendar"
+    }
+
+    /// Today and tomorrow come from the shared cache, other days are requested directly
+    async fn fetch_events(&self, window: &Window) -> anyhow::Result<Vec<Meeting>> {
+        let meetings = if *window == Window::today_and_tomorrow() {
+            fetch_google_meetings(&self.state).await
+        } else {
+            request_google_meetings(&self.state, window.start(), window.end()).await?
+        };
+        Ok(meetings
+            .into_iter()
+            .filter(|m| window.overlaps(m.start_time, m.end_time))
+            .collect())
+    }
+}
+
+/// Meetings of every source in the window, without the hidden ones
+async fn visible_meetings(state: &AppState, window: &Window) -> Vec<Meeting> {
+    let meetings = calendar_sources(state).fetch_meetings(window).await;
+    state.hidden_meetings.retain_visible(meetings).await
+}
+
+/// Every calendar source, in priority order
+fn calendar_sources(state: &AppState) -> Aggregator {
+    // Google events come first so they win over their ICS copies (they carry response status)
+    let configured = state.configured();
+    let mut sources = Aggregator::new()
+        .with_privacy(configured.config.privacy.clone())
+        .with_scripts(configured.scripts.clone())
+        .with_source(GoogleSource { state: state.clone() })
+        .with_source(IcsSource::new(state.calendar_service()))
+        .with_source(state.local_meetings.clone());
+    if let Some(holidays) = &configured.holidays {
+        sources = sources.with_source(holidays.clone());
+    }
+    for issue_source in &configured.issue_sources {
+        sources = sources.with_source(issue_source.clone());
+    }
+    sources
+}
+
+/// Merge the events of all calendar sources into a single deduplicated update
+pub async fn build_meeting_update(state: &AppState) -> MeetingUpdate {
+    live_snapshot(state).await.update
+}
+
+/// Sources whose latest fetch failed: the ICS and CalDAV feeds, Google,
+/// holidays and issue trackers
+async fn source_errors(state: &AppState, configured: &Configured) -> Vec<SourceError> {
+    let mut errors = configured.calendar_service.source_errors().await;
+    errors.extend(state.google_meetings.lock().await.failure.clone());
+    if let Some(holidays) = &configured.holidays {
+        errors.extend(holidays.failure().await);
+    }
+    for issue_source in &configured.issue_sources {
+        errors.extend(issue_source.failure().await);
+    }
+    errors
+}
+
+/// The current calendar state and the update built from it
+async fn live_snapshot(state: &AppState) -> LiveSnapshot {
+    let configured = state.configured();
+    let meetings = visible_meetings(state, &Window::today_and_tomorrow()).await;
+    let stale_since = configured.calendar_service.stale_since().await;
+    let status_override = state.status_override.read().await.clone().filter(StatusOverride::is_active);
+    LiveSnapshot {
+        update: MeetingUpdate {
+            last_updated: configured.calendar_service.last_updated().await,
+            source_errors: source_errors(state, &configured).await,
+            quiet: configured.config.is_quiet_now(),
+            countdown: configured.config.countdown,
+            ..compose_update(&meetings, status_override.as_ref(), stale_since)
+        },
+        sources: configured.calendar_service.named_source_health().await,
+        config_loaded_at: configured.loaded_at,
+        display: configured.config.display.clone(),
+        profiles: configured.profiles.clone(),
+        privacy: configured.config.privacy.clone(),
+        meetings,
+        status_override,
+        stale_since,
+    }
+}
+
+/// The update showing `meetings` (today's and tomorrow's, sorted by start)
+fn compose_update(
+    meetings: &[Meeting],
+    status_override: Option<&StatusOverride>,
+    stale_since: Option<DateTime<Utc>>,
+) -> MeetingUpdate {
+    let (all_day, timed): (Vec<&Meeting>, Vec<&Meeting>) = meetings.iter().partition(|m| m.all_day);
+
+    // Overlapping meetings: the one from the highest-priority source is current
+    let mut current = highest_priority(timed.iter().copied().filter(|m| !m.is_time_block() && m.is_active()))
+        .cloned();
+    match status_override {
+        Some(o) if o.status == OverrideStatus::Busy => current = Some(o.meeting()),
+        Some(_) => current = None,
+        None => {}
+    }
+    let upcoming: Vec<&Meeting> = timed.iter()
+        .copied()
+        .filter(|m| !m.is_time_block() && m.is_upcoming())
+        .collect();
+    // Of the meetings starting soonest, the one from the highest-priority source
+    let next = upcoming.first()
+        .and_then(|first| highest_priority(upcoming.iter().copied().filter(|m| m.start_time == first.start_time)))
+        .cloned();
+    let active_time_blocks: Vec<ActiveTimeBlock> = timed.iter()
+        .filter(|m| m.is_time_block() && m.is_active())
+        .map(|m| ActiveTimeBlock {
+            progress_percent: m.progress_percent(),
+            meeting: (*m).clone(),
+        })
+        .collect();
+    let upcoming_time_blocks: Vec<Meeting> = timed.iter()
+        .filter(|m| m.is_time_block() && m.is_upcoming())
+        .map(|m| (*m).clone())
+        .collect();
+    let all_day_events: Vec<Meeting> = all_day.into_iter()
+        .filter(|m| m.is_active())
+        .cloned()
+        .collect();
+    let holiday = all_day_events.iter()
+        .find(|m| m.is_holiday())
+        .map(|m| m.title.clone());
+    let today = chrono::Local::now().date_naive();
+    let (day_start, day_end) = (local_midnight(today), local_midnight(today + chrono::Days::new(1)));
+    let agenda_today: Vec<Meeting> = meetings.iter()
+        .filter(|m| m.start_time < day_end && m.end_time > day_start)
+        .cloned()
+        .collect();
+
+    tracing::debug!("Merged current: {:?}, next: {:?}",
+        current.as_ref().map(|m| &m.title),
+        next.as_ref().map(|m| &m.title)
+    );
+
+    let countdown_seconds = current.as_ref().map(|m| m.time_until_end());
+    let next_meeting_starts_in_seconds = next.as_ref().map(|m| m.time_until_start().max(0));
+
+    MeetingUpdate {
+        version: API_VERSION,
+        current_meeting: current,
+        next_meeting: next,
+        countdown_seconds,
+        next_meeting_starts_in_seconds,
+        active_time_blocks,
+        upcoming_time_blocks,
+        all_day_events,
+        holiday,
+        agenda_today,
+        stale: stale_since.is_some(),
+        stale_since,
+        timezone: None,
+        last_updated: None,
+        source_errors: Vec::new(),
+        quiet: false,
+        countdown: CountdownConfig::default(),
+    }
+}
+
+/// Hand the stored tokens to the Google service if they changed (e.g. after
+/// signing in); false if it has nothing to authenticate with yet (service
+/// accounts obtain their own tokens)
+async fn restore_google_tokens(state: &AppState, google_service: &mut GoogleCalendarService) -> bool {
+    match state.google_tokens.read().await.as_ref() {
+        Some(tokens) => {
+            let current = google_service.get_tokens();
+            if current.as_ref().map(|t| &t.access_token) != Some(&tokens.access_token) {
+                google_service.set_tokens(tokens.clone());
+            }
+            true
+        }
+        None if google_service.uses_service_account() => true,
+        None => {
+            // Disconnected
+            google_service.clear_tokens();
+            false
+        }
+    }
+}
+
+/// Save tokens the Google service refreshed while making its requests
+async fn store_refreshed_tokens(state: &AppState, google_service: &GoogleCalendarService) {
+    let Some(new_tokens) = google_service.get_tokens() else {
+        return;
+    };
+    let mut stored_tokens = state.google_tokens.write().await;
+    if stored_tokens.as_ref().is_some_and(|tokens| tokens.access_token == new_tokens.access_token) {
+        return;
+    }
+
+    // Service account tokens are short-lived and cheap to mint, so only kept in memory
+    if !google_service.uses_service_account() {
+        if let Err(e) = state.config().save_google_tokens(&new_tokens) {
+            warn!("Failed to save refreshed Google Calendar tokens to disk: {}", e);
+        }
+    }
+    *stored_tokens = Some(new_tokens);
+    info!("Updated stored tokens after refresh");
+}
+
+/// Google meetings for the current tick, served from the cache until
+/// `google.cache_ttl_seconds` have passed.
+///
+/// Concurrent callers share a single request: whoever waited for a fetch in
+/// progress gets its result. A failed fetch keeps the previous meetings, so a
+/// rate limit or network blip doesn't blank the display; it is retried once
+/// the TTL has passed again.
+async fn fetch_google_meetings(state: &AppState) -> Vec<Meeting> {
+    let seen_generation = match state.google_meetings.try_lock() {
+        Ok(cache) => Some(cache.generation),
+        Err(_) => None,
+    };
+    let mut cache = state.google_meetings.lock().await;
+    if seen_generation != Some(cache.generation) {
+        return cache.meetings.clone();
+    }
+    let ttl = Duration::from_secs(state.config().google.cache_ttl_seconds());
+    if cache.fetched_at.is_some_and(|fetched_at| fetched_at.elapsed() < ttl) {
+        return cache.meetings.clone();
+    }
+
+    // The whole window, so meetings that ended earlier today or start late
+    // tomorrow are there for the agenda and stats too
+    let window = Window::today_and_tomorrow();
+    match request_google_meetings(state, window.start(), window.end()).await {
+        Ok(meetings) => {
+            cache.meetings = meetings;
+            cache.failure = None;
+        }
+        Err(e) => {
+            let source = MeetingSource::google().name;
+            cache.failure = Some(SourceError::continuing(cache.failure.as_ref(), &source, e));
+        }
+    }
+    cache.fetched_at = Some(Instant::now());
+    cache.generation += 1;
+    cache.meetings.clone()
+}
+
+/// Fetch Google meetings between the two instants, refreshing stored tokens
+/// when needed
+async fn request_google_meetings(state: &AppState, time_min: DateTime<Utc>, time_max: DateTime<Utc>) -> anyhow::Result<Vec<Meeting>> {
+    match google_service(state) {
+        Ok(Some(google_service)) => {
+            let mut google_service = google_service.write().await;
+            // Set stored tokens if available
+            if !restore_google_tokens(state, &mut google_service).await {
+                tracing::debug!("Google Calendar not authenticated");
+                return Ok(Vec::new());
+            }
+
+            // Refreshes the access token transparently when it expires
+            let result = google_service.fetch_events_between(time_min, time_max).await;
+
+            store_refreshed_tokens(state, &google_service).await;
+
+            match result {
+                Ok(events) => {
+                    info!("Successfully fetched {} Google Calendar events", events.len());
+                    Ok(events)
+                },
+                Err(e) => {
+                    warn!("Failed to fetch Google Calendar events, keeping the previous ones: {}", e);
+                    Err(e)
+                }
+            }
+        }
+        Ok(None) => {
+            tracing::debug!("Google OAuth not configured");
+            Ok(Vec::new())
+        },
+        Err(e) => {
+            warn!("Failed to create Google Calendar service: {}", e);
+            Err(e)
+        },
+    }
+}
+
+/// List the connected Google account's calendars, so users can pick which
+/// IDs to add to `[[google.calendars]]`
+async fn get_google_calendars(State(state): State<AppState>) -> Response {
+    let google_service = match google_service(&state) {
+        Ok(Some(google_service)) => google_service,
+        Ok(None) => return api_error(StatusCode::NOT_FOUND, "Google OAuth is not configured"),
+        Err(e) => return api_error(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create Google Calendar service: {}", e)),
+    };
+    let mut google_service = google_service.write().await;
+    if !restore_google_tokens(&state, &mut google_service).await {
+        return api_error(StatusCode::UNAUTHORIZED, "Google Calendar is not connected, sign in at /auth/google/login");
+    }
+
+    let result = google_service.list_calendars().await;
+    store_refreshed_tokens(&state, &google_service).await;
+
+    match result {
+        Ok(entries) => {
+            let monitored_ids: Vec<String> = state.config().google.calendar_sources()
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect();
+            let calendars = entries
+                .into_iter()
+                .map(|entry| google_calendar_info(entry, &monitored_ids))
+                .collect();
+            Json(GoogleCalendarsResponse { calendars }).into_response()
+        }
+        Err(e) => {
+            warn!("Failed to list Google calendars: {}", e);
+            api_error(StatusCode::BAD_GATEWAY, e.to_string())
+        }
+    }
+}
+
+fn google_calendar_info(entry: GoogleCalendarListEntry, monitored_ids: &[String]) -> GoogleCalendarInfo {
+    let primary = entry.primary.unwrap_or(false);
+    let monitored = monitored_ids.contains(&entry.id)
+        || (primary && monitored_ids.iter().any(|id| id == "primary"));
+    GoogleCalendarInfo {
+        name: entry.summary_override.or(entry.summary).unwrap_or_else(|| entry.id.clone()),
+        id: entry.id,
+        color: entry.background_color,
+        primary,
+        access_role: entry.access_role,
+        monitored,
+    }
+}
+
+/// Per-source fetch health, so admins can see which calendar feed is broken
+async fn get_sources(State(state): State<AppState>) -> impl IntoResponse {
+    Json(SourcesResponse {
+        sources: state.calendar_service().named_source_health().await,
+    })
+}
+
+/// Add an ICS URL at runtime and write it back to the config file
+async fn add_ics_source(
+    State(state): State<AppState>,
+    Json(source): Json<IcsSourceConfig>,
+) -> Response {
+    if !state.config().server.allow_source_management {
+        return api_error(StatusCode::FORBIDDEN, "Source management is disabled (server.allow_source_management)");
+    }
+    if !source.path.starts_with("http://") && !source.path.starts_with("https://") {
+        return api_error(StatusCode::BAD_REQUEST, "Only http:// and https:// ICS URLs can be added at runtime");
+    }
+    if let Err(e) = source.validate() {
+        return api_error(StatusCode::BAD_REQUEST, e.to_string());
+    }
+    if state.calendar_service().source_paths().await.contains(&source.path) {
+        return api_error(StatusCode::CONFLICT, format!("ICS source already configured: {}", source.path));
+    }
+    if let Err(e) = state.calendar_service().add_source(&source).await {
+        return api_error(StatusCode::BAD_REQUEST, e.to_string());
+    }
+
+    let persisted = persist_source_change(&state, |path| {
+        Config::add_ics_source_to_file(path, &source).map(|_| true)
+    })
+    .await;
+
+    let response = SourceChangeResponse {
+        sources: state.calendar_service().named_source_health().await,
+        persisted,
+    };
+    (StatusCode::CREATED, Json(response)).into_response()
+}
+
+/// Remove an ICS source (`?path=<url or file>`) at runtime and from the config file
+async fn remove_ics_source(
+    State(state): State<AppState>,
+    query: Query<HashMap<String, String>>,
+) -> Response {
+    if !state.config().server.allow_source_management {
+        return api_error(StatusCode::FORBIDDEN, "Source management is disabled (server.allow_source_management)");
+    }
+    let Some(path) = query.get("path") else {
+        return api_error(StatusCode::BAD_REQUEST, "Missing 'path' query parameter");
+    };
+    if !state.calendar_service().remove_source(path).await {
+        return api_error(StatusCode::NOT_FOUND, format!("ICS source not configured: {}", path));
+    }
+
+    let persisted = persist_source_change(&state, |config_path| {
+        Config::remove_ics_source_from_file(config_path, path)
+    })
+    .await;
+
+    Json(SourceChangeResponse {
+        sources: state.calendar_service().named_source_health().await,
+        persisted,
+    })
+    .into_response()
+}
+
+/// Apply a source change to the config file, if one is in use.
+/// Returns whether the file was updated; the runtime change stands either way.
+async fn persist_source_change(
+    state: &AppState,
+    change: impl FnOnce(&Path) -> anyhow::Result<bool>,
+) -> bool {
+    let Some(config_file) = &state.config_file else {
+        warn!("No configuration file in use; source change will not survive a restart");
+        return false;
+    };
+
+    let path = config_file.lock().await;
+    match change(&path) {
+        Ok(written) => written,
+        Err(e) => {
+            warn!("Failed to update configuration file: {}", e);
+            false
+        }
+    }
+}
+
+/// The configuration in effect with secrets redacted, and where it came from
+async fn get_config(State(state): State<AppState>) -> Json<ConfigResponse> {
+    let config_file = match &state.config_file {
+        Some(path) => Some(path.lock().await.clone()),
+        None => None,
+    };
+    Json(ConfigResponse {
+        config_file,
+        env_overrides: Config::env_overrides().into_iter().map(String::from).collect(),
+        config: state.config().redacted(),
+    })
+}
+
+/// Re-read and validate the config file and switch to it without a
+/// restart. An invalid file is reported and the running configuration kept.
+async fn reload_config(State(state): State<AppState>) -> Response {
+    let Some(config_file) = &state.config_file else {
+        return api_error(StatusCode::CONFLICT, "No configuration file was loaded at startup");
+    };
+    let path = config_file.lock().await.clone();
+
+    let config = match Config::reload_with(&path, &state.cli) {
+        Ok(config) => config,
+        Err(e) => {
+            warn!("Not reloading configuration: {}", e);
+            return api_error(StatusCode::BAD_REQUEST, e.to_string());
+        }
+    };
+
+    // The listener and the HTTP clients are set up once at startup
+    let previous = state.config();
+    let mut restart_required = Vec::new();
+    if previous.bind_address() != config.bind_address() || previous.server.tls() != config.server.tls() {
+        restart_required.push("server".to_string());
+    }
+    if serde_json::to_value(&previous.http).ok() != serde_json::to_value(&config.http).ok() {
+        restart_required.push("http".to_string());
+    }
+
+    state.apply_config(config).await;
+    info!("Reloaded configuration from {}", path.display());
+    if !restart_required.is_empty() {
+        warn!("Changes to [{}] take effect after a restart", restart_required.join("], ["));
+    }
+
+    Json(ReloadResponse {
+        config_file: path,
+        sources: state.calendar_service().named_source_health().await,
+        restart_required,
+    })
+    .into_response()
+}
+
+/// The `[display]` settings, for clients that don't use the typed WebSocket protocol
+async fn get_display_config(State(state): State<AppState>) -> Json<DisplayConfig> {
+    Json(state.config().display.clone())
+}
+
+async fn get_server_info(State(state): State<AppState>) -> impl IntoResponse {
+    let ip = match local_ip() {
+        Ok(ip) => ip.to_string(),
+        Err(_) => "Unable to determine".to_string(),
+    };
+    
+    let server_info = ServerInfo {
+        local_ip: ip,
+        server_port: 3000, // Default port, could be made configurable
+        connected_clients: state.live_updates.clients(),
+    };
+    
+    Json(server_info)
+}
+
+/// Google OAuth login endpoint
+async fn google_auth_login(State(state): State<AppState>) -> impl IntoResponse {
+    let config = state.config();
+    if config.google.auth_flow == AuthFlow::Device && config.google.service_account_key_file.is_none() {
+        return device_authorization_page(&state).await;
+    }
+
+    match google_service_copy(&state).await {
+        Ok(Some(google_service)) if google_service.uses_service_account() => {
+            Html("<h1>No sign-in needed</h1><p>Google Calendar is accessed with a service account (google.service_account_key_file).</p><p><a href='/'>← Back to Calendar</a></p>").into_response()
+        }
+        Ok(Some(google_service)) => {
+            let (auth_url, csrf_token, pkce_verifier) = google_service.get_auth_url();
+            let mut pending = state.pending_authorizations.lock().await;
+            prune_pending_authorizations(&mut pending);
+            pending.insert(
+                csrf_token.secret().clone(),
+                PendingAuthorization { pkce_verifier: pkce_verifier.secret().clone(), created_at: Utc::now() },
+            );
+            let secure = match &config.google.redirect_uri {
+                Some(redirect_uri) if redirect_uri.starts_with("https://") => "; Secure",
+                _ => "",
+            };
+            let cookie = format!(
+                "{}={}; Path=/auth/google; HttpOnly; SameSite=Lax; Max-Age={}{}",
+                GOOGLE_SIGN_IN_COOKIE,
+                csrf_token.secret(),
+                PENDING_AUTHORIZATION_TTL_MINUTES * 60,
+                secure
+            );
+            let mut response = axum::response::Redirect::temporary(auth_url.as_str()).into_response();
+            response.headers_mut().insert(header::SET_COOKIE, cookie.parse().unwrap());
+            response
+        }
+        Ok(None) => {
+            Html("<h1>Google OAuth not configured</h1><p>Please configure Google OAuth in your TOML config file or set environment variables GOOGLE_CLIENT_ID, GOOGLE_CLIENT_SECRET, and GOOGLE_REDIRECT_URI.</p>".to_string()).into_response()
+        }
+        Err(e) => {
+            warn!("Failed to create Google OAuth client: {}", e);
+            Html(format!("<h1>Error</h1><p>Failed to initialize Google OAuth: {}</p>", e)).into_response()
+        }
+    }
+}
+
+/// Start a Google device authorization, or return the one still waiting for
+/// the user. Polling for approval continues in the background and stores the
+/// tokens once the user has entered the code.
+pub async fn start_device_authorization(state: &AppState) -> anyhow::Result<DeviceAuthorizationStatus> {
+    {
+        let current = state.device_authorization.read().await;
+        if let DeviceAuthorizationStatus::Pending { expires_at, .. } = &*current {
+            if *expires_at > Utc::now() {
+                return Ok(current.clone());
+            }
+        }
+    }
+
+    // Polling takes minutes, so it works on a copy instead of locking the shared service
+    let mut google_service = google_service_copy(state).await?
+        .ok_or_else(|| anyhow::anyhow!("Google OAuth is not configured"))?;
+    if google_service.uses_service_account() {
+        return Err(anyhow::anyhow!("Google Calendar uses a service account, no sign-in needed"));
+    }
+
+    let details = google_service.start_device_authorization().await?;
+    let status = DeviceAuthorizationStatus::Pending {
+        user_code: details.user_code().secret().clone(),
+        verification_url: details.verification_uri().to_string(),
+        expires_at: Utc::now() + chrono::Duration::seconds(details.expires_in().as_secs() as i64),
+    };
+    *state.device_authorization.write().await = status.clone();
+
+    let state = state.clone();
+    tokio::spawn(async move {
+        let status = match google_service.complete_device_authorization(&details).await {
+            Ok(()) => {
+                if let Some(tokens) = google_service.get_tokens() {
+                    if let Err(e) = state.config().save_google_tokens(&tokens) {
+                        warn!("Failed to save Google Calendar tokens to disk: {}", e);
+                    }
+                    *state.google_tokens.write().await = Some(tokens);
+                    state.google_meetings.lock().await.invalidate();
+                }
+                info!("Google Calendar connected through device authorization");
+                DeviceAuthorizationStatus::Connected
+            }
+            Err(e) => {
+                warn!("Google device authorization failed: {}", e);
+                DeviceAuthorizationStatus::Failed { error: e.to_string() }
+            }
+        };
+        *state.device_authorization.write().await = status;
+    });
+
+    Ok(status)
+}
+
+async fn get_device_authorization(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.device_authorization.read().await.clone())
+}
+
+async fn post_device_authorization(State(state): State<AppState>) -> Response {
+    match start_device_authorization(&state).await {
+        Ok(status) => (StatusCode::ACCEPTED, Json(status)).into_response(),
+        Err(e) => api_error(StatusCode::BAD_GATEWAY, e.to_string()),
+    }
+}
+
+/// Page shown on the display itself during the device flow; reloads until connected
+async fn device_authorization_page(state: &AppState) -> Response {
+    let connected = matches!(*state.device_authorization.read().await, DeviceAuthorizationStatus::Connected);
+    if connected && state.google_tokens.read().await.is_some() {
+        return axum::response::Redirect::temporary("/").into_response();
+    }
+
+    match start_device_authorization(state).await {
+        Ok(DeviceAuthorizationStatus::Pending { user_code, verification_url, .. }) => Html(format!(
+            "<html><head><meta http-equiv='refresh' content='5'></head><body>\
+             <h1>Connect Google Calendar</h1>\
+             <p>On your phone or computer, open <strong>{}</strong> and enter the code:</p>\
+             <h2>{}</h2>\
+             <p>This page updates automatically once access is granted.</p></body></html>",
+            html_escape(&verification_url),
+            html_escape(&user_code)
+        )).into_response(),
+        Ok(_) => axum::response::Redirect::temporary("/").into_response(),
+        Err(e) => {
+            warn!("Failed to start Google device authorization: {}", e);
+            Html(format!("<h1>Error</h1><p>Failed to start Google device authorization: {}</p>", html_escape(&e.to_string()))).into_response()
+        }
+    }
+}
+
+/// Start signing in with the `[oidc]` provider, coming back to `?return_to=`
+async fn oidc_login(State(state): State<AppState>, query: Query<HashMap<String, String>>) -> Response {
+    let config = state.config();
+    let Some(oidc) = &config.oidc else {
+        return (StatusCode::NOT_FOUND, Html("<h1>OpenID Connect sign-in is not configured</h1>")).into_response();
+    };
+    // Only pages of this server, so the sign-in can't be used to send people
+    // elsewhere, and only what a `Location` header can carry
+    let return_to = query
+        .get("return_to")
+        .filter(|path| path.starts_with('/') && !path.starts_with("//") && !path.starts_with("/\\"))
+        .filter(|path| path.parse::<axum::http::uri::PathAndQuery>().is_ok() && !path.chars().any(char::is_control))
+        .map_or("/", String::as_str);
+    match state.oidc_sessions.login_url(&state.oauth_http_client, oidc, return_to).await {
+        Ok(url) => axum::response::Redirect::temporary(&url).into_response(),
+        Err(e) => {
+            warn!("Failed to start OpenID Connect sign-in: {}", e);
+            (
+                StatusCode::BAD_GATEWAY,
+                Html(format!("<h1>❌ Sign-in unavailable</h1><p>{}</p><p><a href='/auth/oidc/login'>← Try again</a></p>", html_escape(&e.to_string()))),
+            ).into_response()
+        }
+    }
+}
+
+/// Where the `[oidc]` provider sends the browser back after signing in
+async fn oidc_callback(State(state): State<AppState>, query: Query<HashMap<String, String>>) -> Response {
+    let config = state.config();
+    let Some(oidc) = &config.oidc else {
+        return (StatusCode::NOT_FOUND, Html("<h1>OpenID Connect sign-in is not configured</h1>")).into_response();
+    };
+    if let Some(error) = query.get("error") {
+        let description = query.get("error_description").map_or("", String::as_str);
+        warn!("OpenID Connect sign-in failed at the provider: {} {}", error, description);
+        return (
+            StatusCode::FORBIDDEN,
+            Html(format!(
+                "<h1>❌ Sign-in failed</h1><p>{} {}</p><p><a href='/auth/oidc/login'>← Try again</a></p>",
+                html_escape(error),
+                html_escape(description)
+            )),
+        ).into_response();
+    }
+    let (Some(code), Some(login)) = (query.get("code"), query.get("state").and_then(|s| state.oidc_sessions.take_login(s))) else {
+        warn!("Rejected OpenID Connect callback with an unknown or expired state parameter");
+        return (
+            StatusCode::BAD_REQUEST,
+            Html("<h1>❌ Invalid sign-in callback</h1>
+                  <p>The sign-in request is unknown or has expired.</p>
+                  <p><a href='/auth/oidc/login'>← Try again</a></p>"),
+        ).into_response();
+    };
+
+    let user = match state.oidc_sessions.verify(&state.oauth_http_client, oidc, code, &login).await {
+        Ok(user) => user,
+        Err(e) => {
+            warn!("OpenID Connect sign-in failed: {}", e);
+            return (
+                StatusCode::BAD_GATEWAY,
+                Html(format!("<h1>❌ Sign-in failed</h1><p>{}</p><p><a href='/auth/oidc/login'>← Try again</a></p>", html_escape(&e.to_string()))),
+            ).into_response();
+        }
+    };
+    if !oidc.allows(user.email.as_deref()) {
+        warn!("{} signed in but is not in oidc.allowed_users", user);
+        return (
+            StatusCode::FORBIDDEN,
+            Html("<h1>⛔ Not allowed</h1><p>Your account may not use this calendar monitor.</p>"),
+        ).into_response();
+    }
+
+    info!("{} signed in", user);
+    let session_id = state.oidc_sessions.start_session(user, oidc.session_hours);
+    let mut response = axum::response::Redirect::temporary(&login.return_to).into_response();
+    let cookie = crate::oidc::set_session_cookie(oidc, &session_id);
+    response.headers_mut().insert(header::SET_COOKIE, cookie.parse().unwrap());
+    response
+}
+
+/// Confirmation page for signing out; signing out itself is a POST
+async fn oidc_logout_page() -> impl IntoResponse {
+    Html("<h1>Sign out</h1>
+          <form method='post' action='/auth/oidc/logout'><button type='submit'>Sign out</button></form>
+          <p><a href='/'>← Back to Calendar</a></p>")
+}
+
+/// End the session; the provider's own sign-in is left alone
+async fn oidc_logout(State(state): State<AppState>, headers: axum::http::HeaderMap) -> Response {
+    if let Some(user) = crate::oidc::session_cookie(&headers).and_then(|session| state.oidc_sessions.end_session(&session)) {
+        info!("{} signed out", user);
+    }
+    let mut response = Html("<h1>Signed out</h1><p><a href='/auth/oidc/login'>Sign in again</a></p>").into_response();
+    response.headers_mut().insert(header::SET_COOKIE, crate::oidc::clear_session_cookie().parse().unwrap());
+    response
+}
+
+/// Confirmation page for disconnecting Google Calendar. Its form carries the
+/// server's form token, and the page's `?token=` for `server.auth_token`.
+async fn google_logout_page(State(state): State<AppState>, query: Query<HashMap<String, String>>) -> impl IntoResponse {
+    // Percent-encoded, so nothing in it needs escaping
+    let action = match query.get("token") {
+        Some(token) => format!("/auth/google/logout?token={}", urlencoding::encode(token)),
+        None => "/auth/google/logout".to_string(),
+    };
+    Html(format!(
+        "<h1>Disconnect Google Calendar</h1>
+         <p>This removes the stored tokens and revokes the monitor's access to your Google Calendar.</p>
+         <form method='post' action='{}'><input type='hidden' name='form_token' value='{}'><button type='submit'>Disconnect</button></form>
+         <p><a href='/'>← Back to Calendar</a></p>",
+        action,
+        html_escape(&state.form_token)
+    ))
+}
+
+/// Whether a form was posted from one of the server's own pages: it carries
+/// the form token they are rendered with, and the browser names no other
+/// site as its `Origin`
+fn own_form(state: &AppState, headers: &HeaderMap, body: &str) -> bool {
+    let form_token = url::form_urlencoded::parse(body.as_bytes())
+        .find(|(name, _)| name == "form_token")
+        .map(|(_, value)| value.into_owned());
+    if form_token.as_deref() != Some(state.form_token.as_str()) {
+        return false;
+    }
+    let Some(origin) = headers.get(header::ORIGIN) else {
+        return true;
+    };
+    let origin_host = origin.to_str().ok().and_then(|origin| url::Url::parse(origin).ok()).map(|url| match url.port() {
+        Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+        None => url.host_str().unwrap_or_default().to_string(),
+    });
+    let host = headers.get(header::HOST).and_then(|host| host.to_str().ok());
+    origin_host.is_some() && origin_host.as_deref() == host
+}
+
+/// Disconnect Google Calendar: forget the tokens (in memory and in storage)
+/// and revoke them at Google. Only accepted from the confirmation page's form.
+async fn google_auth_logout(State(state): State<AppState>, headers: HeaderMap, body: String) -> Response {
+    if !own_form(&state, &headers, &body) {
+        warn!("Rejected a Google Calendar logout that was not posted from the confirmation page");
+        return (
+            StatusCode::FORBIDDEN,
+            Html("<h1>❌ Not disconnected</h1><p>Disconnect from the confirmation page.</p><p><a href='/auth/google/logout'>Disconnect Google Calendar</a></p>"),
+        ).into_response();
+    }
+    let google_service = match google_service(&state) {
+        Ok(Some(google_service)) => google_service,
+        Ok(None) => return (StatusCode::NOT_FOUND, Html("<h1>Google OAuth not configured</h1>")).into_response(),
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Html(format!("<h1>❌ Error</h1><p>Failed to initialize Google OAuth: {}</p>", html_escape(&e.to_string()))),
+            ).into_response();
+        }
+    };
+    if google_service.read().await.uses_service_account() {
+        return Html("<h1>Nothing to disconnect</h1><p>Google Calendar is accessed with a service account (google.service_account_key_file).</p><p><a href='/'>← Back to Calendar</a></p>").into_response();
+    }
+
+    let tokens = state.google_tokens.write().await.take();
+    google_service.write().await.clear_tokens();
+    state.google_meetings.lock().await.invalidate();
+    *state.device_authorization.write().await = DeviceAuthorizationStatus::Idle;
+    if let Err(e) = state.config().remove_google_tokens() {
+        warn!("Failed to remove stored Google Calendar tokens: {}", e);
+    }
+
+    let Some(tokens) = tokens else {
+        return Html("<h1>Google Calendar is not connected</h1><p><a href='/'>← Back to Calendar</a></p>").into_response();
+    };
+    info!("Disconnected Google Calendar");
+
+    // The local tokens are gone either way; a failed revocation only means the
+    // grant stays listed in the Google account until it expires or is removed there
+    let revoke_result = google_service.read().await.revoke_tokens(&tokens).await;
+    match revoke_result {
+        Ok(()) => Html("<h1>✅ Google Calendar Disconnected</h1>
+                        <p>The stored tokens were removed and access was revoked at Google.</p>
+                        <p><a href='/'>← Back to Calendar</a> | <a href='/auth/google/login'>🔗 Connect again</a></p>").into_response(),
+        Err(e) => {
+            warn!("Failed to revoke Google Calendar tokens: {}", e);
+            (
+                StatusCode::BAD_GATEWAY,
+                Html(format!(
+                    "<h1>⚠️ Google Calendar Disconnected</h1>
+                     <p>The stored tokens were removed, but Google could not revoke access: {}</p>
+                     <p>You can remove the access under <a href='https://myaccount.google.com/permissions'>Google Account → Third-party access</a>.</p>
+                     <p><a href='/'>← Back to Calendar</a></p>",
+                    html_escape(&e.to_string())
+                )),
+            ).into_response()
+        }
+    }
+}
+
+/// Forget sign-ins that were started but never completed
+fn prune_pending_authorizations(pending: &mut HashMap<String, PendingAuthorization>) {
+    let now = Utc::now();
+    pending.retain(|_, auth| now - auth.created_at < chrono::Duration::minutes(PENDING_AUTHORIZATION_TTL_MINUTES));
+}
+
+/// Google OAuth callback endpoint
+async fn google_auth_callback(
+    query: Query<HashMap<String, String>>,
+    headers: HeaderMap,
+    State(state): State<AppState>
+) -> Response {
+    let page = if let (Some(code), Some(oauth_state)) = (query.get("code"), query.get("state")) {
+        info!("Received OAuth callback with authorization code");
+
+        // Only accept callbacks for a sign-in started by /auth/google/login
+        // in this browser; each state is single-use
+        let pending = match crate::oidc::cookie(&headers, GOOGLE_SIGN_IN_COOKIE) {
+            Some(cookie) if cookie == *oauth_state => {
+                let mut pending = state.pending_authorizations.lock().await;
+                prune_pending_authorizations(&mut pending);
+                pending.remove(oauth_state)
+            }
+            _ => None,
+        };
+        let Some(pending) = pending else {
+            warn!("Rejected OAuth callback with an unknown or expired state parameter");
+            return (
+                StatusCode::BAD_REQUEST,
+                Html("<h1>❌ Invalid OAuth callback</h1>
+                      <p>The sign-in request is unknown or has expired.</p>
+                      <p><a href='/auth/google/login'>← Try again</a></p>"),
+            ).into_response();
+        };
+        let pkce_verifier = oauth2::PkceCodeVerifier::new(pending.pkce_verifier);
+        
+        // Exchange authorization code for tokens; the shared service picks
+        // them up from the token store on its next request
+        match google_service_copy(&state).await {
+            Ok(Some(mut google_service)) => {
+                match google_service.exchange_code(code.clone(), pkce_verifier).await {
+                    Ok(()) => {
+                        // Store tokens in global state and persist to disk
+                        if let Some(tokens) = google_service.get_tokens() {
+                            // Save to disk first
+                            if let Err(e) = state.config().save_google_tokens(&tokens) {
+                                warn!("Failed to save Google Calendar tokens to disk: {}", e);
+                            }
+                            
+                            // Store in memory
+                            *state.google_tokens.write().await = Some(tokens);
+                            state.google_meetings.lock().await.invalidate();
+                            info!("Successfully stored Google Calendar tokens in memory and disk");
+                        }
+                        
+                        Html(format!(
+                            "<h1>✅ Google Calendar Connected!</h1>
+                             <p>Successfully authenticated with Google Calendar.</p>
+                             <p>You can now see your Google Calendar events in the monitor.</p>
+                             <p><a href='/'>← Back to Calendar Monitor</a></p>
+                             <script>
+                                setTimeout(() => window.location.href = '/', 3000);
+                             </script>"
+                        ))
+                    }
+                    Err(e) => {
+                        warn!("Failed to exchange OAuth code for tokens: {}", e);
+                        Html(format!(
+                            "<h1>❌ Google OAuth Error</h1>
+                             <p>Failed to exchange authorization code for tokens: {}</p>
+                             <p><a href='/auth/google/login'>← Try again</a></p>",
+                            html_escape(&e.to_string())
+                        ))
+                    }
+                }
+            }
+            Ok(None) => {
+                Html("<h1>❌ Google OAuth not configured</h1><p>Please configure Google OAuth in your TOML config file or set environment variables.</p>".to_string())
+            }
+            Err(e) => {
+                warn!("Failed to create Google OAuth client: {}", e);
+                Html(format!(
+                    "<h1>❌ Google OAuth Error</h1>
+                     <p>Failed to initialize OAuth client: {}</p>
+                     <p><a href='/auth/google/login'>← Try again</a></p>",
+                    html_escape(&e.to_string())
+                ))
+            }
+        }
+    } else if let Some(error) = query.get("error") {
+        // Anyone can send a link with their own text here
+        Html(format!(
+            "<h1>❌ Google OAuth Error</h1>
+             <p>Error: {}</p>
+             <p><a href='/auth/google/login'>← Try again</a></p>",
+            html_escape(error)
+        ))
+    } else {
+        Html("<h1>❌ Invalid OAuth callback</h1><p>Missing required parameters.</p>".to_string())
+    };
+    let mut response = page.into_response();
+    let cookie = format!("{}=; Path=/auth/google; HttpOnly; SameSite=Lax; Max-Age=0", GOOGLE_SIGN_IN_COOKIE);
+    response.headers_mut().insert(header::SET_COOKIE, cookie.parse().unwrap());
+    response
+}
+
+/// Debug endpoint to check Google authentication status
+async fn google_auth_status(State(state): State<AppState>) -> impl IntoResponse {
+    let mut response = format!("<h1>🔍 Google Calendar Debug Status</h1>");
+    
+    // Check environment variables
+    let has_client_id = env_var("GOOGLE_CLIENT_ID").is_some() || env_var("GOOGLE_CLIENT_ID_FILE").is_some();
+    let has_client_secret = env_var("GOOGLE_CLIENT_SECRET").is_some() || env_var("GOOGLE_CLIENT_SECRET_FILE").is_some();
+    let has_redirect_uri = env_var("GOOGLE_REDIRECT_URI").is_some();
+    
+    response.push_str(&format!("<h2>Environment Variables:</h2>"));
+    response.push_str(&format!("<ul>"));
+    response.push_str(&format!("<li>GOOGLE_CLIENT_ID: {}</li>", if has_client_id { "✅ Set" } else { "❌ Missing" }));
+    response.push_str(&format!("<li>GOOGLE_CLIENT_SECRET: {}</li>", if has_client_secret { "✅ Set" } else { "❌ Missing" }));
+    response.push_str(&format!("<li>GOOGLE_REDIRECT_URI: {}</li>", if has_redirect_uri { "✅ Set" } else { "❌ Missing" }));
+    response.push_str(&format!("</ul>"));
+    
+    // Check stored tokens
+    let has_tokens = state.google_tokens.read().await.is_some();
+    
+    response.push_str(&format!("<h2>Authentication Status:</h2>"));
+    response.push_str(&format!("<ul>"));
+    response.push_str(&format!("<li>Stored Tokens: {}</li>", if has_tokens { "✅ Available" } else { "❌ None" }));
+    response.push_str(&format!("</ul>"));
+    
+    // Test Google Calendar service creation
+    let service_status = match google_service(&state) {
+        Ok(Some(google_service)) => {
+            // Try to restore tokens
+            if restore_google_tokens(&state, &mut *google_service.write().await).await {
+                format!("✅ Service created and tokens restored")
+            } else {
+                format!("⚠️ Service created but no tokens to restore")
+            }
+        },
+        Ok(None) => format!("❌ Service creation returned None (environment issue)"),
+        Err(e) => format!("❌ Service creation failed: {}", e),
+    };
+    
+    response.push_str(&format!("<h2>Service Status:</h2>"));
+    response.push_str(&format!("<ul><li>{}</li></ul>", service_status));
+    
+    response.push_str(&format!("<p><a href='/'>← Back to Calendar</a> | <a href='/auth/google/login'>🔗 Connect Google Calendar</a> | <a href='/auth/google/logout'>Disconnect</a></p>"));
+    
+    Html(response)
+}
+
+/// Serve embedded CSS file
+async fn serve_css() -> impl IntoResponse {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, "text/css".parse().unwrap());
+    (StatusCode::OK, headers, STYLE_CSS)
+}
+
+/// Serve embedded JavaScript file
+async fn serve_js() -> impl IntoResponse {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, "application/javascript".parse().unwrap());
+    (StatusCode::OK, headers, APP_JS)
+}
@@ -0,0 +1,52 @@
+/// Badge colors, as used by shields.io
+pub const BUSY_COLOR: &str = "#e05d44";
+pub const FREE_COLOR: &str = "#4c1";
+
+/// Rough width of text in 11px Verdana; close enough for short badge texts
+fn text_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| match c {
+            'i' | 'l' | 'j' | '.' | ',' | ':' | ' ' | '\'' | '|' => 4,
+            'm' | 'w' | 'M' | 'W' => 10,
+            c if c.is_ascii_uppercase() || c.is_ascii_digit() => 8,
+            _ => 7,
+        })
+        .sum()
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A flat shields.io-style badge with a grey label and a colored message
+pub fn render_badge(label: &str, message: &str, color: &str) -> String {
+    let label_width = text_width(label) + 10;
+    let message_width = text_width(message) + 10;
+    let width = label_width + message_width;
+    let (label, message, color) = (escape_xml(label), escape_xml(message), escape_xml(color));
+    let label_x = label_width as f32 / 2.0;
+    let message_x = label_width as f32 + message_width as f32 / 2.0;
+
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{label}: {message}">
+  <title>{label}: {message}</title>
+  <linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient>
+  <clipPath id="r"><rect width="{width}" height="20" rx="3" fill="#fff"/></clipPath>
+  <g clip-path="url(#r)">
+    <rect width="{label_width}" height="20" fill="#555"/>
+    <rect x="{label_width}" width="{message_width}" height="20" fill="{color}"/>
+    <rect width="{width}" height="20" fill="url(#s)"/>
+  </g>
+  <g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">
+    <text x="{label_x}" y="15" fill="#010101" fill-opacity=".3">{label}</text>
+    <text x="{label_x}" y="14">{label}</text>
+    <text x="{message_x}" y="15" fill="#010101" fill-opacity=".3">{message}</text>
+    <text x="{message_x}" y="14">{message}</text>
+  </g>
+</svg>
+"##
+    )
+}
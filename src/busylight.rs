@@ -0,0 +1,184 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::app::MeetingUpdate;
+use crate::config::{BusyLightConfig, BusyLightDevice};
+use crate::events::{Event, NotificationBackend};
+
+/// How often the color is sent again while it stays the same: Kuando lights
+/// go dark without regular commands, and a replugged light catches up
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A color as the lights take it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const OFF: Rgb = Rgb(0, 0, 0);
+
+    /// A `#rrggbb` color
+    pub fn parse(color: &str) -> Result<Self> {
+        let hex = color.strip_prefix('#').filter(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()));
+        let Some(hex) = hex else {
+            return Err(anyhow!("'{}' is not a #rrggbb color", color));
+        };
+        let channel = |at: usize| u8::from_str_radix(&hex[at..at + 2], 16).unwrap_or_default();
+        Ok(Rgb(channel(0), channel(2), channel(4)))
+    }
+}
+
+/// What the light shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LightState {
+    Busy,
+    /// A meeting starts within `soon_minutes`
+    Soon,
+    Free,
+}
+
+impl LightState {
+    pub fn of(update: &MeetingUpdate, soon: chrono::Duration) -> Self {
+        if update.current_meeting.is_some() {
+            LightState::Busy
+        } else if update.next_meeting_starts_in_seconds.is_some_and(|seconds| seconds <= soon.num_seconds()) {
+            LightState::Soon
+        } else {
+            LightState::Free
+        }
+    }
+}
+
+impl BusyLightDevice {
+    /// Whether the USB vendor and product ids are this kind of light
+    pub fn matches(self, vendor_id: u16, product_id: u16) -> bool {
+        match self {
+            BusyLightDevice::Luxafor => (vendor_id, product_id) == (0x04d8, 0xf372),
+            BusyLightDevice::Blinkstick => (vendor_id, product_id) == (0x20a0, 0x41e5),
+            // Plenom's own vendor id, and the early lights sold with a Microchip one
+            BusyLightDevice::Kuando => vendor_id == 0x27bb || (vendor_id, product_id) == (0x04d8, 0xf848),
+        }
+    }
+
+    /// The HID report setting the light to `color`, starting with its report id.
+    /// BlinkSticks take it as a feature report, the others as an output report.
+    pub fn report(self, color: Rgb) -> Vec<u8> {
+        let Rgb(red, green, blue) = color;
+        match self {
+            // Command 1 (static color) for every LED (0xff)
+            BusyLightDevice::Luxafor => vec![0x00, 0x01, 0xff, red, green, blue, 0x00, 0x00, 0x00],
+            BusyLightDevice::Blinkstick => vec![0x01, red, green, blue],
+            BusyLightDevice::Kuando => {
+                // Seven 8-byte steps, of which the first jumps to itself with the color
+                // on (0-100 per channel), then a footer and a checksum of everything before it
+                let percent = |channel: u8| (u16::from(channel) * 100 / 255) as u8;
+                let mut buffer = [0u8; 64];
+                buffer[..8].copy_from_slice(&[0x10, 0x00, percent(red), percent(green), percent(blue), 0x00, 0x00, 0x00]);
+                buffer[59..62].copy_from_slice(&[0xff, 0xff, 0xff]);
+                let checksum: u16 = buffer[..62].iter().map(|&byte| u16::from(byte)).sum();
+                buffer[62..].copy_from_slice(&checksum.to_be_bytes());
+                std::iter::once(0x00).chain(buffer).collect()
+            }
+        }
+    }
+}
+
+/// Drives the `[busylight]` from the meeting updates on the event bus
+pub struct BusyLight {
+    config: BusyLightConfig,
+    colors: [Rgb; 3],
+    /// Last color sent and when, whether or not it reached the light
+    sent: Mutex<Option<(Rgb, Instant)>>,
+    /// Last error reported, so an unplugged light is not reported every update
+    error: Mutex<Option<String>>,
+}
+
+impl BusyLight {
+    pub fn new(config: BusyLightConfig) -> Self {
+        // Checked when the configuration was loaded
+        let color = |color: &str| Rgb::parse(color).unwrap_or(Rgb::OFF);
+        let colors = [color(&config.busy_color), color(&config.soon_color), color(&config.free_color)];
+        Self { config, colors, sent: Mutex::new(None), error: Mutex::new(None) }
+    }
+
+    /// The color for this update
+    pub fn color(&self, update: &MeetingUpdate) -> Rgb {
+        if update.quiet && self.config.off_in_quiet_hours {
+            return Rgb::OFF;
+        }
+        let [busy, soon, free] = self.colors;
+        match LightState::of(update, chrono::Duration::minutes(self.config.soon_minutes.into())) {
+            LightState::Busy => busy,
+            LightState::Soon => soon,
+            LightState::Free => free,
+        }
+    }
+
+    /// Whether `color` has to be sent now
+    fn due(&self, color: Rgb) -> bool {
+        let mut sent = self.sent.lock().unwrap_or_else(|e| e.into_inner());
+        let due = sent.is_none_or(|(previous, at)| previous != color || at.elapsed() >= REFRESH_INTERVAL);
+        if due {
+            *sent = Some((color, Instant::now()));
+        }
+        due
+    }
+}
+
+#[async_trait]
+impl NotificationBackend for BusyLight {
+    fn name(&self) -> &str {
+        "Busy light"
+    }
+
+    async fn handle(&self, event: &Event) -> Result<()> {
+        let Event::Update(update) = event else { return Ok(()) };
+        let color = self.color(update);
+        if !self.due(color) {
+            return Ok(());
+        }
+
+        let device = self.config.device;
+        let result = tokio::task::spawn_blocking(move || set_color(device, color)).await?;
+        let mut error = self.error.lock().unwrap_or_else(|e| e.into_inner());
+        match result {
+            Ok(()) => {
+                if error.take().is_some() {
+                    tracing::info!("Busy light is back");
+                }
+                Ok(())
+            }
+            Err(e) if error.as_deref() == Some(e.to_string().as_str()) => Ok(()),
+            Err(e) => {
+                *error = Some(e.to_string());
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Send the color to the first light of this kind
+#[cfg(feature = "busylight")]
+fn set_color(device: BusyLightDevice, color: Rgb) -> Result<()> {
+    let api = hidapi::HidApi::new().map_err(|e| anyhow!("Failed to access USB HID devices: {}", e))?;
+    let light = api
+        .device_list()
+        .find(|info| device.matches(info.vendor_id(), info.product_id()))
+        .ok_or_else(|| anyhow!("No {:?} busy light found", device))?
+        .open_device(&api)
+        .map_err(|e| anyhow!("Failed to open the {:?} busy light: {}", device, e))?;
+    let report = device.report(color);
+    match device {
+        BusyLightDevice::Blinkstick => light.send_feature_report(&report),
+        _ => light.write(&report).map(|_| ()),
+    }
+    .map_err(|e| anyhow!("Failed to set the {:?} busy light: {}", device, e))
+}
+
+/// Stand-in used when built without the `busylight` feature;
+/// `Config::validate` rejects `[busylight]` before this is reached
+#[cfg(not(feature = "busylight"))]
+fn set_color(_device: BusyLightDevice, _color: Rgb) -> Result<()> {
+    Err(anyhow!("calendar-monitor was built without busy light support (enable the `busylight` feature)"))
+}
@@ -0,0 +1,93 @@
+use anyhow::{anyhow, Result};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Method;
+
+use crate::config::CaldavSourceConfig;
+use crate::recurrence::Window;
+
+const CALDAV_NAMESPACE: &str = "urn:ietf:params:xml:ns:caldav";
+
+/// Client for a single CalDAV calendar collection (Nextcloud, iCloud, Fastmail, ...)
+#[derive(Clone)]
+pub struct CaldavClient {
+    http_client: reqwest::Client,
+    source: CaldavSourceConfig,
+}
+
+impl CaldavClient {
+    pub fn new(http_client: reqwest::Client, source: CaldavSourceConfig) -> Self {
+        Self { http_client, source }
+    }
+
+    /// URL of the calendar collection
+    pub fn url(&self) -> &str {
+        &self.source.url
+    }
+
+    pub fn source(&self) -> &CaldavSourceConfig {
+        &self.source
+    }
+
+    /// Fetch the iCalendar data of every event overlapping the window with a
+    /// `calendar-query` REPORT. Each entry is a complete VCALENDAR object.
+    pub async fn fetch_calendar_data(&self, window: &Window) -> Result<Vec<String>> {
+        let report = Method::from_bytes(b"REPORT").expect("REPORT is a valid method");
+        let mut request = self.http_client
+            .request(report, &self.source.url)
+            .header("Depth", "1")
+            .header(CONTENT_TYPE, "application/xml; charset=utf-8")
+            .body(calendar_query(window));
+
+        if let Some(username) = &self.source.username {
+            request = request.basic_auth(username, self.source.password.as_deref());
+        }
+
+        let response = request.send().await
+            .map_err(|e| anyhow!("Failed to query CalDAV calendar {}: {}", self.source.url, e))?;
+
+        // A successful REPORT answers 207 Multi-Status
+        if !response.status().is_success() {
+            return Err(anyhow!("HTTP error {} when querying CalDAV calendar {}", response.status(), self.source.url));
+        }
+
+        let body = response.text().await
+            .map_err(|e| anyhow!("Failed to read CalDAV response from {}: {}", self.source.url, e))?;
+        parse_multistatus(&body)
+    }
+}
+
+/// Body of a `calendar-query` REPORT for events overlapping the window
+pub fn calendar_query(window: &Window) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop>
+    <d:getetag/>
+    <c:calendar-data/>
+  </d:prop>
+  <c:filter>
+    <c:comp-filter name="VCALENDAR">
+      <c:comp-filter name="VEVENT">
+        <c:time-range start="{}" end="{}"/>
+      </c:comp-filter>
+    </c:comp-filter>
+  </c:filter>
+</c:calendar-query>"#,
+        window.start().format("%Y%m%dT%H%M%SZ"),
+        window.end().format("%Y%m%dT%H%M%SZ"),
+    )
+}
+
+/// Extract the `calendar-data` of every resource in a multistatus response
+pub fn parse_multistatus(xml: &str) -> Result<Vec<String>> {
+    let document = roxmltree::Document::parse(xml)
+        .map_err(|e| anyhow!("Invalid CalDAV response: {}", e))?;
+
+    Ok(document
+        .descendants()
+        .filter(|node| node.has_tag_name((CALDAV_NAMESPACE, "calendar-data")))
+        .filter_map(|node| node.text())
+        .filter(|data| !data.trim().is_empty())
+        .map(str::to_string)
+        .collect())
+}
@@ -1,47 +1,214 @@
-use crate::meeting::Meeting;
+use crate::caldav::CaldavClient;
+use crate::config::{env_var, HttpConfig, IcsSourceConfig};
+use crate::filter::{TimeBlockMarker, TitleFilter};
+use crate::meeting::{highest_priority, Meeting, MeetingSource};
+use crate::recurrence::{self, RecurringEvent, Window};
 use anyhow::{anyhow, Result};
-use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
-use ical::parser::ical::component::IcalEvent;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use ical::parser::ical::component::{IcalCalendar, IcalEvent};
 use ical::IcalParser;
-use std::env;
-use std::path::Path;
-use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::SystemTime;
+use tokio::sync::RwLock;
+
+/// `error` with the path or URL of `source` replaced by its `name`; HTTP
+/// errors quote URLs in their parsed form, so that is replaced too
+fn mask_source(error: &str, source: &str, name: &str) -> String {
+    let error = error.replace(source, name);
+    match url::Url::parse(source) {
+        Ok(url) => error.replace(url.as_str(), name),
+        Err(_) => error,
+    }
+}
+
+/// Fetch health of a single calendar source
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SourceHealth {
+    pub source: String,
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<DateTime<Utc>>,
+    /// First failure since the last successful fetch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failing_since: Option<DateTime<Utc>>,
+    pub event_count: usize,
+}
+
+impl SourceHealth {
+    fn new(source: String) -> Self {
+        Self {
+            source,
+            last_success: None,
+            last_error: None,
+            last_error_at: None,
+            failing_since: None,
+            event_count: 0,
+        }
+    }
+
+    /// A source is healthy once it has been fetched and the latest fetch succeeded
+    pub fn is_healthy(&self) -> bool {
+        match (self.last_success, self.last_error_at) {
+            (Some(success), Some(error)) => success > error,
+            (Some(_), None) => true,
+            _ => false,
+        }
+    }
+}
+
+/// A source whose latest fetch failed, as shown on the display
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SourceError {
+    /// Name of the source, as attached to its meetings
+    pub source: String,
+    pub error: String,
+    /// When the source started failing
+    pub since: DateTime<Utc>,
+}
+
+impl SourceError {
+    /// A failed fetch of `source`, failing since `previous` if that one
+    /// failed too
+    pub fn continuing(previous: Option<&SourceError>, source: &str, error: impl ToString) -> Self {
+        Self {
+            source: source.to_string(),
+            error: error.to_string(),
+            since: previous.map_or_else(Utc::now, |previous| previous.since),
+        }
+    }
+}
+
+/// ICS sources that can be added or removed at runtime
+#[derive(Default)]
+struct IcsSources {
+    paths: Vec<String>,
+    /// Clients for sources with their own TLS settings, keyed by source path
+    clients: HashMap<String, reqwest::Client>,
+    /// Configured attribution for sources, keyed by source path
+    labels: HashMap<String, MeetingSource>,
+    /// Title include/exclude rules, keyed by ICS path or CalDAV URL
+    filters: HashMap<String, TitleFilter>,
+    /// Rules marking meetings of every source as time blocks
+    time_blocks: TimeBlockMarker,
+    /// `poll_interval_seconds` of the sources that have one, keyed by ICS
+    /// path or CalDAV URL
+    poll_intervals: HashMap<String, u64>,
+}
+
+/// A source's meetings from its last good fetch, reused until its poll
+/// interval has passed
+struct SourceFetch {
+    fetched_at: SystemTime,
+    window: Window,
+    meetings: Vec<Meeting>,
+}
+
+/// Last good fetch, persisted so the display survives a network outage at startup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeetingsSnapshot {
+    pub fetched_at: DateTime<Utc>,
+    pub meetings: Vec<Meeting>,
+}
 
 pub struct CalendarService {
-    ics_file_paths: Vec<String>,
-    cached_meetings: Arc<Mutex<Option<Vec<Meeting>>>>,
-    last_fetch_time: Arc<Mutex<Option<SystemTime>>>,
+    sources: Arc<RwLock<IcsSources>>,
+    cached_meetings: Arc<RwLock<Option<Vec<Meeting>>>>,
+    last_fetch_time: Arc<RwLock<Option<SystemTime>>>,
     cache_duration_secs: u64,
+    source_health: Arc<RwLock<Vec<SourceHealth>>>,
+    http_client: reqwest::Client,
+    /// Timeouts and proxy used when building clients for sources added at runtime
+    http_config: HttpConfig,
+    /// CalDAV calendars fetched alongside the ICS sources
+    caldav: Vec<CaldavClient>,
+    /// Where the last good fetch is persisted, if anywhere
+    snapshot_file: Option<PathBuf>,
+    snapshot: Arc<RwLock<Option<MeetingsSnapshot>>>,
+    /// Set while some source fails, to when every source last loaded
+    stale_since: Arc<RwLock<Option<DateTime<Utc>>>>,
+    /// Last good fetch of each source, keyed by ICS path or CalDAV URL
+    source_fetches: Arc<RwLock<HashMap<String, SourceFetch>>>,
+}
+
+fn ics_sources(paths: Vec<String>) -> Arc<RwLock<IcsSources>> {
+    Arc::new(RwLock::new(IcsSources {
+        paths,
+        ..IcsSources::default()
+    }))
+}
+
+fn initial_source_health(paths: &[String]) -> Arc<RwLock<Vec<SourceHealth>>> {
+    Arc::new(RwLock::new(
+        paths.iter().cloned().map(SourceHealth::new).collect(),
+    ))
+}
+
+/// Parse every calendar in ICS content, skipping the ones that fail
+fn parse_calendars<B: BufRead>(reader: B) -> Vec<IcalCalendar> {
+    IcalParser::new(reader)
+        .filter_map(|calendar| {
+            calendar
+                .map_err(|e| tracing::warn!("Error parsing ICS line: {}", e))
+                .ok()
+        })
+        .collect()
 }
 
 impl CalendarService {
     pub fn new() -> Self {
         Self {
-            ics_file_paths: Vec::new(),
-            cached_meetings: Arc::new(Mutex::new(None)),
-            last_fetch_time: Arc::new(Mutex::new(None)),
+            sources: ics_sources(Vec::new()),
+            cached_meetings: Arc::new(RwLock::new(None)),
+            last_fetch_time: Arc::new(RwLock::new(None)),
             cache_duration_secs: 300, // 5 minutes
+            source_health: initial_source_health(&[]),
+            http_client: reqwest::Client::new(),
+            http_config: HttpConfig::default(),
+            caldav: Vec::new(),
+            snapshot_file: None,
+            snapshot: Arc::new(RwLock::new(None)),
+            stale_since: Arc::new(RwLock::new(None)),
+            source_fetches: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
     /// Initialize with single ICS file path
     pub fn new_with_ics_file(file_path: String) -> Self {
         Self {
-            ics_file_paths: vec![file_path],
-            cached_meetings: Arc::new(Mutex::new(None)),
-            last_fetch_time: Arc::new(Mutex::new(None)),
+            source_health: initial_source_health(std::slice::from_ref(&file_path)),
+            sources: ics_sources(vec![file_path]),
+            cached_meetings: Arc::new(RwLock::new(None)),
+            last_fetch_time: Arc::new(RwLock::new(None)),
             cache_duration_secs: 300, // 5 minutes
+            http_client: reqwest::Client::new(),
+            http_config: HttpConfig::default(),
+            caldav: Vec::new(),
+            snapshot_file: None,
+            snapshot: Arc::new(RwLock::new(None)),
+            stale_since: Arc::new(RwLock::new(None)),
+            source_fetches: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
     /// Initialize with multiple ICS file paths
     pub fn new_with_ics_files(file_paths: Vec<String>) -> Self {
         Self {
-            ics_file_paths: file_paths,
-            cached_meetings: Arc::new(Mutex::new(None)),
-            last_fetch_time: Arc::new(Mutex::new(None)),
+            source_health: initial_source_health(&file_paths),
+            sources: ics_sources(file_paths),
+            cached_meetings: Arc::new(RwLock::new(None)),
+            last_fetch_time: Arc::new(RwLock::new(None)),
             cache_duration_secs: 300, // 5 minutes
+            http_client: reqwest::Client::new(),
+            http_config: HttpConfig::default(),
+            caldav: Vec::new(),
+            snapshot_file: None,
+            snapshot: Arc::new(RwLock::new(None)),
+            stale_since: Arc::new(RwLock::new(None)),
+            source_fetches: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -50,7 +217,7 @@ impl CalendarService {
         let mut ics_paths = Vec::new();
 
         // Support single file: ICS_FILE_PATH=./calendar.ics
-        if let Ok(single_path) = env::var("ICS_FILE_PATH") {
+        if let Some(single_path) = env_var("ICS_FILE_PATH") {
             tracing::info!("Found ICS_FILE_PATH: {}", single_path);
             ics_paths.push(single_path);
         } else {
@@ -58,7 +225,7 @@ impl CalendarService {
         }
 
         // Support multiple files: ICS_FILE_PATHS=./work.ics,./personal.ics,./project.ics
-        if let Ok(multiple_paths) = env::var("ICS_FILE_PATHS") {
+        if let Some(multiple_paths) = env_var("ICS_FILE_PATHS") {
             tracing::info!("Found ICS_FILE_PATHS: {}", multiple_paths);
             for path in multiple_paths.split(',') {
                 let trimmed_path = path.trim().to_string();
@@ -73,25 +240,33 @@ impl CalendarService {
         tracing::info!("Initialized CalendarService with {} ICS paths: {:?}", ics_paths.len(), ics_paths);
 
         Self {
-            ics_file_paths: ics_paths,
-            cached_meetings: Arc::new(Mutex::new(None)),
-            last_fetch_time: Arc::new(Mutex::new(None)),
+            source_health: initial_source_health(&ics_paths),
+            sources: ics_sources(ics_paths),
+            cached_meetings: Arc::new(RwLock::new(None)),
+            last_fetch_time: Arc::new(RwLock::new(None)),
             cache_duration_secs: 300, // 5 minutes cache
+            http_client: reqwest::Client::new(),
+            http_config: HttpConfig::default(),
+            caldav: Vec::new(),
+            snapshot_file: None,
+            snapshot: Arc::new(RwLock::new(None)),
+            stale_since: Arc::new(RwLock::new(None)),
+            source_fetches: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
-    /// Initialize from TOML configuration
-    pub fn new_from_config(config: &crate::config::Config) -> Self {
-        let mut ics_paths = config.ics.file_paths.clone();
+    /// Initialize from TOML configuration, downloading with the shared HTTP client
+    pub fn new_from_config(config: &crate::config::Config, http_client: reqwest::Client) -> Self {
+        let mut ics_paths = config.ics.all_paths();
 
         // If no paths in config, try environment variables as fallback
         if ics_paths.is_empty() {
-            if let Ok(single_path) = env::var("ICS_FILE_PATH") {
+            if let Some(single_path) = env_var("ICS_FILE_PATH") {
                 tracing::info!("Found ICS_FILE_PATH in environment: {}", single_path);
                 ics_paths.push(single_path);
             }
 
-            if let Ok(multiple_paths) = env::var("ICS_FILE_PATHS") {
+            if let Some(multiple_paths) = env_var("ICS_FILE_PATHS") {
                 tracing::info!("Found ICS_FILE_PATHS in environment: {}", multiple_paths);
                 for path in multiple_paths.split(',') {
                     let trimmed_path = path.trim().to_string();
@@ -104,11 +279,326 @@ impl CalendarService {
 
         tracing::info!("Initialized CalendarService from config with {} ICS paths: {:?}", ics_paths.len(), ics_paths);
 
+        let mut clients = HashMap::new();
+        for source in config.ics.sources.iter().filter(|s| s.has_tls_overrides()) {
+            match crate::http::build_source_client(&config.http, source) {
+                Ok(client) => {
+                    clients.insert(source.path.clone(), client);
+                }
+                Err(e) => {
+                    tracing::warn!("{}; using the shared HTTP client for {}", e, source.path);
+                }
+            }
+        }
+
+        let labels = config
+            .ics
+            .sources
+            .iter()
+            .map(|source| (source.path.clone(), source.meeting_source()))
+            .collect();
+
+        let mut filters = HashMap::new();
+        let ics_filters = config.ics.sources.iter().map(|source| (&source.path, source.title_filter()));
+        let caldav_filters = config.caldav.sources.iter().map(|source| (&source.url, source.title_filter()));
+        for (source, filter) in ics_filters.chain(caldav_filters) {
+            match filter {
+                Ok(filter) if filter.is_empty() => {}
+                Ok(filter) => {
+                    filters.insert(source.clone(), filter);
+                }
+                Err(e) => tracing::warn!("Ignoring title filter of {}: {}", source, e),
+            }
+        }
+
+        let ics_intervals = config.ics.sources.iter().map(|source| (&source.path, source.poll_interval_seconds));
+        let caldav_intervals = config.caldav.sources.iter().map(|source| (&source.url, source.poll_interval_seconds));
+        let poll_intervals = ics_intervals
+            .chain(caldav_intervals)
+            .filter_map(|(source, interval)| interval.map(|interval| (source.clone(), interval)))
+            .collect();
+
+        let time_blocks = config.time_blocks.marker().unwrap_or_else(|e| {
+            tracing::warn!("Ignoring [time_blocks] rules: {}", e);
+            TimeBlockMarker::default()
+        });
+
+        let caldav: Vec<CaldavClient> = config
+            .caldav
+            .sources
+            .iter()
+            .map(|source| CaldavClient::new(http_client.clone(), source.clone()))
+            .collect();
+        let health_sources: Vec<String> = ics_paths
+            .iter()
+            .cloned()
+            .chain(caldav.iter().map(|client| client.url().to_string()))
+            .collect();
+
         Self {
-            ics_file_paths: ics_paths,
-            cached_meetings: Arc::new(Mutex::new(None)),
-            last_fetch_time: Arc::new(Mutex::new(None)),
+            source_health: initial_source_health(&health_sources),
+            sources: Arc::new(RwLock::new(IcsSources {
+                paths: ics_paths,
+                clients,
+                labels,
+                filters,
+                time_blocks,
+                poll_intervals,
+            })),
+            cached_meetings: Arc::new(RwLock::new(None)),
+            last_fetch_time: Arc::new(RwLock::new(None)),
             cache_duration_secs: config.server.cache_ttl_seconds, // Use cache TTL from config
+            http_client,
+            http_config: config.http.clone(),
+            caldav,
+            snapshot_file: None,
+            snapshot: Arc::new(RwLock::new(None)),
+            stale_since: Arc::new(RwLock::new(None)),
+            source_fetches: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Attribution for meetings loaded from the given ICS path
+    async fn meeting_source(&self, path: &str) -> MeetingSource {
+        self.sources
+            .read()
+            .await
+            .labels
+            .get(path)
+            .cloned()
+            .unwrap_or_else(|| MeetingSource::from_ics_path(path))
+    }
+
+    /// Drop the meetings excluded by the title filter of an ICS path or CalDAV
+    /// URL, and mark the time blocks among the rest
+    async fn filter_meetings(&self, source: &str, meetings: Vec<Meeting>) -> Vec<Meeting> {
+        let sources = self.sources.read().await;
+        let meetings = match sources.filters.get(source) {
+            Some(filter) => filter.apply(meetings),
+            None => meetings,
+        };
+        sources.time_blocks.apply(meetings)
+    }
+
+    /// Persist every good fetch to `path`, and load the previous one from it
+    /// to fall back on when no source can be reached
+    pub fn with_snapshot_file(mut self, path: PathBuf) -> Self {
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => match serde_json::from_str::<MeetingsSnapshot>(&contents) {
+                Ok(mut snapshot) => {
+                    // Snapshots written before meetings had IDs
+                    for meeting in snapshot.meetings.iter_mut().filter(|m| m.id.is_empty()) {
+                        meeting.id = meeting.compute_id();
+                    }
+                    tracing::info!("Loaded {} meetings from snapshot {} (fetched {})",
+                        snapshot.meetings.len(), path.display(), snapshot.fetched_at);
+                    self.snapshot = Arc::new(RwLock::new(Some(snapshot)));
+                }
+                Err(e) => tracing::warn!("Ignoring unreadable snapshot {}: {}", path.display(), e),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                tracing::debug!("No meetings snapshot at {}", path.display());
+            }
+            Err(e) => tracing::warn!("Failed to read snapshot {}: {}", path.display(), e),
+        }
+        self.snapshot_file = Some(path);
+        self
+    }
+
+    /// While some source fails, when every source last loaded (the snapshot's fetch time)
+    pub async fn stale_since(&self) -> Option<DateTime<Utc>> {
+        *self.stale_since.read().await
+    }
+
+    /// Remember a good fetch in memory and on disk
+    async fn save_snapshot(&self, meetings: &[Meeting]) {
+        let snapshot = MeetingsSnapshot {
+            fetched_at: Utc::now(),
+            meetings: meetings.to_vec(),
+        };
+
+        if let Some(path) = &self.snapshot_file {
+            match serde_json::to_vec(&snapshot) {
+                Ok(json) => {
+                    if let Err(e) = tokio::fs::write(path, json).await {
+                        tracing::warn!("Failed to write snapshot {}: {}", path.display(), e);
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to serialize snapshot: {}", e),
+            }
+        }
+
+        *self.snapshot.write().await = Some(snapshot);
+    }
+
+    /// Get a snapshot of the fetch health of every configured ICS source
+    pub async fn source_health(&self) -> Vec<SourceHealth> {
+        self.source_health.read().await.clone()
+    }
+
+    /// `source_health` as shown to clients: each source is named like its
+    /// meetings rather than by path or URL, which may carry a password or be
+    /// a secret link, in `source` and in `last_error` alike
+    pub async fn named_source_health(&self) -> Vec<SourceHealth> {
+        let mut named = Vec::new();
+        for mut health in self.source_health().await {
+            let name = self.source_name(&health.source).await;
+            health.last_error = health.last_error.map(|error| mask_source(&error, &health.source, &name));
+            health.source = name;
+            named.push(health);
+        }
+        named
+    }
+
+    /// Sources whose latest fetch failed, named as in `named_source_health`
+    pub async fn source_errors(&self) -> Vec<SourceError> {
+        self.named_source_health()
+            .await
+            .into_iter()
+            .filter_map(|health| {
+                Some(SourceError {
+                    error: health.last_error?,
+                    since: health.failing_since?,
+                    source: health.source,
+                })
+            })
+            .collect()
+    }
+
+    /// Display name of an ICS path or CalDAV URL from `source_health`
+    pub async fn source_name(&self, source: &str) -> String {
+        match self.caldav.iter().find(|client| client.url() == source) {
+            Some(client) => client.source().meeting_source().name,
+            None => self.meeting_source(source).await.name,
+        }
+    }
+
+    /// Newest successful fetch of any source
+    pub async fn last_updated(&self) -> Option<DateTime<Utc>> {
+        self.source_health.read().await.iter().filter_map(|h| h.last_success).max()
+    }
+
+    /// Paths of all ICS sources currently being fetched
+    pub async fn source_paths(&self) -> Vec<String> {
+        self.sources.read().await.paths.clone()
+    }
+
+    /// Start fetching an additional ICS source; takes effect on the next refresh
+    pub async fn add_source(&self, source: &IcsSourceConfig) -> Result<()> {
+        let client = if source.has_tls_overrides() {
+            Some(crate::http::build_source_client(&self.http_config, source)?)
+        } else {
+            None
+        };
+        let filter = source.title_filter()?;
+
+        {
+            let mut sources = self.sources.write().await;
+            if sources.paths.contains(&source.path) {
+                return Err(anyhow!("ICS source already configured: {}", source.path));
+            }
+            sources.paths.push(source.path.clone());
+            if let Some(client) = client {
+                sources.clients.insert(source.path.clone(), client);
+            }
+            sources.labels.insert(source.path.clone(), source.meeting_source());
+            if !filter.is_empty() {
+                sources.filters.insert(source.path.clone(), filter);
+            }
+            if let Some(interval) = source.poll_interval_seconds {
+                sources.poll_intervals.insert(source.path.clone(), interval);
+            }
+        }
+
+        self.source_health.write().await.push(SourceHealth::new(source.path.clone()));
+        self.invalidate_cache().await;
+        tracing::info!("Added ICS source: {}", source.path);
+        Ok(())
+    }
+
+    /// Stop fetching an ICS source. Returns false if it was not configured.
+    pub async fn remove_source(&self, path: &str) -> bool {
+        {
+            let mut sources = self.sources.write().await;
+            let count_before = sources.paths.len();
+            sources.paths.retain(|p| p != path);
+            if sources.paths.len() == count_before {
+                return false;
+            }
+            sources.clients.remove(path);
+            sources.labels.remove(path);
+            sources.filters.remove(path);
+            sources.poll_intervals.remove(path);
+        }
+
+        self.source_health.write().await.retain(|h| h.source != path);
+        self.invalidate_cache().await;
+        tracing::info!("Removed ICS source: {}", path);
+        true
+    }
+
+    /// Force the next request to fetch fresh data
+    async fn invalidate_cache(&self) {
+        let mut last_fetch = self.last_fetch_time.write().await;
+        *last_fetch = None;
+        self.source_fetches.write().await.clear();
+    }
+
+    /// Seconds between fetches of an ICS path or CalDAV URL
+    async fn poll_interval(&self, source: &str) -> u64 {
+        self.sources.read().await.poll_intervals.get(source).copied().unwrap_or(self.cache_duration_secs)
+    }
+
+    /// How long the merged meetings stay valid: until the first source is due again
+    async fn refresh_interval(&self) -> u64 {
+        let sources = self.sources.read().await;
+        sources
+            .paths
+            .iter()
+            .map(String::as_str)
+            .chain(self.caldav.iter().map(CaldavClient::url))
+            .map(|source| sources.poll_intervals.get(source).copied().unwrap_or(self.cache_duration_secs))
+            .min()
+            .unwrap_or(self.cache_duration_secs)
+    }
+
+    /// Meetings of `source` fetched for `window` less than its poll interval before `now`
+    async fn recent_fetch(&self, source: &str, window: &Window, now: Option<SystemTime>) -> Option<Vec<Meeting>> {
+        let interval = self.poll_interval(source).await;
+        let fetches = self.source_fetches.read().await;
+        let fetch = fetches.get(source).filter(|fetch| fetch.window == *window)?;
+        let age = now?.duration_since(fetch.fetched_at).ok()?;
+        if age.as_secs() >= interval {
+            return None;
+        }
+        tracing::debug!("Reusing {} meetings of {} until its poll interval passes", fetch.meetings.len(), source);
+        Some(fetch.meetings.clone())
+    }
+
+    async fn remember_fetch(&self, source: &str, window: &Window, now: SystemTime, meetings: &[Meeting]) {
+        self.source_fetches.write().await.insert(
+            source.to_string(),
+            SourceFetch { fetched_at: now, window: *window, meetings: meetings.to_vec() },
+        );
+    }
+
+    /// Record the outcome of fetching a single source
+    async fn record_source_result(&self, source: &str, result: Result<usize, String>) {
+        let mut health = self.source_health.write().await;
+        if let Some(entry) = health.iter_mut().find(|h| h.source == source) {
+            let now = Utc::now();
+            match result {
+                Ok(event_count) => {
+                    entry.last_success = Some(now);
+                    entry.failing_since = None;
+                    entry.event_count = event_count;
+                }
+                Err(error) => {
+                    entry.last_error = Some(error);
+                    entry.last_error_at = Some(now);
+                    entry.failing_since.get_or_insert(now);
+                }
+            }
         }
     }
 
@@ -121,16 +611,15 @@ impl CalendarService {
             .filter(|m| !m.is_time_block())
             .collect();
         
-        let current_meeting = regular_meetings
-            .iter()
-            .find(|m| m.is_active())
-            .cloned()
+        let current_meeting = highest_priority(regular_meetings.iter().copied().filter(|m| m.is_active()))
             .cloned();
 
         let next_meeting = regular_meetings
             .iter()
             .find(|m| m.is_upcoming())
-            .cloned()
+            .and_then(|first| {
+                highest_priority(regular_meetings.iter().copied().filter(|m| m.is_upcoming() && m.start_time == first.start_time))
+            })
             .cloned();
             
 
@@ -159,14 +648,15 @@ impl CalendarService {
 
     /// Get all meetings for today and tomorrow
     pub async fn get_meetings_for_today_and_tomorrow(&self) -> Result<Vec<Meeting>> {
-        if !self.ics_file_paths.is_empty() {
+        if !self.sources.read().await.paths.is_empty() || !self.caldav.is_empty() {
             // Check if cache is still valid
             let now = SystemTime::now();
+            let refresh_interval = self.refresh_interval().await;
             let cache_valid = {
-                let last_fetch = self.last_fetch_time.lock().unwrap();
+                let last_fetch = self.last_fetch_time.read().await;
                 if let Some(last_time) = *last_fetch {
                     now.duration_since(last_time)
-                        .map(|d| d.as_secs() < self.cache_duration_secs)
+                        .map(|d| d.as_secs() < refresh_interval)
                         .unwrap_or(false)
                 } else {
                     false
@@ -175,7 +665,7 @@ impl CalendarService {
 
             if cache_valid {
                 // Return cached data
-                let cached = self.cached_meetings.lock().unwrap();
+                let cached = self.cached_meetings.read().await;
                 if let Some(meetings) = cached.as_ref() {
                     tracing::debug!("Returning cached meetings ({} items)", meetings.len());
                     return Ok(meetings.clone());
@@ -184,16 +674,34 @@ impl CalendarService {
 
             // Cache is expired or empty, fetch fresh data
             tracing::info!("Cache expired or empty, fetching fresh calendar data");
-            let fresh_meetings = self.parse_multiple_ics_files_extended().await?;
+            let (mut fresh_meetings, loaded_sources, failed_sources) = self.parse_multiple_ics_files_extended(&Window::today_and_tomorrow(), Some(now)).await?;
+
+            if failed_sources == 0 {
+                *self.stale_since.write().await = None;
+                self.save_snapshot(&fresh_meetings).await;
+            } else if loaded_sources > 0 {
+                // Some sources failed: show what loaded, but keep the snapshot of the
+                // last complete fetch and flag the data as stale since then
+                let fetched_at = self.snapshot.read().await.as_ref().map(|s| s.fetched_at);
+                let mut stale_since = self.stale_since.write().await;
+                if stale_since.is_none() {
+                    *stale_since = Some(fetched_at.unwrap_or_else(Utc::now));
+                }
+            } else if let Some(snapshot) = self.snapshot.read().await.as_ref() {
+                // Every source failed (e.g. offline at startup): show the last good fetch instead
+                tracing::warn!("All calendar sources failed, using snapshot from {}", snapshot.fetched_at);
+                *self.stale_since.write().await = Some(snapshot.fetched_at);
+                fresh_meetings = snapshot.meetings.clone();
+            }
             
             // Update cache with fresh data (even if empty)
             {
-                let mut cached = self.cached_meetings.lock().unwrap();
+                let mut cached = self.cached_meetings.write().await;
                 *cached = Some(fresh_meetings.clone());
                 tracing::info!("Updated cache with {} fresh meetings", fresh_meetings.len());
             }
             {
-                let mut last_fetch = self.last_fetch_time.lock().unwrap();
+                let mut last_fetch = self.last_fetch_time.write().await;
                 *last_fetch = Some(now);
             }
             
@@ -205,6 +713,21 @@ impl CalendarService {
         }
     }
 
+    /// Get all meetings starting within the window. Today and tomorrow come
+    /// from the cache; other days are read from the sources on every call.
+    pub async fn get_meetings_in_window(&self, window: &Window) -> Result<Vec<Meeting>> {
+        if *window == Window::today_and_tomorrow() {
+            return self.get_meetings_for_today_and_tomorrow().await;
+        }
+        if self.sources.read().await.paths.is_empty() && self.caldav.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        tracing::info!("Fetching calendar data for {} to {}", window.first_day, window.last_day);
+        let (meetings, _, _) = self.parse_multiple_ics_files_extended(window, None).await?;
+        Ok(meetings)
+    }
+
     /// Get all meetings for today
     pub async fn get_meetings_for_today(&self) -> Result<Vec<Meeting>> {
         // Get today and tomorrow meetings, then filter to today only
@@ -217,13 +740,35 @@ impl CalendarService {
             .collect())
     }
 
-    /// Parse multiple ICS files and merge all meetings (today and tomorrow)
-    async fn parse_multiple_ics_files_extended(&self) -> Result<Vec<Meeting>> {
+    /// Parse multiple ICS files and merge all meetings within the window.
+    /// Also returns how many sources loaded and how many failed. With `now`,
+    /// sources fetched less than their poll interval before it are not
+    /// fetched again, and the others are remembered as fetched at `now`.
+    async fn parse_multiple_ics_files_extended(&self, window: &Window, now: Option<SystemTime>) -> Result<(Vec<Meeting>, usize, usize)> {
         let mut all_meetings = Vec::new();
+        let mut loaded_sources = 0;
+        let mut failed_sources = 0;
         
-        for ics_path in &self.ics_file_paths {
-            match self.parse_ics_file_extended(ics_path).await {
+        let ics_paths = self.source_paths().await;
+        for ics_path in &ics_paths {
+            if let Some(meetings) = self.recent_fetch(ics_path, window, now).await {
+                loaded_sources += 1;
+                all_meetings.extend(meetings);
+                continue;
+            }
+            match self.parse_ics_file_extended(ics_path, window).await {
                 Ok(meetings) => {
+                    let meetings = self.filter_meetings(ics_path, meetings).await;
+                    self.record_source_result(ics_path, Ok(meetings.len())).await;
+                    loaded_sources += 1;
+                    let source = self.meeting_source(ics_path).await;
+                    let meetings: Vec<Meeting> = meetings
+                        .into_iter()
+                        .map(|m| m.with_source(source.clone()))
+                        .collect();
+                    if let Some(now) = now {
+                        self.remember_fetch(ics_path, window, now, &meetings).await;
+                    }
                     let _count_before = all_meetings.len();
                     all_meetings.extend(meetings.clone());
                     let count_after = all_meetings.len();
@@ -235,12 +780,41 @@ impl CalendarService {
                 }
                 Err(e) => {
                     tracing::warn!("Failed to parse ICS file '{}': {}", ics_path, e);
+                    self.record_source_result(ics_path, Err(e.to_string())).await;
+                    failed_sources += 1;
                     // Continue processing other files even if one fails
                     continue;
                 }
             }
         }
 
+        for client in &self.caldav {
+            if let Some(meetings) = self.recent_fetch(client.url(), window, now).await {
+                loaded_sources += 1;
+                all_meetings.extend(meetings);
+                continue;
+            }
+            match self.fetch_caldav_meetings(client, window).await {
+                Ok(meetings) => {
+                    let meetings = self.filter_meetings(client.url(), meetings).await;
+                    self.record_source_result(client.url(), Ok(meetings.len())).await;
+                    loaded_sources += 1;
+                    let source = client.source().meeting_source();
+                    tracing::info!("Loaded {} meetings from CalDAV calendar {}", meetings.len(), client.url());
+                    let meetings: Vec<Meeting> = meetings.into_iter().map(|m| m.with_source(source.clone())).collect();
+                    if let Some(now) = now {
+                        self.remember_fetch(client.url(), window, now, &meetings).await;
+                    }
+                    all_meetings.extend(meetings);
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to fetch CalDAV calendar '{}': {}", client.url(), e);
+                    self.record_source_result(client.url(), Err(e.to_string())).await;
+                    failed_sources += 1;
+                }
+            }
+        }
+
         tracing::info!("Before sort/dedup: {} meetings", all_meetings.len());
         
         // Sort all meetings by start time
@@ -248,19 +822,22 @@ impl CalendarService {
         
         // Duplicates will be removed by custom logic below
         
-        tracing::info!("After sort: {} meetings from {} ICS files", all_meetings.len(), self.ics_file_paths.len());
-        Ok(all_meetings)
+        tracing::info!("After sort: {} meetings from {} ICS files", all_meetings.len(), ics_paths.len());
+        Ok((all_meetings, loaded_sources, failed_sources))
     }
 
     /// Parse multiple ICS files and merge all meetings
     async fn parse_multiple_ics_files(&self) -> Result<Vec<Meeting>> {
         let mut all_meetings = Vec::new();
         
-        for ics_path in &self.ics_file_paths {
+        let ics_paths = self.source_paths().await;
+        for ics_path in &ics_paths {
             match self.parse_ics_file(ics_path).await {
-                Ok(mut meetings) => {
-                    all_meetings.append(&mut meetings);
+                Ok(meetings) => {
+                    let meetings = self.filter_meetings(ics_path, meetings).await;
+                    let source = self.meeting_source(ics_path).await;
                     tracing::info!("Loaded {} meetings from {}", meetings.len(), ics_path);
+                    all_meetings.extend(meetings.into_iter().map(|m| m.with_source(source.clone())));
                 }
                 Err(e) => {
                     tracing::warn!("Failed to parse ICS file '{}': {}", ics_path, e);
@@ -321,7 +898,7 @@ impl CalendarService {
             }
         }
         
-        tracing::info!("Total meetings loaded: {} from {} ICS files", all_meetings.len(), self.ics_file_paths.len());
+        tracing::info!("Total meetings loaded: {} from {} ICS files", all_meetings.len(), ics_paths.len());
         Ok(all_meetings)
     }
 
@@ -362,52 +939,55 @@ impl CalendarService {
         Ok(meetings)
     }
 
-    /// Parse ICS file and return meetings for today
-    async fn parse_ics_file(&self, file_path: &str) -> Result<Vec<Meeting>> {
-        let ics_content = if file_path.starts_with("http://") || file_path.starts_with("https://") {
+    /// Read and parse the calendars of a URL or local file without blocking the runtime
+    async fn read_ics_calendars(&self, file_path: &str) -> Result<Vec<IcalCalendar>> {
+        if file_path.starts_with("http://") || file_path.starts_with("https://") {
             // Download ICS from URL
             tracing::info!("Downloading ICS from URL: {}", file_path);
-            let response = reqwest::get(file_path).await
+            let client = self.sources.read().await.clients.get(file_path).cloned()
+                .unwrap_or_else(|| self.http_client.clone());
+            let response = client.get(file_path).send().await
                 .map_err(|e| anyhow!("Failed to download ICS from URL {}: {}", file_path, e))?;
             
             if !response.status().is_success() {
                 return Err(anyhow!("HTTP error {} when downloading ICS from {}", response.status(), file_path));
             }
             
-            response.text().await
-                .map_err(|e| anyhow!("Failed to read ICS content from {}: {}", file_path, e))?
+            let bytes = response.bytes().await
+                .map_err(|e| anyhow!("Failed to read ICS content from {}: {}", file_path, e))?;
+            Ok(parse_calendars(bytes.as_ref()))
         } else {
-            // Read local file
-            if !Path::new(file_path).exists() {
-                return Err(anyhow!("ICS file not found: {}", file_path));
-            }
-            
-            std::fs::read_to_string(file_path)
-                .map_err(|e| anyhow!("Failed to read ICS file: {}", e))?
-        };
+            // Stream the file into the parser on the blocking pool (local files may
+            // live on slow storage like NFS or SD cards)
+            let path = file_path.to_string();
+            tokio::task::spawn_blocking(move || match std::fs::File::open(&path) {
+                Ok(file) => Ok(parse_calendars(std::io::BufReader::new(file))),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    Err(anyhow!("ICS file not found: {}", path))
+                }
+                Err(e) => Err(anyhow!("Failed to read ICS file: {}", e)),
+            })
+            .await
+            .map_err(|e| anyhow!("Failed to read ICS file {}: {}", file_path, e))?
+        }
+    }
 
-        let reader = IcalParser::new(ics_content.as_bytes());
+    /// Parse ICS file and return meetings for today
+    async fn parse_ics_file(&self, file_path: &str) -> Result<Vec<Meeting>> {
+        let calendars = self.read_ics_calendars(file_path).await?;
 
         let mut meetings = Vec::new();
         let today = Utc::now().date_naive();
 
-        for line in reader {
-            match line {
-                Ok(calendar) => {
-                    for event in calendar.events {
-                        let event_meetings = self.convert_ical_event_to_meeting(event)?;
-                        for meeting in event_meetings {
-                            // Only include events for today
-                            if meeting.start_time.date_naive() == today {
-                                meetings.push(meeting);
-                            }
-                        }
+        for calendar in calendars {
+            for event in calendar.events {
+                let event_meetings = self.convert_ical_event_to_meeting(event)?;
+                for meeting in event_meetings {
+                    // Only include events for today
+                    if meeting.start_time.date_naive() == today {
+                        meetings.push(meeting);
                     }
                 }
-                Err(e) => {
-                    tracing::warn!("Error parsing ICS line: {}", e);
-                    continue;
-                }
             }
         }
 
@@ -417,54 +997,34 @@ impl CalendarService {
         Ok(meetings)
     }
 
-    /// Parse ICS file and return meetings for today and tomorrow
-    async fn parse_ics_file_extended(&self, file_path: &str) -> Result<Vec<Meeting>> {
-        let ics_content = if file_path.starts_with("http://") || file_path.starts_with("https://") {
-            // Download ICS from URL
-            tracing::info!("Downloading ICS from URL: {}", file_path);
-            let response = reqwest::get(file_path).await
-                .map_err(|e| anyhow!("Failed to download ICS from URL {}: {}", file_path, e))?;
-            
-            if !response.status().is_success() {
-                return Err(anyhow!("HTTP error {} when downloading ICS from {}", response.status(), file_path));
-            }
-            
-            response.text().await
-                .map_err(|e| anyhow!("Failed to read ICS content from {}: {}", file_path, e))?
-        } else {
-            // Read local file
-            if !Path::new(file_path).exists() {
-                return Err(anyhow!("ICS file not found: {}", file_path));
-            }
-            
-            std::fs::read_to_string(file_path)
-                .map_err(|e| anyhow!("Failed to read ICS file: {}", e))?
-        };
+    /// Parse ICS file and return meetings within the window
+    async fn parse_ics_file_extended(&self, file_path: &str, window: &Window) -> Result<Vec<Meeting>> {
+        let calendars = self.read_ics_calendars(file_path).await?;
+        self.meetings_in_window(calendars, window)
+    }
 
-        let reader = IcalParser::new(ics_content.as_bytes());
+    /// Query a CalDAV calendar and return meetings within the window
+    async fn fetch_caldav_meetings(&self, client: &CaldavClient, window: &Window) -> Result<Vec<Meeting>> {
+        let mut meetings = Vec::new();
+        for calendar_data in client.fetch_calendar_data(window).await? {
+            meetings.extend(self.meetings_in_window(parse_calendars(calendar_data.as_bytes()), window)?);
+        }
+        meetings.sort_by_key(|m| m.start_time);
+        Ok(meetings)
+    }
 
+    /// Return the meetings of parsed calendars starting within the window
+    fn meetings_in_window(&self, calendars: Vec<IcalCalendar>, window: &Window) -> Result<Vec<Meeting>> {
         let mut meetings = Vec::new();
-        let today = Utc::now().date_naive();
-        let tomorrow = today + Duration::days(1);
-
-        for line in reader {
-            match line {
-                Ok(calendar) => {
-                    for event in calendar.events {
-                        let event_meetings = self.convert_ical_event_to_meeting(event)?;
-                        for meeting in event_meetings {
-                            // Include events for today and tomorrow
-                            let meeting_date = meeting.start_time.date_naive();
-                            if meeting_date == today || meeting_date == tomorrow {
-                                meetings.push(meeting);
-                            }
-                        }
+
+        for calendar in calendars {
+            for event in calendar.events {
+                let event_meetings = self.convert_ical_event_in_window(event, window)?;
+                for meeting in event_meetings {
+                    if window.contains(meeting.start_time.date_naive()) {
+                        meetings.push(meeting);
                     }
                 }
-                Err(e) => {
-                    tracing::warn!("Error parsing ICS line: {}", e);
-                    continue;
-                }
             }
         }
 
@@ -476,6 +1036,11 @@ impl CalendarService {
 
     /// Convert ICS event to our Meeting struct, handling recurring events
     pub fn convert_ical_event_to_meeting(&self, event: IcalEvent) -> Result<Vec<Meeting>> {
+        self.convert_ical_event_in_window(event, &Window::today_and_tomorrow())
+    }
+
+    /// Convert ICS event to our Meeting struct, expanding recurring events into the window
+    fn convert_ical_event_in_window(&self, event: IcalEvent, window: &Window) -> Result<Vec<Meeting>> {
         let mut title = "Untitled Event".to_string();
         let mut start_time: Option<DateTime<Utc>> = None;
         let mut end_time: Option<DateTime<Utc>> = None;
@@ -483,6 +1048,8 @@ impl CalendarService {
         let mut location: Option<String> = None;
         let mut rrule: Option<String> = None;
         let mut user_response_status: Option<crate::meeting::ResponseStatus> = None;
+        let mut uid: Option<String> = None;
+        let mut categories: Vec<String> = Vec::new();
 
         // Parse event properties
         for property in event.properties {
@@ -492,6 +1059,11 @@ impl CalendarService {
                         title = value;
                     }
                 }
+                "UID" => {
+                    if let Some(value) = property.value {
+                        uid = Some(value);
+                    }
+                }
                 "DTSTART" => {
                     if let Some(value) = property.value {
                         start_time = self.parse_ical_datetime(&value)?;
@@ -524,6 +1096,13 @@ impl CalendarService {
                         location = Some(value);
                     }
                 }
+                "CATEGORIES" => {
+                    if let Some(value) = property.value {
+                        categories.extend(
+                            value.split(',').map(str::trim).filter(|c| !c.is_empty()).map(str::to_string),
+                        );
+                    }
+                }
                 "ATTENDEE" => {
                     // Parse ATTENDEE property to determine user's response status
                     if let Some(response_status) = self.parse_ical_attendee_status(&property) {
@@ -544,7 +1123,16 @@ impl CalendarService {
         if let (Some(start), Some(end)) = (start_time, end_time) {
             // Check if this is a recurring event
             if let Some(rrule_value) = rrule {
-                self.expand_recurring_event(title, start, end, &rrule_value, description, location, user_response_status)
+                let occurrences = self.expand_recurring_event(title, start, end, &rrule_value, description, location, user_response_status, window)?;
+                // Every occurrence shares the series UID (duplicates are matched by UID + start time)
+                Ok(occurrences
+                    .into_iter()
+                    .map(|m| match &uid {
+                        Some(uid) => m.with_uid(uid.clone()),
+                        None => m,
+                    })
+                    .map(|m| m.with_categories(categories.clone()))
+                    .collect())
             } else {
                 // Non-recurring event
                 let mut meeting = Meeting::new(title.clone(), start, end);
@@ -561,8 +1149,12 @@ impl CalendarService {
                 if let Some(ref status) = user_response_status {
                     meeting = meeting.with_response_status(status.clone());
                 }
-                
-                Ok(vec![meeting])
+
+                if let Some(uid) = uid {
+                    meeting = meeting.with_uid(uid);
+                }
+
+                Ok(vec![meeting.with_categories(categories)])
             }
         } else {
             tracing::debug!("Skipping event '{}' - missing start or end time", title);
@@ -570,7 +1162,8 @@ impl CalendarService {
         }
     }
 
-    /// Expand recurring events for today and tomorrow
+    /// Expand recurring events into the window
+    #[allow(clippy::too_many_arguments)]
     fn expand_recurring_event(
         &self,
         title: String,
@@ -580,83 +1173,20 @@ impl CalendarService {
         description: Option<String>,
         location: Option<String>,
         user_response_status: Option<crate::meeting::ResponseStatus>,
+        window: &Window,
     ) -> Result<Vec<Meeting>> {
-        let today = Utc::now().date_naive();
-        let tomorrow = today + chrono::Duration::days(1);
-        let mut meetings = Vec::new();
+        let event = RecurringEvent {
+            start,
+            end,
+            rrule: rrule.to_string(),
+        };
+        let occurrences = recurrence::expand(&event, window);
+        tracing::debug!("Expanded RRULE for '{}' ({}) into {} occurrences", title, rrule, occurrences.len());
 
-        // Parse RRULE (basic support for common patterns)
-        if rrule.contains("FREQ=WEEKLY") {
-            tracing::info!("Expanding RRULE for '{}': {}", title, rrule);
-            tracing::info!("Original time: {} - {} (weekday: {:?})", 
-                start.format("%Y-%m-%d %H:%M:%S UTC"), 
-                end.format("%Y-%m-%d %H:%M:%S UTC"), 
-                start.weekday());
-            
-            // Check for UNTIL clause and respect it
-            if let Some(until_date) = self.parse_rrule_until(rrule) {
-                tracing::info!("  UNTIL clause found: {}", until_date.format("%Y-%m-%d"));
-                if today > until_date || tomorrow > until_date {
-                    tracing::info!("  → Skipping recurring event - past UNTIL date");
-                    return Ok(meetings); // Return empty if past the UNTIL date
-                }
-            }
-                
-            let duration = end - start;
-            
-            // Get the day of week from the original start time
-            let original_weekday = start.weekday();
-            
-            // Check if event should occur today
-            if self.should_occur_on_day(rrule, today, original_weekday) {
-                let today_start = self.adjust_time_to_date(start, today);
-                let today_end = self.adjust_time_to_date(end, today);
-                
-                // Handle cross-midnight events: check if original event crosses midnight
-                // by comparing the day of the original start and end times
-                let original_crosses_midnight = start.date_naive() != end.date_naive();
-                let today_end = if original_crosses_midnight {
-                    // If original event crossed midnight, move end to next day
-                    today_end + chrono::Duration::days(1)
-                } else {
-                    today_end
-                };
-                
-                tracing::info!("  → Generating for TODAY: {} -> {} (duration: {})", 
-                    today_start.format("%Y-%m-%d %H:%M:%S UTC"), 
-                    today_end.format("%Y-%m-%d %H:%M:%S UTC"),
-                    duration.num_minutes());
-                
-                let mut meeting = Meeting::new(title.clone(), today_start, today_end);
-                if let Some(desc) = description.as_ref() {
-                    meeting = meeting.with_description(desc.clone());
-                }
-                if let Some(loc) = location.as_ref() {
-                    meeting = meeting.with_location(loc.clone());
-                }
-                if let Some(ref status) = user_response_status {
-                    meeting = meeting.with_response_status(status.clone());
-                }
-                meetings.push(meeting);
-                tracing::debug!("Generated recurring event for today: '{}' at {}", title, today_start);
-            }
-            
-            // Check if event should occur tomorrow
-            if self.should_occur_on_day(rrule, tomorrow, original_weekday) {
-                let tomorrow_start = self.adjust_time_to_date(start, tomorrow);
-                let tomorrow_end = self.adjust_time_to_date(end, tomorrow);
-                
-                // Handle cross-midnight events: check if original event crosses midnight
-                // by comparing the day of the original start and end times
-                let original_crosses_midnight = start.date_naive() != end.date_naive();
-                let tomorrow_end = if original_crosses_midnight {
-                    // If original event crossed midnight, move end to next day
-                    tomorrow_end + chrono::Duration::days(1)
-                } else {
-                    tomorrow_end
-                };
-                
-                let mut meeting = Meeting::new(title.clone(), tomorrow_start, tomorrow_end);
+        let meetings = occurrences
+            .into_iter()
+            .map(|occurrence| {
+                let mut meeting = Meeting::new(title.clone(), occurrence.start, occurrence.end);
                 if let Some(desc) = description.as_ref() {
                     meeting = meeting.with_description(desc.clone());
                 }
@@ -666,73 +1196,16 @@ impl CalendarService {
                 if let Some(ref status) = user_response_status {
                     meeting = meeting.with_response_status(status.clone());
                 }
-                meetings.push(meeting);
-                tracing::debug!("Generated recurring event for tomorrow: '{}' at {}", title, tomorrow_start);
-            }
-        } else {
-            tracing::debug!("Unsupported RRULE pattern: {}", rrule);
-        }
-        
+                meeting
+            })
+            .collect();
+
         Ok(meetings)
     }
 
     /// Parse UNTIL date from RRULE string
     pub fn parse_rrule_until(&self, rrule: &str) -> Option<chrono::NaiveDate> {
-        if let Some(start) = rrule.find("UNTIL=") {
-            let start = start + 6; // Skip "UNTIL="
-            let end = rrule[start..].find(';').map(|i| start + i).unwrap_or(rrule.len());
-            let until_str = &rrule[start..end];
-            
-            // Parse different UNTIL formats
-            // Format: 20250620T235959 or 20250620T235959Z
-            if let Some(date_part) = until_str.get(0..8) {
-                if let Ok(date) = chrono::NaiveDate::parse_from_str(date_part, "%Y%m%d") {
-                    tracing::debug!("Parsed UNTIL date: {} from '{}'", date.format("%Y-%m-%d"), until_str);
-                    return Some(date);
-                }
-            }
-            
-            tracing::warn!("Failed to parse UNTIL date: '{}'", until_str);
-        }
-        None
-    }
-
-    /// Check if a recurring event should occur on a given date
-    fn should_occur_on_day(&self, rrule: &str, date: chrono::NaiveDate, original_weekday: chrono::Weekday) -> bool {
-        let weekday = date.weekday();
-        
-        // Check for BYDAY restrictions
-        if rrule.contains("BYDAY=") {
-            let byday_pattern = if let Some(start) = rrule.find("BYDAY=") {
-                let start = start + 6; // Skip "BYDAY="
-                let end = rrule[start..].find(';').map(|i| start + i).unwrap_or(rrule.len());
-                &rrule[start..end]
-            } else {
-                return weekday == original_weekday; // Default to original weekday
-            };
-            
-            // Parse weekday codes (MO, TU, WE, TH, FR, SA, SU)
-            let weekday_code = match weekday {
-                chrono::Weekday::Mon => "MO",
-                chrono::Weekday::Tue => "TU",
-                chrono::Weekday::Wed => "WE",
-                chrono::Weekday::Thu => "TH",
-                chrono::Weekday::Fri => "FR",
-                chrono::Weekday::Sat => "SA",
-                chrono::Weekday::Sun => "SU",
-            };
-            
-            return byday_pattern.contains(weekday_code);
-        }
-        
-        // No BYDAY restriction, use original weekday
-        weekday == original_weekday
-    }
-
-    /// Adjust a DateTime to occur on a specific date, keeping the same time
-    fn adjust_time_to_date(&self, original_time: DateTime<Utc>, target_date: chrono::NaiveDate) -> DateTime<Utc> {
-        let time = original_time.time();
-        target_date.and_time(time).and_local_timezone(chrono::Utc).unwrap()
+        recurrence::parse_until(rrule)
     }
 
     /// Parse ATTENDEE property to determine response status
@@ -0,0 +1,144 @@
+use std::fmt;
+
+use crate::calendar::CalendarService;
+use crate::cli::Cli;
+use crate::config::Config;
+use crate::google_calendar::{GoogleCalendarService, GoogleTokens};
+
+/// Outcome of checking one part of the setup
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckResult {
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+impl CheckResult {
+    fn pass(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self { name: name.into(), passed: true, detail: detail.into() }
+    }
+
+    fn fail(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self { name: name.into(), passed: false, detail: detail.into() }
+    }
+}
+
+/// Everything `calendar-monitor check` looked at, printed one line per check
+#[derive(Debug, Clone, Default)]
+pub struct CheckReport {
+    pub results: Vec<CheckResult>,
+}
+
+impl CheckReport {
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(|result| result.passed)
+    }
+
+    /// 0 when every check passed, 1 otherwise
+    pub fn exit_code(&self) -> i32 {
+        if self.passed() { 0 } else { 1 }
+    }
+}
+
+impl fmt::Display for CheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for result in &self.results {
+            let status = if result.passed { "PASS" } else { "FAIL" };
+            writeln!(f, "{}  {}: {}", status, result.name, result.detail)?;
+        }
+        let passed = self.results.iter().filter(|result| result.passed).count();
+        writeln!(f, "{} of {} checks passed", passed, self.results.len())
+    }
+}
+
+/// Load and validate the configuration the way the server would with
+/// `--strict`, then try every calendar source once
+pub async fn run(cli: &Cli) -> CheckReport {
+    let cli = &Cli { strict: true, ..cli.clone() };
+    let config = match Config::load_with(cli) {
+        Ok(config) => config,
+        Err(e) => {
+            return CheckReport { results: vec![CheckResult::fail("config", e.to_string())] };
+        }
+    };
+    let config_detail = match cli.config.clone().or_else(Config::find_config_file) {
+        Some(path) => format!("{} is valid", path.display()),
+        None => "no config file, settings from the environment and flags are valid".to_string(),
+    };
+
+    let google_tokens = if config.google.service_account_key_file.is_some() {
+        None
+    } else {
+        config.load_google_tokens().unwrap_or_else(|e| {
+            tracing::warn!("Failed to load saved Google Calendar tokens: {}", e);
+            None
+        })
+    };
+
+    let mut report = check_sources(&config, google_tokens).await;
+    report.results.insert(0, CheckResult::pass("config", config_detail));
+    report
+}
+
+/// Fetch every ICS and CalDAV source for today and tomorrow, and Google
+/// Calendar when it is configured. Sources are named like their meetings,
+/// so private calendar URLs stay out of CI logs.
+pub async fn check_sources(config: &Config, google_tokens: Option<GoogleTokens>) -> CheckReport {
+    let mut results = Vec::new();
+
+    let http_client = crate::http::build_client(&config.http).unwrap_or_else(|e| {
+        tracing::warn!("{}; falling back to default HTTP client", e);
+        reqwest::Client::new()
+    });
+    let calendar_service = CalendarService::new_from_config(config, http_client);
+    if let Err(e) = calendar_service.get_meetings_for_today_and_tomorrow().await {
+        tracing::debug!("Calendar fetch failed: {}", e);
+    }
+    for health in calendar_service.source_health().await {
+        let name = calendar_service.source_name(&health.source).await;
+        results.push(if health.is_healthy() {
+            CheckResult::pass(&name, format!("{} events today and tomorrow", health.event_count))
+        } else {
+            let error = health.last_error.as_deref().unwrap_or("not fetched");
+            CheckResult::fail(&name, error.replace(&health.source, &name))
+        });
+    }
+
+    if let Some(result) = check_google(config, google_tokens).await {
+        results.push(result);
+    }
+
+    CheckReport { results }
+}
+
+/// Reach Google Calendar with the saved tokens or the service account;
+/// None when Google is not configured
+async fn check_google(config: &Config, google_tokens: Option<GoogleTokens>) -> Option<CheckResult> {
+    let mut google_service = match GoogleCalendarService::new_from_config(config) {
+        Ok(Some(google_service)) => google_service,
+        Ok(None) => return None,
+        Err(e) => return Some(CheckResult::fail("google", e.to_string())),
+    };
+    if let Ok(http_client) = crate::http::build_client(&config.http) {
+        google_service = google_service.with_http_client(http_client);
+    }
+    if let Ok(oauth_http_client) = crate::http::build_oauth_client(&config.http) {
+        google_service = google_service.with_oauth_http_client(oauth_http_client);
+    }
+
+    match google_tokens {
+        Some(tokens) => google_service.set_tokens(tokens),
+        None if google_service.uses_service_account() => {}
+        None => {
+            return Some(CheckResult::fail(
+                "google",
+                "not signed in, open /auth/google/login on the running server",
+            ));
+        }
+    }
+
+    Some(match google_service.fetch_events().await {
+        Ok(events) => CheckResult::pass("google", format!("{} events in the next day", events.len())),
+        Err(e) => CheckResult::fail("google", e.to_string()),
+    })
+}
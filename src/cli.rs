@@ -0,0 +1,62 @@
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+use tracing::level_filters::LevelFilter;
+
+use crate::config::Config;
+
+/// Command-line flags. Anything given here overrides both the config file
+/// and environment variables.
+#[derive(Debug, Clone, Default, Parser)]
+#[command(name = "calendar-monitor", version, about = "Shows your current and next meeting on a dedicated screen")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Config file to use instead of searching the default locations
+    #[arg(long, value_name = "PATH", global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Address to listen on
+    #[arg(long, global = true)]
+    pub host: Option<String>,
+
+    /// Port to listen on
+    #[arg(long, global = true)]
+    pub port: Option<u16>,
+
+    /// ICS file or URL to read, replacing the configured ones (repeat for several)
+    #[arg(long = "ics", value_name = "PATH_OR_URL", global = true)]
+    pub ics: Vec<String>,
+
+    /// Most verbose log level shown: off, error, warn, info, debug or trace
+    #[arg(long, value_name = "LEVEL", global = true)]
+    pub log_level: Option<LevelFilter>,
+
+    /// Refuse to start with a config file that has errors or unknown
+    /// settings, instead of logging them and carrying on
+    #[arg(long, global = true)]
+    pub strict: bool,
+}
+
+/// What to do instead of serving the display
+#[derive(Debug, Clone, PartialEq, Eq, Subcommand)]
+pub enum Command {
+    /// Validate the configuration, strictly, and try every calendar source
+    /// once, exiting non-zero if anything fails
+    Check,
+}
+
+impl Cli {
+    /// Override the loaded configuration with the flags that were given
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(host) = &self.host {
+            config.server.host = host.clone();
+        }
+        if let Some(port) = self.port {
+            config.server.port = port;
+        }
+        if !self.ics.is_empty() {
+            config.ics.file_paths = self.ics.clone();
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use chrono::{DateTime, Local, Utc};
+
+use crate::meeting::{Meeting, ResponseStatus};
+
+const HEADER: &str = "title,start,end,location,source,response_status";
+
+/// Quote a field when needed. Text starting like a formula is prefixed with
+/// an apostrophe so spreadsheets don't evaluate it.
+fn field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn local_time(time: DateTime<Utc>) -> String {
+    time.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string()
+}
+
+fn response_status(status: &ResponseStatus) -> &'static str {
+    match status {
+        ResponseStatus::Accepted => "accepted",
+        ResponseStatus::Declined => "declined",
+        ResponseStatus::Tentative => "tentative",
+        ResponseStatus::NoResponse => "no_response",
+    }
+}
+
+/// One row per meeting, times in the server's local time zone
+pub fn to_csv(meetings: &[Meeting]) -> String {
+    let mut csv = format!("{}\r\n", HEADER);
+    for meeting in meetings {
+        let row = [
+            field(&meeting.title),
+            local_time(meeting.start_time),
+            local_time(meeting.end_time),
+            field(meeting.location.as_deref().unwrap_or_default()),
+            field(meeting.source.as_ref().map(|s| s.name.as_str()).unwrap_or_default()),
+            meeting.response_status.as_ref().map(response_status).unwrap_or_default().to_string(),
+        ];
+        csv.push_str(&row.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
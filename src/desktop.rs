@@ -0,0 +1,83 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Duration;
+
+use crate::config::DesktopConfig;
+use crate::notify::{render_template, MeetingEvent, Notifier};
+
+/// Native desktop notifications (as libnotify shows them) of meetings about
+/// to start, for a monitor running on the user's own machine
+pub struct DesktopNotifier {
+    config: DesktopConfig,
+    clock_24h: bool,
+}
+
+impl DesktopNotifier {
+    pub fn new(config: DesktopConfig, clock_24h: bool) -> Self {
+        Self { config, clock_24h }
+    }
+
+    /// Summary and body of the notification for `event`, if it is one to show
+    pub fn notification(&self, event: &MeetingEvent) -> Option<(String, String)> {
+        match event {
+            MeetingEvent::Reminder(meeting) => Some((
+                render_template(&self.config.summary, meeting, self.clock_24h),
+                render_template(&self.config.body, meeting, self.clock_24h),
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for DesktopNotifier {
+    fn name(&self) -> &str {
+        "Desktop"
+    }
+
+    fn remind_before(&self) -> Option<Duration> {
+        Some(Duration::minutes(self.config.remind_minutes_before.into()))
+    }
+
+    async fn notify(&self, event: &MeetingEvent) -> Result<()> {
+        if let Some((summary, body)) = self.notification(event) {
+            show(&summary, &body).await?;
+        }
+        Ok(())
+    }
+}
+
+/// `org.freedesktop.Notifications.Notify` on the session bus
+#[cfg(feature = "desktop-notifications")]
+async fn show(summary: &str, body: &str) -> Result<()> {
+    use anyhow::anyhow;
+    use std::collections::HashMap;
+    use zbus::zvariant::Value;
+
+    let connection = zbus::Connection::session()
+        .await
+        .map_err(|e| anyhow!("Failed to connect to the session D-Bus: {}", e))?;
+    // Critical urgency keeps the notification up until it is dismissed
+    let hints = HashMap::from([("urgency", Value::U8(2))]);
+    let actions: Vec<&str> = Vec::new();
+    connection
+        .call_method(
+            Some("org.freedesktop.Notifications"),
+            "/org/freedesktop/Notifications",
+            Some("org.freedesktop.Notifications"),
+            "Notify",
+            &("Calendar Monitor", 0u32, "x-office-calendar", summary, body, actions, hints, -1i32),
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to show desktop notification: {}", e))?;
+    Ok(())
+}
+
+/// Stand-in used when built without the `desktop-notifications` feature;
+/// `Config::validate` rejects `[notifications.desktop]` before this is reached
+#[cfg(not(feature = "desktop-notifications"))]
+async fn show(_summary: &str, _body: &str) -> Result<()> {
+    Err(anyhow::anyhow!(
+        "calendar-monitor was built without desktop notification support (enable the `desktop-notifications` feature)"
+    ))
+}
@@ -0,0 +1,98 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::NaiveTime;
+use serde_json::json;
+use std::sync::Mutex;
+
+use crate::config::DiscordConfig;
+use crate::notify::{render_template, MeetingEvent, Notifier};
+
+/// Longest message content Discord accepts
+const MAX_CONTENT_CHARS: usize = 2000;
+
+/// Posts meeting starts and the day's agenda to a Discord webhook
+pub struct DiscordWebhook {
+    http_client: reqwest::Client,
+    config: DiscordConfig,
+    agenda_time: Option<NaiveTime>,
+    clock_24h: bool,
+    /// Meeting whose start was posted last, so one that is extended while
+    /// on (and so starts again) is not announced twice
+    announced: Mutex<Option<String>>,
+}
+
+impl DiscordWebhook {
+    pub fn new(http_client: reqwest::Client, config: DiscordConfig, clock_24h: bool) -> Self {
+        // Checked when the configuration was loaded
+        let agenda_time = config.agenda_time().ok().flatten();
+        Self { http_client, config, agenda_time, clock_24h, announced: Mutex::new(None) }
+    }
+
+    /// The message for `event`, if it is one to post
+    pub fn message(&self, event: &MeetingEvent) -> Option<String> {
+        match event {
+            MeetingEvent::Started(meeting) if !self.config.start_message.is_empty() => {
+                Some(render_template(&self.config.start_message, meeting, self.clock_24h))
+            }
+            MeetingEvent::Agenda(meetings) if !meetings.is_empty() => {
+                let lines = meetings
+                    .iter()
+                    .map(|meeting| render_template(&self.config.agenda_line, meeting, self.clock_24h));
+                Some(std::iter::once(self.config.agenda_header.clone()).chain(lines).collect::<Vec<_>>().join("\n"))
+            }
+            _ => None,
+        }
+    }
+
+    async fn post(&self, content: &str) -> Result<()> {
+        let content: String = content.chars().take(MAX_CONTENT_CHARS).collect();
+        let mut body = json!({
+            "content": content,
+            // Titles like "@everyone sync" must not ping anybody
+            "allowed_mentions": { "parse": [] },
+        });
+        if let Some(username) = &self.config.username {
+            body["username"] = json!(username);
+        }
+        let url = self.config.webhook_url.as_deref().unwrap_or_default();
+        let response = self
+            .http_client
+            .post(url)
+            .json(&body)
+            .send()
+            .await
+            // The URL is the webhook's credential
+            .map_err(|e| anyhow!("Discord webhook request failed: {}", e.without_url()))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(anyhow!("Discord webhook returned HTTP {}: {}", status, text));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Notifier for DiscordWebhook {
+    fn name(&self) -> &str {
+        "Discord"
+    }
+
+    fn agenda_time(&self) -> Option<NaiveTime> {
+        self.agenda_time
+    }
+
+    async fn notify(&self, event: &MeetingEvent) -> Result<()> {
+        if let MeetingEvent::Started(meeting) = event {
+            let mut announced = self.announced.lock().unwrap_or_else(|e| e.into_inner());
+            if announced.as_deref() == Some(meeting.id.as_str()) {
+                return Ok(());
+            }
+            *announced = Some(meeting.id.clone());
+        }
+        if let Some(content) = self.message(event) {
+            self.post(&content).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local, Utc};
+use embedded_graphics::mono_font::iso_8859_1::FONT_10X20;
+use embedded_graphics::mono_font::MonoTextStyle;
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::text::{Baseline, Text};
+use std::convert::Infallible;
+
+use crate::app::MeetingUpdate;
+
+/// Size of a FONT_10X20 glyph before scaling
+const GLYPH_WIDTH: u32 = 10;
+const GLYPH_HEIGHT: u32 = 20;
+
+/// A black and white image; `true` pixels are black
+pub struct Framebuffer {
+    width: u32,
+    height: u32,
+    pixels: Vec<bool>,
+}
+
+impl Framebuffer {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![false; (width * height) as usize],
+        }
+    }
+
+    pub fn is_black(&self, x: u32, y: u32) -> bool {
+        x < self.width && y < self.height && self.pixels[(y * self.width + x) as usize]
+    }
+
+    fn set(&mut self, x: i32, y: i32, black: bool) {
+        if x >= 0 && y >= 0 && (x as u32) < self.width && (y as u32) < self.height {
+            self.pixels[(y as u32 * self.width + x as u32) as usize] = black;
+        }
+    }
+
+    fn fill_rect(&mut self, x: i32, y: i32, width: u32, height: u32) {
+        for dy in 0..height as i32 {
+            for dx in 0..width as i32 {
+                self.set(x + dx, y + dy, true);
+            }
+        }
+    }
+
+    /// Draw a line of text with its top left corner at (x, y), each font
+    /// pixel drawn as a `scale` x `scale` block
+    fn text(&mut self, text: &str, x: i32, y: i32, scale: u32) {
+        let mut target = Scaled { framebuffer: self, origin: Point::new(x, y), scale };
+        let style = MonoTextStyle::new(&FONT_10X20, BinaryColor::On);
+        // Drawing into the framebuffer can't fail
+        let _ = Text::with_baseline(text, Point::zero(), style, Baseline::Top).draw(&mut target);
+    }
+
+    /// Encode as a 1-bit grayscale PNG, the format e-ink frames understand best
+    pub fn to_png(&self) -> Result<Vec<u8>> {
+        let row_bytes = self.width.div_ceil(8) as usize;
+        let mut data = vec![0u8; row_bytes * self.height as usize];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                // In 1-bit grayscale a set bit is white
+                if !self.is_black(x, y) {
+                    data[y as usize * row_bytes + x as usize / 8] |= 0x80 >> (x % 8);
+                }
+            }
+        }
+
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, self.width, self.height);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::One);
+        let mut writer = encoder.write_header()
+            .map_err(|e| anyhow!("Failed to encode PNG: {}", e))?;
+        writer.write_image_data(&data)
+            .map_err(|e| anyhow!("Failed to encode PNG: {}", e))?;
+        writer.finish()
+            .map_err(|e| anyhow!("Failed to encode PNG: {}", e))?;
+        Ok(png)
+    }
+}
+
+/// Draws into the framebuffer at an offset, enlarging every pixel
+struct Scaled<'a> {
+    framebuffer: &'a mut Framebuffer,
+    origin: Point,
+    scale: u32,
+}
+
+impl DrawTarget for Scaled<'_> {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if color.is_on() {
+                let x = self.origin.x + point.x * self.scale as i32;
+                let y = self.origin.y + point.y * self.scale as i32;
+                self.framebuffer.fill_rect(x, y, self.scale, self.scale);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl OriginDimensions for Scaled<'_> {
+    fn size(&self) -> Size {
+        Size::new(self.framebuffer.width / self.scale, self.framebuffer.height / self.scale)
+    }
+}
+
+/// Shorten text to at most `max_chars` characters
+fn fit(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max_chars.saturating_sub(3)).collect();
+    format!("{}...", kept.trim_end())
+}
+
+fn local_time(time: DateTime<Utc>) -> String {
+    time.with_timezone(&Local).format("%H:%M").to_string()
+}
+
+/// "12:34" or "1:05:00"
+fn countdown(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}", minutes, seconds)
+    }
+}
+
+/// Draw the current and next meeting with the countdown, laid out for the
+/// given resolution (designed at 800x480, scaled to fit others)
+pub fn render(update: &MeetingUpdate, width: u32, height: u32) -> Framebuffer {
+    let mut frame = Framebuffer::new(width, height);
+    let unit = (width / 400).min(height / 240).max(1);
+    let (small, large) = (unit, 2 * unit);
+    let margin = 10 * unit as i32;
+    let columns = |scale: u32| (width.saturating_sub(2 * margin as u32) / (GLYPH_WIDTH * scale)) as usize;
+    let line = |scale: u32| (GLYPH_HEIGHT * scale) as i32;
+
+    let mut y = margin;
+    match &update.current_meeting {
+        Some(meeting) => {
+            frame.text("NOW", margin, y, small);
+            y += line(small) + 3 * unit as i32;
+            frame.text(&fit(&meeting.title, columns(large)), margin, y, large);
+            y += line(large) + 4 * unit as i32;
+            let remaining = update.countdown_seconds.unwrap_or_else(|| meeting.time_until_end());
+            let ends = format!("{} left, until {}", countdown(remaining), local_time(meeting.end_time));
+            frame.text(&fit(&ends, columns(large)), margin, y, large);
+        }
+        None => {
+            frame.text("NOW", margin, y, small);
+            y += line(small) + 3 * unit as i32;
+            frame.text("Free", margin, y, large);
+            y += line(large) + 4 * unit as i32;
+            if let Some(holiday) = &update.holiday {
+                frame.text(&fit(holiday, columns(large)), margin, y, large);
+            } else if let Some(starts_in) = update.next_meeting_starts_in_seconds {
+                let next = format!("Next meeting in {}", countdown(starts_in));
+                frame.text(&fit(&next, columns(large)), margin, y, large);
+            }
+        }
+    }
+    y += line(large) + 5 * unit as i32;
+
+    frame.fill_rect(margin, y, width.saturating_sub(2 * margin as u32), unit);
+    y += 5 * unit as i32;
+
+    frame.text("NEXT", margin, y, small);
+    y += line(small) + 3 * unit as i32;
+    let next = match &update.next_meeting {
+        Some(meeting) => format!("{}  {}", local_time(meeting.start_time), meeting.title),
+        None => "Nothing else scheduled".to_string(),
+    };
+    frame.text(&fit(&next, columns(small)), margin, y, small);
+
+    let footer = format!("Updated {}", Local::now().format("%H:%M"));
+    frame.text(&footer, margin, height as i32 - margin - line(small), small);
+
+    frame
+}
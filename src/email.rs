@@ -0,0 +1,104 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{Duration, Local, NaiveTime};
+use lettre::message::header::ContentType;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use crate::config::{EmailConfig, SmtpSecurity};
+use crate::meeting::Meeting;
+use crate::notify::{render_template, MeetingEvent, Notifier};
+
+/// Mails the day's agenda and reminders of meetings about to start
+pub struct EmailNotifier {
+    config: EmailConfig,
+    agenda_time: Option<NaiveTime>,
+    clock_24h: bool,
+}
+
+impl EmailNotifier {
+    pub fn new(config: EmailConfig, clock_24h: bool) -> Self {
+        // Checked when the configuration was loaded
+        let agenda_time = config.agenda_time().ok().flatten();
+        Self { config, agenda_time, clock_24h }
+    }
+
+    /// Subject and body of the mail for `event`, if it is one to send
+    pub fn mail(&self, event: &MeetingEvent) -> Option<(String, String)> {
+        match event {
+            MeetingEvent::Agenda(meetings) if !meetings.is_empty() => {
+                let body = meetings.iter().map(|meeting| self.describe(meeting)).collect::<Vec<_>>().join("\n");
+                Some((self.config.agenda_subject.clone(), body + "\n"))
+            }
+            MeetingEvent::Reminder(meeting) => Some((
+                render_template(&self.config.reminder_subject, meeting, self.clock_24h),
+                self.describe(meeting) + "\n",
+            )),
+            _ => None,
+        }
+    }
+
+    /// A meeting's time and title, followed by where it is
+    fn describe(&self, meeting: &Meeting) -> String {
+        let mut lines = vec![render_template("{start} - {end}  {title}", meeting, self.clock_24h)];
+        if let Some(location) = meeting.location.as_deref().filter(|location| !location.is_empty()) {
+            lines.push(format!("    {}", location));
+        }
+        lines.join("\n")
+    }
+
+    async fn send(&self, subject: &str, body: String) -> Result<()> {
+        let mut message = Message::builder()
+            .from(self.config.from.parse::<Mailbox>()?)
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN)
+            .date(Local::now().into());
+        for to in &self.config.to {
+            message = message.to(to.parse::<Mailbox>()?);
+        }
+        let message = message.body(body).map_err(|e| anyhow!("Failed to build mail: {}", e))?;
+
+        self.transport()?
+            .send(message)
+            .await
+            .map_err(|e| anyhow!("Failed to send mail through {}: {}", self.config.host, e))?;
+        Ok(())
+    }
+
+    fn transport(&self) -> Result<AsyncSmtpTransport<Tokio1Executor>> {
+        let host = self.config.host.as_str();
+        let builder = match self.config.security {
+            SmtpSecurity::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?,
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host)?,
+            SmtpSecurity::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+        };
+        let mut builder = builder.port(self.config.port());
+        if let (Some(username), Some(password)) = (&self.config.username, &self.config.password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+        Ok(builder.build())
+    }
+}
+
+#[async_trait]
+impl Notifier for EmailNotifier {
+    fn name(&self) -> &str {
+        "Email"
+    }
+
+    fn agenda_time(&self) -> Option<NaiveTime> {
+        self.agenda_time
+    }
+
+    fn remind_before(&self) -> Option<Duration> {
+        (self.config.remind_minutes_before > 0).then(|| Duration::minutes(self.config.remind_minutes_before.into()))
+    }
+
+    async fn notify(&self, event: &MeetingEvent) -> Result<()> {
+        if let Some((subject, body)) = self.mail(event) {
+            self.send(&subject, body).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,153 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+use crate::app::MeetingUpdate;
+use crate::busylight::BusyLight;
+use crate::calendar::SourceError;
+use crate::config::Config;
+use crate::gpio::GpioPins;
+use crate::hue::HueLights;
+use crate::notify::MeetingEvent;
+
+/// Events kept for a subscriber that is still busy with an earlier one
+const EVENT_BACKLOG: usize = 64;
+
+/// Something that happened in the server, published to every subscriber
+#[derive(Debug, Clone)]
+pub enum Event {
+    /// A new meeting update was built, every `update_interval_seconds`
+    Update(Arc<MeetingUpdate>),
+    /// The current meeting started or ended; `quiet` during quiet hours
+    Meeting { event: Box<MeetingEvent>, quiet: bool },
+    /// A calendar source started failing
+    SourceFailed { source: String, error: String },
+    /// A failing calendar source was fetched again
+    SourceRecovered { source: String },
+    /// The configuration was reloaded
+    ConfigChanged { loaded_at: DateTime<Utc> },
+}
+
+impl Event {
+    /// Short name used in logs
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Event::Update(_) => "update",
+            Event::Meeting { .. } => "meeting",
+            Event::SourceFailed { .. } => "source failure",
+            Event::SourceRecovered { .. } => "source recovery",
+            Event::ConfigChanged { .. } => "config change",
+        }
+    }
+}
+
+/// A subscriber of the event bus, such as a chat integration or a webhook.
+/// Each backend handles events in its own task, so a slow one does not hold
+/// up the others.
+#[async_trait]
+pub trait NotificationBackend: Send + Sync {
+    /// Short name used in logs
+    fn name(&self) -> &str;
+
+    /// React to an event; events a backend has no use for are ignored
+    async fn handle(&self, event: &Event) -> Result<()>;
+}
+
+/// Publishes server events to the subscribed backends
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<Arc<Event>>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self { sender: broadcast::channel(EVENT_BACKLOG).0 }
+    }
+}
+
+impl EventBus {
+    /// Send the event to every subscriber; without any it is dropped
+    pub fn publish(&self, event: Event) {
+        let _ = self.sender.send(Arc::new(event));
+    }
+
+    pub fn has_subscribers(&self) -> bool {
+        self.sender.receiver_count() > 0
+    }
+
+    /// Hand every event published from now on to the backend, until the
+    /// returned subscription is dropped. Failures are logged.
+    pub fn subscribe(&self, backend: Arc<dyn NotificationBackend>) -> Subscription {
+        let mut receiver = self.sender.subscribe();
+        Subscription(tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => {
+                        if let Err(e) = backend.handle(&event).await {
+                            tracing::warn!("{} failed to handle {}: {}", backend.name(), event.kind(), e);
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(missed)) => {
+                        tracing::warn!("{} fell behind and missed {} events", backend.name(), missed);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        }))
+    }
+}
+
+/// The configured backends that subscribe to the bus themselves, rather than
+/// as `[notifications]` notifiers
+pub fn backends_from_config(config: &Config, http_client: &reqwest::Client) -> Vec<Arc<dyn NotificationBackend>> {
+    let mut backends: Vec<Arc<dyn NotificationBackend>> = Vec::new();
+    if let Some(busylight) = &config.busylight {
+        backends.push(Arc::new(BusyLight::new(busylight.clone())));
+    }
+    if let Some(hue) = &config.hue {
+        backends.push(Arc::new(HueLights::new(http_client.clone(), hue.clone())));
+    }
+    if let Some(gpio) = &config.gpio {
+        backends.push(Arc::new(GpioPins::new(gpio.clone())));
+    }
+    backends
+}
+
+/// A backend's place on the bus; dropping it unsubscribes the backend
+pub struct Subscription(JoinHandle<()>);
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Turns the failing sources of successive updates into failure and recovery events
+#[derive(Debug, Default)]
+pub struct SourceWatch {
+    failing: BTreeSet<String>,
+}
+
+impl SourceWatch {
+    /// The events since the previous call. A source still failing is only
+    /// reported again once it has recovered in between.
+    pub fn observe(&mut self, errors: &[SourceError]) -> Vec<Event> {
+        let mut events: Vec<Event> = self
+            .failing
+            .iter()
+            .filter(|source| errors.iter().all(|error| &&error.source != source))
+            .map(|source| Event::SourceRecovered { source: source.clone() })
+            .collect();
+        for error in errors {
+            if !self.failing.contains(&error.source) {
+                events.push(Event::SourceFailed { source: error.source.clone(), error: error.error.clone() });
+            }
+        }
+        self.failing = errors.iter().map(|error| error.source.clone()).collect();
+        events
+    }
+}
@@ -0,0 +1,99 @@
+use anyhow::{anyhow, Result};
+use regex::Regex;
+
+use crate::meeting::{EventType, Meeting};
+
+/// Regex rules deciding which meetings of a source are kept, by title
+#[derive(Debug, Clone, Default)]
+pub struct TitleFilter {
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+}
+
+impl TitleFilter {
+    /// With `include` patterns, only titles matching one of them are kept;
+    /// titles matching any `exclude` pattern are always dropped
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self> {
+        Ok(Self {
+            include: compile("include", include)?,
+            exclude: compile("exclude", exclude)?,
+        })
+    }
+
+    /// Whether this filter keeps every meeting
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    pub fn matches(&self, title: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|re| re.is_match(title)))
+            && !self.exclude.iter().any(|re| re.is_match(title))
+    }
+
+    /// Drop the meetings this filter does not keep
+    pub fn apply(&self, meetings: Vec<Meeting>) -> Vec<Meeting> {
+        if self.is_empty() {
+            return meetings;
+        }
+        let count_before = meetings.len();
+        let kept: Vec<Meeting> = meetings.into_iter().filter(|m| self.matches(&m.title)).collect();
+        tracing::debug!("Title filter kept {} of {} meetings", kept.len(), count_before);
+        kept
+    }
+}
+
+fn compile(kind: &str, patterns: &[String]) -> Result<Vec<Regex>> {
+    patterns
+        .iter()
+        .map(|pattern| Regex::new(pattern).map_err(|e| anyhow!("Invalid {} pattern '{}': {}", kind, pattern, e)))
+        .collect()
+}
+
+/// Rules marking meetings as time blocks besides "[Bracketed]" titles
+#[derive(Debug, Clone, Default)]
+pub struct TimeBlockMarker {
+    prefix: Option<String>,
+    pattern: Option<Regex>,
+    category: Option<String>,
+}
+
+impl TimeBlockMarker {
+    /// Titles starting with `prefix` or matching `pattern`, and ICS events
+    /// in `category` (case-insensitive), become time blocks
+    pub fn new(prefix: Option<&str>, pattern: Option<&str>, category: Option<&str>) -> Result<Self> {
+        let pattern = pattern
+            .map(|pattern| {
+                Regex::new(pattern).map_err(|e| anyhow!("Invalid time block pattern '{}': {}", pattern, e))
+            })
+            .transpose()?;
+        Ok(Self {
+            prefix: prefix.filter(|prefix| !prefix.is_empty()).map(str::to_string),
+            pattern,
+            category: category.filter(|category| !category.is_empty()).map(str::to_string),
+        })
+    }
+
+    /// Whether only bracketed titles are time blocks
+    pub fn is_empty(&self) -> bool {
+        self.prefix.is_none() && self.pattern.is_none() && self.category.is_none()
+    }
+
+    pub fn matches(&self, meeting: &Meeting) -> bool {
+        self.prefix.as_ref().is_some_and(|prefix| meeting.title.starts_with(prefix.as_str()))
+            || self.pattern.as_ref().is_some_and(|re| re.is_match(&meeting.title))
+            || self.category.as_ref().is_some_and(|category| {
+                meeting.categories.iter().any(|c| c.eq_ignore_ascii_case(category))
+            })
+    }
+
+    /// Mark the matching meetings as time blocks
+    pub fn apply(&self, meetings: Vec<Meeting>) -> Vec<Meeting> {
+        if self.is_empty() {
+            return meetings;
+        }
+        meetings
+            .into_iter()
+            .map(|m| if !m.is_time_block() && self.matches(&m) { m.with_event_type(EventType::TimeBlock) } else { m })
+            .collect()
+    }
+}
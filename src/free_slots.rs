@@ -0,0 +1,100 @@
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::WorkingHoursConfig;
+use crate::meeting::Meeting;
+
+/// A gap between meetings within working hours
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FreeSlot {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl FreeSlot {
+    pub fn duration_minutes(&self) -> i64 {
+        (self.end - self.start).num_minutes()
+    }
+}
+
+/// Parse a duration like "30m", "1h" or "1h30m"; a bare number is minutes
+pub fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(minutes) = value.parse::<i64>() {
+        return (minutes > 0).then(|| Duration::minutes(minutes));
+    }
+
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    for c in value.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'h' | 'm' if !number.is_empty() => {
+                let amount: i64 = number.parse().ok()?;
+                total += if c == 'h' { Duration::hours(amount) } else { Duration::minutes(amount) };
+                number.clear();
+            }
+            _ => return None,
+        }
+    }
+    (number.is_empty() && total > Duration::zero()).then_some(total)
+}
+
+/// The local time of day on a date, as UTC
+fn local_time(date: NaiveDate, time: NaiveTime) -> Option<DateTime<Utc>> {
+    Local
+        .from_local_datetime(&date.and_time(time))
+        .earliest()
+        .map(|local| local.with_timezone(&Utc))
+}
+
+/// The first `limit` gaps of at least `duration` after `from`, within the
+/// working hours of the next `days` days.
+///
+/// Every timed meeting (time blocks included) counts as busy; all-day events
+/// don't, so a deadline or holiday marker doesn't hide a whole day.
+pub fn find_free_slots(
+    meetings: &[Meeting],
+    from: DateTime<Utc>,
+    working_hours: &WorkingHoursConfig,
+    duration: Duration,
+    days: i64,
+    limit: usize,
+) -> Vec<FreeSlot> {
+    let Ok((day_start, day_end)) = working_hours.hours() else {
+        return Vec::new();
+    };
+    let mut busy: Vec<&Meeting> = meetings.iter().filter(|m| !m.all_day).collect();
+    busy.sort_by_key(|m| m.start_time);
+
+    let mut slots = Vec::new();
+    let first_day = from.with_timezone(&Local).date_naive();
+    for date in first_day.iter_days().take(days.max(0) as usize) {
+        if !working_hours.days.contains(&date.weekday()) {
+            continue;
+        }
+        let (Some(start), Some(end)) = (local_time(date, day_start), local_time(date, day_end)) else {
+            continue;
+        };
+
+        let mut cursor = start.max(from);
+        for meeting in &busy {
+            if meeting.end_time <= cursor || meeting.start_time >= end {
+                continue;
+            }
+            if meeting.start_time - cursor >= duration {
+                slots.push(FreeSlot { start: cursor, end: meeting.start_time });
+            }
+            cursor = cursor.max(meeting.end_time);
+        }
+        if end - cursor >= duration {
+            slots.push(FreeSlot { start: cursor, end });
+        }
+
+        if slots.len() >= limit {
+            slots.truncate(limit);
+            break;
+        }
+    }
+    slots
+}
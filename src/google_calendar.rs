@@ -1,24 +1,113 @@
 use anyhow::{anyhow, Result};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use oauth2::{
-    AuthUrl, AuthorizationCode, ClientId, ClientSecret, CsrfToken, RedirectUrl, RefreshToken, Scope,
-    TokenResponse, TokenUrl,
+    AuthUrl, AuthorizationCode, ClientId, ClientSecret, CsrfToken, DeviceAuthorizationUrl,
+    HttpRequest, HttpResponse, PkceCodeChallenge, PkceCodeVerifier, RedirectUrl, RefreshToken,
+    Scope, TokenResponse, TokenUrl,
 };
 use oauth2::basic::BasicClient;
-use oauth2::reqwest::async_http_client;
+use oauth2::devicecode::StandardDeviceAuthorizationResponse;
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
 
 use url::Url;
 
-use crate::meeting::Meeting;
+use crate::meeting::{EventType, Meeting, MeetingSource};
+use crate::filter::{TimeBlockMarker, TitleFilter};
+
+const CALENDAR_SCOPE: &str = "https://www.googleapis.com/auth/calendar.readonly";
+const JWT_BEARER_GRANT: &str = "urn:ietf:params:oauth:grant-type:jwt-bearer";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GoogleOAuthConfig {
     pub client_id: String,
+    /// Empty for public clients, which rely on PKCE alone
     pub client_secret: String,
     pub redirect_uri: String,
 }
 
+/// Google OAuth and Calendar API endpoints.
+///
+/// Defaults to Google's production endpoints; overridable so the service can be
+/// pointed at a stub server in integration tests.
+#[derive(Debug, Clone)]
+pub struct GoogleEndpoints {
+    pub auth_url: String,
+    pub token_url: String,
+    pub device_auth_url: String,
+    pub revoke_url: String,
+    pub api_base_url: String,
+}
+
+impl Default for GoogleEndpoints {
+    fn default() -> Self {
+        Self {
+            auth_url: "https://accounts.google.com/o/oauth2/auth".to_string(),
+            token_url: "https://oauth2.googleapis.com/token".to_string(),
+            device_auth_url: "https://oauth2.googleapis.com/device/code".to_string(),
+            revoke_url: "https://oauth2.googleapis.com/revoke".to_string(),
+            api_base_url: "https://www.googleapis.com".to_string(),
+        }
+    }
+}
+
+/// Service account key file, as downloaded from the Google Cloud Console
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServiceAccountKey {
+    pub client_email: String,
+    pub private_key: String,
+    #[serde(default)]
+    pub private_key_id: Option<String>,
+    #[serde(default = "default_token_uri")]
+    pub token_uri: String,
+}
+
+fn default_token_uri() -> String {
+    GoogleEndpoints::default().token_url
+}
+
+impl ServiceAccountKey {
+    /// Read a service account JSON key file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read service account key {}: {}", path.display(), e))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| anyhow!("Invalid service account key {}: {}", path.display(), e))
+    }
+}
+
+/// Service account credentials used instead of the interactive OAuth flow
+#[derive(Clone)]
+struct ServiceAccount {
+    client_email: String,
+    private_key_id: Option<String>,
+    encoding_key: EncodingKey,
+    token_url: String,
+    /// Workspace user to act as through domain-wide delegation
+    subject: Option<String>,
+}
+
+/// Claims of the signed JWT exchanged for an access token
+#[derive(Debug, Serialize)]
+struct ServiceAccountClaims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: i64,
+    exp: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sub: Option<&'a str>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ServiceAccountTokenResponse {
+    access_token: String,
+    expires_in: Option<i64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GoogleTokens {
     pub access_token: String,
@@ -26,15 +115,30 @@ pub struct GoogleTokens {
     pub expires_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GoogleCalendarEvent {
     pub id: String,
+    /// iCalendar UID, identical to the `UID` of the same event in an ICS export
+    #[serde(rename = "iCalUID", default)]
+    pub ical_uid: Option<String>,
     pub summary: Option<String>,
     pub start: Option<GoogleEventTime>,
     pub end: Option<GoogleEventTime>,
     pub description: Option<String>,
     pub location: Option<String>,
     pub attendees: Option<Vec<GoogleEventAttendee>>,
+    /// Key into the `event` palette of the colors definition; unset means the calendar's color
+    #[serde(rename = "colorId", default)]
+    pub color_id: Option<String>,
+    /// "confirmed", "tentative" or "cancelled" (e.g. a removed instance of a recurring event)
+    #[serde(default)]
+    pub status: Option<String>,
+    /// Series this event is an instance of, for expanded recurring events
+    #[serde(rename = "recurringEventId", default)]
+    pub recurring_event_id: Option<String>,
+    /// "default", "outOfOffice", "focusTime", "workingLocation", ...
+    #[serde(rename = "eventType", default)]
+    pub event_type: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,50 +164,274 @@ pub struct GoogleEventTime {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GoogleCalendarResponse {
     pub items: Option<Vec<GoogleCalendarEvent>>,
+    #[serde(rename = "nextPageToken")]
+    pub next_page_token: Option<String>,
+}
+
+/// A palette entry of the colors definition (`/calendar/v3/colors`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoogleColorDefinition {
+    pub background: String,
+    pub foreground: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoogleColorsResponse {
+    /// Event colors keyed by `colorId`
+    #[serde(default)]
+    pub event: HashMap<String, GoogleColorDefinition>,
+}
+
+/// One entry of the user's calendar list (`users/me/calendarList`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoogleCalendarListEntry {
+    pub id: String,
+    pub summary: Option<String>,
+    /// The name the user gave the calendar, if they renamed it
+    #[serde(rename = "summaryOverride")]
+    pub summary_override: Option<String>,
+    pub description: Option<String>,
+    #[serde(rename = "backgroundColor")]
+    pub background_color: Option<String>,
+    pub primary: Option<bool>,
+    #[serde(rename = "accessRole")]
+    pub access_role: Option<String>, // "owner", "writer", "reader", "freeBusyReader"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoogleCalendarListResponse {
+    pub items: Option<Vec<GoogleCalendarListEntry>>,
+    #[serde(rename = "nextPageToken")]
+    pub next_page_token: Option<String>,
 }
 
+/// How rate-limited Calendar API requests (429, or 403 `rateLimitExceeded`)
+/// are retried: exponential backoff starting at `base_delay`, unless Google
+/// sends a `Retry-After`
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Longest wait between two attempts, whatever Google asks for
+    const MAX_DELAY: Duration = Duration::from_secs(30);
+
+    fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        retry_after
+            .unwrap_or_else(|| self.base_delay.saturating_mul(2u32.saturating_pow(attempt)))
+            .min(Self::MAX_DELAY)
+    }
+}
+
+#[derive(Clone)]
 pub struct GoogleCalendarService {
     client: BasicClient,
     tokens: Option<GoogleTokens>,
     http_client: reqwest::Client,
+    oauth_http_client: reqwest::Client,
+    api_base_url: String,
+    revoke_url: String,
+    /// Calendar IDs to fetch, each with the attribution for its meetings
+    calendars: Vec<(String, MeetingSource)>,
+    /// Title include/exclude rules, keyed by calendar ID
+    title_filters: HashMap<String, TitleFilter>,
+    /// Rules marking meetings of every calendar as time blocks
+    time_blocks: TimeBlockMarker,
+    service_account: Option<ServiceAccount>,
+    /// Event `colorId` -> background color, fetched once per service
+    event_colors: Option<HashMap<String, String>>,
+    retry_policy: RetryPolicy,
+}
+
+/// Send an OAuth token request through the given client.
+///
+/// Equivalent to `oauth2::reqwest::async_http_client`, but lets token requests
+/// share the configured timeouts and proxy instead of a bare default client.
+async fn oauth_http_request(
+    client: reqwest::Client,
+    request: HttpRequest,
+) -> std::result::Result<HttpResponse, oauth2::reqwest::Error<reqwest::Error>> {
+    let mut request_builder = client
+        .request(request.method, request.url.as_str())
+        .body(request.body);
+    for (name, value) in &request.headers {
+        request_builder = request_builder.header(name.as_str(), value.as_bytes());
+    }
+
+    let response = request_builder.send().await.map_err(oauth2::reqwest::Error::Reqwest)?;
+    let status_code = response.status();
+    let headers = response.headers().to_owned();
+    let body = response.bytes().await.map_err(oauth2::reqwest::Error::Reqwest)?;
+
+    Ok(HttpResponse {
+        status_code,
+        headers,
+        body: body.to_vec(),
+    })
 }
 
 impl GoogleCalendarService {
     /// Create a new Google Calendar service using OAuth
     pub fn new(config: GoogleOAuthConfig) -> Result<Self> {
+        Self::new_with_endpoints(config, GoogleEndpoints::default())
+    }
+
+    /// Create a new Google Calendar service using OAuth against custom endpoints
+    pub fn new_with_endpoints(config: GoogleOAuthConfig, endpoints: GoogleEndpoints) -> Result<Self> {
+        let client_secret = Some(config.client_secret)
+            .filter(|secret| !secret.is_empty())
+            .map(ClientSecret::new);
         let client = BasicClient::new(
             ClientId::new(config.client_id),
-            Some(ClientSecret::new(config.client_secret)),
-            AuthUrl::new("https://accounts.google.com/o/oauth2/auth".to_string())?,
-            Some(TokenUrl::new("https://oauth2.googleapis.com/token".to_string())?),
+            client_secret,
+            AuthUrl::new(endpoints.auth_url.clone())?,
+            Some(TokenUrl::new(endpoints.token_url.clone())?),
         )
-        .set_redirect_uri(RedirectUrl::new(config.redirect_uri)?);
-
-        let http_client = reqwest::Client::new();
+        .set_redirect_uri(RedirectUrl::new(config.redirect_uri)?)
+        .set_device_authorization_url(DeviceAuthorizationUrl::new(endpoints.device_auth_url.clone())?);
 
+        let service = Self::with_client(client, endpoints)?;
         tracing::info!("Successfully initialized Google Calendar OAuth client");
+        Ok(service)
+    }
+
+    /// Create a service that authenticates as a service account, for displays
+    /// nobody can sign in on. With `subject` set, it acts as that Workspace user
+    /// through domain-wide delegation; otherwise it sees calendars shared with
+    /// the service account itself.
+    pub fn new_with_service_account(
+        key: ServiceAccountKey,
+        subject: Option<String>,
+        endpoints: GoogleEndpoints,
+    ) -> Result<Self> {
+        let encoding_key = EncodingKey::from_rsa_pem(key.private_key.as_bytes())
+            .map_err(|e| anyhow!("Invalid service account private key: {}", e))?;
+
+        // Only used for its endpoints; tokens come from signed JWTs, not user consent
+        let client = BasicClient::new(
+            ClientId::new(key.client_email.clone()),
+            None,
+            AuthUrl::new(endpoints.auth_url.clone())?,
+            Some(TokenUrl::new(endpoints.token_url.clone())?),
+        );
+
+        let token_url = endpoints.token_url.clone();
+        let mut service = Self::with_client(client, endpoints)?;
+        service.service_account = Some(ServiceAccount {
+            client_email: key.client_email,
+            private_key_id: key.private_key_id,
+            encoding_key,
+            token_url,
+            subject,
+        });
+
+        tracing::info!("Successfully initialized Google Calendar service account client");
+        Ok(service)
+    }
+
+    fn with_client(client: BasicClient, endpoints: GoogleEndpoints) -> Result<Self> {
+        let http_client = reqwest::Client::new();
+        let oauth_http_client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .map_err(|e| anyhow!("Failed to build OAuth HTTP client: {}", e))?;
 
         Ok(Self {
             client,
             tokens: None,
             http_client,
+            oauth_http_client,
+            api_base_url: endpoints.api_base_url.trim_end_matches('/').to_string(),
+            revoke_url: endpoints.revoke_url,
+            calendars: vec![("primary".to_string(), MeetingSource::google())],
+            title_filters: HashMap::new(),
+            time_blocks: TimeBlockMarker::default(),
+            service_account: None,
+            event_colors: None,
+            retry_policy: RetryPolicy::default(),
         })
     }
 
-    /// Create OAuth authorization URL
-    pub fn get_auth_url(&self) -> (Url, CsrfToken) {
-        self.client
+    /// Fetch these calendars (ID and meeting attribution) instead of just the primary one
+    pub fn with_calendars(mut self, calendars: Vec<(String, MeetingSource)>) -> Self {
+        self.calendars = calendars;
+        self
+    }
+
+    /// Only keep the meetings of a calendar its filter matches
+    pub fn with_title_filters(mut self, title_filters: HashMap<String, TitleFilter>) -> Self {
+        self.title_filters = title_filters;
+        self
+    }
+
+    /// Mark the meetings matched by the `[time_blocks]` rules as time blocks
+    pub fn with_time_blocks(mut self, time_blocks: TimeBlockMarker) -> Self {
+        self.time_blocks = time_blocks;
+        self
+    }
+
+    /// Drop the events excluded by the calendar's title filter, and mark the
+    /// time blocks among the rest
+    fn filter_events(&self, calendar_id: &str, events: Vec<Meeting>) -> Vec<Meeting> {
+        let events = match self.title_filters.get(calendar_id) {
+            Some(filter) => filter.apply(events),
+            None => events,
+        };
+        self.time_blocks.apply(events)
+    }
+
+    /// Use a shared HTTP client for Calendar API requests
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Retry rate-limited Calendar API requests with this policy
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Use a shared (non-redirecting) HTTP client for OAuth token requests
+    pub fn with_oauth_http_client(mut self, oauth_http_client: reqwest::Client) -> Self {
+        self.oauth_http_client = oauth_http_client;
+        self
+    }
+
+    /// Create OAuth authorization URL with a PKCE (S256) challenge.
+    ///
+    /// Keep the returned state and verifier until the callback: the caller must
+    /// check the callback's `state` against the CSRF token before passing the
+    /// verifier to `exchange_code`.
+    pub fn get_auth_url(&self) -> (Url, CsrfToken, PkceCodeVerifier) {
+        let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
+        let (url, csrf_token) = self.client
             .authorize_url(CsrfToken::new_random)
-            .add_scope(Scope::new("https://www.googleapis.com/auth/calendar.readonly".to_string()))
-            .url()
+            .add_scope(Scope::new(CALENDAR_SCOPE.to_string()))
+            .set_pkce_challenge(pkce_challenge)
+            .url();
+        (url, csrf_token, pkce_verifier)
     }
 
-    /// Exchange authorization code for access token
-    pub async fn exchange_code(&mut self, code: String, _csrf_token: CsrfToken) -> Result<()> {
+    /// Exchange authorization code for access token, proving possession of the
+    /// PKCE verifier from `get_auth_url`
+    pub async fn exchange_code(&mut self, code: String, pkce_verifier: PkceCodeVerifier) -> Result<()> {
         let token_result = self
             .client
             .exchange_code(AuthorizationCode::new(code))
-            .request_async(async_http_client)
+            .set_pkce_verifier(pkce_verifier)
+            .request_async(|request| oauth_http_request(self.oauth_http_client.clone(), request))
             .await
             .map_err(|e| anyhow!("OAuth token exchange failed: {}", e))?;
 
@@ -121,6 +449,49 @@ impl GoogleCalendarService {
         Ok(())
     }
 
+    /// Start the device authorization grant (RFC 8628). Show the returned user
+    /// code and verification URL, then call `complete_device_authorization`.
+    pub async fn start_device_authorization(&self) -> Result<StandardDeviceAuthorizationResponse> {
+        let details: StandardDeviceAuthorizationResponse = self
+            .client
+            .exchange_device_code()
+            .map_err(|e| anyhow!("Device authorization is not configured: {}", e))?
+            .add_scope(Scope::new(CALENDAR_SCOPE.to_string()))
+            .request_async(|request| oauth_http_request(self.oauth_http_client.clone(), request))
+            .await
+            .map_err(|e| anyhow!("Device authorization request failed: {}", e))?;
+
+        tracing::info!("Started Google device authorization, code expires in {} seconds", details.expires_in().as_secs());
+        Ok(details)
+    }
+
+    /// Poll until the user approves (or denies) the device authorization, then keep the tokens
+    pub async fn complete_device_authorization(&mut self, details: &StandardDeviceAuthorizationResponse) -> Result<()> {
+        let token_result = self
+            .client
+            .exchange_device_access_token(details)
+            .request_async(
+                |request| oauth_http_request(self.oauth_http_client.clone(), request),
+                tokio::time::sleep,
+                None,
+            )
+            .await
+            .map_err(|e| anyhow!("Device authorization failed: {}", e))?;
+
+        let expires_at = token_result.expires_in().map(|duration| {
+            Utc::now() + chrono::Duration::seconds(duration.as_secs() as i64)
+        });
+
+        self.tokens = Some(GoogleTokens {
+            access_token: token_result.access_token().secret().clone(),
+            refresh_token: token_result.refresh_token().map(|rt| rt.secret().clone()),
+            expires_at,
+        });
+
+        tracing::info!("Google device authorization completed");
+        Ok(())
+    }
+
     /// Check if we have valid tokens
     pub fn is_authenticated(&self) -> bool {
         if let Some(tokens) = &self.tokens {
@@ -148,40 +519,85 @@ impl GoogleCalendarService {
         self.tokens.clone()
     }
 
+    /// Forget the current tokens (e.g. after disconnecting)
+    pub fn clear_tokens(&mut self) {
+        self.tokens = None;
+    }
+
+    /// Revoke the user's grant at Google. Revoking the refresh token also
+    /// invalidates the access tokens issued from it.
+    pub async fn revoke_tokens(&self, tokens: &GoogleTokens) -> Result<()> {
+        let token = tokens.refresh_token.as_ref().unwrap_or(&tokens.access_token);
+
+        let response = self.oauth_http_client
+            .post(&self.revoke_url)
+            .form(&[("token", token)])
+            .send()
+            .await
+            .map_err(|e| anyhow!("Token revocation failed: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(anyhow!("Token revocation failed with {}: {}", status, text));
+        }
+
+        tracing::info!("Revoked Google Calendar access");
+        Ok(())
+    }
+
+    /// Whether this service authenticates as a service account
+    pub fn uses_service_account(&self) -> bool {
+        self.service_account.is_some()
+    }
+
+    /// Whether the access token expires within the next 5 minutes
+    pub fn needs_refresh(&self) -> bool {
+        match self.tokens.as_ref() {
+            // Service accounts can fetch their first token on their own
+            None => self.uses_service_account(),
+            Some(tokens) => match tokens.expires_at {
+                Some(expires_at) => expires_at <= Utc::now() + chrono::Duration::minutes(5),
+                None => false, // No expiration info: assume still valid
+            },
+        }
+    }
+
+    /// Whether a new access token can be obtained without the user
+    fn can_refresh(&self) -> bool {
+        self.uses_service_account()
+            || self.tokens.as_ref().is_some_and(|tokens| tokens.refresh_token.is_some())
+    }
+
     /// Refresh expired access token using refresh token
     pub async fn refresh_token_if_needed(&mut self) -> Result<bool> {
-        let needs_refresh = if let Some(tokens) = &self.tokens {
-            if let Some(expires_at) = tokens.expires_at {
-                let now = Utc::now();
-                // Refresh if expires within 5 minutes
-                expires_at <= now + chrono::Duration::minutes(5)
-            } else {
-                false // No expiration info, assume still valid
-            }
-        } else {
-            false // No tokens to refresh
-        };
-
-        if !needs_refresh {
+        if !self.needs_refresh() {
             return Ok(false); // No refresh needed
         }
 
-        // Get refresh token
-        let refresh_token = if let Some(tokens) = &self.tokens {
-            tokens.refresh_token.clone()
-        } else {
-            return Err(anyhow!("No tokens available for refresh"));
-        };
+        self.refresh_access_token().await?;
+        Ok(true) // Refresh successful
+    }
 
-        let refresh_token = refresh_token.ok_or_else(|| anyhow!("No refresh token available"))?;
+    /// Exchange the stored refresh token (or, for service accounts, a newly
+    /// signed assertion) for a new access token
+    pub async fn refresh_access_token(&mut self) -> Result<()> {
+        if self.uses_service_account() {
+            return self.request_service_account_token().await;
+        }
+
+        let tokens = self.tokens.as_ref()
+            .ok_or_else(|| anyhow!("No tokens available for refresh"))?;
+        let refresh_token = tokens.refresh_token.clone()
+            .ok_or_else(|| anyhow!("No refresh token available"))?;
 
-        tracing::info!("Refreshing expired Google Calendar access token");
+        tracing::info!("Refreshing Google Calendar access token");
 
         // Use OAuth2 client to refresh token
         let token_result = self
             .client
-            .exchange_refresh_token(&RefreshToken::new(refresh_token))
-            .request_async(async_http_client)
+            .exchange_refresh_token(&RefreshToken::new(refresh_token.clone()))
+            .request_async(|request| oauth_http_request(self.oauth_http_client.clone(), request))
             .await
             .map_err(|e| anyhow!("OAuth token refresh failed: {}", e))?;
 
@@ -190,35 +606,76 @@ impl GoogleCalendarService {
             Utc::now() + chrono::Duration::seconds(duration.as_secs() as i64)
         });
 
-        let new_tokens = GoogleTokens {
+        self.tokens = Some(GoogleTokens {
             access_token: token_result.access_token().secret().clone(),
             // Keep existing refresh token, or use new one if provided
             refresh_token: token_result.refresh_token()
                 .map(|rt| rt.secret().clone())
-                .or_else(|| self.tokens.as_ref().and_then(|t| t.refresh_token.clone())),
+                .or(Some(refresh_token)),
             expires_at,
+        });
+
+        tracing::info!("Successfully refreshed Google Calendar access token");
+        Ok(())
+    }
+
+    /// Obtain an access token with the JWT bearer grant (RFC 7523)
+    async fn request_service_account_token(&mut self) -> Result<()> {
+        let account = self.service_account.as_ref()
+            .ok_or_else(|| anyhow!("No service account configured"))?;
+
+        let now = Utc::now();
+        let claims = ServiceAccountClaims {
+            iss: &account.client_email,
+            scope: CALENDAR_SCOPE,
+            aud: &account.token_url,
+            iat: now.timestamp(),
+            exp: (now + chrono::Duration::hours(1)).timestamp(),
+            sub: account.subject.as_deref(),
         };
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = account.private_key_id.clone();
+        let assertion = jsonwebtoken::encode(&header, &claims, &account.encoding_key)
+            .map_err(|e| anyhow!("Failed to sign service account assertion: {}", e))?;
 
-        self.tokens = Some(new_tokens.clone());
+        tracing::info!("Requesting Google Calendar access token for service account {}", account.client_email);
 
-        // Save refreshed tokens to disk
-        if let Err(e) = crate::config::Config::save_google_tokens(&new_tokens) {
-            tracing::warn!("Failed to save refreshed Google Calendar tokens to disk: {}", e);
-        } else {
-            tracing::info!("Successfully refreshed and saved Google Calendar tokens");
+        let response = self.oauth_http_client
+            .post(&account.token_url)
+            .form(&[("grant_type", JWT_BEARER_GRANT), ("assertion", assertion.as_str())])
+            .send()
+            .await
+            .map_err(|e| anyhow!("Service account token request failed: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(anyhow!("Service account token request failed with {}: {}", status, text));
         }
 
-        Ok(true) // Refresh successful
+        let token: ServiceAccountTokenResponse = response
+            .json()
+            .await
+            .map_err(|e| anyhow!("Failed to parse service account token response: {}", e))?;
+
+        self.tokens = Some(GoogleTokens {
+            access_token: token.access_token,
+            refresh_token: None,
+            expires_at: token.expires_in.map(|seconds| now + chrono::Duration::seconds(seconds)),
+        });
+
+        tracing::info!("Successfully obtained Google Calendar service account token");
+        Ok(())
     }
 
     /// Create a new Google Calendar service from environment variables (OAuth)
     pub fn new_from_env() -> Result<Option<Self>> {
-        let client_id = std::env::var("GOOGLE_CLIENT_ID");
-        let client_secret = std::env::var("GOOGLE_CLIENT_SECRET"); 
-        let redirect_uri = std::env::var("GOOGLE_REDIRECT_URI");
+        let client_id = crate::config::env_var("GOOGLE_CLIENT_ID");
+        let client_secret = crate::config::env_var("GOOGLE_CLIENT_SECRET");
+        let redirect_uri = crate::config::env_var("GOOGLE_REDIRECT_URI");
 
         match (client_id, client_secret, redirect_uri) {
-            (Ok(client_id), Ok(client_secret), Ok(redirect_uri)) => {
+            (Some(client_id), Some(client_secret), Some(redirect_uri)) => {
                 let config = GoogleOAuthConfig {
                     client_id,
                     client_secret,
@@ -236,18 +693,50 @@ impl GoogleCalendarService {
         }
     }
 
-    /// Create a new Google Calendar service from configuration (TOML or env)
+    /// Create a new Google Calendar service from configuration (TOML or env).
+    /// A service account key takes precedence over OAuth client credentials.
     pub fn new_from_config(config: &crate::config::Config) -> Result<Option<Self>> {
+        let mut endpoints = GoogleEndpoints::default();
+        if let Some(auth_url) = &config.google.auth_url {
+            endpoints.auth_url = auth_url.clone();
+        }
+        if let Some(token_url) = &config.google.token_url {
+            endpoints.token_url = token_url.clone();
+        }
+        if let Some(device_auth_url) = &config.google.device_auth_url {
+            endpoints.device_auth_url = device_auth_url.clone();
+        }
+        if let Some(revoke_url) = &config.google.revoke_url {
+            endpoints.revoke_url = revoke_url.clone();
+        }
+        if let Some(api_base_url) = &config.google.api_base_url {
+            endpoints.api_base_url = api_base_url.clone();
+        }
+
+        if let Some(key_file) = &config.google.service_account_key_file {
+            let key = ServiceAccountKey::from_file(Path::new(key_file))?;
+            if config.google.token_url.is_none() {
+                endpoints.token_url = key.token_uri.clone();
+            }
+            let service = Self::new_with_service_account(key, config.google.impersonate_user.clone(), endpoints)?;
+            return Ok(Some(service
+                .with_calendars(config.google.calendar_sources())
+                .with_title_filters(config.google.title_filters())
+                .with_time_blocks(config.time_blocks.marker()?)));
+        }
+
         // Try to get Google OAuth config from the config struct
-        if let (Some(client_id), Some(client_secret), Some(redirect_uri)) = 
-            (&config.google.client_id, &config.google.client_secret, &config.google.redirect_uri) {
+        if let Some((client_id, client_secret, redirect_uri)) = config.google_oauth_config() {
             let oauth_config = GoogleOAuthConfig {
-                client_id: client_id.clone(),
-                client_secret: client_secret.clone(),
-                redirect_uri: redirect_uri.clone(),
+                client_id,
+                client_secret,
+                redirect_uri,
             };
-            match Self::new(oauth_config) {
-                Ok(service) => Ok(Some(service)),
+            match Self::new_with_endpoints(oauth_config, endpoints) {
+                Ok(service) => Ok(Some(service
+                    .with_calendars(config.google.calendar_sources())
+                    .with_title_filters(config.google.title_filters())
+                    .with_time_blocks(config.time_blocks.marker()?))),
                 Err(e) => Err(e),
             }
         } else {
@@ -256,30 +745,294 @@ impl GoogleCalendarService {
         }
     }
 
-    /// Get calendar events for today and tomorrow
-    pub async fn get_calendar_events(&self) -> Result<Vec<Meeting>> {
+    /// Get events for today and tomorrow from all configured calendars,
+    /// keeping the access token fresh.
+    ///
+    /// The token is refreshed before the requests when it is about to expire,
+    /// and once more if Google rejects it anyway (e.g. it was revoked early).
+    /// Callers should store `get_tokens()` afterwards, as they may have changed.
+    pub async fn fetch_events(&mut self) -> Result<Vec<Meeting>> {
+        let now = Utc::now();
+        self.fetch_events_between(now, now + chrono::Duration::days(1)).await
+    }
+
+    /// Get events overlapping `time_min` to `time_max` from all configured
+    /// calendars. Token handling works as in `fetch_events`.
+    pub async fn fetch_events_between(&mut self, time_min: DateTime<Utc>, time_max: DateTime<Utc>) -> Result<Vec<Meeting>> {
+        self.ensure_fresh_token().await?;
+        self.ensure_event_colors().await;
+
+        let mut results = Vec::new();
+        let mut refreshed = false;
+        for (calendar_id, source) in self.calendars.clone() {
+            let mut response = self.send_events_request(&calendar_id, time_min, time_max, None).await;
+
+            let unauthorized = matches!(&response, Ok(r) if r.status() == reqwest::StatusCode::UNAUTHORIZED);
+            if unauthorized && self.can_refresh() && !refreshed {
+                tracing::info!("Google rejected the access token, refreshing and retrying");
+                self.refresh_access_token().await?;
+                refreshed = true;
+                response = self.send_events_request(&calendar_id, time_min, time_max, None).await;
+            }
+
+            let result = match response {
+                Ok(response) => self.read_event_pages(response, &calendar_id, &source, time_min, time_max).await,
+                Err(e) => Err(e),
+            };
+            results.push((calendar_id, result));
+        }
+
+        Self::merge_calendar_results(results)
+    }
+
+    /// List the calendars the authenticated user can see, so they can pick
+    /// which ones to monitor. Token refresh works as in `fetch_events`.
+    pub async fn list_calendars(&mut self) -> Result<Vec<GoogleCalendarListEntry>> {
+        self.ensure_fresh_token().await?;
+
+        let mut calendars = Vec::new();
+        let mut page_token: Option<String> = None;
+        let mut refreshed = false;
+        loop {
+            let mut response = self.send_calendar_list_request(page_token.as_deref()).await?;
+
+            if response.status() == reqwest::StatusCode::UNAUTHORIZED && self.can_refresh() && !refreshed {
+                tracing::info!("Google rejected the access token, refreshing and retrying");
+                self.refresh_access_token().await?;
+                refreshed = true;
+                response = self.send_calendar_list_request(page_token.as_deref()).await?;
+            }
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                return Err(anyhow!("Google Calendar API error {}: {}", status, text));
+            }
+
+            let page: GoogleCalendarListResponse = response
+                .json()
+                .await
+                .map_err(|e| anyhow!("Failed to parse Google calendar list: {}", e))?;
+            calendars.extend(page.items.unwrap_or_default());
+
+            match page.next_page_token {
+                Some(token) => page_token = Some(token),
+                None => break,
+            }
+        }
+
+        tracing::info!("Found {} Google calendars", calendars.len());
+        Ok(calendars)
+    }
+
+    async fn send_calendar_list_request(&self, page_token: Option<&str>) -> Result<reqwest::Response> {
         let tokens = self.tokens.as_ref()
             .ok_or_else(|| anyhow!("No OAuth tokens available. Please authenticate first."))?;
 
+        let mut url = format!("{}/calendar/v3/users/me/calendarList?maxResults=250", self.api_base_url);
+        if let Some(page_token) = page_token {
+            url.push_str(&format!("&pageToken={}", urlencoding::encode(page_token)));
+        }
+
+        let request = self.http_client
+            .get(&url)
+            .bearer_auth(&tokens.access_token);
+        self.send_with_backoff(request)
+            .await
+            .map_err(|e| anyhow!("Failed to fetch Google calendar list: {}", e))
+    }
+
+    /// Load the event color palette so `colorId`s can be shown as colors.
+    /// Without it meetings simply keep their calendar's color; it is retried
+    /// on the next fetch.
+    async fn ensure_event_colors(&mut self) {
+        if self.event_colors.is_some() {
+            return;
+        }
+        match self.fetch_event_colors().await {
+            Ok(colors) => self.event_colors = Some(colors),
+            Err(e) => tracing::warn!("Failed to fetch Google Calendar colors: {}", e),
+        }
+    }
+
+    async fn fetch_event_colors(&self) -> Result<HashMap<String, String>> {
+        let tokens = self.tokens.as_ref()
+            .ok_or_else(|| anyhow!("No OAuth tokens available. Please authenticate first."))?;
+
+        let request = self.http_client
+            .get(format!("{}/calendar/v3/colors", self.api_base_url))
+            .bearer_auth(&tokens.access_token);
+        let response = self.send_with_backoff(request).await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(anyhow!("Google Calendar API error {}: {}", status, text));
+        }
+
+        let colors: GoogleColorsResponse = response
+            .json()
+            .await
+            .map_err(|e| anyhow!("Failed to parse Google Calendar colors: {}", e))?;
+        Ok(colors.event.into_iter().map(|(id, color)| (id, color.background)).collect())
+    }
+
+    /// Refresh the access token if it is about to expire; only fails when it
+    /// has actually expired, otherwise the current token is still worth trying
+    async fn ensure_fresh_token(&mut self) -> Result<()> {
+        if let Err(e) = self.refresh_token_if_needed().await {
+            let expired = match &self.tokens {
+                None => true,
+                Some(tokens) => tokens.expires_at.is_some_and(|expires_at| expires_at <= Utc::now()),
+            };
+            if expired {
+                return Err(anyhow!("Google access token expired and could not be refreshed: {}", e));
+            }
+            tracing::warn!("Failed to refresh Google access token early, using the current one: {}", e);
+        }
+        Ok(())
+    }
+
+    /// Get events for today and tomorrow from all configured calendars
+    pub async fn get_calendar_events(&self) -> Result<Vec<Meeting>> {
         let now = Utc::now();
-        let time_min = now.format("%Y-%m-%dT%H:%M:%SZ").to_string();
-        let time_max = (now + chrono::Duration::days(1)).format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        let mut results = Vec::new();
+        for (calendar_id, source) in &self.calendars {
+            let time_max = now + chrono::Duration::days(1);
+            let result = match self.send_events_request(calendar_id, now, time_max, None).await {
+                Ok(response) => self.read_event_pages(response, calendar_id, source, now, time_max).await,
+                Err(e) => Err(e),
+            };
+            results.push((calendar_id.clone(), result));
+        }
+
+        Self::merge_calendar_results(results)
+    }
+
+    /// Combine per-calendar results; one unreachable calendar (e.g. a shared
+    /// calendar that was unshared) does not hide the others
+    fn merge_calendar_results(results: Vec<(String, Result<Vec<Meeting>>)>) -> Result<Vec<Meeting>> {
+        let mut meetings = Vec::new();
+        let mut first_error = None;
+        let calendar_count = results.len();
+        let mut failed = 0;
+
+        for (calendar_id, result) in results {
+            match result {
+                Ok(events) => meetings.extend(events),
+                Err(e) => {
+                    tracing::warn!("Failed to fetch Google calendar '{}': {}", calendar_id, e);
+                    failed += 1;
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        match first_error {
+            Some(e) if failed == calendar_count => Err(e),
+            _ => Ok(meetings),
+        }
+    }
+
+    /// Read the events of a calendar starting with the first page's
+    /// `response`, requesting the following pages as long as Google has more
+    async fn read_event_pages(
+        &self,
+        mut response: reqwest::Response,
+        calendar_id: &str,
+        source: &MeetingSource,
+        time_min: DateTime<Utc>,
+        time_max: DateTime<Utc>,
+    ) -> Result<Vec<Meeting>> {
+        let mut meetings = Vec::new();
+        loop {
+            let (events, next_page_token) = self.parse_events_response(response, source).await?;
+            meetings.extend(self.filter_events(calendar_id, events));
+            let Some(page_token) = next_page_token else {
+                break;
+            };
+            response = self.send_events_request(calendar_id, time_min, time_max, Some(&page_token)).await?;
+        }
+        tracing::info!("Successfully fetched {} events from {}", meetings.len(), source.name);
+        Ok(meetings)
+    }
+
+    async fn send_events_request(
+        &self,
+        calendar_id: &str,
+        time_min: DateTime<Utc>,
+        time_max: DateTime<Utc>,
+        page_token: Option<&str>,
+    ) -> Result<reqwest::Response> {
+        let tokens = self.tokens.as_ref()
+            .ok_or_else(|| anyhow!("No OAuth tokens available. Please authenticate first."))?;
 
-        tracing::debug!("Fetching Google Calendar events from {} to {}", time_min, time_max);
+        let time_min = time_min.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        let time_max = time_max.format("%Y-%m-%dT%H:%M:%SZ").to_string();
 
-        let url = format!(
-            "https://www.googleapis.com/calendar/v3/calendars/primary/events?timeMin={}&timeMax={}&singleEvents=true&orderBy=startTime&maxResults=50",
+        tracing::debug!("Fetching Google Calendar '{}' events from {} to {}", calendar_id, time_min, time_max);
+
+        // 2500 is the most Google returns per page
+        let mut url = format!(
+            "{}/calendar/v3/calendars/{}/events?timeMin={}&timeMax={}&singleEvents=true&orderBy=startTime&maxResults=2500",
+            self.api_base_url,
+            urlencoding::encode(calendar_id),
             urlencoding::encode(&time_min),
             urlencoding::encode(&time_max)
         );
+        if let Some(page_token) = page_token {
+            url.push_str(&format!("&pageToken={}", urlencoding::encode(page_token)));
+        }
 
-        let response = self.http_client
+        let request = self.http_client
             .get(&url)
-            .bearer_auth(&tokens.access_token)
-            .send()
+            .bearer_auth(&tokens.access_token);
+        self.send_with_backoff(request)
             .await
-            .map_err(|e| anyhow!("Failed to fetch Google Calendar events: {}", e))?;
+            .map_err(|e| anyhow!("Failed to fetch Google Calendar events: {}", e))
+    }
+
+    /// Send a Calendar API request, waiting and retrying while Google reports
+    /// that a rate limit was hit. Other responses are returned as they are.
+    async fn send_with_backoff(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let mut attempt = 0;
+        loop {
+            let response = request
+                .try_clone()
+                .ok_or_else(|| anyhow!("Request cannot be retried"))?
+                .send()
+                .await?;
+
+            let status = response.status();
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .map(Duration::from_secs);
+
+            if status == reqwest::StatusCode::FORBIDDEN {
+                // 403 is also used for permission errors; only the rate limit reasons are retried
+                let text = response.text().await.unwrap_or_default();
+                if !is_rate_limit_error(&text) {
+                    return Err(anyhow!("Google Calendar API error {}: {}", status, text));
+                }
+            } else if status != reqwest::StatusCode::TOO_MANY_REQUESTS {
+                return Ok(response);
+            }
+
+            if attempt >= self.retry_policy.max_retries {
+                return Err(anyhow!("Google Calendar API rate limit exceeded ({}), gave up after {} retries", status, attempt));
+            }
+            let delay = self.retry_policy.delay(attempt, retry_after);
+            tracing::warn!("Google Calendar API rate limit exceeded ({}), retrying in {:?}", status, delay);
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
 
+    /// The meetings of one page of events, and the token of the next page
+    async fn parse_events_response(&self, response: reqwest::Response, source: &MeetingSource) -> Result<(Vec<Meeting>, Option<String>)> {
         if !response.status().is_success() {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
@@ -291,18 +1044,18 @@ impl GoogleCalendarService {
             .await
             .map_err(|e| anyhow!("Failed to parse Google Calendar response: {}", e))?;
 
-        let meetings = self.convert_google_events_to_meetings(calendar_response)?;
-        tracing::info!("Successfully fetched {} events from Google Calendar", meetings.len());
-        Ok(meetings)
+        let next_page_token = calendar_response.next_page_token.clone();
+        let meetings = self.convert_google_events_to_meetings(calendar_response, source)?;
+        Ok((meetings, next_page_token))
     }
 
     /// Convert Google Calendar events to our Meeting format
-    fn convert_google_events_to_meetings(&self, response: GoogleCalendarResponse) -> Result<Vec<Meeting>> {
+    fn convert_google_events_to_meetings(&self, response: GoogleCalendarResponse, source: &MeetingSource) -> Result<Vec<Meeting>> {
         let mut meetings = Vec::new();
 
         if let Some(events) = response.items {
             for event in events {
-                if let Some(meeting) = self.convert_single_event_to_meeting(event)? {
+                if let Some(meeting) = self.convert_single_event_to_meeting(event, source)? {
                     meetings.push(meeting);
                 }
             }
@@ -312,46 +1065,51 @@ impl GoogleCalendarService {
     }
 
     /// Convert a single Google Calendar event to our Meeting struct
-    fn convert_single_event_to_meeting(&self, event: GoogleCalendarEvent) -> Result<Option<Meeting>> {
-        // Determine the user's response status for this event first
+    fn convert_single_event_to_meeting(&self, event: GoogleCalendarEvent, source: &MeetingSource) -> Result<Option<Meeting>> {
+        // Declined events are kept (hidden later via `should_display`) so they
+        // also suppress their copies from ICS feeds when merged
         let response_status = self.get_user_response_status(&event);
-        
-        // Skip declined events entirely
-        if let Some(crate::meeting::ResponseStatus::Declined) = response_status {
-            tracing::debug!("Skipping declined event: {:?}", event.summary);
+
+        // Cancelled instances of a recurring series are still listed by singleEvents=true
+        if event.status.as_deref() == Some("cancelled") {
+            tracing::debug!("Skipping cancelled event: {:?}", event.summary);
             return Ok(None);
         }
 
-        // Skip events without start/end times (all-day events, etc.)
-        let (start_time, end_time) = match (event.start, event.end) {
-            (Some(start), Some(end)) => {
-                match (start.date_time, end.date_time) {
-                    (Some(start_dt), Some(end_dt)) => {
-                        let start_parsed = DateTime::parse_from_rfc3339(&start_dt)
-                            .map_err(|e| anyhow!("Failed to parse start time: {}", e))?
-                            .with_timezone(&Utc);
-                        let end_parsed = DateTime::parse_from_rfc3339(&end_dt)
-                            .map_err(|e| anyhow!("Failed to parse end time: {}", e))?
-                            .with_timezone(&Utc);
-                        (start_parsed, end_parsed)
-                    }
-                    _ => {
-                        // Skip all-day events or events without proper times
-                        tracing::debug!("Skipping all-day event: {:?}", event.summary);
-                        return Ok(None);
-                    }
-                }
+        let (Some(start), Some(end)) = (event.start, event.end) else {
+            tracing::debug!("Skipping event without start/end times: {:?}", event.summary);
+            return Ok(None);
+        };
+        let event_type = event.event_type.as_deref().and_then(EventType::from_google);
+        let title = event.summary
+            .or_else(|| event_type.map(|t| t.label().to_string()))
+            .unwrap_or_else(|| "Untitled Event".to_string());
+
+        // Timed events use `dateTime`; all-day and multi-day events (vacations,
+        // holidays) use `date`, with an exclusive end date
+        let mut meeting = match (start.date_time, end.date_time, start.date, end.date) {
+            (Some(start_dt), Some(end_dt), _, _) => {
+                let start_parsed = DateTime::parse_from_rfc3339(&start_dt)
+                    .map_err(|e| anyhow!("Failed to parse start time: {}", e))?
+                    .with_timezone(&Utc);
+                let end_parsed = DateTime::parse_from_rfc3339(&end_dt)
+                    .map_err(|e| anyhow!("Failed to parse end time: {}", e))?
+                    .with_timezone(&Utc);
+                Meeting::new(title, start_parsed, end_parsed)
+            }
+            (_, _, Some(start_date), Some(end_date)) => {
+                let first_day = NaiveDate::parse_from_str(&start_date, "%Y-%m-%d")
+                    .map_err(|e| anyhow!("Failed to parse start date: {}", e))?;
+                let end_day = NaiveDate::parse_from_str(&end_date, "%Y-%m-%d")
+                    .map_err(|e| anyhow!("Failed to parse end date: {}", e))?;
+                Meeting::new_all_day(title, first_day, end_day)
             }
             _ => {
-                tracing::debug!("Skipping event without start/end times: {:?}", event.summary);
+                tracing::debug!("Skipping event without start/end times: {:?}", title);
                 return Ok(None);
             }
         };
 
-        // Create the meeting
-        let title = event.summary.unwrap_or_else(|| "Untitled Event".to_string());
-        let mut meeting = Meeting::new(title, start_time, end_time);
-
         // Add response status if available
         if let Some(status) = response_status {
             meeting = meeting.with_response_status(status);
@@ -367,6 +1125,24 @@ impl GoogleCalendarService {
             meeting = meeting.with_location(location);
         }
 
+        if let Some(uid) = event.ical_uid {
+            meeting = meeting.with_uid(uid);
+        }
+
+        if let Some(recurring_event_id) = event.recurring_event_id {
+            meeting = meeting.with_recurring_event_id(recurring_event_id);
+        }
+
+        if let Some(event_type) = event_type {
+            meeting = meeting.with_event_type(event_type);
+        }
+
+        meeting = meeting.with_source(source.clone());
+
+        if let Some(color) = event.color_id.as_ref().and_then(|id| self.event_colors.as_ref()?.get(id)) {
+            meeting = meeting.with_color(color.clone());
+        }
+
         // Add attendees information
         if let Some(attendees) = event.attendees {
             let attendee_names: Vec<String> = attendees
@@ -392,7 +1168,8 @@ impl GoogleCalendarService {
         Ok(Some(meeting))
     }
 
-    /// Determine the authenticated user's response status for an event
+    /// Determine the authenticated user's response status for an event from
+    /// the attendee marked `self`
     fn get_user_response_status(&self, event: &GoogleCalendarEvent) -> Option<crate::meeting::ResponseStatus> {
         if let Some(attendees) = &event.attendees {
             // Find the authenticated user in the attendees list
@@ -414,4 +1191,17 @@ impl GoogleCalendarService {
             Some(crate::meeting::ResponseStatus::Accepted)
         }
     }
-}
\ No newline at end of file
+}
+
+/// Whether a 403 error body reports a rate limit rather than missing access
+fn is_rate_limit_error(body: &str) -> bool {
+    let Ok(error) = serde_json::from_str::<serde_json::Value>(body) else {
+        return false;
+    };
+    error["error"]["errors"]
+        .as_array()
+        .map(|errors| {
+            errors.iter().any(|e| matches!(e["reason"].as_str(), Some("rateLimitExceeded" | "userRateLimitExceeded")))
+        })
+        .unwrap_or(false)
+}
@@ -0,0 +1,108 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Mutex;
+
+use crate::config::GpioConfig;
+use crate::events::{Event, NotificationBackend};
+use crate::hue::RoomState;
+
+/// Switches the `[gpio]` pins from the meeting updates on the event bus
+pub struct GpioPins {
+    config: GpioConfig,
+    /// State the pins were last set for
+    shown: Mutex<Option<RoomState>>,
+    /// Last error reported, so a failing pin is not reported every update
+    error: Mutex<Option<String>>,
+    /// Pins opened so far; they keep their level only while held
+    #[cfg(feature = "gpio")]
+    outputs: Mutex<std::collections::HashMap<u8, rppal::gpio::OutputPin>>,
+}
+
+impl GpioPins {
+    pub fn new(config: GpioConfig) -> Self {
+        Self {
+            config,
+            shown: Mutex::new(None),
+            error: Mutex::new(None),
+            #[cfg(feature = "gpio")]
+            outputs: Mutex::new(std::collections::HashMap::new()),
+        }
+    }
+
+    /// Every configured pin with whether it is driven high in `state`. A pin
+    /// listed for several states stays on in all of them.
+    pub fn levels(&self, state: RoomState) -> Vec<(u8, bool)> {
+        let on = match state {
+            RoomState::Busy => &self.config.busy_pins,
+            RoomState::EndingSoon => &self.config.ending_soon_pins,
+            RoomState::Free => &self.config.free_pins,
+        };
+        self.config.pins().into_iter().map(|pin| (pin, on.contains(&pin) != self.config.active_low)).collect()
+    }
+}
+
+#[async_trait]
+impl NotificationBackend for GpioPins {
+    fn name(&self) -> &str {
+        "GPIO"
+    }
+
+    async fn handle(&self, event: &Event) -> Result<()> {
+        let Event::Update(update) = event else { return Ok(()) };
+        let state = RoomState::of(update, chrono::Duration::minutes(self.config.ending_soon_minutes.into()));
+        let mut shown = self.shown.lock().unwrap_or_else(|e| e.into_inner());
+        if *shown == Some(state) {
+            return Ok(());
+        }
+        // Tried again with every update until it works, but reported once
+        let mut error = self.error.lock().unwrap_or_else(|e| e.into_inner());
+        match self.set_levels(&self.levels(state)) {
+            Ok(()) => {
+                *shown = Some(state);
+                *error = None;
+                Ok(())
+            }
+            Err(e) if error.as_deref() == Some(e.to_string().as_str()) => Ok(()),
+            Err(e) => {
+                *error = Some(e.to_string());
+                Err(e)
+            }
+        }
+    }
+}
+
+impl GpioPins {
+    #[cfg(feature = "gpio")]
+    fn set_levels(&self, levels: &[(u8, bool)]) -> Result<()> {
+        use anyhow::anyhow;
+        use std::collections::hash_map::Entry;
+
+        let mut outputs = self.outputs.lock().unwrap_or_else(|e| e.into_inner());
+        if levels.iter().any(|(pin, _)| !outputs.contains_key(pin)) {
+            let gpio = rppal::gpio::Gpio::new().map_err(|e| anyhow!("Failed to access the GPIO pins: {}", e))?;
+            for &(pin, _) in levels {
+                if let Entry::Vacant(entry) = outputs.entry(pin) {
+                    let output = gpio.get(pin).map_err(|e| anyhow!("Failed to open GPIO pin {}: {}", pin, e))?.into_output();
+                    entry.insert(output);
+                }
+            }
+        }
+        for &(pin, high) in levels {
+            if let Some(output) = outputs.get_mut(&pin) {
+                if high {
+                    output.set_high();
+                } else {
+                    output.set_low();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Stand-in used when built without the `gpio` feature;
+    /// `Config::validate` rejects `[gpio]` before this is reached
+    #[cfg(not(feature = "gpio"))]
+    fn set_levels(&self, _levels: &[(u8, bool)]) -> Result<()> {
+        Err(anyhow::anyhow!("calendar-monitor was built without GPIO support (enable the `gpio` feature)"))
+    }
+}
@@ -0,0 +1,289 @@
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Result, Schema, ID};
+use chrono::{DateTime, Utc};
+
+use crate::app::{build_meeting_update, busy_until, meetings_between, AgendaEntryKind, AppState, FREE_SLOT_DAYS, MAX_RANGE_DAYS};
+use crate::calendar::SourceHealth;
+use crate::free_slots::{find_free_slots, parse_duration, FreeSlot};
+use crate::meeting::{Meeting, MeetingSource, ResponseStatus};
+use crate::recurrence::Window;
+
+/// Deepest query /graphql accepts; the schema itself is only a few levels deep
+const MAX_DEPTH: usize = 8;
+
+pub type CalendarSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// The read-only schema served at /graphql. Resolvers read the `AppState`
+/// from the request data.
+pub fn build_schema() -> CalendarSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .limit_depth(MAX_DEPTH)
+        .finish()
+}
+
+/// The range of a `from`/`to` pair, today and tomorrow by default
+fn range(from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Result<(DateTime<Utc>, DateTime<Utc>)> {
+    let window = Window::today_and_tomorrow();
+    let from = from.unwrap_or_else(|| window.start());
+    let to = to.unwrap_or_else(|| window.end());
+    if from >= to {
+        return Err("'from' must be before 'to'".into());
+    }
+    if to - from > chrono::Duration::days(MAX_RANGE_DAYS) {
+        return Err(format!("Ranges are limited to {} days", MAX_RANGE_DAYS).into());
+    }
+    Ok((from, to))
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Events overlapping `from`..`to` (at most 31 days; today and tomorrow
+    /// by default), filtered like the live view and sorted by start time
+    async fn meetings(
+        &self,
+        ctx: &Context<'_>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<MeetingObject>> {
+        let state = ctx.data::<AppState>()?;
+        let (from, to) = range(from, to)?;
+        Ok(meetings_between(state, from, to).await.into_iter().map(MeetingObject).collect())
+    }
+
+    /// One of today's or tomorrow's events, by its `id`
+    async fn meeting(&self, ctx: &Context<'_>, id: ID) -> Result<Option<MeetingObject>> {
+        let state = ctx.data::<AppState>()?;
+        let window = Window::today_and_tomorrow();
+        Ok(meetings_between(state, window.start(), window.end())
+            .await
+            .into_iter()
+            .find(|m| m.id == *id)
+            .map(MeetingObject))
+    }
+
+    /// The meeting in progress, if any
+    async fn current_meeting(&self, ctx: &Context<'_>) -> Result<Option<MeetingObject>> {
+        let update = build_meeting_update(ctx.data::<AppState>()?).await;
+        Ok(update.current_meeting.map(MeetingObject))
+    }
+
+    /// The next meeting to start
+    async fn next_meeting(&self, ctx: &Context<'_>) -> Result<Option<MeetingObject>> {
+        let update = build_meeting_update(ctx.data::<AppState>()?).await;
+        Ok(update.next_meeting.map(MeetingObject))
+    }
+
+    /// Time blocks (`[Focus]`, out of office, focus time) overlapping
+    /// `from`..`to`, today and tomorrow by default
+    async fn time_blocks(
+        &self,
+        ctx: &Context<'_>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<MeetingObject>> {
+        let state = ctx.data::<AppState>()?;
+        let (from, to) = range(from, to)?;
+        Ok(meetings_between(state, from, to)
+            .await
+            .into_iter()
+            .filter(|m| !m.all_day && m.is_time_block())
+            .map(MeetingObject)
+            .collect())
+    }
+
+    /// Fetch health of every ICS and CalDAV source
+    async fn sources(&self, ctx: &Context<'_>) -> Result<Vec<SourceHealthObject>> {
+        let state = ctx.data::<AppState>()?;
+        Ok(state.calendar_service().named_source_health().await.into_iter().map(SourceHealthObject).collect())
+    }
+
+    /// Whether a meeting is in progress, and the next free time
+    async fn free_busy(&self, ctx: &Context<'_>) -> Result<FreeBusy> {
+        let update = build_meeting_update(ctx.data::<AppState>()?).await;
+        Ok(FreeBusy {
+            busy_until: busy_until(&update),
+        })
+    }
+}
+
+pub struct FreeBusy {
+    busy_until: Option<DateTime<Utc>>,
+}
+
+#[Object]
+impl FreeBusy {
+    async fn busy(&self) -> bool {
+        self.busy_until.is_some()
+    }
+
+    /// End of the current meeting, or of the meetings following it back to back
+    async fn busy_until(&self) -> Option<DateTime<Utc>> {
+        self.busy_until
+    }
+
+    /// The next gaps of at least `duration` (e.g. "30m", "1h30m") within
+    /// working hours over the coming week
+    async fn free_slots(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = "30m")] duration: String,
+        #[graphql(default = 5)] limit: usize,
+    ) -> Result<Vec<FreeSlotObject>> {
+        let state = ctx.data::<AppState>()?;
+        let Some(duration) = parse_duration(&duration) else {
+            return Err(format!("Invalid duration '{}', expected e.g. 30m or 1h30m", duration).into());
+        };
+        if limit == 0 {
+            return Err("'limit' must be a positive number".into());
+        }
+
+        let now = Utc::now();
+        let meetings = meetings_between(state, now, now + chrono::Duration::days(FREE_SLOT_DAYS)).await;
+        Ok(find_free_slots(&meetings, now, &state.config().working_hours, duration, FREE_SLOT_DAYS, limit)
+            .into_iter()
+            .map(FreeSlotObject)
+            .collect())
+    }
+}
+
+pub struct MeetingObject(Meeting);
+
+#[Object(name = "Meeting")]
+impl MeetingObject {
+    async fn id(&self) -> ID {
+        ID(self.0.id.clone())
+    }
+
+    async fn kind(&self) -> AgendaEntryKind {
+        AgendaEntryKind::of(&self.0)
+    }
+
+    async fn title(&self) -> &str {
+        &self.0.title
+    }
+
+    async fn start_time(&self) -> DateTime<Utc> {
+        self.0.start_time
+    }
+
+    async fn end_time(&self) -> DateTime<Utc> {
+        self.0.end_time
+    }
+
+    async fn description(&self) -> Option<&str> {
+        self.0.description.as_deref()
+    }
+
+    async fn location(&self) -> Option<&str> {
+        self.0.location.as_deref()
+    }
+
+    async fn attendees(&self) -> &[String] {
+        &self.0.attendees
+    }
+
+    /// "accepted", "declined", "tentative" or "no_response" for invitations
+    async fn response_status(&self) -> Option<&str> {
+        self.0.response_status.as_ref().map(|status| match status {
+            ResponseStatus::Accepted => "accepted",
+            ResponseStatus::Declined => "declined",
+            ResponseStatus::Tentative => "tentative",
+            ResponseStatus::NoResponse => "no_response",
+        })
+    }
+
+    async fn source(&self) -> Option<SourceObject> {
+        self.0.source.clone().map(SourceObject)
+    }
+
+    /// Event color, falling back to the source color
+    async fn color(&self) -> Option<&str> {
+        self.0.color.as_deref().or(self.0.source.as_ref().and_then(|s| s.color.as_deref()))
+    }
+
+    async fn recurring_event_id(&self) -> Option<&str> {
+        self.0.recurring_event_id.as_deref()
+    }
+
+    async fn all_day(&self) -> bool {
+        self.0.all_day
+    }
+
+    /// Seconds until the meeting starts; negative once it has
+    async fn starts_in_seconds(&self) -> i64 {
+        (self.0.start_time - Utc::now()).num_seconds()
+    }
+}
+
+pub struct SourceObject(MeetingSource);
+
+#[Object(name = "Source")]
+impl SourceObject {
+    async fn id(&self) -> &str {
+        &self.0.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn color(&self) -> Option<&str> {
+        self.0.color.as_deref()
+    }
+
+    async fn priority(&self) -> i32 {
+        self.0.priority
+    }
+}
+
+pub struct SourceHealthObject(SourceHealth);
+
+#[Object(name = "SourceHealth")]
+impl SourceHealthObject {
+    async fn source(&self) -> &str {
+        &self.0.source
+    }
+
+    async fn healthy(&self) -> bool {
+        self.0.is_healthy()
+    }
+
+    async fn last_success(&self) -> Option<DateTime<Utc>> {
+        self.0.last_success
+    }
+
+    async fn last_error(&self) -> Option<&str> {
+        self.0.last_error.as_deref()
+    }
+
+    async fn last_error_at(&self) -> Option<DateTime<Utc>> {
+        self.0.last_error_at
+    }
+
+    /// First failure since the last successful fetch
+    async fn failing_since(&self) -> Option<DateTime<Utc>> {
+        self.0.failing_since
+    }
+
+    async fn event_count(&self) -> usize {
+        self.0.event_count
+    }
+}
+
+pub struct FreeSlotObject(FreeSlot);
+
+#[Object(name = "FreeSlot")]
+impl FreeSlotObject {
+    async fn start(&self) -> DateTime<Utc> {
+        self.0.start
+    }
+
+    async fn end(&self) -> DateTime<Utc> {
+        self.0.end
+    }
+
+    async fn duration_minutes(&self) -> i64 {
+        self.0.duration_minutes()
+    }
+}
@@ -0,0 +1,128 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::meeting::Meeting;
+
+/// A meeting kept off the display, for good or until `until`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HiddenMeeting {
+    pub id: String,
+    /// Title at the time it was hidden, to tell entries apart
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl HiddenMeeting {
+    pub fn is_active(&self) -> bool {
+        self.until.is_none_or(|until| Utc::now() < until)
+    }
+}
+
+/// Meetings hidden through POST /api/meetings/{id}/hide, e.g. a ghost event
+/// that keeps syncing wrong or a meeting being skipped. Kept in a JSON file
+/// so they stay hidden across refreshes and restarts.
+#[derive(Clone, Default)]
+pub struct HiddenMeetings {
+    entries: Arc<Mutex<Vec<HiddenMeeting>>>,
+    path: Option<PathBuf>,
+}
+
+impl HiddenMeetings {
+    /// Hidden meetings that are forgotten on restart
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Hidden meetings persisted to `path`, starting with the ones saved there
+    pub fn open(path: PathBuf) -> Self {
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(contents) => match serde_json::from_str::<Vec<HiddenMeeting>>(&contents) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    tracing::warn!("Ignoring unreadable hidden meetings {}: {}", path.display(), e);
+                    Vec::new()
+                }
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                tracing::warn!("Failed to read hidden meetings {}: {}", path.display(), e);
+                Vec::new()
+            }
+        };
+        Self {
+            entries: Arc::new(Mutex::new(entries)),
+            path: Some(path),
+        }
+    }
+
+    /// Meetings hidden right now
+    pub async fn list(&self) -> Vec<HiddenMeeting> {
+        self.entries.lock().await.iter().filter(|e| e.is_active()).cloned().collect()
+    }
+
+    /// Drop the meetings that are hidden right now
+    pub async fn retain_visible(&self, meetings: Vec<Meeting>) -> Vec<Meeting> {
+        let entries = self.entries.lock().await;
+        meetings
+            .into_iter()
+            .filter(|m| !entries.iter().any(|e| e.id == m.id && e.is_active()))
+            .collect()
+    }
+
+    /// Hide a meeting, replacing an earlier entry for it
+    pub async fn hide(&self, meeting: &Meeting, until: Option<DateTime<Utc>>) -> Result<HiddenMeeting> {
+        let entry = HiddenMeeting {
+            id: meeting.id.clone(),
+            title: meeting.title.clone(),
+            until,
+        };
+        let mut entries = self.entries.lock().await;
+        let mut updated: Vec<HiddenMeeting> = entries
+            .iter()
+            .filter(|e| e.is_active() && e.id != entry.id)
+            .cloned()
+            .collect();
+        updated.push(entry.clone());
+
+        self.save(&updated).await?;
+        *entries = updated;
+        Ok(entry)
+    }
+
+    /// Show a meeting again; false if it was not hidden
+    pub async fn unhide(&self, id: &str) -> Result<bool> {
+        let mut entries = self.entries.lock().await;
+        let updated: Vec<HiddenMeeting> = entries
+            .iter()
+            .filter(|e| e.is_active() && e.id != id)
+            .cloned()
+            .collect();
+        if !entries.iter().any(|e| e.is_active() && e.id == id) {
+            return Ok(false);
+        }
+
+        self.save(&updated).await?;
+        *entries = updated;
+        Ok(true)
+    }
+
+    /// Write via a temporary file so a crash never leaves a truncated file behind
+    async fn save(&self, entries: &[HiddenMeeting]) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let json = serde_json::to_vec_pretty(entries)
+            .map_err(|e| anyhow!("Failed to serialize hidden meetings: {}", e))?;
+        let tmp_path = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp_path, json).await
+            .map_err(|e| anyhow!("Failed to write hidden meetings {}: {}", tmp_path.display(), e))?;
+        tokio::fs::rename(&tmp_path, path).await
+            .map_err(|e| anyhow!("Failed to replace hidden meetings {}: {}", path.display(), e))?;
+        Ok(())
+    }
+}
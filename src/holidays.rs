@@ -0,0 +1,159 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{Datelike, NaiveDate};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use crate::calendar::SourceError;
+use crate::config::{Config, HolidaysConfig};
+use crate::meeting::Meeting;
+use crate::recurrence::Window;
+use crate::source::CalendarSource;
+
+const DEFAULT_API_URL: &str = "https://date.nager.at";
+
+/// How long to wait before asking the holiday API again after a failed request
+const RETRY_AFTER: Duration = Duration::from_secs(15 * 60);
+
+/// A public holiday as listed by `/api/v3/PublicHolidays/{year}/{country}`
+#[derive(Debug, Clone, Deserialize)]
+pub struct PublicHoliday {
+    pub date: NaiveDate,
+    /// Name in the country's language
+    #[serde(rename = "localName")]
+    pub local_name: String,
+    /// English name
+    pub name: String,
+    /// False for holidays only observed in some regions
+    pub global: bool,
+    /// ISO 3166-2 codes of the regions observing a regional holiday
+    #[serde(default)]
+    pub counties: Option<Vec<String>>,
+}
+
+impl PublicHoliday {
+    /// Whether the holiday is observed nationwide or in the given region
+    pub fn observed_in(&self, region: Option<&str>) -> bool {
+        if self.global {
+            return true;
+        }
+        match (region, &self.counties) {
+            (Some(region), Some(counties)) => counties.iter().any(|c| c.eq_ignore_ascii_case(region)),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Default)]
+struct HolidayCache {
+    years: HashMap<i32, Vec<PublicHoliday>>,
+    failed_at: Option<Instant>,
+    /// Latest failure, until a year loads again
+    failure: Option<SourceError>,
+}
+
+/// Public holidays of the configured country, shown as all-day events.
+/// Each year is fetched once and kept for the lifetime of the process.
+#[derive(Clone)]
+pub struct HolidaySource {
+    http_client: reqwest::Client,
+    config: HolidaysConfig,
+    country: String,
+    cache: Arc<Mutex<HolidayCache>>,
+}
+
+impl HolidaySource {
+    /// None when no holiday country is configured
+    pub fn new_from_config(config: &Config, http_client: reqwest::Client) -> Option<Self> {
+        let country = config.holidays.country.as_ref()?.to_ascii_uppercase();
+        Some(Self {
+            http_client,
+            config: config.holidays.clone(),
+            country,
+            cache: Arc::new(Mutex::new(HolidayCache::default())),
+        })
+    }
+
+    /// Holidays of a year, from the cache when it has been fetched before
+    async fn holidays_in(&self, year: i32) -> Result<Vec<PublicHoliday>> {
+        let mut cache = self.cache.lock().await;
+        if let Some(holidays) = cache.years.get(&year) {
+            return Ok(holidays.clone());
+        }
+        if cache.failed_at.is_some_and(|failed_at| failed_at.elapsed() < RETRY_AFTER) {
+            tracing::debug!("Holiday API failed recently, not retrying yet");
+            return Ok(Vec::new());
+        }
+
+        match self.fetch_year(year).await {
+            Ok(holidays) => {
+                tracing::info!("Loaded {} public holidays for {} in {}", holidays.len(), self.country, year);
+                cache.failed_at = None;
+                cache.failure = None;
+                cache.years.insert(year, holidays.clone());
+                Ok(holidays)
+            }
+            Err(e) => {
+                cache.failed_at = Some(Instant::now());
+                cache.failure = Some(SourceError::continuing(cache.failure.as_ref(), &self.config.meeting_source().name, &e));
+                Err(e)
+            }
+        }
+    }
+
+    /// Why the holidays could not be loaded, while they can't
+    pub async fn failure(&self) -> Option<SourceError> {
+        self.cache.lock().await.failure.clone()
+    }
+
+    async fn fetch_year(&self, year: i32) -> Result<Vec<PublicHoliday>> {
+        let base_url = self.config.api_url.as_deref().unwrap_or(DEFAULT_API_URL);
+        let url = format!("{}/api/v3/PublicHolidays/{}/{}", base_url.trim_end_matches('/'), year, self.country);
+
+        let response = self.http_client.get(&url).send().await
+            .map_err(|e| anyhow!("Failed to fetch public holidays from {}: {}", url, e))?;
+        if !response.status().is_success() {
+            return Err(anyhow!("HTTP error {} when fetching public holidays from {}", response.status(), url));
+        }
+
+        response.json().await
+            .map_err(|e| anyhow!("Invalid public holiday data from {}: {}", url, e))
+    }
+}
+
+#[async_trait]
+impl CalendarSource for HolidaySource {
+    fn name(&self) -> &str {
+        "public holiday"
+    }
+
+    async fn fetch_events(&self, window: &Window) -> Result<Vec<Meeting>> {
+        let source = self.config.meeting_source();
+        let region = self.config.region.as_deref();
+
+        let mut meetings = Vec::new();
+        for year in window.first_day.year()..=window.last_day.year() {
+            for holiday in self.holidays_in(year).await? {
+                if !holiday.observed_in(region) {
+                    continue;
+                }
+                let Some(next_day) = holiday.date.succ_opt() else {
+                    continue;
+                };
+
+                let mut meeting = Meeting::new_all_day(holiday.name.clone(), holiday.date, next_day)
+                    .with_source(source.clone());
+                if holiday.local_name != holiday.name {
+                    meeting = meeting.with_description(holiday.local_name.clone());
+                }
+                if window.overlaps(meeting.start_time, meeting.end_time) {
+                    meetings.push(meeting);
+                }
+            }
+        }
+        Ok(meetings)
+    }
+}
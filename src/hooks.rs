@@ -0,0 +1,110 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use std::process::Stdio;
+use tokio::process::Command;
+
+use crate::config::HooksConfig;
+use crate::meeting::Meeting;
+use crate::notify::{MeetingEvent, Notifier};
+
+/// Runs the `[hooks]` commands as meetings start, end and come up
+pub struct ShellHooks {
+    config: HooksConfig,
+}
+
+impl ShellHooks {
+    pub fn new(config: HooksConfig) -> Self {
+        Self { config }
+    }
+
+    /// The command for `event` and the name passed in `MEETING_EVENT`
+    fn command<'a>(&'a self, event: &'a MeetingEvent) -> Option<(&'a str, &'a Meeting, &'static str)> {
+        let (command, meeting, name) = match event {
+            MeetingEvent::Started(meeting) => (&self.config.meeting_start, meeting, "start"),
+            MeetingEvent::Ended(meeting) => (&self.config.meeting_end, meeting, "end"),
+            MeetingEvent::Reminder(meeting) => (&self.config.meeting_reminder, meeting, "reminder"),
+            MeetingEvent::Agenda(_) => return None,
+        };
+        command.as_deref().map(|command| (command, meeting, name))
+    }
+
+    async fn run(&self, command: &str, env: Vec<(&'static str, String)>) -> Result<()> {
+        let mut shell = shell(command);
+        shell
+            .envs(env)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        let child = shell.spawn().map_err(|e| anyhow!("Failed to run hook '{}': {}", command, e))?;
+
+        let timeout = std::time::Duration::from_secs(self.config.timeout_seconds);
+        // Dropping the unfinished child kills it
+        let output = tokio::time::timeout(timeout, child.wait_with_output())
+            .await
+            .map_err(|_| anyhow!("Hook '{}' did not finish within {}s and was killed", command, self.config.timeout_seconds))?
+            .map_err(|e| anyhow!("Failed to run hook '{}': {}", command, e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("Hook '{}' failed ({}): {}", command, output.status, stderr.trim()));
+        }
+        Ok(())
+    }
+}
+
+/// The variables describing `meeting` to a hook, as of `now`
+pub fn meeting_env(event: &str, meeting: &Meeting, now: DateTime<Utc>) -> Vec<(&'static str, String)> {
+    vec![
+        ("MEETING_EVENT", event.to_string()),
+        ("MEETING_ID", meeting.id.clone()),
+        ("MEETING_TITLE", meeting.title.clone()),
+        ("MEETING_START", meeting.start_time.to_rfc3339()),
+        ("MEETING_END", meeting.end_time.to_rfc3339()),
+        ("MEETING_LOCATION", meeting.location.clone().unwrap_or_default()),
+        ("MEETING_URL", meeting.url().unwrap_or_default()),
+        ("SECONDS_UNTIL_START", (meeting.start_time - now).num_seconds().to_string()),
+        ("SECONDS_UNTIL_END", (meeting.end_time - now).num_seconds().to_string()),
+    ]
+}
+
+#[cfg(not(windows))]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+#[async_trait]
+impl Notifier for ShellHooks {
+    fn name(&self) -> &str {
+        "Hooks"
+    }
+
+    fn remind_before(&self) -> Option<Duration> {
+        self.config
+            .meeting_reminder
+            .as_ref()
+            .map(|_| Duration::minutes(self.config.remind_minutes_before.into()))
+    }
+
+    /// Scripts muting speakers or switching scenes follow the meetings
+    /// whatever the time
+    fn silent_in_quiet_hours(&self) -> bool {
+        false
+    }
+
+    async fn notify(&self, event: &MeetingEvent) -> Result<()> {
+        if let Some((command, meeting, name)) = self.command(event) {
+            self.run(command, meeting_env(name, meeting, Utc::now())).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+use anyhow::{anyhow, Result};
+use std::time::Duration;
+
+use crate::config::{redact_url_password, HttpConfig, IcsSourceConfig};
+
+/// Build the shared outbound HTTP client used for ICS downloads and Google API calls
+pub fn build_client(config: &HttpConfig) -> Result<reqwest::Client> {
+    client_builder(config)?
+        .build()
+        .map_err(|e| anyhow!("Failed to build HTTP client: {}", e))
+}
+
+/// Build the HTTP client used for OAuth token requests.
+///
+/// Same timeouts and proxy as the shared client, but redirects are disabled:
+/// following redirects on token requests opens the client up to SSRF.
+pub fn build_oauth_client(config: &HttpConfig) -> Result<reqwest::Client> {
+    client_builder(config)?
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| anyhow!("Failed to build OAuth HTTP client: {}", e))
+}
+
+/// Build an HTTP client for an ICS source with its own TLS options
+pub fn build_source_client(config: &HttpConfig, source: &IcsSourceConfig) -> Result<reqwest::Client> {
+    let mut builder = client_builder(config)?;
+
+    if let Some(ca_path) = &source.ca_cert {
+        let pem = std::fs::read(ca_path)
+            .map_err(|e| anyhow!("Failed to read CA certificate {}: {}", ca_path, e))?;
+        let certificates = parse_pem_bundle(&pem)
+            .map_err(|e| anyhow!("Invalid CA certificate bundle {}: {}", ca_path, e))?;
+        for certificate in certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    if source.accept_invalid_certs {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+
+    builder
+        .build()
+        .map_err(|e| anyhow!("Failed to build HTTP client for {}: {}", source.path, e))
+}
+
+/// Split a PEM bundle into individual certificates
+fn parse_pem_bundle(pem: &[u8]) -> Result<Vec<reqwest::Certificate>> {
+    const BEGIN: &str = "-----BEGIN CERTIFICATE-----";
+    let text = std::str::from_utf8(pem).map_err(|e| anyhow!("not valid UTF-8: {}", e))?;
+
+    let certificates = text
+        .match_indices(BEGIN)
+        .map(|(start, _)| {
+            let block = &text[start..];
+            let end = block.find("-----END CERTIFICATE-----")
+                .map(|i| i + "-----END CERTIFICATE-----".len())
+                .ok_or_else(|| anyhow!("unterminated certificate"))?;
+            reqwest::Certificate::from_pem(&block.as_bytes()[..end]).map_err(|e| anyhow!("{}", e))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if certificates.is_empty() {
+        return Err(anyhow!("no certificates found"));
+    }
+    Ok(certificates)
+}
+
+fn client_builder(config: &HttpConfig) -> Result<reqwest::ClientBuilder> {
+    let mut builder = reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(config.connect_timeout_seconds))
+        .timeout(Duration::from_secs(config.request_timeout_seconds));
+
+    // Without an explicit proxy reqwest falls back to HTTP_PROXY/HTTPS_PROXY
+    if let Some(proxy_url) = &config.proxy {
+        // Proxy URLs often carry credentials, which stay out of logs and errors
+        let proxy = reqwest::Proxy::all(proxy_url.as_str())
+            .map_err(|e| anyhow!("Invalid HTTP proxy URL: {}", e.without_url()))?
+            .no_proxy(config.no_proxy.as_deref().and_then(reqwest::NoProxy::from_string));
+        let mut shown = proxy_url.clone();
+        redact_url_password(&mut shown);
+        tracing::info!("Using HTTP proxy {} for calendar fetching", shown);
+        builder = builder.proxy(proxy);
+    }
+
+    Ok(builder)
+}
@@ -0,0 +1,176 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::app::MeetingUpdate;
+use crate::busylight::Rgb;
+use crate::config::HueConfig;
+use crate::events::{Event, NotificationBackend};
+
+/// How long to wait before trying a state again that the bridge did not take
+const RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// What the Hue light or room shows in a state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HueAction {
+    Color(Rgb),
+    Off,
+    /// A scene of the configured group, by id
+    Scene(String),
+}
+
+impl HueAction {
+    /// `#rrggbb`, `off` or `scene:<id>`
+    pub fn parse(action: &str) -> Result<Self> {
+        if action.eq_ignore_ascii_case("off") {
+            return Ok(HueAction::Off);
+        }
+        if let Some(scene) = action.strip_prefix("scene:") {
+            if scene.trim().is_empty() {
+                return Err(anyhow!("'{}' names no scene", action));
+            }
+            return Ok(HueAction::Scene(scene.trim().to_string()));
+        }
+        Rgb::parse(action)
+            .map(HueAction::Color)
+            .map_err(|_| anyhow!("'{}' is neither a #rrggbb color, off nor scene:<id>", action))
+    }
+}
+
+/// The meeting state a Hue light shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoomState {
+    Busy,
+    /// The current meeting ends within `ending_soon_minutes`
+    EndingSoon,
+    Free,
+}
+
+impl RoomState {
+    pub fn of(update: &MeetingUpdate, ending_soon: chrono::Duration) -> Self {
+        if update.current_meeting.is_none() {
+            RoomState::Free
+        } else if update.countdown_seconds.is_some_and(|seconds| seconds <= ending_soon.num_seconds()) {
+            RoomState::EndingSoon
+        } else {
+            RoomState::Busy
+        }
+    }
+}
+
+/// CIE xy coordinates of a color, which is how Hue lights take colors
+pub fn xy(color: Rgb) -> [f64; 2] {
+    let linear = |channel: u8| {
+        let value = f64::from(channel) / 255.0;
+        if value > 0.04045 {
+            ((value + 0.055) / 1.055).powf(2.4)
+        } else {
+            value / 12.92
+        }
+    };
+    let (red, green, blue) = (linear(color.0), linear(color.1), linear(color.2));
+    let x = red * 0.664511 + green * 0.154324 + blue * 0.162028;
+    let y = red * 0.283881 + green * 0.668433 + blue * 0.047685;
+    let z = red * 0.000088 + green * 0.072310 + blue * 0.986039;
+    let sum = x + y + z;
+    if sum == 0.0 {
+        // Black has no chromaticity; the white point will do
+        return [0.3127, 0.329];
+    }
+    let round = |value: f64| (value * 10_000.0).round() / 10_000.0;
+    [round(x / sum), round(y / sum)]
+}
+
+/// Sets the `[hue]` light or room from the meeting updates on the event bus
+pub struct HueLights {
+    http_client: reqwest::Client,
+    config: HueConfig,
+    /// Busy, ending soon and free
+    actions: [HueAction; 3],
+    /// Last action sent, whether the bridge took it, and when
+    sent: Mutex<Option<(HueAction, bool, Instant)>>,
+}
+
+impl HueLights {
+    pub fn new(http_client: reqwest::Client, config: HueConfig) -> Self {
+        // Checked when the configuration was loaded
+        let action = |action: &str| HueAction::parse(action).unwrap_or(HueAction::Off);
+        let actions = [action(&config.busy), action(&config.ending_soon), action(&config.free)];
+        Self { http_client, config, actions, sent: Mutex::new(None) }
+    }
+
+    /// What to show for this update
+    pub fn action(&self, update: &MeetingUpdate) -> &HueAction {
+        let [busy, ending_soon, free] = &self.actions;
+        match RoomState::of(update, chrono::Duration::minutes(self.config.ending_soon_minutes.into())) {
+            RoomState::Busy => busy,
+            RoomState::EndingSoon => ending_soon,
+            RoomState::Free => free,
+        }
+    }
+
+    /// Whether `action` has to be sent now: it is new, or the bridge did
+    /// not take it a while ago
+    fn due(&self, action: &HueAction) -> bool {
+        let sent = self.sent.lock().unwrap_or_else(|e| e.into_inner());
+        sent.as_ref().is_none_or(|(previous, ok, at)| previous != action || (!ok && at.elapsed() >= RETRY_INTERVAL))
+    }
+
+    /// The bridge resource to change and the state to put there
+    pub fn request(&self, action: &HueAction) -> (String, Value) {
+        let path = match (&self.config.light, &self.config.group) {
+            (Some(light), _) => format!("lights/{}/state", light),
+            (None, group) => format!("groups/{}/action", group.as_deref().unwrap_or("0")),
+        };
+        let body = match action {
+            HueAction::Color(color) => json!({ "on": true, "xy": xy(*color), "bri": self.config.brightness }),
+            HueAction::Off => json!({ "on": false }),
+            HueAction::Scene(scene) => json!({ "scene": scene }),
+        };
+        (path, body)
+    }
+
+    async fn apply(&self, action: &HueAction) -> Result<()> {
+        let (path, body) = self.request(action);
+        let bridge = self.config.bridge.trim_end_matches('/');
+        let base = if bridge.contains("://") { bridge.to_string() } else { format!("http://{}", bridge) };
+        let url = format!("{}/api/{}/{}", base, self.config.api_key.as_deref().unwrap_or_default(), path);
+        let response = self
+            .http_client
+            .put(url)
+            .json(&body)
+            .send()
+            .await
+            // The URL carries the API key
+            .map_err(|e| anyhow!("Hue bridge request failed: {}", e.without_url()))?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Hue bridge returned HTTP {}", response.status()));
+        }
+        // Errors come back as HTTP 200 with a list of results
+        let results: Vec<Value> = response.json().await.map_err(|e| anyhow!("Invalid Hue bridge response: {}", e))?;
+        if let Some(description) = results.iter().find_map(|result| result["error"]["description"].as_str()) {
+            return Err(anyhow!("Hue bridge refused {}: {}", path, description));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl NotificationBackend for HueLights {
+    fn name(&self) -> &str {
+        "Hue"
+    }
+
+    async fn handle(&self, event: &Event) -> Result<()> {
+        let Event::Update(update) = event else { return Ok(()) };
+        let action = self.action(update).clone();
+        if !self.due(&action) {
+            return Ok(());
+        }
+        let result = self.apply(&action).await;
+        *self.sent.lock().unwrap_or_else(|e| e.into_inner()) = Some((action, result.is_ok(), Instant::now()));
+        result
+    }
+}
@@ -0,0 +1,113 @@
+use chrono::{DateTime, Local, Utc};
+use std::collections::HashMap;
+
+use crate::meeting::{Meeting, ResponseStatus};
+
+const PRODID: &str = "-//SystemCraftsman//calendar-monitor//EN";
+
+/// Escape a TEXT value (RFC 5545 section 3.3.11)
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line into chunks of at most 75 octets, continuation lines
+/// starting with a space. Lines are not folded next to a space where it can
+/// be avoided, as some parsers trim whitespace around folds.
+fn fold(line: &str) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut folded = String::with_capacity(line.len() + 8);
+    let mut start = 0;
+    while start < chars.len() {
+        // Continuation lines lose one octet to the leading space
+        let limit = if start == 0 { 75 } else { 74 };
+        let mut end = start;
+        let mut octets = 0;
+        while end < chars.len() && octets + chars[end].len_utf8() <= limit {
+            octets += chars[end].len_utf8();
+            end += 1;
+        }
+        if end < chars.len() {
+            let mut preferred = end;
+            while preferred > start + 1 && (chars[preferred] == ' ' || chars[preferred - 1] == ' ') {
+                preferred -= 1;
+            }
+            if preferred > start + 1 {
+                end = preferred;
+            }
+        }
+
+        if start > 0 {
+            folded.push(' ');
+        }
+        folded.extend(&chars[start..end]);
+        folded.push_str("\r\n");
+        start = end;
+    }
+    folded
+}
+
+fn utc_stamp(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// UID of an exported event. Occurrences of a recurring event share their
+/// series UID, so each gets its start appended to stay unique.
+fn export_uid(meeting: &Meeting, uid_counts: &HashMap<&str, usize>) -> String {
+    match meeting.uid.as_deref() {
+        Some(uid) if uid_counts.get(uid) == Some(&1) => uid.to_string(),
+        Some(uid) => format!("{}-{}", uid, utc_stamp(meeting.start_time)),
+        None => format!("{}@calendar-monitor", meeting.id),
+    }
+}
+
+/// Write meetings as an iCalendar feed
+pub fn to_ics(meetings: &[Meeting], calendar_name: &str) -> String {
+    let mut uid_counts: HashMap<&str, usize> = HashMap::new();
+    for uid in meetings.iter().filter_map(|m| m.uid.as_deref()) {
+        *uid_counts.entry(uid).or_default() += 1;
+    }
+
+    let now = utc_stamp(Utc::now());
+    let mut ics = String::new();
+    ics.push_str("BEGIN:VCALENDAR\r\n");
+    ics.push_str("VERSION:2.0\r\n");
+    ics.push_str(&format!("PRODID:{}\r\n", PRODID));
+    ics.push_str("CALSCALE:GREGORIAN\r\n");
+    ics.push_str(&fold(&format!("X-WR-CALNAME:{}", escape_text(calendar_name))));
+
+    for meeting in meetings {
+        ics.push_str("BEGIN:VEVENT\r\n");
+        ics.push_str(&fold(&format!("UID:{}", export_uid(meeting, &uid_counts))));
+        ics.push_str(&format!("DTSTAMP:{}\r\n", now));
+        if meeting.all_day {
+            // All-day events start at local midnight
+            let date = |time: DateTime<Utc>| time.with_timezone(&Local).format("%Y%m%d").to_string();
+            ics.push_str(&format!("DTSTART;VALUE=DATE:{}\r\n", date(meeting.start_time)));
+            ics.push_str(&format!("DTEND;VALUE=DATE:{}\r\n", date(meeting.end_time)));
+        } else {
+            ics.push_str(&format!("DTSTART:{}\r\n", utc_stamp(meeting.start_time)));
+            ics.push_str(&format!("DTEND:{}\r\n", utc_stamp(meeting.end_time)));
+        }
+        ics.push_str(&fold(&format!("SUMMARY:{}", escape_text(&meeting.title))));
+        if let Some(description) = &meeting.description {
+            ics.push_str(&fold(&format!("DESCRIPTION:{}", escape_text(description))));
+        }
+        if let Some(location) = &meeting.location {
+            ics.push_str(&fold(&format!("LOCATION:{}", escape_text(location))));
+        }
+        if let Some(source) = &meeting.source {
+            ics.push_str(&fold(&format!("CATEGORIES:{}", escape_text(&source.name))));
+        }
+        if meeting.response_status == Some(ResponseStatus::Tentative) {
+            ics.push_str("STATUS:TENTATIVE\r\n");
+        }
+        ics.push_str("END:VEVENT\r\n");
+    }
+
+    ics.push_str("END:VCALENDAR\r\n");
+    ics
+}
@@ -0,0 +1,220 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use crate::calendar::SourceError;
+use crate::config::{IssueProvider, IssueSourceConfig};
+use crate::meeting::{Meeting, MeetingSource};
+use crate::recurrence::Window;
+use crate::source::CalendarSource;
+
+const GITHUB_API_URL: &str = "https://api.github.com";
+const GITLAB_API_URL: &str = "https://gitlab.com/api/v4";
+
+/// Due dates change rarely, and both APIs are rate limited
+const REFRESH_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Debug, Deserialize)]
+struct GitHubMilestone {
+    title: String,
+    html_url: String,
+    due_on: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabMilestone {
+    title: String,
+    web_url: Option<String>,
+    due_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabIssue {
+    iid: u64,
+    title: String,
+    web_url: String,
+    due_date: Option<NaiveDate>,
+}
+
+/// Something in a repository that is due on a given day
+#[derive(Debug, Clone, PartialEq)]
+pub struct DueItem {
+    pub title: String,
+    pub url: Option<String>,
+    pub due: NaiveDate,
+}
+
+impl DueItem {
+    fn into_meeting(self, source: &MeetingSource) -> Option<Meeting> {
+        let mut meeting = Meeting::new_all_day(self.title, self.due, self.due.succ_opt()?)
+            .with_source(source.clone());
+        if let Some(url) = self.url {
+            meeting = meeting.with_description(url);
+        }
+        Some(meeting)
+    }
+}
+
+#[derive(Default)]
+struct DueDateCache {
+    meetings: Vec<Meeting>,
+    fetched_at: Option<Instant>,
+    /// Latest failed refresh, until one succeeds
+    failure: Option<SourceError>,
+}
+
+/// Milestones (and on GitLab, issues) with due dates from one repository,
+/// shown as all-day events
+#[derive(Clone)]
+pub struct IssueSource {
+    http_client: reqwest::Client,
+    config: IssueSourceConfig,
+    token: Option<String>,
+    cache: Arc<Mutex<DueDateCache>>,
+}
+
+impl IssueSource {
+    pub fn new(http_client: reqwest::Client, config: IssueSourceConfig) -> Self {
+        let token_variable = match config.provider {
+            IssueProvider::GitHub => "GITHUB_TOKEN",
+            IssueProvider::GitLab => "GITLAB_TOKEN",
+        };
+        let token = config.token.clone().or_else(|| crate::config::env_var(token_variable));
+        Self {
+            http_client,
+            config,
+            token,
+            cache: Arc::new(Mutex::new(DueDateCache::default())),
+        }
+    }
+
+    fn api_url(&self) -> &str {
+        let default = match self.config.provider {
+            IssueProvider::GitHub => GITHUB_API_URL,
+            IssueProvider::GitLab => GITLAB_API_URL,
+        };
+        self.config.api_url.as_deref().unwrap_or(default).trim_end_matches('/')
+    }
+
+    /// Why the latest refresh failed, until one succeeds
+    pub async fn failure(&self) -> Option<SourceError> {
+        self.cache.lock().await.failure.clone()
+    }
+
+    /// Everything with a due date in the repository
+    pub async fn fetch_due_items(&self) -> Result<Vec<DueItem>> {
+        match self.config.provider {
+            IssueProvider::GitHub => self.fetch_github_due_items().await,
+            IssueProvider::GitLab => self.fetch_gitlab_due_items().await,
+        }
+    }
+
+    async fn fetch_github_due_items(&self) -> Result<Vec<DueItem>> {
+        let url = format!("{}/repos/{}/milestones?state=open&per_page=100", self.api_url(), self.config.repository);
+        let milestones: Vec<GitHubMilestone> = self.get_json(&url).await?;
+
+        Ok(milestones
+            .into_iter()
+            .filter_map(|milestone| {
+                Some(DueItem {
+                    due: milestone.due_on?.date_naive(),
+                    title: format!("Milestone: {}", milestone.title),
+                    url: Some(milestone.html_url),
+                })
+            })
+            .collect())
+    }
+
+    async fn fetch_gitlab_due_items(&self) -> Result<Vec<DueItem>> {
+        let project = urlencoding::encode(&self.config.repository);
+
+        let url = format!("{}/projects/{}/milestones?state=active&per_page=100", self.api_url(), project);
+        let milestones: Vec<GitLabMilestone> = self.get_json(&url).await?;
+        let url = format!("{}/projects/{}/issues?state=opened&due_date=any&per_page=100", self.api_url(), project);
+        let issues: Vec<GitLabIssue> = self.get_json(&url).await?;
+
+        let milestones = milestones.into_iter().filter_map(|milestone| {
+            Some(DueItem {
+                due: milestone.due_date?,
+                title: format!("Milestone: {}", milestone.title),
+                url: milestone.web_url,
+            })
+        });
+        let issues = issues.into_iter().filter_map(|issue| {
+            Some(DueItem {
+                due: issue.due_date?,
+                title: format!("#{} {}", issue.iid, issue.title),
+                url: Some(issue.web_url),
+            })
+        });
+        Ok(milestones.chain(issues).collect())
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let mut request = self.http_client.get(url);
+        request = match self.config.provider {
+            IssueProvider::GitHub => {
+                // GitHub rejects requests without a User-Agent
+                let request = request
+                    .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+                    .header(reqwest::header::USER_AGENT, "calendar-monitor");
+                match &self.token {
+                    Some(token) => request.bearer_auth(token),
+                    None => request,
+                }
+            }
+            IssueProvider::GitLab => match &self.token {
+                Some(token) => request.header("PRIVATE-TOKEN", token),
+                None => request,
+            },
+        };
+
+        let response = request.send().await
+            .map_err(|e| anyhow!("Failed to query {}: {}", url, e))?;
+        if !response.status().is_success() {
+            return Err(anyhow!("HTTP error {} when querying {}", response.status(), url));
+        }
+        response.json().await
+            .map_err(|e| anyhow!("Invalid response from {}: {}", url, e))
+    }
+}
+
+#[async_trait]
+impl CalendarSource for IssueSource {
+    fn name(&self) -> &str {
+        &self.config.repository
+    }
+
+    /// Due dates in the window, refreshed every few minutes. A failed refresh
+    /// keeps the previous due dates until the next attempt.
+    async fn fetch_events(&self, window: &Window) -> Result<Vec<Meeting>> {
+        let mut cache = self.cache.lock().await;
+        let fresh = cache.fetched_at.is_some_and(|fetched_at| fetched_at.elapsed() < REFRESH_INTERVAL);
+        if !fresh {
+            match self.fetch_due_items().await {
+                Ok(items) => {
+                    tracing::info!("Loaded {} due dates from {}", items.len(), self.config.repository);
+                    let source = self.config.meeting_source();
+                    cache.meetings = items.into_iter().filter_map(|item| item.into_meeting(&source)).collect();
+                    cache.failure = None;
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to fetch due dates from {}, keeping the previous ones: {}", self.config.repository, e);
+                    cache.failure = Some(SourceError::continuing(cache.failure.as_ref(), &self.config.meeting_source().name, &e));
+                }
+            }
+            cache.fetched_at = Some(Instant::now());
+        }
+
+        Ok(cache.meetings
+            .iter()
+            .filter(|m| window.overlaps(m.start_time, m.end_time))
+            .cloned()
+            .collect())
+    }
+}
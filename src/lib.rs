@@ -1,4 +1,41 @@
+pub mod check;
+pub mod cli;
 pub mod config;
+pub mod filter;
+pub mod profile;
+pub mod events;
+pub mod notify;
+pub mod slack;
+pub mod teams;
+pub mod discord;
+pub mod telegram;
+pub mod email;
+pub mod desktop;
+pub mod webhook;
+pub mod hooks;
+pub mod busylight;
+pub mod hue;
+pub mod gpio;
+pub mod scripting;
 pub mod meeting;
 pub mod calendar;
+pub mod source;
+pub mod recurrence;
 pub mod google_calendar;
+pub mod app;
+pub mod http;
+pub mod systemd;
+pub mod oidc;
+pub mod caldav;
+pub mod holidays;
+pub mod issues;
+pub mod free_slots;
+pub mod badge;
+pub mod eink;
+pub mod ics_export;
+pub mod csv_export;
+pub mod graphql;
+pub mod local_meetings;
+pub mod hidden_meetings;
+pub mod stats;
+pub mod payload;
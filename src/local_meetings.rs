@@ -0,0 +1,134 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::meeting::{Meeting, MeetingSource};
+use crate::recurrence::Window;
+use crate::source::CalendarSource;
+
+/// Ended meetings are dropped from the store once they are this old
+const KEEP_ENDED_FOR: Duration = Duration::days(7);
+
+/// Most meetings the store holds, so the file can't grow without bound
+const MAX_MEETINGS: usize = 1000;
+
+/// Meetings created on the monitor itself (POST /api/meetings), e.g. walk-in
+/// bookings on a room display without write access to the calendar. They are
+/// kept in a JSON file so they survive restarts.
+#[derive(Clone, Default)]
+pub struct LocalMeetingStore {
+    meetings: Arc<Mutex<Vec<Meeting>>>,
+    path: Option<PathBuf>,
+}
+
+impl LocalMeetingStore {
+    /// A store that is lost on restart
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// A store persisted to `path`, starting with the meetings saved there
+    pub fn open(path: PathBuf) -> Self {
+        let meetings = match std::fs::read_to_string(&path) {
+            Ok(contents) => match serde_json::from_str::<Vec<Meeting>>(&contents) {
+                Ok(meetings) => {
+                    tracing::info!("Loaded {} local meetings from {}", meetings.len(), path.display());
+                    meetings
+                }
+                Err(e) => {
+                    tracing::warn!("Ignoring unreadable local meetings {}: {}", path.display(), e);
+                    Vec::new()
+                }
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                tracing::warn!("Failed to read local meetings {}: {}", path.display(), e);
+                Vec::new()
+            }
+        };
+        Self {
+            meetings: Arc::new(Mutex::new(meetings)),
+            path: Some(path),
+        }
+    }
+
+    /// Attribution of meetings created on the monitor
+    pub fn source() -> MeetingSource {
+        MeetingSource::new("local".to_string(), "Local".to_string(), None)
+    }
+
+    pub async fn meetings(&self) -> Vec<Meeting> {
+        self.meetings.lock().await.clone()
+    }
+
+    /// Store a meeting, dropping ones that ended a while ago
+    pub async fn add(&self, meeting: Meeting) -> Result<Meeting> {
+        let meeting = meeting.with_source(Self::source());
+        let mut meetings = self.meetings.lock().await;
+        if meetings.iter().any(|m| m.id == meeting.id) {
+            return Err(anyhow!("A meeting '{}' at {} already exists", meeting.title, meeting.start_time));
+        }
+
+        let mut updated = meetings.clone();
+        let cutoff = Utc::now() - KEEP_ENDED_FOR;
+        updated.retain(|m| m.end_time > cutoff);
+        if updated.len() >= MAX_MEETINGS {
+            return Err(anyhow!("No room for more than {} local meetings", MAX_MEETINGS));
+        }
+        updated.push(meeting.clone());
+        updated.sort_by_key(|m| m.start_time);
+
+        self.save(&updated).await?;
+        *meetings = updated;
+        Ok(meeting)
+    }
+
+    /// Delete a meeting; false if there is none with that id
+    pub async fn remove(&self, id: &str) -> Result<bool> {
+        let mut meetings = self.meetings.lock().await;
+        let mut updated = meetings.clone();
+        updated.retain(|m| m.id != id);
+        if updated.len() == meetings.len() {
+            return Ok(false);
+        }
+
+        self.save(&updated).await?;
+        *meetings = updated;
+        Ok(true)
+    }
+
+    /// Write via a temporary file so a crash never leaves a truncated store behind
+    async fn save(&self, meetings: &[Meeting]) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let json = serde_json::to_vec_pretty(meetings)
+            .map_err(|e| anyhow!("Failed to serialize local meetings: {}", e))?;
+        let tmp_path = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp_path, json).await
+            .map_err(|e| anyhow!("Failed to write local meetings {}: {}", tmp_path.display(), e))?;
+        tokio::fs::rename(&tmp_path, path).await
+            .map_err(|e| anyhow!("Failed to replace local meetings {}: {}", path.display(), e))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CalendarSource for LocalMeetingStore {
+    fn name(&self) -> &str {
+        "local"
+    }
+
+    async fn fetch_events(&self, window: &Window) -> Result<Vec<Meeting>> {
+        Ok(self.meetings
+            .lock()
+            .await
+            .iter()
+            .filter(|m| window.overlaps(m.start_time, m.end_time))
+            .cloned()
+            .collect())
+    }
+}
@@ -1,77 +1,52 @@
-use axum::{
-    extract::{Query, ws::{Message, WebSocket, WebSocketUpgrade}, State},
-    response::{Html, IntoResponse},
-    routing::get,
-    http::{StatusCode, HeaderMap, header},
-    Json, Router,
-};
-use local_ip_address::local_ip;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
-use std::time::Duration;
-use tokio::time::interval;
-use tower_http::cors::CorsLayer;
-use tracing::{info, warn};
+use std::sync::Arc;
+use clap::Parser;
+use tracing::{error, info, warn};
+use tracing::level_filters::LevelFilter;
 use chrono::Utc;
 
-mod config;
-mod calendar;
-mod meeting;
-mod google_calendar;
-
-use config::Config;
-use calendar::CalendarService;
-use meeting::Meeting;
-use google_calendar::{GoogleCalendarService, GoogleTokens};
-
-// Embed static files into the binary
-const STYLE_CSS: &str = include_str!("../static/style.css");
-const APP_JS: &str = include_str!("../static/app.js");
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MeetingUpdate {
-    pub current_meeting: Option<Meeting>,
-    pub next_meeting: Option<Meeting>,
-    pub countdown_seconds: Option<i64>,
-    pub active_time_blocks: Vec<Meeting>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ServerInfo {
-    pub local_ip: String,
-    pub server_port: u16,
-}
-
-// Global state for Google Calendar tokens
-type GoogleTokensStore = Arc<Mutex<Option<GoogleTokens>>>;
-
-#[derive(Clone)]
-pub struct AppState {
-    pub config: Arc<Config>,
-    pub google_tokens: GoogleTokensStore,
-}
+use calendar_monitor::app::{build_router, run_notifications, run_telegram_bot, run_watchdog, serve, start_device_authorization, AppState, DeviceAuthorizationStatus};
+use calendar_monitor::check;
+use calendar_monitor::cli::{Cli, Command};
+use calendar_monitor::config::{AuthFlow, Config};
+use calendar_monitor::systemd;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // Initialize tracing
-    tracing_subscriber::fmt::init();
+    // Exits here for --help, --version and invalid flags
+    let cli = Cli::parse();
+
+    // Initialize tracing; `check` reports failures itself, so only errors by default
+    let default_level = match cli.command {
+        Some(Command::Check) => LevelFilter::ERROR,
+        None => LevelFilter::INFO,
+    };
+    tracing_subscriber::fmt()
+        .with_max_level(cli.log_level.unwrap_or(default_level))
+        .init();
 
     // Load environment variables from .env file (for development)
     if let Err(_e) = dotenv::dotenv() {
         // .env file is optional - don't warn in production
     }
 
+    if let Some(Command::Check) = cli.command {
+        let report = check::run(&cli).await;
+        print!("{}", report);
+        std::process::exit(report.exit_code());
+    }
+
     info!("Starting Calendar Monitor application");
 
     // Load configuration
-    let config = match Config::load() {
+    let config = match Config::load_with(&cli) {
         Ok(config) => {
             info!("Configuration loaded successfully");
             Arc::new(config)
         }
+        // A file asked for by name is never silently replaced by defaults
+        Err(e) if cli.config.is_some() || cli.strict => return Err(e),
         Err(e) => {
-            warn!("Failed to load configuration: {}", e);
+            error!("Failed to load configuration: {}", e);
             info!("Using default configuration with environment variables");
             // Fallback to default config with env vars
             let mut default_config = Config::default();
@@ -81,6 +56,7 @@ async fn main() -> anyhow::Result<()> {
                     env_err, e
                 ));
             }
+            cli.apply(&mut default_config);
             if let Err(validation_err) = default_config.validate() {
                 return Err(anyhow::anyhow!(
                     "Configuration validation failed: {}", 
@@ -91,466 +67,95 @@ async fn main() -> anyhow::Result<()> {
         }
     };
 
-    // Load any saved Google Calendar tokens
-    let saved_tokens = match Config::load_google_tokens() {
-        Ok(tokens) => {
-            if let Some(ref token_info) = tokens {
-                if let Some(expires_at) = token_info.expires_at {
-                    let now = Utc::now();
-                    if expires_at > now {
-                        let minutes_until_expiry = (expires_at - now).num_minutes();
-                        info!("Loaded saved Google Calendar tokens from disk - expires in {} minutes", minutes_until_expiry);
+    // Load any saved Google Calendar tokens (service accounts fetch their own)
+    let saved_tokens = if config.google.service_account_key_file.is_some() {
+        info!("Using a Google service account, no saved tokens needed");
+        None
+    } else {
+        match config.load_google_tokens() {
+            Ok(tokens) => {
+                if let Some(ref token_info) = tokens {
+                    if let Some(expires_at) = token_info.expires_at {
+                        let now = Utc::now();
+                        if expires_at > now {
+                            let minutes_until_expiry = (expires_at - now).num_minutes();
+                            info!("Loaded saved Google Calendar tokens from disk - expires in {} minutes", minutes_until_expiry);
+                        } else {
+                            let minutes_expired = (now - expires_at).num_minutes();
+                            info!("Loaded saved Google Calendar tokens from disk - expired {} minutes ago (will auto-refresh)", minutes_expired);
+                        }
                     } else {
-                        let minutes_expired = (now - expires_at).num_minutes();
-                        info!("Loaded saved Google Calendar tokens from disk - expired {} minutes ago (will auto-refresh)", minutes_expired);
+                        info!("Loaded saved Google Calendar tokens from disk - no expiration info");
                     }
-                } else {
-                    info!("Loaded saved Google Calendar tokens from disk - no expiration info");
                 }
+                tokens
+            },
+            Err(e) => {
+                warn!("Failed to load saved Google Calendar tokens: {}", e);
+                None
             }
-            tokens
-        },
-        Err(e) => {
-            warn!("Failed to load saved Google Calendar tokens: {}", e);
-            None
         }
     };
 
-    // Create shared state for Google tokens
-    let app_state = AppState {
-        config: config.clone(),
-        google_tokens: Arc::new(Mutex::new(saved_tokens)),
+    // Keep the last good calendar fetch on disk so a restart without network still shows
+    // events, along with the meetings created or hidden on the monitor
+    let data_dir = match Config::get_data_dir() {
+        Ok(data_dir) => Some(data_dir),
+        Err(e) => {
+            warn!("Offline snapshot, local and hidden meetings disabled: {}", e);
+            None
+        }
     };
 
-    // Build our application with routes
-    let app = Router::new()
-        .route("/", get(index))
-        .route("/ws", get(websocket_handler))
-        .route("/api/meetings", get(get_meetings))
-        .route("/api/server-info", get(get_server_info))
-        .route("/auth/google/login", get(google_auth_login))
-        .route("/auth/google/callback", get(google_auth_callback))
-        .route("/auth/google/status", get(google_auth_status))
-        .route("/static/style.css", get(serve_css))
-        .route("/static/app.js", get(serve_js))
-        .layer(CorsLayer::permissive())
-        .with_state(app_state);
-
-    // Run the server
-    let bind_address = config.bind_address();
-    let listener = tokio::net::TcpListener::bind(&bind_address).await?;
-    info!("Server running on http://{}", bind_address);
-    
-    axum::serve(listener, app).await?;
-
-    Ok(())
-}
-
-async fn index() -> impl IntoResponse {
-    Html(include_str!("../templates/index.html"))
-}
-
-async fn websocket_handler(
-    ws: WebSocketUpgrade,
-    State(state): State<AppState>
-) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_socket(socket, state))
-}
-
-async fn handle_socket(mut socket: WebSocket, state: AppState) {
-    let mut interval = interval(Duration::from_secs(1));
-    let calendar_service = CalendarService::new_from_config(&state.config);
-
-    loop {
-        interval.tick().await;
-        
-        // Get regular meetings and active time blocks from ICS sources
-        let meetings_result = calendar_service.get_current_and_next_meetings().await;
-        let time_blocks_result = calendar_service.get_active_time_blocks().await;
-        
-        // Try to get Google Calendar events and merge them
-        let google_meetings = match GoogleCalendarService::new_from_config(&state.config) {
-            Ok(Some(mut google_service)) => {
-                // Set stored tokens if available
-                if let Ok(tokens_guard) = state.google_tokens.lock() {
-                    if let Some(ref tokens) = *tokens_guard {
-                        google_service.set_tokens(tokens.clone());
-                    }
-                }
-                
-                // Refresh token if needed
-                match google_service.refresh_token_if_needed().await {
-                    Ok(true) => {
-                        // Token was refreshed, update stored tokens
-                        if let Some(new_tokens) = google_service.get_tokens() {
-                            if let Ok(mut tokens_guard) = state.google_tokens.lock() {
-                                *tokens_guard = Some(new_tokens);
-                                tracing::info!("WebSocket: Updated stored tokens after refresh");
-                            }
-                        }
-                    },
-                    Ok(false) => {
-                        // No refresh needed
-                    },
-                    Err(e) => {
-                        tracing::warn!("WebSocket: Failed to refresh Google Calendar tokens: {}", e);
-                    }
-                }
-                
-                if google_service.is_authenticated() {
-                    match google_service.get_calendar_events().await {
-                        Ok(events) => {
-                            tracing::info!("WebSocket: Fetched {} Google Calendar events", events.len());
-                            events
-                        },
-                        Err(e) => {
-                            tracing::warn!("WebSocket: Failed to fetch Google Calendar events: {}", e);
-                            Vec::new()
-                        }
-                    }
-                } else {
-                    tracing::info!("WebSocket: Google Calendar not authenticated or tokens expired");
-                    Vec::new()
-                }
-            }
-            Ok(None) => {
-                tracing::debug!("WebSocket: Google OAuth not configured");
-                Vec::new()
-            },
-            Err(e) => {
-                tracing::warn!("WebSocket: Failed to create Google Calendar service: {}", e);
-                Vec::new()
-            },
-        };
-        
-        match (meetings_result, time_blocks_result) {
-            (Ok((mut current, mut next)), Ok(active_time_blocks)) => {
-                tracing::info!("WebSocket: ICS current: {:?}, ICS next: {:?}", 
-                    current.as_ref().map(|m| &m.title), 
-                    next.as_ref().map(|m| &m.title)
-                );
-                
-                // Merge Google Calendar events with ICS events
-                if !google_meetings.is_empty() {
-                    // Find current/next from Google Calendar events
-                    let google_current = google_meetings.iter().find(|m| m.is_active()).cloned();
-                    let google_next = google_meetings.iter().find(|m| m.is_upcoming()).cloned();
-                    
-                    tracing::info!("WebSocket: Google current: {:?}, Google next: {:?}", 
-                        google_current.as_ref().map(|m| &m.title), 
-                        google_next.as_ref().map(|m| &m.title)
-                    );
-                    
-                    // Prioritize Google Calendar events when available
-                    // Use Google Calendar current event if it exists, or if no ICS current event, or if Google event is earlier
-                    if google_current.is_some() && (current.is_none() || google_current.as_ref().unwrap().start_time < current.as_ref().unwrap().start_time) {
-                        tracing::info!("WebSocket: Using Google current event: {}", google_current.as_ref().unwrap().title);
-                        current = google_current;
-                    }
-                    
-                    // Use Google Calendar next event if it exists, or if no ICS next event, or if Google event is earlier  
-                    if google_next.is_some() && (next.is_none() || google_next.as_ref().unwrap().start_time < next.as_ref().unwrap().start_time) {
-                        tracing::info!("WebSocket: Using Google next event: {}", google_next.as_ref().unwrap().title);
-                        next = google_next;
-                    }
-                } else {
-                    tracing::info!("WebSocket: No Google Calendar events to merge");
-                }
-                
-                let countdown_seconds = current.as_ref().map(|m| m.time_until_end());
-                
-                let update = MeetingUpdate {
-                    current_meeting: current,
-                    next_meeting: next,
-                    countdown_seconds,
-                    active_time_blocks,
-                };
-
-                if let Ok(message) = serde_json::to_string(&update) {
-                    if socket.send(Message::Text(message)).await.is_err() {
-                        break;
-                    }
-                }
-            }
-            (Err(e), _) | (_, Err(e)) => {
-                warn!("Error fetching meetings or time blocks: {}", e);
+    let app_state = AppState::new(config.clone(), saved_tokens)
+        .with_config_file(cli.config.clone().or_else(Config::find_config_file))
+        .with_cli(cli)
+        .with_snapshot_file(data_dir.as_ref().map(|dir| dir.join("meetings_snapshot.json")))
+        .with_local_meetings_file(data_dir.as_ref().map(|dir| dir.join("local_meetings.json")))
+        .with_hidden_meetings_file(data_dir.as_ref().map(|dir| dir.join("hidden_meetings.json")));
+
+    // Without a browser on this device, show a code to enter elsewhere
+    if config.google.auth_flow == AuthFlow::Device
+        && config.google.service_account_key_file.is_none()
+        && config.google_oauth_config().is_some()
+        && app_state.google_tokens.read().await.is_none()
+    {
+        match start_device_authorization(&app_state).await {
+            Ok(DeviceAuthorizationStatus::Pending { user_code, verification_url, .. }) => {
+                info!("To connect Google Calendar, open {} and enter the code {}", verification_url, user_code);
             }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to start Google device authorization: {}", e),
         }
     }
-}
 
-async fn get_meetings(State(state): State<AppState>) -> impl IntoResponse {
-    let calendar_service = CalendarService::new_from_config(&state.config);
-    
-    // Get regular meetings and active time blocks from ICS sources
-    let meetings_result = calendar_service.get_current_and_next_meetings().await;
-    let time_blocks_result = calendar_service.get_active_time_blocks().await;
-    
-    // Try to get Google Calendar events and merge them
-    let google_meetings = match GoogleCalendarService::new_from_config(&state.config) {
-        Ok(Some(mut google_service)) => {
-            // Set stored tokens if available
-            if let Ok(tokens_guard) = state.google_tokens.lock() {
-                if let Some(ref tokens) = *tokens_guard {
-                    google_service.set_tokens(tokens.clone());
-                }
-            }
-            
-            // Refresh token if needed
-            match google_service.refresh_token_if_needed().await {
-                Ok(true) => {
-                    // Token was refreshed, update stored tokens
-                    if let Some(new_tokens) = google_service.get_tokens() {
-                        if let Ok(mut tokens_guard) = state.google_tokens.lock() {
-                            *tokens_guard = Some(new_tokens);
-                            info!("API: Updated stored tokens after refresh");
-                        }
-                    }
-                },
-                Ok(false) => {
-                    // No refresh needed
-                },
-                Err(e) => {
-                    warn!("API: Failed to refresh Google Calendar tokens: {}", e);
-                }
-            }
-            
-            if google_service.is_authenticated() {
-                match google_service.get_calendar_events().await {
-                    Ok(events) => {
-                        info!("Successfully fetched {} Google Calendar events", events.len());
-                        events
-                    },
-                    Err(e) => {
-                        warn!("Failed to fetch Google Calendar events: {}", e);
-                        Vec::new()
-                    }
-                }
-            } else {
-                info!("Google Calendar not authenticated or tokens expired");
-                Vec::new()
-            }
-        }
-        Ok(None) => {
-            info!("Google OAuth not configured");
-            Vec::new()
-        },
-        Err(e) => {
-            warn!("Failed to create Google Calendar service: {}", e);
-            Vec::new()
-        },
-    };
-    
-    match (meetings_result, time_blocks_result) {
-        (Ok((mut current, mut next)), Ok(active_time_blocks)) => {
-            // Merge Google Calendar events with ICS events
-            if !google_meetings.is_empty() {
-                // Find current/next from Google Calendar events
-                let google_current = google_meetings.iter().find(|m| m.is_active()).cloned();
-                let google_next = google_meetings.iter().find(|m| m.is_upcoming()).cloned();
-                
-                // Prioritize Google Calendar events when available
-                // Use Google Calendar current event if it exists, or if no ICS current event, or if Google event is earlier
-                if google_current.is_some() && (current.is_none() || google_current.as_ref().unwrap().start_time < current.as_ref().unwrap().start_time) {
-                    current = google_current;
-                }
-                
-                // Use Google Calendar next event if it exists, or if no ICS next event, or if Google event is earlier
-                if google_next.is_some() && (next.is_none() || google_next.as_ref().unwrap().start_time < next.as_ref().unwrap().start_time) {
-                    next = google_next;
-                }
-            }
-            
-            let countdown_seconds = current.as_ref().map(|m| m.time_until_end());
-            
-            let update = MeetingUpdate {
-                current_meeting: current,
-                next_meeting: next,
-                countdown_seconds,
-                active_time_blocks,
-            };
-            
-            Json(update)
-        }
-        _ => Json(MeetingUpdate {
-            current_meeting: None,
-            next_meeting: None,
-            countdown_seconds: None,
-            active_time_blocks: vec![],
-        }),
+    // Slack status and other [notifications], and the Telegram bot's commands
+    tokio::spawn(run_notifications(app_state.clone()));
+    tokio::spawn(run_telegram_bot(app_state.clone()));
+    // WatchdogSec= in the unit
+    if let Some(every) = systemd::watchdog_interval() {
+        tokio::spawn(run_watchdog(app_state.clone(), every));
     }
-}
 
-async fn get_server_info() -> impl IntoResponse {
-    let ip = match local_ip() {
-        Ok(ip) => ip.to_string(),
-        Err(_) => "Unable to determine".to_string(),
-    };
-    
-    let server_info = ServerInfo {
-        local_ip: ip,
-        server_port: 3000, // Default port, could be made configurable
-    };
-    
-    Json(server_info)
-}
+    // Build our application with routes
+    let app = build_router(app_state);
 
-/// Google OAuth login endpoint
-async fn google_auth_login(State(state): State<AppState>) -> impl IntoResponse {
-    match GoogleCalendarService::new_from_config(&state.config) {
-        Ok(Some(google_service)) => {
-            let (auth_url, _csrf_token) = google_service.get_auth_url();
-            // For now, just redirect to Google OAuth
-            axum::response::Redirect::temporary(auth_url.as_str()).into_response()
-        }
-        Ok(None) => {
-            Html("<h1>Google OAuth not configured</h1><p>Please configure Google OAuth in your TOML config file or set environment variables GOOGLE_CLIENT_ID, GOOGLE_CLIENT_SECRET, and GOOGLE_REDIRECT_URI.</p>".to_string()).into_response()
-        }
-        Err(e) => {
-            warn!("Failed to create Google OAuth client: {}", e);
-            Html(format!("<h1>Error</h1><p>Failed to initialize Google OAuth: {}</p>", e)).into_response()
+    // Run the server
+    let bind_address = config.bind_address();
+    let scheme = if config.server.tls().is_some() { "https" } else { "http" };
+    let listener = match systemd::activated_listener()? {
+        Some(listener) => {
+            info!("Server running on {}://{} (socket from systemd)", scheme, listener.local_addr()?);
+            listener
         }
-    }
-}
-
-/// Google OAuth callback endpoint
-async fn google_auth_callback(
-    query: Query<HashMap<String, String>>,
-    State(state): State<AppState>
-) -> impl IntoResponse {
-    if let (Some(code), Some(_state)) = (query.get("code"), query.get("state")) {
-        info!("Received OAuth callback with authorization code");
-        
-        // Exchange authorization code for tokens
-        match GoogleCalendarService::new_from_config(&state.config) {
-            Ok(Some(mut google_service)) => {
-                match google_service.exchange_code(code.clone(), oauth2::CsrfToken::new(_state.clone())).await {
-                    Ok(()) => {
-                        // Store tokens in global state and persist to disk
-                        if let Some(tokens) = google_service.get_tokens() {
-                            // Save to disk first
-                            if let Err(e) = Config::save_google_tokens(&tokens) {
-                                warn!("Failed to save Google Calendar tokens to disk: {}", e);
-                            }
-                            
-                            // Store in memory
-                            if let Ok(mut tokens_guard) = state.google_tokens.lock() {
-                                *tokens_guard = Some(tokens);
-                                info!("Successfully stored Google Calendar tokens in memory and disk");
-                            }
-                        }
-                        
-                        Html(format!(
-                            "<h1>✅ Google Calendar Connected!</h1>
-                             <p>Successfully authenticated with Google Calendar.</p>
-                             <p>You can now see your Google Calendar events in the monitor.</p>
-                             <p><a href='/'>← Back to Calendar Monitor</a></p>
-                             <script>
-                                setTimeout(() => window.location.href = '/', 3000);
-                             </script>"
-                        ))
-                    }
-                    Err(e) => {
-                        warn!("Failed to exchange OAuth code for tokens: {}", e);
-                        Html(format!(
-                            "<h1>❌ Google OAuth Error</h1>
-                             <p>Failed to exchange authorization code for tokens: {}</p>
-                             <p><a href='/auth/google/login'>← Try again</a></p>",
-                            e
-                        ))
-                    }
-                }
-            }
-            Ok(None) => {
-                Html("<h1>❌ Google OAuth not configured</h1><p>Please configure Google OAuth in your TOML config file or set environment variables.</p>".to_string())
-            }
-            Err(e) => {
-                warn!("Failed to create Google OAuth client: {}", e);
-                Html(format!(
-                    "<h1>❌ Google OAuth Error</h1>
-                     <p>Failed to initialize OAuth client: {}</p>
-                     <p><a href='/auth/google/login'>← Try again</a></p>",
-                    e
-                ))
-            }
+        None => {
+            let listener = tokio::net::TcpListener::bind(&bind_address).await?;
+            info!("Server running on {}://{}", scheme, bind_address);
+            listener
         }
-    } else if let Some(error) = query.get("error") {
-        Html(format!(
-            "<h1>❌ Google OAuth Error</h1>
-             <p>Error: {}</p>
-             <p><a href='/auth/google/login'>← Try again</a></p>",
-            error
-        ))
-    } else {
-        Html("<h1>❌ Invalid OAuth callback</h1><p>Missing required parameters.</p>".to_string())
-    }
-}
-
-/// Debug endpoint to check Google authentication status
-async fn google_auth_status(State(state): State<AppState>) -> impl IntoResponse {
-    let mut response = format!("<h1>🔍 Google Calendar Debug Status</h1>");
-    
-    // Check environment variables
-    let has_client_id = std::env::var("GOOGLE_CLIENT_ID").is_ok();
-    let has_client_secret = std::env::var("GOOGLE_CLIENT_SECRET").is_ok();
-    let has_redirect_uri = std::env::var("GOOGLE_REDIRECT_URI").is_ok();
-    
-    response.push_str(&format!("<h2>Environment Variables:</h2>"));
-    response.push_str(&format!("<ul>"));
-    response.push_str(&format!("<li>GOOGLE_CLIENT_ID: {}</li>", if has_client_id { "✅ Set" } else { "❌ Missing" }));
-    response.push_str(&format!("<li>GOOGLE_CLIENT_SECRET: {}</li>", if has_client_secret { "✅ Set" } else { "❌ Missing" }));
-    response.push_str(&format!("<li>GOOGLE_REDIRECT_URI: {}</li>", if has_redirect_uri { "✅ Set" } else { "❌ Missing" }));
-    response.push_str(&format!("</ul>"));
-    
-    // Check stored tokens
-    let has_tokens = if let Ok(tokens_guard) = state.google_tokens.lock() {
-        tokens_guard.is_some()
-    } else {
-        false
     };
-    
-    response.push_str(&format!("<h2>Authentication Status:</h2>"));
-    response.push_str(&format!("<ul>"));
-    response.push_str(&format!("<li>Stored Tokens: {}</li>", if has_tokens { "✅ Available" } else { "❌ None" }));
-    response.push_str(&format!("</ul>"));
-    
-    // Test Google Calendar service creation
-    let service_status = match GoogleCalendarService::new_from_config(&state.config) {
-        Ok(Some(mut google_service)) => {
-            // Try to restore tokens
-            if let Ok(tokens_guard) = state.google_tokens.lock() {
-                if let Some(ref tokens) = *tokens_guard {
-                    google_service.set_tokens(tokens.clone());
-                    format!("✅ Service created and tokens restored")
-                } else {
-                    format!("⚠️ Service created but no tokens to restore")
-                }
-            } else {
-                format!("❌ Service created but failed to access token store")
-            }
-        },
-        Ok(None) => format!("❌ Service creation returned None (environment issue)"),
-        Err(e) => format!("❌ Service creation failed: {}", e),
-    };
-    
-    response.push_str(&format!("<h2>Service Status:</h2>"));
-    response.push_str(&format!("<ul><li>{}</li></ul>", service_status));
-    
-    response.push_str(&format!("<p><a href='/'>← Back to Calendar</a> | <a href='/auth/google/login'>🔗 Connect Google Calendar</a></p>"));
-    
-    Html(response)
-}
 
-/// Serve embedded CSS file
-async fn serve_css() -> impl IntoResponse {
-    let mut headers = HeaderMap::new();
-    headers.insert(header::CONTENT_TYPE, "text/css".parse().unwrap());
-    (StatusCode::OK, headers, STYLE_CSS)
-}
+    serve(listener, app, &config.server).await?;
 
-/// Serve embedded JavaScript file
-async fn serve_js() -> impl IntoResponse {
-    let mut headers = HeaderMap::new();
-    headers.insert(header::CONTENT_TYPE, "application/javascript".parse().unwrap());
-    (StatusCode::OK, headers, APP_JS)
+    Ok(())
 }
@@ -1,8 +1,11 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Meeting {
+    /// Stable identifier of this occurrence, see `Meeting::compute_id`
+    #[serde(default)]
+    pub id: String,
     pub title: String,
     pub start_time: DateTime<Utc>,
     pub end_time: DateTime<Utc>,
@@ -10,6 +13,122 @@ pub struct Meeting {
     pub location: Option<String>,
     pub attendees: Vec<String>,
     pub response_status: Option<ResponseStatus>,
+    /// iCalendar UID (ICS `UID` / Google `iCalUID`), shared by copies of the same event
+    #[serde(default)]
+    pub uid: Option<String>,
+    /// Calendar this meeting was loaded from
+    #[serde(default)]
+    pub source: Option<MeetingSource>,
+    /// CSS color of this particular event (e.g. a Google event color),
+    /// overriding the source color
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Series this meeting belongs to, if it is an instance of a recurring event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurring_event_id: Option<String>,
+    /// Date-only event (vacation, holiday) spanning whole days rather than a time slot
+    #[serde(default)]
+    pub all_day: bool,
+    /// Set for Google out-of-office and focus-time events, which count as time blocks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_type: Option<EventType>,
+    /// iCalendar `CATEGORIES` of the event
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<String>,
+    /// Times in the time zone a live update client asked for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local: Option<LocalTimes>,
+}
+
+/// A meeting's times formatted for a display in another time zone
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocalTimes {
+    /// Day the meeting starts, e.g. "2024-01-15"
+    pub date: String,
+    /// e.g. "14:00"
+    pub start: String,
+    pub end: String,
+    /// "14:00 - 15:30", or "All day"
+    pub range: String,
+}
+
+/// Calendar a meeting came from, with its display label and color
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MeetingSource {
+    /// Short identifier: the ICS source alias, "google" or "holidays"
+    pub id: String,
+    /// Label shown next to the meeting
+    pub name: String,
+    /// CSS color used to tag the meeting in the UI
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Meetings from higher-priority sources win when several share a slot
+    #[serde(default, skip_serializing_if = "is_default_priority")]
+    pub priority: i32,
+    /// Meetings from this source are redacted as configured under `[privacy]`
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub private: bool,
+    /// Every meeting from this source is a time block (a dedicated focus calendar)
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub time_blocks: bool,
+}
+
+fn is_default_priority(priority: &i32) -> bool {
+    *priority == 0
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+impl MeetingSource {
+    pub fn new(id: String, name: String, color: Option<String>) -> Self {
+        Self { id, name, color, priority: 0, private: false, time_blocks: false }
+    }
+
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn with_private(mut self, private: bool) -> Self {
+        self.private = private;
+        self
+    }
+
+    pub fn with_time_blocks(mut self, time_blocks: bool) -> Self {
+        self.time_blocks = time_blocks;
+        self
+    }
+
+    /// Default attribution for Google Calendar events
+    pub fn google() -> Self {
+        Self::new("google".to_string(), "Google Calendar".to_string(), None)
+    }
+
+    /// Default attribution for public holidays
+    pub fn holidays() -> Self {
+        Self::new("holidays".to_string(), "Public holidays".to_string(), None)
+    }
+
+    /// Attribution for the busy status set by hand through /api/override
+    pub fn manual_override() -> Self {
+        Self::new("override".to_string(), "Manual override".to_string(), None)
+    }
+
+    /// Default attribution for an ICS source without an alias, named after
+    /// the last path segment (e.g. `https://host/team.ics?key=x` -> `team`)
+    pub fn from_ics_path(path: &str) -> Self {
+        let without_query = path.split(['?', '#']).next().unwrap_or(path);
+        let label = without_query
+            .rsplit(['/', '\\'])
+            .find(|segment| !segment.is_empty())
+            .map(|segment| segment.strip_suffix(".ics").unwrap_or(segment))
+            .filter(|segment| !segment.is_empty())
+            .unwrap_or(path)
+            .to_string();
+        Self::new(label.clone(), label, None)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -20,6 +139,34 @@ pub enum ResponseStatus {
     NoResponse,
 }
 
+/// Google Calendar event types that are not regular meetings
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum EventType {
+    OutOfOffice,
+    FocusTime,
+    /// Matched by the `[time_blocks]` rules
+    TimeBlock,
+}
+
+impl EventType {
+    /// Parse Google's `eventType`; regular ("default") and other event types yield None
+    pub fn from_google(event_type: &str) -> Option<Self> {
+        match event_type {
+            "outOfOffice" => Some(EventType::OutOfOffice),
+            "focusTime" => Some(EventType::FocusTime),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EventType::OutOfOffice => "Out of office",
+            EventType::FocusTime => "Focus time",
+            EventType::TimeBlock => "Time block",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum MeetingStatus {
     Upcoming,
@@ -33,7 +180,8 @@ impl Meeting {
         start_time: DateTime<Utc>,
         end_time: DateTime<Utc>,
     ) -> Self {
-        Self {
+        let mut meeting = Self {
+            id: String::new(),
             title,
             start_time,
             end_time,
@@ -41,7 +189,41 @@ impl Meeting {
             location: None,
             attendees: Vec::new(),
             response_status: None,
-        }
+            uid: None,
+            source: None,
+            color: None,
+            recurring_event_id: None,
+            all_day: false,
+            event_type: None,
+            categories: Vec::new(),
+            local: None,
+        };
+        meeting.id = meeting.compute_id();
+        meeting
+    }
+
+    /// Identifier derived from the UID and start time (occurrences of a
+    /// recurring event share their UID), or else from the source, start time
+    /// and title. Copies of an event from several calendars share the UID and
+    /// so the ID.
+    pub fn compute_id(&self) -> String {
+        let start = self.start_time.timestamp().to_string();
+        let hash = match &self.uid {
+            Some(uid) => stable_hash(&["uid", uid, &start]),
+            None => {
+                let source = self.source.as_ref().map(|s| s.id.as_str()).unwrap_or_default();
+                stable_hash(&["event", source, &start, &self.title])
+            }
+        };
+        format!("{:016x}", hash)
+    }
+
+    /// All-day event from `first_day` up to (not including) `end_day`, as
+    /// calendars store them. Days start at midnight local time.
+    pub fn new_all_day(title: String, first_day: NaiveDate, end_day: NaiveDate) -> Self {
+        let mut meeting = Self::new(title, local_midnight(first_day), local_midnight(end_day));
+        meeting.all_day = true;
+        meeting
     }
 
     pub fn with_description(mut self, description: String) -> Self {
@@ -54,6 +236,68 @@ impl Meeting {
         self
     }
 
+    pub fn with_uid(mut self, uid: String) -> Self {
+        self.uid = Some(uid);
+        self.id = self.compute_id();
+        self
+    }
+
+    pub fn with_source(mut self, source: MeetingSource) -> Self {
+        self.source = Some(source);
+        self.id = self.compute_id();
+        self
+    }
+
+    pub fn with_recurring_event_id(mut self, recurring_event_id: String) -> Self {
+        self.recurring_event_id = Some(recurring_event_id);
+        self
+    }
+
+    /// Whether this meeting is an instance of a recurring series
+    pub fn is_recurring(&self) -> bool {
+        self.recurring_event_id.is_some()
+    }
+
+    /// Whether this is a public holiday from the built-in holiday source
+    pub fn is_holiday(&self) -> bool {
+        self.source.as_ref().is_some_and(|s| s.id == MeetingSource::holidays().id)
+    }
+
+    /// Case-insensitive match of the title, description or location
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        [Some(&self.title), self.description.as_ref(), self.location.as_ref()]
+            .into_iter()
+            .flatten()
+            .any(|text| text.to_lowercase().contains(&query))
+    }
+
+    /// First web link in the location or description, usually the video
+    /// call to join
+    pub fn url(&self) -> Option<String> {
+        [self.location.as_ref(), self.description.as_ref()]
+            .into_iter()
+            .flatten()
+            .flat_map(|text| text.split(|c: char| c.is_whitespace() || c == '<' || c == '>' || c == '"'))
+            .find(|word| word.starts_with("https://") || word.starts_with("http://"))
+            .map(|url| url.trim_end_matches(['.', ',', ';', ')']).to_string())
+    }
+
+    pub fn with_event_type(mut self, event_type: EventType) -> Self {
+        self.event_type = Some(event_type);
+        self
+    }
+
+    pub fn with_categories(mut self, categories: Vec<String>) -> Self {
+        self.categories = categories;
+        self
+    }
+
+    pub fn with_color(mut self, color: String) -> Self {
+        self.color = Some(color);
+        self
+    }
+
     pub fn with_response_status(mut self, response_status: ResponseStatus) -> Self {
         self.response_status = Some(response_status);
         self
@@ -107,6 +351,35 @@ impl Meeting {
         (self.end_time - Utc::now()).num_seconds()
     }
 
+    /// Attach the meeting's times as shown in `timezone`. All-day events keep
+    /// the date they have in the server's time zone, where their days are.
+    pub fn with_local_times(mut self, timezone: chrono_tz::Tz) -> Self {
+        let start = self.start_time.with_timezone(&timezone);
+        let end = self.end_time.with_timezone(&timezone);
+        let (date, range) = if self.all_day {
+            (self.start_time.with_timezone(&Local).date_naive(), "All day".to_string())
+        } else {
+            (start.date_naive(), format!("{} - {}", start.format("%H:%M"), end.format("%H:%M")))
+        };
+        self.local = Some(LocalTimes {
+            date: date.format("%Y-%m-%d").to_string(),
+            start: start.format("%H:%M").to_string(),
+            end: end.format("%H:%M").to_string(),
+            range,
+        });
+        self
+    }
+
+    /// How far the meeting has got, from 0 before it starts to 100 once it has ended
+    pub fn progress_percent(&self) -> u8 {
+        let duration = (self.end_time - self.start_time).num_seconds();
+        if duration <= 0 {
+            return if self.has_ended() { 100 } else { 0 };
+        }
+        let elapsed = (Utc::now() - self.start_time).num_seconds().clamp(0, duration);
+        (elapsed * 100 / duration) as u8
+    }
+
     /// Get the duration of the meeting in minutes
     pub fn duration_minutes(&self) -> i64 {
         (self.end_time - self.start_time).num_minutes()
@@ -170,17 +443,115 @@ impl Meeting {
         )
     }
 
-    /// Check if this meeting is a time block (title starts with [ and ends with ])
+    /// Check if this meeting is a time block (title starts with [ and ends with ],
+    /// an out-of-office / focus-time event, matched by the `[time_blocks]` rules,
+    /// or from a source of time blocks)
     pub fn is_time_block(&self) -> bool {
-        self.title.starts_with('[') && self.title.ends_with(']')
+        self.event_type.is_some()
+            || (self.title.starts_with('[') && self.title.ends_with(']'))
+            || self.source.as_ref().is_some_and(|source| source.time_blocks)
     }
 
     /// Get the time block name without brackets (if it's a time block)
     pub fn time_block_name(&self) -> Option<String> {
-        if self.is_time_block() && self.title.len() > 2 {
+        if self.title.starts_with('[') && self.title.ends_with(']') && self.title.len() > 2 {
             Some(self.title[1..self.title.len()-1].to_string())
+        } else if self.is_time_block() {
+            Some(self.title.clone())
         } else {
             None
         }
     }
+
+    /// This meeting as shown on a public display: `title` instead of the real
+    /// one, and no description, location, attendees or UID. Time blocks stay
+    /// time blocks, keeping their names when `keep_time_blocks` is set.
+    pub fn redacted(mut self, title: &str, keep_time_blocks: bool) -> Self {
+        let bracketed = self.title.starts_with('[') && self.title.ends_with(']');
+        if !(keep_time_blocks && self.is_time_block()) {
+            self.title = if bracketed { format!("[{}]", title) } else { title.to_string() };
+        }
+        self.description = None;
+        self.location = None;
+        self.attendees.clear();
+        self.categories.clear();
+        self.uid = None;
+        self
+    }
+
+    /// Priority of the source this meeting came from (0 when unattributed)
+    pub fn priority(&self) -> i32 {
+        self.source.as_ref().map_or(0, |source| source.priority)
+    }
+
+    /// Check if another meeting is a copy of this one from a different source.
+    ///
+    /// Copies start at the same time and share a UID; when either side has no
+    /// UID, the (case-insensitive) titles must match instead.
+    pub fn is_duplicate_of(&self, other: &Meeting) -> bool {
+        if self.start_time != other.start_time {
+            return false;
+        }
+
+        match (&self.uid, &other.uid) {
+            (Some(a), Some(b)) => a == b,
+            _ => self.title.trim().eq_ignore_ascii_case(other.title.trim()),
+        }
+    }
+}
+
+/// Merge meetings from several sources into one list sorted by start time.
+///
+/// When the same event appears in more than one source, the copy from the
+/// source with the highest configured priority is kept, and on equal
+/// priorities the copy from the earlier source.
+pub fn merge_meetings(sources: Vec<Vec<Meeting>>) -> Vec<Meeting> {
+    let mut merged: Vec<Meeting> = Vec::new();
+
+    for meeting in sources.into_iter().flatten() {
+        if let Some(existing) = merged.iter_mut().find(|m| m.is_duplicate_of(&meeting)) {
+            if meeting.priority() > existing.priority() {
+                tracing::debug!("Replacing duplicate event '{}' at {} with the copy from a higher-priority source",
+                    existing.title, existing.start_time.format("%H:%M"));
+                *existing = meeting;
+            } else {
+                tracing::debug!("Dropping duplicate event '{}' at {} (already have '{}')",
+                    meeting.title, meeting.start_time.format("%H:%M"), existing.title);
+            }
+            continue;
+        }
+        merged.push(meeting);
+    }
+
+    merged.sort_by_key(|m| m.start_time);
+    merged
+}
+
+/// The meeting from the highest-priority source, the earliest listed on ties
+pub fn highest_priority<'a>(meetings: impl IntoIterator<Item = &'a Meeting>) -> Option<&'a Meeting> {
+    meetings.into_iter().min_by_key(|m| std::cmp::Reverse(m.priority()))
+}
+
+/// 64-bit FNV-1a over the parts, which unlike `DefaultHasher` is guaranteed
+/// to stay the same across Rust releases
+fn stable_hash(parts: &[&str]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for part in parts {
+        // Separator, so ("ab", "c") and ("a", "bc") differ
+        for byte in part.bytes().chain(std::iter::once(0)) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+/// Start of the day in the server's local time zone, as UTC
+pub fn local_midnight(date: NaiveDate) -> DateTime<Utc> {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    match Local.from_local_datetime(&midnight).earliest() {
+        Some(local) => local.with_timezone(&Utc),
+        // Midnight skipped by a DST change
+        None => midnight.and_utc(),
+    }
 }
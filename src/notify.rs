@@ -0,0 +1,264 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Days, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::config::Config;
+use crate::desktop::DesktopNotifier;
+use crate::discord::DiscordWebhook;
+use crate::email::EmailNotifier;
+use crate::events::{Event, NotificationBackend};
+use crate::hooks::ShellHooks;
+use crate::meeting::Meeting;
+use crate::slack::SlackStatus;
+use crate::teams::TeamsPresence;
+use crate::telegram::TelegramBot;
+use crate::webhook::Webhook;
+
+/// A change of the current meeting that integrations react to
+#[derive(Debug, Clone)]
+pub enum MeetingEvent {
+    Started(Meeting),
+    Ended(Meeting),
+    /// Today's meetings, once a day at the notifier's `agenda_time`
+    Agenda(Vec<Meeting>),
+    /// A meeting starting within the notifier's `remind_before`
+    Reminder(Meeting),
+}
+
+/// Turns the current meeting of successive updates into start and end events
+#[derive(Debug, Default)]
+pub struct MeetingTracker {
+    current: Option<Meeting>,
+}
+
+impl MeetingTracker {
+    /// The events since the previous call. A meeting whose times changed
+    /// while it was going on starts again, so its end is announced correctly.
+    pub fn observe(&mut self, current: Option<&Meeting>) -> Vec<MeetingEvent> {
+        let unchanged = match (&self.current, current) {
+            (Some(previous), Some(current)) => {
+                previous.id == current.id
+                    && previous.start_time == current.start_time
+                    && previous.end_time == current.end_time
+            }
+            (None, None) => true,
+            _ => false,
+        };
+        if unchanged {
+            return Vec::new();
+        }
+
+        let mut events = Vec::new();
+        if let Some(previous) = self.current.take() {
+            events.push(MeetingEvent::Ended(previous));
+        }
+        if let Some(current) = current {
+            events.push(MeetingEvent::Started(current.clone()));
+            self.current = Some(current.clone());
+        }
+        events
+    }
+}
+
+/// An integration told about meetings starting and ending
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// Short name used in logs
+    fn name(&self) -> &str;
+
+    /// When the notifier wants the day's agenda, if at all
+    fn agenda_time(&self) -> Option<NaiveTime> {
+        None
+    }
+
+    /// How long before a meeting starts the notifier wants a reminder, if at all
+    fn remind_before(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Whether it stays silent during quiet hours; statuses that are not
+    /// notifications keep following the meetings
+    fn silent_in_quiet_hours(&self) -> bool {
+        true
+    }
+
+    async fn notify(&self, event: &MeetingEvent) -> Result<()>;
+}
+
+/// The integrations configured under `[notifications]`, and the `[hooks]`
+pub fn notifiers_from_config(config: &Config, http_client: &reqwest::Client) -> Vec<Arc<dyn Notifier>> {
+    let mut notifiers: Vec<Arc<dyn Notifier>> = Vec::new();
+    if let Some(slack) = &config.notifications.slack {
+        notifiers.push(Arc::new(SlackStatus::new(http_client.clone(), slack.clone(), config.display.clock_24h)));
+    }
+    if let Some(teams) = &config.notifications.teams {
+        notifiers.push(Arc::new(TeamsPresence::new(http_client.clone(), teams.clone(), config.display.clock_24h)));
+    }
+    if let Some(discord) = &config.notifications.discord {
+        notifiers.push(Arc::new(DiscordWebhook::new(http_client.clone(), discord.clone(), config.display.clock_24h)));
+    }
+    if let Some(telegram) = &config.notifications.telegram {
+        notifiers.push(Arc::new(TelegramBot::new(http_client.clone(), telegram.clone(), config.display.clock_24h)));
+    }
+    if let Some(email) = &config.notifications.email {
+        notifiers.push(Arc::new(EmailNotifier::new(email.clone(), config.display.clock_24h)));
+    }
+    if let Some(desktop) = &config.notifications.desktop {
+        notifiers.push(Arc::new(DesktopNotifier::new(desktop.clone(), config.display.clock_24h)));
+    }
+    for webhook in &config.notifications.webhooks {
+        notifiers.push(Arc::new(Webhook::new(http_client.clone(), webhook.clone(), config.display.clock_24h)));
+    }
+    if config.hooks.any() {
+        notifiers.push(Arc::new(ShellHooks::new(config.hooks.clone())));
+    }
+    notifiers
+}
+
+/// Tell every notifier about the event, apart from the silent ones during
+/// quiet hours; failures are logged and do not keep the others from being told
+pub async fn dispatch(notifiers: &[Arc<dyn Notifier>], event: &MeetingEvent, quiet: bool) {
+    for notifier in notifiers {
+        if quiet && notifier.silent_in_quiet_hours() {
+            continue;
+        }
+        if let Err(e) = notifier.notify(event).await {
+            tracing::warn!("{} notification failed: {}", notifier.name(), e);
+        }
+    }
+}
+
+/// Agendas and reminders already sent, shared by every [`NotifierBackend`]
+/// and kept across configuration reloads so nothing is sent twice
+#[derive(Debug, Default)]
+pub struct Schedules {
+    pub agendas: AgendaSchedule,
+    pub reminders: ReminderSchedule,
+}
+
+/// A notifier subscribed to the event bus. It is told about meetings
+/// starting and ending, and picks its agenda and reminders from the updates.
+pub struct NotifierBackend {
+    notifier: Arc<dyn Notifier>,
+    schedules: Arc<Mutex<Schedules>>,
+}
+
+impl NotifierBackend {
+    pub fn new(notifier: Arc<dyn Notifier>, schedules: Arc<Mutex<Schedules>>) -> Self {
+        Self { notifier, schedules }
+    }
+
+    /// The agenda and reminders due with this update
+    fn due(&self, today: &[Meeting], next: Option<&Meeting>) -> Vec<MeetingEvent> {
+        let mut schedules = self.schedules.lock().unwrap_or_else(|e| e.into_inner());
+        let mut due = Vec::new();
+        if let Some(at) = self.notifier.agenda_time() {
+            if schedules.agendas.due(self.notifier.name(), at, Local::now().naive_local()) {
+                due.push(MeetingEvent::Agenda(today.iter().filter(|meeting| !meeting.all_day).cloned().collect()));
+            }
+        }
+        if let Some(before) = self.notifier.remind_before() {
+            // The next meeting may be tomorrow's, soon after midnight
+            let upcoming: Vec<Meeting> = today.iter()
+                .chain(next.filter(|next| today.iter().all(|meeting| meeting.id != next.id)))
+                .cloned()
+                .collect();
+            let reminders = schedules.reminders.due(self.notifier.name(), before, &upcoming, Utc::now());
+            due.extend(reminders.into_iter().map(MeetingEvent::Reminder));
+        }
+        due
+    }
+}
+
+#[async_trait]
+impl NotificationBackend for NotifierBackend {
+    fn name(&self) -> &str {
+        self.notifier.name()
+    }
+
+    async fn handle(&self, event: &Event) -> Result<()> {
+        let notifiers = std::slice::from_ref(&self.notifier);
+        match event {
+            Event::Meeting { event, quiet } => dispatch(notifiers, event, *quiet).await,
+            Event::Update(update) => {
+                for event in self.due(&update.agenda_today, update.next_meeting.as_ref()) {
+                    dispatch(notifiers, &event, update.quiet).await;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// When each notifier's daily agenda is due
+#[derive(Debug, Default)]
+pub struct AgendaSchedule {
+    /// Day each notifier last got its agenda, by name
+    sent: HashMap<String, NaiveDate>,
+}
+
+impl AgendaSchedule {
+    /// Whether the agenda of notifier `name`, posted at `at`, is due at `now`
+    /// (local time). Due once a day; a server started after `at` waits for
+    /// the next day rather than posting late.
+    pub fn due(&mut self, name: &str, at: NaiveTime, now: NaiveDateTime) -> bool {
+        let today = now.date();
+        let sent = self.sent.entry(name.to_string()).or_insert_with(|| {
+            if now.time() >= at {
+                today
+            } else {
+                today - Days::new(1)
+            }
+        });
+        if now.time() >= at && *sent < today {
+            *sent = today;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Which meetings each notifier has been reminded of
+#[derive(Debug, Default)]
+pub struct ReminderSchedule {
+    /// Start of each reminded meeting, by notifier name and meeting id
+    sent: HashMap<(String, String), DateTime<Utc>>,
+}
+
+impl ReminderSchedule {
+    /// The meetings of `candidates` starting within `before` of `now` that
+    /// notifier `name` has not been reminded of yet. All-day events get no
+    /// reminders.
+    pub fn due(&mut self, name: &str, before: Duration, candidates: &[Meeting], now: DateTime<Utc>) -> Vec<Meeting> {
+        // Forget meetings long started
+        self.sent.retain(|_, start| *start > now - Duration::days(1));
+
+        let mut due = Vec::new();
+        for meeting in candidates {
+            if meeting.all_day || meeting.start_time <= now || meeting.start_time - now > before {
+                continue;
+            }
+            if let Entry::Vacant(entry) = self.sent.entry((name.to_string(), meeting.id.clone())) {
+                entry.insert(meeting.start_time);
+                due.push(meeting.clone());
+            }
+        }
+        due
+    }
+}
+
+/// `template` with `{title}`, `{start}`, `{end}` and `{location}` filled in
+/// from the meeting, times in the server's time zone
+pub fn render_template(template: &str, meeting: &Meeting, clock_24h: bool) -> String {
+    let format = if clock_24h { "%H:%M" } else { "%-I:%M %p" };
+    template
+        .replace("{title}", &meeting.title)
+        .replace("{start}", &meeting.start_time.with_timezone(&Local).format(format).to_string())
+        .replace("{end}", &meeting.end_time.with_timezone(&Local).format(format).to_string())
+        .replace("{location}", meeting.location.as_deref().unwrap_or(""))
+}
@@ -0,0 +1,290 @@
+use anyhow::{anyhow, Result};
+use axum::http::{header, HeaderMap};
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use oauth2::{CsrfToken, PkceCodeChallenge};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::config::OidcConfig;
+
+/// Cookie holding the session id once signed in
+pub const SESSION_COOKIE: &str = "calendar_monitor_session";
+
+/// How long a sign-in may take at the provider
+const PENDING_LOGIN_MINUTES: i64 = 10;
+
+/// How long the provider's endpoints and signing keys are reused
+const PROVIDER_TTL_MINUTES: i64 = 60;
+
+#[derive(Debug, Clone, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+/// Discovery document and signing keys of the configured issuer
+#[derive(Clone)]
+struct Provider {
+    configured_issuer: String,
+    metadata: ProviderMetadata,
+    keys: JwkSet,
+    fetched_at: DateTime<Utc>,
+}
+
+/// A sign-in waiting for its callback, keyed by the OAuth `state`
+pub struct PendingLogin {
+    nonce: String,
+    pkce_verifier: String,
+    /// Page to go back to once signed in
+    pub return_to: String,
+    created_at: DateTime<Utc>,
+}
+
+/// Who signed in, from the ID token
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OidcUser {
+    pub subject: String,
+    /// Only when the provider has verified it
+    pub email: Option<String>,
+    pub name: Option<String>,
+}
+
+impl std::fmt::Display for OidcUser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.email.as_deref().or(self.name.as_deref()).unwrap_or(&self.subject))
+    }
+}
+
+struct Session {
+    user: OidcUser,
+    expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdTokenClaims {
+    sub: String,
+    #[serde(default)]
+    nonce: Option<String>,
+    #[serde(default)]
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: Option<bool>,
+    #[serde(default)]
+    name: Option<String>,
+}
+
+/// Sign-ins in progress and the sessions they started. Sessions are kept in
+/// memory, so a restart signs everyone out.
+#[derive(Clone, Default)]
+pub struct OidcSessions {
+    provider: Arc<tokio::sync::Mutex<Option<Provider>>>,
+    pending: Arc<Mutex<HashMap<String, PendingLogin>>>,
+    sessions: Arc<Mutex<HashMap<String, Session>>>,
+}
+
+impl OidcSessions {
+    /// The provider's authorization URL for a new sign-in, which returns to
+    /// `return_to` when done
+    pub async fn login_url(&self, http_client: &reqwest::Client, config: &OidcConfig, return_to: &str) -> Result<String> {
+        let provider = self.provider(http_client, config, false).await?;
+        let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
+        let state = CsrfToken::new_random().secret().clone();
+        let nonce = CsrfToken::new_random().secret().clone();
+
+        let mut url = url::Url::parse(&provider.metadata.authorization_endpoint)
+            .map_err(|e| anyhow!("Invalid authorization_endpoint from the OpenID provider: {}", e))?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &config.client_id)
+            .append_pair("redirect_uri", &config.redirect_uri)
+            .append_pair("scope", &config.scopes.join(" "))
+            .append_pair("state", &state)
+            .append_pair("nonce", &nonce)
+            .append_pair("code_challenge", pkce_challenge.as_str())
+            .append_pair("code_challenge_method", "S256");
+
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Utc::now();
+        pending.retain(|_, login| now - login.created_at < Duration::minutes(PENDING_LOGIN_MINUTES));
+        let login = PendingLogin {
+            nonce,
+            pkce_verifier: pkce_verifier.secret().clone(),
+            return_to: return_to.to_string(),
+            created_at: now,
+        };
+        pending.insert(state, login);
+        Ok(url.into())
+    }
+
+    /// The sign-in started with `state`; each is single-use
+    pub fn take_login(&self, state: &str) -> Option<PendingLogin> {
+        let login = self.pending.lock().unwrap_or_else(|e| e.into_inner()).remove(state)?;
+        (Utc::now() - login.created_at < Duration::minutes(PENDING_LOGIN_MINUTES)).then_some(login)
+    }
+
+    /// Trade the callback's `code` for an ID token and check it: signed by
+    /// the provider, issued for this client and for this sign-in
+    pub async fn verify(
+        &self,
+        http_client: &reqwest::Client,
+        config: &OidcConfig,
+        code: &str,
+        login: &PendingLogin,
+    ) -> Result<OidcUser> {
+        let provider = self.provider(http_client, config, false).await?;
+        let mut request = http_client.post(&provider.metadata.token_endpoint).form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", config.redirect_uri.as_str()),
+            ("client_id", config.client_id.as_str()),
+            ("code_verifier", login.pkce_verifier.as_str()),
+        ]);
+        if let Some(secret) = &config.client_secret {
+            request = request.basic_auth(urlencoding::encode(&config.client_id), Some(urlencoding::encode(secret)));
+        }
+        let response = request.send().await.map_err(|e| anyhow!("OpenID token request failed: {}", e))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body: serde_json::Value = response.json().await.unwrap_or_default();
+            let description = body["error_description"].as_str().or(body["error"].as_str()).unwrap_or("no details");
+            return Err(anyhow!("OpenID token request returned HTTP {}: {}", status, description));
+        }
+        let token: TokenResponse =
+            response.json().await.map_err(|e| anyhow!("Invalid OpenID token response: {}", e))?;
+
+        let header = jsonwebtoken::decode_header(&token.id_token).map_err(|e| anyhow!("Invalid ID token: {}", e))?;
+        if matches!(header.alg, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512) {
+            return Err(anyhow!("ID tokens signed with {:?} are not accepted", header.alg));
+        }
+        let key = match self.key(&provider, header.kid.as_deref()) {
+            Some(key) => key,
+            // The provider may have rotated its keys since they were read
+            None => {
+                let provider = self.provider(http_client, config, true).await?;
+                self.key(&provider, header.kid.as_deref())
+                    .ok_or_else(|| anyhow!("The ID token is signed with a key the OpenID provider doesn't publish"))?
+            }
+        };
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&provider.metadata.issuer]);
+        validation.set_audience(&[&config.client_id]);
+        let claims = jsonwebtoken::decode::<IdTokenClaims>(&token.id_token, &key, &validation)
+            .map_err(|e| anyhow!("ID token rejected: {}", e))?
+            .claims;
+        if claims.nonce.as_deref() != Some(login.nonce.as_str()) {
+            return Err(anyhow!("ID token rejected: it was not issued for this sign-in"));
+        }
+
+        Ok(OidcUser {
+            subject: claims.sub,
+            email: claims.email.filter(|_| claims.email_verified != Some(false)),
+            name: claims.name,
+        })
+    }
+
+    /// Start a session for `user`, returning its id for the cookie
+    pub fn start_session(&self, user: OidcUser, hours: u32) -> String {
+        let id = CsrfToken::new_random().secret().clone();
+        let now = Utc::now();
+        let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        sessions.retain(|_, session| session.expires_at > now);
+        sessions.insert(id.clone(), Session { user, expires_at: now + Duration::hours(hours.into()) });
+        id
+    }
+
+    /// The user signed in with `session_id`, unless the session has expired
+    pub fn user(&self, session_id: &str) -> Option<OidcUser> {
+        let sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        sessions.get(session_id).filter(|session| session.expires_at > Utc::now()).map(|session| session.user.clone())
+    }
+
+    /// End the session, returning who was signed in
+    pub fn end_session(&self, session_id: &str) -> Option<OidcUser> {
+        self.sessions.lock().unwrap_or_else(|e| e.into_inner()).remove(session_id).map(|session| session.user)
+    }
+
+    async fn provider(&self, http_client: &reqwest::Client, config: &OidcConfig, refresh: bool) -> Result<Provider> {
+        let mut cached = self.provider.lock().await;
+        if let Some(provider) = &*cached {
+            let fresh = Utc::now() - provider.fetched_at < Duration::minutes(PROVIDER_TTL_MINUTES);
+            if provider.configured_issuer == config.issuer && fresh && !refresh {
+                return Ok(provider.clone());
+            }
+        }
+
+        let issuer = config.issuer.trim_end_matches('/');
+        let metadata: ProviderMetadata = fetch_json(http_client, &format!("{}/.well-known/openid-configuration", issuer)).await?;
+        if metadata.issuer.trim_end_matches('/') != issuer {
+            return Err(anyhow!("The OpenID provider names its issuer '{}', not '{}'", metadata.issuer, config.issuer));
+        }
+        let keys: JwkSet = fetch_json(http_client, &metadata.jwks_uri).await?;
+        let provider = Provider { configured_issuer: config.issuer.clone(), metadata, keys, fetched_at: Utc::now() };
+        *cached = Some(provider.clone());
+        Ok(provider)
+    }
+
+    /// The signing key `kid`, or the only one when the token names none
+    fn key(&self, provider: &Provider, kid: Option<&str>) -> Option<DecodingKey> {
+        let jwk = match kid {
+            Some(kid) => provider.keys.find(kid)?,
+            None if provider.keys.keys.len() == 1 => &provider.keys.keys[0],
+            None => return None,
+        };
+        DecodingKey::from_jwk(jwk).ok()
+    }
+}
+
+async fn fetch_json<T: serde::de::DeserializeOwned>(http_client: &reqwest::Client, url: &str) -> Result<T> {
+    let response = http_client
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| anyhow!("Failed to read {} from the OpenID provider: {}", url, e))?;
+    response.json().await.map_err(|e| anyhow!("Invalid response for {} from the OpenID provider: {}", url, e))
+}
+
+/// The session id sent in the request's cookies
+pub fn session_cookie(headers: &HeaderMap) -> Option<String> {
+    cookie(headers, SESSION_COOKIE)
+}
+
+/// The value of the cookie `name` sent with a request
+pub fn cookie(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(cookie_name, _)| *cookie_name == name)
+        .map(|(_, value)| value.to_string())
+}
+
+/// `Set-Cookie` value for a session; `Secure` when the server is reached
+/// over HTTPS, as `redirect_uri` tells
+pub fn set_session_cookie(config: &OidcConfig, session_id: &str) -> String {
+    let secure = if config.redirect_uri.starts_with("https://") { "; Secure" } else { "" };
+    format!(
+        "{}={}; Path=/; HttpOnly; SameSite=Lax; Max-Age={}{}",
+        SESSION_COOKIE,
+        session_id,
+        u64::from(config.session_hours) * 3600,
+        secure
+    )
+}
+
+/// `Set-Cookie` value removing the session cookie
+pub fn clear_session_cookie() -> String {
+    format!("{}=; Path=/; HttpOnly; SameSite=Lax; Max-Age=0", SESSION_COOKIE)
+}
@@ -0,0 +1,70 @@
+use anyhow::{anyhow, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Encoding of API responses and live updates. The binary formats carry the
+/// same structures as the JSON, field names included, for microcontroller
+/// clients that would rather not parse JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PayloadFormat {
+    #[default]
+    Json,
+    MessagePack,
+    Cbor,
+}
+
+impl PayloadFormat {
+    /// A `?format=` value: "json", "msgpack" or "cbor"
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "json" => Some(PayloadFormat::Json),
+            "msgpack" => Some(PayloadFormat::MessagePack),
+            "cbor" => Some(PayloadFormat::Cbor),
+            _ => None,
+        }
+    }
+
+    /// The first supported format listed in an `Accept` header, if any.
+    /// Quality values are not weighed.
+    pub fn from_accept(accept: &str) -> Option<Self> {
+        accept.split(',').find_map(|range| {
+            match range.split(';').next().unwrap_or_default().trim() {
+                "application/json" => Some(PayloadFormat::Json),
+                "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack" => Some(PayloadFormat::MessagePack),
+                "application/cbor" => Some(PayloadFormat::Cbor),
+                _ => None,
+            }
+        })
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            PayloadFormat::Json => "application/json",
+            PayloadFormat::MessagePack => "application/msgpack",
+            PayloadFormat::Cbor => "application/cbor",
+        }
+    }
+
+    pub fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>> {
+        match self {
+            PayloadFormat::Json => serde_json::to_vec(value).map_err(|e| anyhow!("Failed to encode JSON: {}", e)),
+            // Structs as maps rather than arrays, so fields keep their names
+            PayloadFormat::MessagePack => {
+                rmp_serde::to_vec_named(value).map_err(|e| anyhow!("Failed to encode MessagePack: {}", e))
+            }
+            PayloadFormat::Cbor => {
+                let mut bytes = Vec::new();
+                ciborium::into_writer(value, &mut bytes).map_err(|e| anyhow!("Failed to encode CBOR: {}", e))?;
+                Ok(bytes)
+            }
+        }
+    }
+
+    pub fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T> {
+        match self {
+            PayloadFormat::Json => serde_json::from_slice(bytes).map_err(|e| anyhow!("{}", e)),
+            PayloadFormat::MessagePack => rmp_serde::from_slice(bytes).map_err(|e| anyhow!("{}", e)),
+            PayloadFormat::Cbor => ciborium::from_reader(bytes).map_err(|e| anyhow!("{}", e)),
+        }
+    }
+}
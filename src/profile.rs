@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use crate::app::MeetingUpdate;
+use crate::config::{Config, PrivacyConfig, PrivacyLevel, ProfileConfig};
+use crate::filter::TitleFilter;
+use crate::meeting::Meeting;
+use anyhow::Result;
+
+/// A `[profile.NAME]` ready to apply to the live updates
+#[derive(Debug, Clone)]
+pub struct Profile {
+    sources: Vec<String>,
+    include_time_blocks: bool,
+    filter: TitleFilter,
+    privacy: PrivacyLevel,
+    /// Title and time block handling of redacted meetings
+    redaction: PrivacyConfig,
+}
+
+impl Profile {
+    pub fn new(config: &ProfileConfig, redaction: &PrivacyConfig) -> Result<Self> {
+        Ok(Self {
+            sources: config.sources.clone(),
+            include_time_blocks: config.include_time_blocks,
+            filter: config.title_filter()?,
+            privacy: config.privacy,
+            redaction: redaction.clone(),
+        })
+    }
+
+    /// Every configured profile, by name. Invalid ones are left out; they fail
+    /// validation anyway.
+    pub fn from_config(config: &Config) -> HashMap<String, Profile> {
+        let mut profiles = HashMap::new();
+        for (name, profile) in &config.profile {
+            match Profile::new(profile, &config.privacy) {
+                Ok(profile) => {
+                    profiles.insert(name.clone(), profile);
+                }
+                Err(e) => tracing::warn!("Ignoring profile '{}': {}", name, e),
+            }
+        }
+        profiles
+    }
+
+    /// Shown in place of a profile a reload removed: nothing beyond busy/free
+    pub fn fallback(redaction: &PrivacyConfig) -> Self {
+        Self {
+            sources: Vec::new(),
+            include_time_blocks: true,
+            filter: TitleFilter::default(),
+            privacy: PrivacyLevel::BusyFree,
+            redaction: redaction.clone(),
+        }
+    }
+
+    pub fn include_time_blocks(&self) -> bool {
+        self.include_time_blocks
+    }
+
+    /// Whether the profile shows this meeting at all
+    pub fn includes(&self, meeting: &Meeting) -> bool {
+        (self.sources.is_empty() || meeting.source.as_ref().is_some_and(|source| self.sources.contains(&source.id)))
+            && self.filter.matches(&meeting.title)
+    }
+
+    /// Redact an update built from the meetings the profile includes
+    pub fn apply(&self, update: &mut MeetingUpdate) {
+        match self.privacy {
+            PrivacyLevel::Full => {}
+            PrivacyLevel::Redacted => {
+                let keep_time_blocks = self.redaction.keep_time_blocks;
+                update.for_each_meeting(|meeting| self.redact(meeting, keep_time_blocks));
+            }
+            PrivacyLevel::BusyFree => {
+                update.for_each_meeting(|meeting| {
+                    self.redact(meeting, false);
+                    meeting.source = None;
+                });
+                update.agenda_today.clear();
+                update.upcoming_time_blocks.clear();
+                update.source_errors.clear();
+            }
+        }
+    }
+
+    fn redact(&self, meeting: &mut Meeting, keep_time_blocks: bool) {
+        if !meeting.is_holiday() {
+            *meeting = meeting.clone().redacted(&self.redaction.title, keep_time_blocks);
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc, Weekday};
+
+/// One concrete occurrence of a recurring event
+#[derive(Debug, Clone, PartialEq)]
+pub struct Occurrence {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// The first occurrence of a recurring event together with its RRULE
+#[derive(Debug, Clone)]
+pub struct RecurringEvent {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub rrule: String,
+}
+
+/// Inclusive range of (UTC) dates to expand occurrences into
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Window {
+    pub first_day: NaiveDate,
+    pub last_day: NaiveDate,
+}
+
+impl Window {
+    pub fn new(first_day: NaiveDate, last_day: NaiveDate) -> Self {
+        Self { first_day, last_day }
+    }
+
+    /// Today and tomorrow, the range shown on the monitor
+    pub fn today_and_tomorrow() -> Self {
+        let today = Utc::now().date_naive();
+        Self::new(today, today + Duration::days(1))
+    }
+
+    /// Whether the date is one of the window's days
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.first_day <= date && date <= self.last_day
+    }
+
+    /// Midnight (UTC) at the start of the first day
+    pub fn start(&self) -> DateTime<Utc> {
+        self.first_day.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc()
+    }
+
+    /// Midnight (UTC) after the last day, exclusive
+    pub fn end(&self) -> DateTime<Utc> {
+        (self.last_day + Duration::days(1)).and_hms_opt(0, 0, 0).unwrap_or_default().and_utc()
+    }
+
+    /// Whether an event from `start` to `end` overlaps any day of the window
+    pub fn overlaps(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> bool {
+        start < self.end() && end > self.start()
+    }
+
+    fn days(&self) -> impl Iterator<Item = NaiveDate> {
+        let last_day = self.last_day;
+        self.first_day.iter_days().take_while(move |day| *day <= last_day)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Frequency {
+    Weekly,
+}
+
+/// The subset of an RRULE that is currently understood
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    /// Last date (inclusive) the series may occur on
+    pub until: Option<NaiveDate>,
+    /// Weekdays the series occurs on; empty means the weekday of the first occurrence
+    pub by_day: Vec<Weekday>,
+}
+
+impl RecurrenceRule {
+    /// Parse an RRULE value such as `FREQ=WEEKLY;UNTIL=20250620T235959Z;BYDAY=MO,WE`.
+    /// Returns None for frequencies that are not supported yet.
+    pub fn parse(rrule: &str) -> Option<Self> {
+        let frequency = match rule_part(rrule, "FREQ")? {
+            "WEEKLY" => Frequency::Weekly,
+            _ => return None,
+        };
+
+        let by_day = rule_part(rrule, "BYDAY")
+            .map(|days| days.split(',').filter_map(parse_weekday).collect())
+            .unwrap_or_default();
+
+        Some(Self {
+            frequency,
+            until: parse_until(rrule),
+            by_day,
+        })
+    }
+
+    /// Whether a series starting on `first_day` occurs on `date`
+    pub fn occurs_on(&self, first_day: NaiveDate, date: NaiveDate) -> bool {
+        if date < first_day {
+            return false;
+        }
+        if let Some(until) = self.until {
+            if date > until {
+                return false;
+            }
+        }
+
+        match self.frequency {
+            Frequency::Weekly => {
+                if self.by_day.is_empty() {
+                    date.weekday() == first_day.weekday()
+                } else {
+                    self.by_day.contains(&date.weekday())
+                }
+            }
+        }
+    }
+}
+
+/// Expand a recurring event into its occurrences within the window.
+///
+/// Each occurrence keeps the time of day and duration of the first one, so
+/// events that cross midnight still end on the following day.
+pub fn expand(event: &RecurringEvent, window: &Window) -> Vec<Occurrence> {
+    let Some(rule) = RecurrenceRule::parse(&event.rrule) else {
+        tracing::debug!("Unsupported RRULE pattern: {}", event.rrule);
+        return Vec::new();
+    };
+
+    let first_day = event.start.date_naive();
+    let duration = event.end - event.start;
+
+    window
+        .days()
+        .filter(|day| rule.occurs_on(first_day, *day))
+        .map(|day| {
+            let start = day.and_time(event.start.time()).and_utc();
+            Occurrence { start, end: start + duration }
+        })
+        .collect()
+}
+
+/// Parse the UNTIL date from an RRULE string
+/// (`20250620T235959Z`, `20250620T235959` or `20250620`)
+pub fn parse_until(rrule: &str) -> Option<NaiveDate> {
+    let until = rule_part(rrule, "UNTIL")?;
+
+    if let Some(date_part) = until.get(0..8) {
+        if let Ok(date) = NaiveDate::parse_from_str(date_part, "%Y%m%d") {
+            tracing::debug!("Parsed UNTIL date: {} from '{}'", date.format("%Y-%m-%d"), until);
+            return Some(date);
+        }
+    }
+
+    tracing::warn!("Failed to parse UNTIL date: '{}'", until);
+    None
+}
+
+/// Value of a `NAME=value` part of an RRULE
+fn rule_part<'a>(rrule: &'a str, name: &str) -> Option<&'a str> {
+    rrule.split(';').find_map(|part| {
+        let (key, value) = part.split_once('=')?;
+        key.trim().eq_ignore_ascii_case(name).then_some(value.trim())
+    })
+}
+
+/// Parse a BYDAY entry; ordinal prefixes like `1MO` or `-1FR` are ignored
+fn parse_weekday(code: &str) -> Option<Weekday> {
+    let code = code.trim();
+    let day = code.get(code.len().checked_sub(2)?..)?;
+    match day.to_ascii_uppercase().as_str() {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
@@ -0,0 +1,196 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use rhai::{CallFnOptions, Dynamic, Engine, Map, Scope, AST};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::meeting::Meeting;
+use crate::notify::{MeetingEvent, Notifier};
+
+/// Operations one script call may take, so a runaway loop cannot stall updates
+const MAX_OPERATIONS: u64 = 100_000;
+
+/// The `*.rhai` scripts of `[scripting] directory`.
+///
+/// A script may define `transform(meeting)`, called for every merged meeting
+/// with a map of its fields: it returns the meeting with `title`,
+/// `description`, `location`, `categories`, `color` or `priority` changed,
+/// or `()` to hide it. It may also define `on_event(event, meeting)`, called
+/// with "start" or "end" as meetings come and go.
+pub struct Scripts {
+    engine: Engine,
+    scripts: Vec<Script>,
+}
+
+struct Script {
+    /// File name, for logs
+    name: String,
+    ast: AST,
+    transform: bool,
+    on_event: bool,
+    /// Last error logged, so a script failing on every update is logged once
+    last_error: Mutex<Option<String>>,
+}
+
+impl Script {
+    fn report(&self, function: &str, error: &str) {
+        let mut last_error = self.last_error.lock().unwrap_or_else(|e| e.into_inner());
+        if last_error.as_deref() != Some(error) {
+            tracing::warn!("Script {} failed in {}: {}", self.name, function, error);
+            *last_error = Some(error.to_string());
+        }
+    }
+}
+
+impl Scripts {
+    /// Compile every `*.rhai` file of `directory`, in name order
+    pub fn load(directory: &Path) -> Result<Self> {
+        let mut paths: Vec<_> = fs::read_dir(directory)
+            .map_err(|e| anyhow!("Failed to read scripts directory {}: {}", directory.display(), e))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|extension| extension == "rhai"))
+            .collect();
+        paths.sort();
+
+        let engine = engine();
+        let mut scripts = Vec::new();
+        for path in paths {
+            let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            let source = fs::read_to_string(&path).map_err(|e| anyhow!("Failed to read script {}: {}", name, e))?;
+            scripts.push(Self::compile(&engine, name, &source)?);
+        }
+        Ok(Self { engine, scripts })
+    }
+
+    /// A single script given as source, as if loaded from a file called `name`
+    pub fn from_source(name: &str, source: &str) -> Result<Self> {
+        let engine = engine();
+        let script = Self::compile(&engine, name.to_string(), source)?;
+        Ok(Self { engine, scripts: vec![script] })
+    }
+
+    fn compile(engine: &Engine, name: String, source: &str) -> Result<Script> {
+        let ast = engine.compile(source).map_err(|e| anyhow!("Failed to compile script {}: {}", name, e))?;
+        let defines = |function: &str, params: usize| {
+            ast.iter_functions().any(|f| f.name == function && f.params.len() == params)
+        };
+        let (transform, on_event) = (defines("transform", 1), defines("on_event", 2));
+        if !transform && !on_event {
+            tracing::warn!("Script {} defines neither transform(meeting) nor on_event(event, meeting)", name);
+        }
+        Ok(Script { name, transform, on_event, ast, last_error: Mutex::new(None) })
+    }
+
+    /// Names of the loaded scripts
+    pub fn names(&self) -> Vec<&str> {
+        self.scripts.iter().map(|script| script.name.as_str()).collect()
+    }
+
+    /// Whether any script has an `on_event`
+    pub fn reacts_to_events(&self) -> bool {
+        self.scripts.iter().any(|script| script.on_event)
+    }
+
+    /// The meetings as the `transform` functions leave them, script after
+    /// script. A script failing on a meeting leaves it as it was.
+    pub fn transform(&self, meetings: Vec<Meeting>) -> Vec<Meeting> {
+        let scripts: Vec<_> = self.scripts.iter().filter(|script| script.transform).collect();
+        if scripts.is_empty() {
+            return meetings;
+        }
+        meetings
+            .into_iter()
+            .filter_map(|mut meeting| {
+                for script in &scripts {
+                    match self.call(script, "transform", (to_map(&meeting),)) {
+                        Ok(result) if result.is_unit() => return None,
+                        Ok(result) => match result.try_cast::<Map>() {
+                            Some(map) => apply_map(&mut meeting, &map),
+                            None => script.report("transform", "must return the meeting or ()"),
+                        },
+                        Err(e) => script.report("transform", &e),
+                    }
+                }
+                Some(meeting)
+            })
+            .collect()
+    }
+
+    fn call(&self, script: &Script, function: &str, args: impl rhai::FuncArgs) -> Result<Dynamic, String> {
+        let options = CallFnOptions::new().eval_ast(false);
+        self.engine
+            .call_fn_with_options(options, &mut Scope::new(), &script.ast, function, args)
+            .map_err(|e| e.to_string())
+    }
+}
+
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.on_print(|text| tracing::info!("script: {}", text));
+    engine.on_debug(|text, _, _| tracing::debug!("script: {}", text));
+    engine
+}
+
+/// The fields scripts see; times are RFC 3339 strings
+fn to_map(meeting: &Meeting) -> Map {
+    let optional = |value: &Option<String>| value.clone().map_or(Dynamic::UNIT, Dynamic::from);
+    let mut map = Map::new();
+    map.insert("id".into(), meeting.id.clone().into());
+    map.insert("title".into(), meeting.title.clone().into());
+    map.insert("description".into(), optional(&meeting.description));
+    map.insert("location".into(), optional(&meeting.location));
+    map.insert("start".into(), meeting.start_time.to_rfc3339().into());
+    map.insert("end".into(), meeting.end_time.to_rfc3339().into());
+    map.insert("all_day".into(), meeting.all_day.into());
+    map.insert("time_block".into(), meeting.is_time_block().into());
+    map.insert("source".into(), optional(&meeting.source.as_ref().map(|source| source.id.clone())));
+    map.insert("categories".into(), meeting.categories.iter().cloned().map(Dynamic::from).collect::<rhai::Array>().into());
+    map.insert("color".into(), optional(&meeting.color));
+    map.insert("priority".into(), (meeting.priority() as i64).into());
+    map
+}
+
+/// Take over the fields a script may change; the rest is read-only
+fn apply_map(meeting: &mut Meeting, map: &Map) {
+    let text = |name: &str| map.get(name).and_then(|value| value.clone().into_string().ok());
+    if let Some(title) = text("title") {
+        meeting.title = title;
+    }
+    meeting.description = text("description");
+    meeting.location = text("location");
+    meeting.color = text("color");
+    if let Some(categories) = map.get("categories").and_then(|value| value.clone().into_array().ok()) {
+        meeting.categories = categories.into_iter().filter_map(|value| value.into_string().ok()).collect();
+    }
+    // Priority lives on the meeting's copy of its source, where overlapping meetings are compared
+    if let (Some(priority), Some(source)) = (map.get("priority").and_then(|value| value.as_int().ok()), &mut meeting.source) {
+        source.priority = priority.clamp(i32::MIN as i64, i32::MAX as i64) as i32;
+    }
+}
+
+#[async_trait]
+impl Notifier for Scripts {
+    fn name(&self) -> &str {
+        "Scripts"
+    }
+
+    fn silent_in_quiet_hours(&self) -> bool {
+        false
+    }
+
+    async fn notify(&self, event: &MeetingEvent) -> Result<()> {
+        let (name, meeting) = match event {
+            MeetingEvent::Started(meeting) => ("start", meeting),
+            MeetingEvent::Ended(meeting) => ("end", meeting),
+            MeetingEvent::Agenda(_) | MeetingEvent::Reminder(_) => return Ok(()),
+        };
+        for script in self.scripts.iter().filter(|script| script.on_event) {
+            if let Err(e) = self.call(script, "on_event", (name.to_string(), to_map(meeting))) {
+                script.report("on_event", &e);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::config::SlackConfig;
+use crate::notify::{render_template, MeetingEvent, Notifier};
+
+const DEFAULT_API_URL: &str = "https://slack.com/api";
+
+/// Every Slack Web API response, successful or not
+#[derive(Debug, Deserialize)]
+struct SlackResponse {
+    ok: bool,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// Sets the Slack status (and snoozes notifications) while a meeting is on,
+/// and clears it when the meeting ends
+pub struct SlackStatus {
+    http_client: reqwest::Client,
+    config: SlackConfig,
+    clock_24h: bool,
+}
+
+impl SlackStatus {
+    pub fn new(http_client: reqwest::Client, config: SlackConfig, clock_24h: bool) -> Self {
+        Self { http_client, config, clock_24h }
+    }
+
+    /// `users.profile.set`; the status expires by itself at `expiration`
+    /// (Unix time, 0 for never) in case the end is missed
+    async fn set_status(&self, text: &str, emoji: &str, expiration: i64) -> Result<()> {
+        let body = json!({
+            "profile": {
+                "status_text": text,
+                "status_emoji": emoji,
+                "status_expiration": expiration,
+            }
+        });
+        self.call("users.profile.set", self.request("users.profile.set").json(&body)).await
+    }
+
+    async fn call(&self, method: &str, request: reqwest::RequestBuilder) -> Result<()> {
+        let response = request
+            .send()
+            .await
+            .map_err(|e| anyhow!("Slack {} request failed: {}", method, e))?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Slack {} returned HTTP {}", method, response.status()));
+        }
+        let result: SlackResponse = response
+            .json()
+            .await
+            .map_err(|e| anyhow!("Invalid Slack {} response: {}", method, e))?;
+        if result.ok {
+            Ok(())
+        } else {
+            Err(anyhow!("Slack {} failed: {}", method, result.error.unwrap_or_else(|| "unknown error".to_string())))
+        }
+    }
+
+    fn request(&self, method: &str) -> reqwest::RequestBuilder {
+        let base_url = self.config.api_base_url.as_deref().unwrap_or(DEFAULT_API_URL);
+        self.http_client
+            .post(format!("{}/{}", base_url.trim_end_matches('/'), method))
+            .bearer_auth(self.config.token.as_deref().unwrap_or_default())
+    }
+}
+
+#[async_trait]
+impl Notifier for SlackStatus {
+    fn name(&self) -> &str {
+        "Slack"
+    }
+
+    fn silent_in_quiet_hours(&self) -> bool {
+        false
+    }
+
+    async fn notify(&self, event: &MeetingEvent) -> Result<()> {
+        match event {
+            MeetingEvent::Started(meeting) => {
+                let text = render_template(&self.config.status_text, meeting, self.clock_24h);
+                self.set_status(&text, &self.config.status_emoji, meeting.end_time.timestamp()).await?;
+                if self.config.dnd {
+                    let minutes = (meeting.end_time - Utc::now()).num_minutes().max(1);
+                    let request = self.request("dnd.setSnooze").form(&[("num_minutes", minutes.to_string())]);
+                    self.call("dnd.setSnooze", request).await?;
+                }
+                tracing::info!("Slack status set until {}", meeting.end_time);
+            }
+            MeetingEvent::Ended(_) => {
+                self.set_status("", "", 0).await?;
+                if self.config.dnd {
+                    match self.call("dnd.endSnooze", self.request("dnd.endSnooze")).await {
+                        // Ended early in Slack already
+                        Err(e) if e.to_string().contains("snooze_not_active") => {}
+                        result => result?,
+                    }
+                }
+                tracing::info!("Slack status cleared");
+            }
+            MeetingEvent::Agenda(_) | MeetingEvent::Reminder(_) => {}
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::calendar::CalendarService;
+use crate::config::PrivacyConfig;
+use crate::meeting::{merge_meetings, Meeting};
+use crate::recurrence::Window;
+use crate::scripting::Scripts;
+
+/// A backend meetings are read from (ICS feeds, Google Calendar, ...)
+#[async_trait]
+pub trait CalendarSource: Send + Sync {
+    /// Short name used in logs
+    fn name(&self) -> &str;
+
+    /// Meetings overlapping the window. Sources take care of their own caching.
+    async fn fetch_events(&self, window: &Window) -> Result<Vec<Meeting>>;
+}
+
+/// The configured ICS feeds and CalDAV calendars
+pub struct IcsSource {
+    calendar_service: Arc<CalendarService>,
+}
+
+impl IcsSource {
+    pub fn new(calendar_service: Arc<CalendarService>) -> Self {
+        Self { calendar_service }
+    }
+}
+
+#[async_trait]
+impl CalendarSource for IcsSource {
+    fn name(&self) -> &str {
+        "ICS"
+    }
+
+    async fn fetch_events(&self, window: &Window) -> Result<Vec<Meeting>> {
+        let meetings = self.calendar_service.get_meetings_in_window(window).await?;
+        Ok(meetings
+            .into_iter()
+            .filter(|m| window.overlaps(m.start_time, m.end_time))
+            .collect())
+    }
+}
+
+/// Fetches every source and owns merging their meetings into one list
+#[derive(Default)]
+pub struct Aggregator {
+    sources: Vec<Box<dyn CalendarSource>>,
+    privacy: PrivacyConfig,
+    scripts: Option<Arc<Scripts>>,
+}
+
+impl Aggregator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a source. Sources are added in priority order: when the same
+    /// event comes from several sources, the copy from the earliest one wins.
+    pub fn with_source(mut self, source: impl CalendarSource + 'static) -> Self {
+        self.sources.push(Box::new(source));
+        self
+    }
+
+    /// Redact merged meetings as configured under `[privacy]`
+    pub fn with_privacy(mut self, privacy: PrivacyConfig) -> Self {
+        self.privacy = privacy;
+        self
+    }
+
+    /// Run merged meetings through the `transform` functions of user scripts
+    pub fn with_scripts(mut self, scripts: Option<Arc<Scripts>>) -> Self {
+        self.scripts = scripts;
+        self
+    }
+
+    /// Meetings of all sources in the window, deduplicated and sorted by start time.
+    ///
+    /// A source that fails contributes nothing. Declined invitations are only
+    /// dropped after merging, taking their copies from other sources with them.
+    /// Scripts see the meetings before privacy rules, which apply last so
+    /// duplicates are still recognised by title.
+    pub async fn fetch_meetings(&self, window: &Window) -> Vec<Meeting> {
+        let mut per_source = Vec::with_capacity(self.sources.len());
+        for source in &self.sources {
+            match source.fetch_events(window).await {
+                Ok(meetings) => per_source.push(meetings),
+                Err(e) => tracing::warn!("Error fetching {} meetings: {}", source.name(), e),
+            }
+        }
+
+        let mut meetings: Vec<_> = merge_meetings(per_source).into_iter().filter(|m| m.should_display()).collect();
+        if let Some(scripts) = &self.scripts {
+            meetings = scripts.transform(meetings);
+        }
+        meetings
+            .into_iter()
+            .map(|m| self.privacy.apply(m))
+            .collect()
+    }
+}
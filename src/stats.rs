@@ -0,0 +1,104 @@
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::meeting::{local_midnight, Meeting};
+
+/// A meeting starting at most this long after the previous one ended
+/// counts as back to back
+const BACK_TO_BACK_GAP: Duration = Duration::minutes(5);
+
+/// The period /api/stats summarizes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsPeriod {
+    Day,
+    Week,
+}
+
+impl StatsPeriod {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "day" => Some(StatsPeriod::Day),
+            "week" => Some(StatsPeriod::Week),
+            _ => None,
+        }
+    }
+
+    /// The day, or the Monday to Sunday week, containing `date`, from local
+    /// midnight to local midnight
+    pub fn range(&self, date: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+        let (first, days) = match self {
+            StatsPeriod::Day => (date, 1),
+            StatsPeriod::Week => (date - Duration::days(date.weekday().num_days_from_monday() as i64), 7),
+        };
+        (local_midnight(first), local_midnight(first + Duration::days(days)))
+    }
+}
+
+/// Time spent in meetings over a period
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MeetingStats {
+    pub period: StatsPeriod,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub meeting_count: usize,
+    /// Time in meetings within the period; overlapping meetings count once
+    pub meeting_minutes: i64,
+    /// Longest free stretch between two meetings on the same day
+    pub longest_gap_minutes: Option<i64>,
+    /// Meetings starting right after (or during) an earlier one
+    pub back_to_back: usize,
+}
+
+/// Summarize the meetings overlapping `from`..`to`.
+///
+/// Only timed meetings count: all-day events, time blocks and declined
+/// invitations are left out.
+pub fn meeting_stats(meetings: &[Meeting], period: StatsPeriod, from: DateTime<Utc>, to: DateTime<Utc>) -> MeetingStats {
+    let mut counted: Vec<&Meeting> = meetings
+        .iter()
+        .filter(|m| !m.all_day && !m.is_time_block() && m.should_display())
+        .filter(|m| m.start_time < to && m.end_time > from)
+        .collect();
+    counted.sort_by_key(|m| m.start_time);
+
+    let mut meeting_minutes = 0;
+    let mut longest_gap: Option<Duration> = None;
+    let mut back_to_back = 0;
+    // The stretch of overlapping or adjoining meetings seen so far
+    let mut busy: Option<(DateTime<Utc>, DateTime<Utc>)> = None;
+    for meeting in &counted {
+        let (start, end) = (meeting.start_time.max(from), meeting.end_time.min(to));
+        busy = match busy {
+            Some((busy_start, busy_end)) if start <= busy_end => {
+                back_to_back += 1;
+                Some((busy_start, busy_end.max(end)))
+            }
+            Some((busy_start, busy_end)) => {
+                meeting_minutes += (busy_end - busy_start).num_minutes();
+                if start - busy_end <= BACK_TO_BACK_GAP {
+                    back_to_back += 1;
+                }
+                let same_day = start.with_timezone(&Local).date_naive() == busy_end.with_timezone(&Local).date_naive();
+                if same_day && longest_gap.is_none_or(|gap| start - busy_end > gap) {
+                    longest_gap = Some(start - busy_end);
+                }
+                Some((start, end))
+            }
+            None => Some((start, end)),
+        };
+    }
+    if let Some((busy_start, busy_end)) = busy {
+        meeting_minutes += (busy_end - busy_start).num_minutes();
+    }
+
+    MeetingStats {
+        period,
+        from,
+        to,
+        meeting_count: counted.len(),
+        meeting_minutes,
+        longest_gap_minutes: longest_gap.map(|gap| gap.num_minutes()),
+        back_to_back,
+    }
+}
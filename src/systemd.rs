@@ -0,0 +1,49 @@
+use anyhow::{anyhow, Result};
+use std::time::Duration;
+
+/// The listening socket passed by systemd socket activation (`LISTEN_FDS`),
+/// if the server was started that way
+pub fn activated_listener() -> Result<Option<tokio::net::TcpListener>> {
+    let mut fds = listenfd::ListenFd::from_env();
+    let Some(listener) = fds
+        .take_tcp_listener(0)
+        .map_err(|e| anyhow!("The socket passed by systemd is not a TCP listener: {}", e))?
+    else {
+        return Ok(None);
+    };
+    listener.set_nonblocking(true)?;
+    Ok(Some(tokio::net::TcpListener::from_std(listener)?))
+}
+
+/// Tell systemd the server is up, for units with `Type=notify`
+pub fn ready() {
+    #[cfg(unix)]
+    notify(sd_notify::NotifyState::Ready);
+}
+
+/// Tell systemd's watchdog the server is still alive
+pub fn watchdog() {
+    #[cfg(unix)]
+    notify(sd_notify::NotifyState::Watchdog);
+}
+
+/// How often to ping the watchdog when the unit sets `WatchdogSec=`: half
+/// the timeout, as systemd recommends
+pub fn watchdog_interval() -> Option<Duration> {
+    #[cfg(unix)]
+    {
+        let mut usec = 0;
+        if sd_notify::watchdog_enabled(false, &mut usec) && usec > 0 {
+            return Some(Duration::from_micros(usec / 2));
+        }
+    }
+    None
+}
+
+/// Does nothing unless systemd set `NOTIFY_SOCKET`
+#[cfg(unix)]
+fn notify(state: sd_notify::NotifyState) {
+    if let Err(e) = sd_notify::notify(false, &[state]) {
+        tracing::warn!("Failed to notify systemd: {}", e);
+    }
+}
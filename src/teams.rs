@@ -0,0 +1,163 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::TeamsConfig;
+use crate::notify::{render_template, MeetingEvent, Notifier};
+
+const DEFAULT_LOGIN_URL: &str = "https://login.microsoftonline.com";
+const DEFAULT_API_URL: &str = "https://graph.microsoft.com/v1.0";
+const SCOPE: &str = "https://graph.microsoft.com/Presence.ReadWrite offline_access";
+
+/// Access tokens are renewed this long before they expire
+const TOKEN_MARGIN: Duration = Duration::from_secs(60);
+
+/// Shortest preferred presence Teams takes
+const MIN_PRESENCE_MINUTES: i64 = 5;
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+    /// Microsoft rotates refresh tokens; the newest one is kept
+    #[serde(default)]
+    refresh_token: Option<String>,
+}
+
+/// Sets your Teams presence (and status message) while a meeting is on, and
+/// clears it when the meeting ends
+pub struct TeamsPresence {
+    http_client: reqwest::Client,
+    config: TeamsConfig,
+    clock_24h: bool,
+    refresh_token: Mutex<String>,
+    /// Current access token and when it needs renewing
+    access_token: Mutex<Option<(String, Instant)>>,
+}
+
+impl TeamsPresence {
+    pub fn new(http_client: reqwest::Client, config: TeamsConfig, clock_24h: bool) -> Self {
+        let refresh_token = Mutex::new(config.refresh_token.clone().unwrap_or_default());
+        Self { http_client, config, clock_24h, refresh_token, access_token: Mutex::new(None) }
+    }
+
+    /// A valid access token, traded for the refresh token when needed
+    async fn access_token(&self) -> Result<String> {
+        if let Some((token, renew_at)) = &*self.access_token.lock().unwrap_or_else(|e| e.into_inner()) {
+            if Instant::now() < *renew_at {
+                return Ok(token.clone());
+            }
+        }
+
+        let refresh_token = self.refresh_token.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        let mut form = vec![
+            ("grant_type", "refresh_token"),
+            ("client_id", self.config.client_id.as_str()),
+            ("refresh_token", refresh_token.as_str()),
+            ("scope", SCOPE),
+        ];
+        if let Some(secret) = &self.config.client_secret {
+            form.push(("client_secret", secret.as_str()));
+        }
+        let base_url = self.config.login_base_url.as_deref().unwrap_or(DEFAULT_LOGIN_URL);
+        let url = format!("{}/{}/oauth2/v2.0/token", base_url.trim_end_matches('/'), self.config.tenant);
+        let response = self
+            .http_client
+            .post(url)
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| anyhow!("Microsoft token request failed: {}", e))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body: Value = response.json().await.unwrap_or_default();
+            let description = body["error_description"].as_str().or(body["error"].as_str()).unwrap_or("no details");
+            return Err(anyhow!("Microsoft token request returned HTTP {}: {}", status, description));
+        }
+        let token: TokenResponse =
+            response.json().await.map_err(|e| anyhow!("Invalid Microsoft token response: {}", e))?;
+        if let Some(refresh_token) = token.refresh_token {
+            *self.refresh_token.lock().unwrap_or_else(|e| e.into_inner()) = refresh_token;
+        }
+        let renew_at = Instant::now() + Duration::from_secs(token.expires_in).saturating_sub(TOKEN_MARGIN);
+        *self.access_token.lock().unwrap_or_else(|e| e.into_inner()) = Some((token.access_token.clone(), renew_at));
+        Ok(token.access_token)
+    }
+
+    /// POST `body` to `/me/presence/<action>`
+    async fn call(&self, action: &str, body: Value) -> Result<()> {
+        let token = self.access_token().await?;
+        let base_url = self.config.api_base_url.as_deref().unwrap_or(DEFAULT_API_URL);
+        let response = self
+            .http_client
+            .post(format!("{}/me/presence/{}", base_url.trim_end_matches('/'), action))
+            .bearer_auth(token)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| anyhow!("Teams {} request failed: {}", action, e))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body: Value = response.json().await.unwrap_or_default();
+            let message = body["error"]["message"].as_str().unwrap_or("no details");
+            return Err(anyhow!("Teams {} returned HTTP {}: {}", action, status, message));
+        }
+        Ok(())
+    }
+
+    /// `setStatusMessage`; the message expires by itself at `expiry` in case
+    /// the end is missed
+    async fn set_status_message(&self, message: &str, expiry: Option<DateTime<Utc>>) -> Result<()> {
+        let mut status = json!({ "message": { "content": message, "contentType": "text" } });
+        if let Some(expiry) = expiry {
+            status["expiryDateTime"] = json!({
+                "dateTime": expiry.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                "timeZone": "UTC",
+            });
+        }
+        self.call("setStatusMessage", json!({ "statusMessage": status })).await
+    }
+}
+
+#[async_trait]
+impl Notifier for TeamsPresence {
+    fn name(&self) -> &str {
+        "Teams"
+    }
+
+    fn silent_in_quiet_hours(&self) -> bool {
+        false
+    }
+
+    async fn notify(&self, event: &MeetingEvent) -> Result<()> {
+        match event {
+            MeetingEvent::Started(meeting) => {
+                let minutes = (meeting.end_time - Utc::now()).num_minutes().max(MIN_PRESENCE_MINUTES);
+                let body = json!({
+                    "availability": self.config.availability,
+                    "activity": self.config.availability,
+                    "expirationDuration": format!("PT{}M", minutes),
+                });
+                self.call("setUserPreferredPresence", body).await?;
+                if !self.config.status_message.is_empty() {
+                    let message = render_template(&self.config.status_message, meeting, self.clock_24h);
+                    self.set_status_message(&message, Some(meeting.end_time)).await?;
+                }
+                tracing::info!("Teams presence set until {}", meeting.end_time);
+            }
+            MeetingEvent::Ended(_) => {
+                self.call("clearUserPreferredPresence", json!({})).await?;
+                if !self.config.status_message.is_empty() {
+                    self.set_status_message("", None).await?;
+                }
+                tracing::info!("Teams presence cleared");
+            }
+            MeetingEvent::Agenda(_) | MeetingEvent::Reminder(_) => {}
+        }
+        Ok(())
+    }
+}
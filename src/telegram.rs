@@ -0,0 +1,187 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{Duration, Local};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::app::MeetingUpdate;
+use crate::config::TelegramConfig;
+use crate::notify::{render_template, MeetingEvent, Notifier};
+
+const DEFAULT_API_URL: &str = "https://api.telegram.org";
+
+/// Seconds `getUpdates` waits for a message before returning empty
+const POLL_TIMEOUT_SECONDS: u64 = 25;
+
+const HELP: &str = "Send /next for the current and next meeting, or /agenda for today's meetings.";
+
+/// Every Bot API response, successful or not
+#[derive(Debug, Deserialize)]
+struct TelegramResponse<T> {
+    ok: bool,
+    #[serde(default)]
+    description: Option<String>,
+    result: Option<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Update {
+    update_id: i64,
+    #[serde(default)]
+    message: Option<Message>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    chat: Chat,
+    #[serde(default)]
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Chat {
+    id: i64,
+}
+
+/// Sends meeting reminders to a Telegram chat and answers the commands
+/// sent from it
+pub struct TelegramBot {
+    http_client: reqwest::Client,
+    config: TelegramConfig,
+    clock_24h: bool,
+}
+
+impl TelegramBot {
+    pub fn new(http_client: reqwest::Client, config: TelegramConfig, clock_24h: bool) -> Self {
+        Self { http_client, config, clock_24h }
+    }
+
+    /// Post `text` to the configured chat
+    pub async fn send(&self, text: &str) -> Result<()> {
+        let body = json!({ "chat_id": self.config.chat_id, "text": text });
+        let request = self.http_client.post(self.method_url("sendMessage")).json(&body);
+        self.call::<serde_json::Value>("sendMessage", request).await?;
+        Ok(())
+    }
+
+    /// Commands sent from the configured chat since `offset`, which is moved
+    /// past them. Waits a while for one to arrive; messages from other chats
+    /// are skipped.
+    pub async fn commands(&self, offset: &mut Option<i64>) -> Result<Vec<String>> {
+        let mut query = vec![("timeout", POLL_TIMEOUT_SECONDS.to_string()), ("allowed_updates", r#"["message"]"#.to_string())];
+        if let Some(offset) = offset {
+            query.push(("offset", offset.to_string()));
+        }
+        let request = self
+            .http_client
+            .get(self.method_url("getUpdates"))
+            .query(&query)
+            // Longer than Telegram holds the request open
+            .timeout(std::time::Duration::from_secs(POLL_TIMEOUT_SECONDS + 10));
+        let updates: Vec<Update> = self.call("getUpdates", request).await?.unwrap_or_default();
+
+        let mut commands = Vec::new();
+        for update in updates {
+            *offset = Some(update.update_id + 1);
+            let Some(message) = update.message else { continue };
+            if Some(message.chat.id) != self.config.chat_id {
+                tracing::debug!("Ignoring Telegram message from chat {}", message.chat.id);
+                continue;
+            }
+            if let Some(text) = message.text.filter(|text| text.starts_with('/')) {
+                commands.push(text);
+            }
+        }
+        Ok(commands)
+    }
+
+    /// The reply to `command`, from the meetings in `update`
+    pub fn answer(&self, command: &str, update: &MeetingUpdate) -> String {
+        // "/next@SomeBot" in group chats
+        let name = command.split_whitespace().next().unwrap_or_default();
+        let name = name.split('@').next().unwrap_or_default().to_lowercase();
+        match name.as_str() {
+            "/next" => {
+                let mut lines = Vec::new();
+                if let Some(current) = &update.current_meeting {
+                    lines.push(render_template("Now: {title} until {end}", current, self.clock_24h));
+                }
+                if let Some(next) = &update.next_meeting {
+                    let starts = next.start_time.with_timezone(&Local);
+                    if starts.date_naive() == Local::now().date_naive() {
+                        lines.push(render_template("Next: {title} at {start}", next, self.clock_24h));
+                    } else {
+                        let template = format!("Next: {{title}} on {} at {{start}}", starts.format("%a %-d %b"));
+                        lines.push(render_template(&template, next, self.clock_24h));
+                    }
+                }
+                if lines.is_empty() {
+                    "Nothing coming up".to_string()
+                } else {
+                    lines.join("\n")
+                }
+            }
+            "/agenda" => {
+                if update.agenda_today.is_empty() {
+                    return "No meetings today".to_string();
+                }
+                let lines = update.agenda_today.iter().map(|meeting| {
+                    if meeting.all_day {
+                        format!("All day  {}", meeting.title)
+                    } else {
+                        render_template("{start} - {end}  {title}", meeting, self.clock_24h)
+                    }
+                });
+                std::iter::once("Today's meetings:".to_string()).chain(lines).collect::<Vec<_>>().join("\n")
+            }
+            _ => HELP.to_string(),
+        }
+    }
+
+    async fn call<T: DeserializeOwned>(&self, method: &str, request: reqwest::RequestBuilder) -> Result<Option<T>> {
+        let response = request
+            .send()
+            .await
+            // The URL carries the bot token
+            .map_err(|e| anyhow!("Telegram {} request failed: {}", method, e.without_url()))?;
+        // Errors come with a description in the body too
+        let result: TelegramResponse<T> = response
+            .json()
+            .await
+            .map_err(|e| anyhow!("Invalid Telegram {} response: {}", method, e.without_url()))?;
+        if result.ok {
+            Ok(result.result)
+        } else {
+            Err(anyhow!("Telegram {} failed: {}", method, result.description.unwrap_or_else(|| "unknown error".to_string())))
+        }
+    }
+
+    fn method_url(&self, method: &str) -> String {
+        let base_url = self.config.api_base_url.as_deref().unwrap_or(DEFAULT_API_URL);
+        format!(
+            "{}/bot{}/{}",
+            base_url.trim_end_matches('/'),
+            self.config.bot_token.as_deref().unwrap_or_default(),
+            method
+        )
+    }
+}
+
+#[async_trait]
+impl Notifier for TelegramBot {
+    fn name(&self) -> &str {
+        "Telegram"
+    }
+
+    fn remind_before(&self) -> Option<Duration> {
+        (self.config.remind_minutes_before > 0).then(|| Duration::minutes(self.config.remind_minutes_before.into()))
+    }
+
+    async fn notify(&self, event: &MeetingEvent) -> Result<()> {
+        if let MeetingEvent::Reminder(meeting) = event {
+            self.send(&render_template(&self.config.reminder_message, meeting, self.clock_24h)).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{Duration, Local};
+use handlebars::{handlebars_helper, Handlebars};
+use serde_json::{json, Value};
+
+use crate::config::{WebhookConfig, WebhookEvent};
+use crate::meeting::Meeting;
+use crate::notify::{MeetingEvent, Notifier};
+
+/// Longest part of an error response kept in the log
+const MAX_ERROR_CHARS: usize = 200;
+
+handlebars_helper!(json_helper: |value: Json| serde_json::to_string(value).unwrap_or_default());
+
+/// The webhook's URL, header and body templates, registered under `url`,
+/// `body` and `header:<name>`. Nothing is HTML-escaped, and `{{json x}}`
+/// writes `x` as JSON, quoted and escaped, for JSON bodies.
+pub fn templates(config: &WebhookConfig) -> Result<Handlebars<'static>> {
+    let mut templates = Handlebars::new();
+    templates.register_escape_fn(handlebars::no_escape);
+    templates.register_helper("json", Box::new(json_helper));
+    templates.register_template_string("url", &config.url).map_err(|e| anyhow!("invalid url template: {}", e))?;
+    templates.register_template_string("body", &config.body).map_err(|e| anyhow!("invalid body template: {}", e))?;
+    for (name, value) in &config.headers {
+        templates
+            .register_template_string(&format!("header:{}", name), value)
+            .map_err(|e| anyhow!("invalid template for header {}: {}", name, e))?;
+    }
+    Ok(templates)
+}
+
+/// A request rendered from a webhook's templates
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookRequest {
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+/// Sends a request defined in `[[notifications.webhooks]]` on meeting
+/// events, so services without their own integration can be told
+pub struct Webhook {
+    http_client: reqwest::Client,
+    config: WebhookConfig,
+    templates: Handlebars<'static>,
+    clock_24h: bool,
+}
+
+impl Webhook {
+    pub fn new(http_client: reqwest::Client, config: WebhookConfig, clock_24h: bool) -> Self {
+        // Checked when the configuration was loaded
+        let templates = templates(&config).unwrap_or_default();
+        Self { http_client, config, templates, clock_24h }
+    }
+
+    /// The request for `event`, if the webhook is sent for it. Templates see
+    /// `event` (`start`, `end` or `reminder`), the `meeting` as the API
+    /// returns it, its `start` and `end` in the server's time zone and
+    /// `join_url`.
+    pub fn request(&self, event: &MeetingEvent) -> Result<Option<WebhookRequest>> {
+        let (kind, meeting) = match event {
+            MeetingEvent::Started(meeting) => (WebhookEvent::Start, meeting),
+            MeetingEvent::Ended(meeting) => (WebhookEvent::End, meeting),
+            MeetingEvent::Reminder(meeting) => (WebhookEvent::Reminder, meeting),
+            MeetingEvent::Agenda(_) => return Ok(None),
+        };
+        if !self.config.events.contains(&kind) {
+            return Ok(None);
+        }
+        let data = self.data(kind, meeting);
+        let render = |template: &str| {
+            self.templates.render(template, &data).map_err(|e| anyhow!("Failed to render the {}: {}", template, e))
+        };
+        let headers = self
+            .config
+            .headers
+            .keys()
+            .map(|name| Ok((name.clone(), render(&format!("header:{}", name))?)))
+            .collect::<Result<_>>()?;
+        Ok(Some(WebhookRequest { url: render("url")?, headers, body: render("body")? }))
+    }
+
+    fn data(&self, kind: WebhookEvent, meeting: &Meeting) -> Value {
+        let format = if self.clock_24h { "%H:%M" } else { "%-I:%M %p" };
+        json!({
+            "event": kind,
+            "meeting": meeting,
+            "start": meeting.start_time.with_timezone(&Local).format(format).to_string(),
+            "end": meeting.end_time.with_timezone(&Local).format(format).to_string(),
+            "join_url": meeting.url(),
+        })
+    }
+
+    async fn send(&self, request: WebhookRequest) -> Result<()> {
+        // Checked when the configuration was loaded
+        let method = reqwest::Method::from_bytes(self.config.method.to_uppercase().as_bytes()).unwrap_or(reqwest::Method::POST);
+        let mut builder = self.http_client.request(method, &request.url);
+        let has_content_type = request.headers.iter().any(|(name, _)| name.eq_ignore_ascii_case("content-type"));
+        for (name, value) in request.headers {
+            builder = builder.header(name, value);
+        }
+        if !request.body.is_empty() {
+            if !has_content_type {
+                let json = serde_json::from_str::<Value>(&request.body).is_ok();
+                builder = builder.header("content-type", if json { "application/json" } else { "text/plain; charset=utf-8" });
+            }
+            builder = builder.body(request.body);
+        }
+        let response = builder
+            .send()
+            .await
+            // Webhook URLs often carry their credential
+            .map_err(|e| anyhow!("Request failed: {}", e.without_url()))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text: String = response.text().await.unwrap_or_default().chars().take(MAX_ERROR_CHARS).collect();
+            return Err(anyhow!("Returned HTTP {}: {}", status, text));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Notifier for Webhook {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn remind_before(&self) -> Option<Duration> {
+        self.config
+            .events
+            .contains(&WebhookEvent::Reminder)
+            .then(|| Duration::minutes(self.config.remind_minutes_before.into()))
+    }
+
+    async fn notify(&self, event: &MeetingEvent) -> Result<()> {
+        if let Some(request) = self.request(event)? {
+            self.send(request).await?;
+        }
+        Ok(())
+    }
+}
@@ -1,31 +1,49 @@
 class CalendarMonitor {
     constructor() {
         this.ws = null;
+        this.eventSource = null;
+        // The server only pushes changes; countdowns tick from this between pushes
+        this.lastUpdate = null;
+        // WebSocket attempts that failed before connecting; some proxies block them
+        this.failedConnects = 0;
         this.reconnectInterval = null;
         this.reconnectDelay = 1000;
         this.maxReconnectDelay = 30000;
+        // server.auth_token, passed to the page as /?token=...
+        this.token = new URLSearchParams(window.location.search).get('token');
+        // Next meeting the start alert already sounded for
+        this.alertedMeetingId = null;
+        // The server's [display] settings, pushed on connect
+        this.display = { clock_24h: true, locale: 'en-US', theme: 'default', show_descriptions: false, show_locations: true };
         
         this.init();
     }
 
     init() {
+        // Signing in to Google needs the token as well
+        document.querySelectorAll('a[href^="/auth/google/"]').forEach(link => {
+            link.href = this.withToken(link.getAttribute('href'));
+        });
         this.updateCurrentTime();
         this.loadServerInfo();
+        this.loadStats();
         this.connectWebSocket();
         
-        // Update current time every second
+        // Update current time and countdowns every second
         setInterval(() => this.updateCurrentTime(), 1000);
+        setInterval(() => this.refreshCountdowns(), 1000);
+        setInterval(() => this.loadStats(), 5 * 60 * 1000);
     }
 
     updateCurrentTime() {
         const now = new Date();
-        const timeString = now.toLocaleTimeString('en-US', {
-            hour12: false,
+        const timeString = now.toLocaleTimeString(this.display.locale, {
+            hour12: !this.display.clock_24h,
             hour: '2-digit',
             minute: '2-digit',
             second: '2-digit'
         });
-        const dateString = now.toLocaleDateString('en-US', {
+        const dateString = now.toLocaleDateString(this.display.locale, {
             weekday: 'long',
             year: 'numeric',
             month: 'long',
@@ -35,9 +53,24 @@ class CalendarMonitor {
         document.getElementById('currentTime').textContent = `${dateString} - ${timeString}`;
     }
 
+    // Fetch from the API, sending the token when the page was given one
+    apiFetch(path) {
+        const headers = this.token ? { Authorization: `Bearer ${this.token}` } : {};
+        return fetch(path, { headers });
+    }
+
+    // WebSocket and EventSource can't send headers, so the token goes in the query
+    withToken(url) {
+        if (!this.token) {
+            return url;
+        }
+        const separator = url.includes('?') ? '&' : '?';
+        return `${url}${separator}token=${encodeURIComponent(this.token)}`;
+    }
+
     async loadServerInfo() {
         try {
-            const response = await fetch('/api/server-info');
+            const response = await this.apiFetch('/api/v1/server-info');
             if (response.ok) {
                 const serverInfo = await response.json();
                 const ipInfoElement = document.getElementById('ipInfo');
@@ -50,23 +83,41 @@ class CalendarMonitor {
         }
     }
 
+    async loadStats() {
+        try {
+            const response = await this.apiFetch('/api/v1/stats?period=day');
+            if (response.ok) {
+                const stats = await response.json();
+                const hours = Math.floor(stats.meeting_minutes / 60);
+                const minutes = stats.meeting_minutes % 60;
+                const time = hours > 0 ? `${hours}h ${minutes}m` : `${minutes}m`;
+                document.getElementById('statsInfo').textContent = `${time} in meetings today`;
+            }
+        } catch (error) {
+            console.error('Failed to load meeting stats:', error);
+        }
+    }
+
     connectWebSocket() {
         const protocol = window.location.protocol === 'https:' ? 'wss:' : 'ws:';
-        const wsUrl = `${protocol}//${window.location.host}/ws`;
+        const wsUrl = `${protocol}//${window.location.host}/ws?protocol=2`;
         
         try {
-            this.ws = new WebSocket(wsUrl);
+            this.ws = new WebSocket(this.withToken(wsUrl));
+            let opened = false;
             
             this.ws.onopen = () => {
                 console.log('WebSocket connected');
+                opened = true;
+                this.failedConnects = 0;
                 this.updateConnectionStatus(true);
                 this.reconnectDelay = 1000; // Reset reconnect delay
+                this.sendSubscription();
             };
             
             this.ws.onmessage = (event) => {
                 try {
-                    const data = JSON.parse(event.data);
-                    this.updateMeetingDisplay(data);
+                    this.receiveMessage(JSON.parse(event.data));
                 } catch (error) {
                     console.error('Error parsing WebSocket message:', error);
                 }
@@ -75,7 +126,11 @@ class CalendarMonitor {
             this.ws.onclose = () => {
                 console.log('WebSocket disconnected');
                 this.updateConnectionStatus(false);
-                this.scheduleReconnect();
+                if (!opened && ++this.failedConnects >= 3 && window.EventSource) {
+                    this.connectEventSource();
+                } else {
+                    this.scheduleReconnect();
+                }
             };
             
             this.ws.onerror = (error) => {
@@ -90,6 +145,93 @@ class CalendarMonitor {
         }
     }
 
+    // Tailor the updates from the page URL, e.g. /?sources=work&time_blocks=false&timezone=Europe/Istanbul&profile=lobby
+    sendSubscription() {
+        const params = new URLSearchParams(window.location.search);
+        const subscription = {};
+        if (params.get('sources')) {
+            subscription.sources = params.get('sources').split(',');
+        }
+        if (params.get('time_blocks') === 'false') {
+            subscription.include_time_blocks = false;
+        }
+        if (params.get('timezone')) {
+            subscription.timezone = params.get('timezone');
+        }
+        if (params.get('profile')) {
+            subscription.profile = params.get('profile');
+        }
+        if (Object.keys(subscription).length > 0) {
+            this.ws.send(JSON.stringify({ type: 'subscribe', data: subscription }));
+        }
+    }
+
+    receiveMessage(message) {
+        switch (message.type) {
+            case 'update':
+                this.receiveUpdate(message.data);
+                break;
+            case 'error':
+                console.warn('Server rejected a message:', message.data.message);
+                break;
+            case 'source_status': {
+                const failing = message.data.filter(source => !source.last_success
+                    || (source.last_error_at && new Date(source.last_error_at) > new Date(source.last_success)));
+                if (failing.length > 0) {
+                    console.warn('Calendar sources failing:', failing.map(source => source.source).join(', '));
+                }
+                break;
+            }
+            case 'display':
+                this.applyDisplay(message.data);
+                break;
+            case 'config_changed':
+                // Settings such as the stats period may have changed
+                this.loadStats();
+                break;
+            case 'pong':
+                break;
+            default:
+                console.warn('Unknown WebSocket message:', message.type);
+        }
+    }
+
+    // Apply the server's [display] settings: clock, locale, theme and which details show
+    applyDisplay(display) {
+        this.display = { ...this.display, ...display };
+        const body = document.body;
+        [...body.classList].filter(name => name.startsWith('theme-')).forEach(name => body.classList.remove(name));
+        body.classList.add(`theme-${this.display.theme}`);
+        body.classList.toggle('show-descriptions', this.display.show_descriptions);
+        body.classList.toggle('hide-locations', !this.display.show_locations);
+        this.updateCurrentTime();
+        if (this.lastUpdate) {
+            this.updateMeetingDisplay(this.lastUpdate);
+        }
+    }
+
+    // Fall back to Server-Sent Events, which reconnect by themselves
+    connectEventSource() {
+        console.log('WebSocket unavailable, switching to Server-Sent Events');
+        const profile = new URLSearchParams(window.location.search).get('profile');
+        const eventsUrl = profile ? `/events?profile=${encodeURIComponent(profile)}` : '/events';
+        this.eventSource = new EventSource(this.withToken(eventsUrl));
+        this.apiFetch('/api/v1/display-config')
+            .then(response => response.json())
+            .then(display => this.applyDisplay(display))
+            .catch(error => console.error('Error loading display settings:', error));
+
+        this.eventSource.onopen = () => this.updateConnectionStatus(true);
+        this.eventSource.onmessage = (event) => {
+            try {
+                this.receiveUpdate(JSON.parse(event.data));
+            } catch (error) {
+                console.error('Error parsing event:', error);
+            }
+        };
+        this.eventSource.onerror = () => this.updateConnectionStatus(false);
+    }
+
     scheduleReconnect() {
         if (this.reconnectInterval) {
             clearTimeout(this.reconnectInterval);
@@ -119,13 +261,148 @@ class CalendarMonitor {
         }
     }
 
+    receiveUpdate(data) {
+        this.lastUpdate = data;
+        this.updateMeetingDisplay(data);
+    }
+
+    // Count down from the meeting times between pushes from the server
+    refreshCountdowns() {
+        const data = this.lastUpdate;
+        if (!data) {
+            return;
+        }
+
+        const secondsUntil = (time) => Math.max(0, Math.floor((new Date(time) - new Date()) / 1000));
+        this.updateMeetingDisplay({
+            ...data,
+            countdown_seconds: data.current_meeting ? secondsUntil(data.current_meeting.end_time) : null,
+            next_meeting_starts_in_seconds: data.next_meeting ? secondsUntil(data.next_meeting.start_time) : null,
+        });
+    }
+
     updateMeetingDisplay(data) {
-        this.updateCurrentMeeting(data.current_meeting, data.countdown_seconds);
-        this.updateNextMeeting(data.next_meeting);
+        this.updateCurrentMeeting(data.current_meeting, data.countdown_seconds, data.countdown);
+        this.updateNextMeeting(data.next_meeting, data.next_meeting_starts_in_seconds, data.countdown, data.quiet);
         this.updateActiveTimeBlocks(data.active_time_blocks);
+        this.updateNextTimeBlock(data.upcoming_time_blocks);
+        this.updateAllDayEvents(data.all_day_events);
+        this.updateHoliday(data.holiday, data.next_meeting_starts_in_seconds);
+        this.updateStaleInfo(data.stale, data.stale_since);
+        document.body.classList.toggle('quiet', !!data.quiet);
+        this.updateSourceErrors(data.source_errors, data.last_updated);
+        this.updateAgenda(data.agenda_today);
+    }
+
+    updateAgenda(agenda) {
+        const list = document.getElementById('agendaList');
+        list.replaceChildren();
+        if (!agenda || agenda.length === 0) {
+            const empty = document.createElement('li');
+            empty.textContent = 'Nothing on today';
+            list.appendChild(empty);
+            return;
+        }
+
+        const now = new Date();
+        const isTimeBlock = (event) => event.event_type || event.source?.time_blocks
+            || (event.title.startsWith('[') && event.title.endsWith(']'));
+        for (const event of agenda) {
+            const item = document.createElement('li');
+            const time = document.createElement('span');
+            time.className = 'agenda-time';
+            time.textContent = event.local?.range
+                ?? (event.all_day ? 'All day' : this.formatTimeRange(event.start_time, event.end_time));
+            const title = document.createElement('span');
+            title.textContent = event.title;
+            item.append(time, title);
+
+            item.classList.toggle('ended', new Date(event.end_time) <= now);
+            item.classList.toggle('active', !event.all_day && new Date(event.start_time) <= now && new Date(event.end_time) > now);
+            item.classList.toggle('time-block', Boolean(isTimeBlock(event)));
+            list.appendChild(item);
+        }
     }
 
-    updateCurrentMeeting(meeting, countdownSeconds) {
+    updateAllDayEvents(events) {
+        const allDayElement = document.getElementById('allDayEvents');
+        if (!events || events.length === 0) {
+            allDayElement.style.display = 'none';
+            return;
+        }
+
+        document.getElementById('allDayEventsTitles').textContent = events.map(event => event.title).join(', ');
+        allDayElement.style.display = 'flex';
+    }
+
+    updateHoliday(holiday, nextStartsIn) {
+        // Explain an empty day instead of just saying there is nothing on
+        let text = holiday ? `No events - today is ${holiday}` : 'No event in progress';
+        if (nextStartsIn !== undefined && nextStartsIn !== null) {
+            text += ` - next meeting in ${this.formatCountdown(nextStartsIn)}`;
+        }
+        document.querySelector('#noCurrentMeeting p').textContent = text;
+    }
+
+    updateStaleInfo(stale, staleSince) {
+        const staleElement = document.getElementById('staleInfo');
+        if (!stale) {
+            staleElement.style.display = 'none';
+            return;
+        }
+
+        const since = staleSince ? new Date(staleSince) : null;
+        staleElement.textContent = since
+            ? `Offline - showing calendar from ${since.toLocaleString([], { weekday: 'short', hour: '2-digit', minute: '2-digit' })}`
+            : 'Offline - showing saved calendar';
+        staleElement.style.display = 'inline';
+    }
+
+    // A warning sign with the failing calendars as its tooltip, so missing meetings don't go unnoticed
+    updateSourceErrors(errors, lastUpdated) {
+        const warningElement = document.getElementById('sourceWarning');
+        if (!errors || errors.length === 0) {
+            warningElement.style.display = 'none';
+            return;
+        }
+
+        const time = (value) => new Date(value).toLocaleString([], { weekday: 'short', hour: '2-digit', minute: '2-digit' });
+        const lines = errors.map(error => `${error.source}: failing since ${time(error.since)} (${error.error})`);
+        if (lastUpdated) {
+            lines.push(`Last successful update: ${time(lastUpdated)}`);
+        }
+        warningElement.title = lines.join('\n');
+        warningElement.style.display = 'inline';
+    }
+
+    // 'critical', 'warning' or null, by the server's [countdown] thresholds
+    countdownLevel(seconds, thresholds) {
+        const { warning_threshold_seconds: warning = 300, critical_threshold_seconds: critical = 60 } = thresholds || {};
+        if (seconds === null || seconds === undefined || seconds <= 0) {
+            return null;
+        }
+        if (seconds <= critical) {
+            return 'critical';
+        }
+        return seconds <= warning ? 'warning' : null;
+    }
+
+    // A short beep; browsers may keep it silent until the page was interacted with
+    playAlert() {
+        try {
+            const context = new (window.AudioContext || window.webkitAudioContext)();
+            const oscillator = context.createOscillator();
+            oscillator.frequency.value = 880;
+            oscillator.connect(context.destination);
+            oscillator.start();
+            oscillator.stop(context.currentTime + 0.3);
+            oscillator.onended = () => context.close();
+        } catch (error) {
+            console.warn('Could not play alert', error);
+        }
+    }
+
+    updateCurrentMeeting(meeting, countdownSeconds, thresholds) {
         const noMeetingDiv = document.getElementById('noCurrentMeeting');
         const meetingInfoDiv = document.getElementById('currentMeetingInfo');
         const meetingCard = document.getElementById('currentMeetingCard');
@@ -133,7 +410,8 @@ class CalendarMonitor {
         if (!meeting) {
             noMeetingDiv.style.display = 'block';
             meetingInfoDiv.style.display = 'none';
-            meetingCard.classList.remove('urgent');
+            meetingCard.classList.remove('urgent', 'warning');
+            meetingCard.style.borderLeftColor = '';
             return;
         }
         
@@ -148,23 +426,24 @@ class CalendarMonitor {
             titleElement.innerHTML = `${meeting.title} <span class="response-status ${this.getResponseStatusClass(meeting.response_status)}">${responseStatusLabel}</span>`;
         }
         
+        this.markRecurring(titleElement, meeting);
+        this.updateMeetingSource('currentMeetingSource', meeting.source);
+        meetingCard.style.borderLeftColor = meeting.color || '';
         document.getElementById('currentMeetingTime').textContent = this.formatTimeRange(meeting.start_time, meeting.end_time);
         document.getElementById('currentMeetingLocation').textContent = meeting.location || 'No location specified';
+        document.getElementById('currentMeetingDescription').textContent = meeting.description || '';
         
         // Update countdown
         const countdownElement = document.getElementById('currentMeetingCountdown');
         if (countdownSeconds && countdownSeconds > 0) {
             countdownElement.textContent = this.formatCountdown(countdownSeconds);
             
-            // Add urgent styling if less than 5 minutes remaining
-            if (countdownSeconds <= 300) {
-                meetingCard.classList.add('urgent');
-            } else {
-                meetingCard.classList.remove('urgent');
-            }
+            const level = this.countdownLevel(countdownSeconds, thresholds);
+            meetingCard.classList.toggle('urgent', level === 'critical');
+            meetingCard.classList.toggle('warning', level === 'warning');
         } else {
             countdownElement.textContent = '00:00';
-            meetingCard.classList.remove('urgent');
+            meetingCard.classList.remove('urgent', 'warning');
         }
         
         // Add update animation
@@ -172,7 +451,7 @@ class CalendarMonitor {
         setTimeout(() => meetingCard.classList.remove('updating'), 300);
     }
 
-    updateNextMeeting(meeting) {
+    updateNextMeeting(meeting, startsInSeconds, thresholds, quiet) {
         const noMeetingDiv = document.getElementById('noNextMeeting');
         const meetingInfoDiv = document.getElementById('nextMeetingInfo');
         const meetingCard = document.getElementById('nextMeetingCard');
@@ -180,6 +459,7 @@ class CalendarMonitor {
         if (!meeting) {
             noMeetingDiv.style.display = 'block';
             meetingInfoDiv.style.display = 'none';
+            meetingCard.style.borderLeftColor = '';
             return;
         }
         
@@ -194,8 +474,12 @@ class CalendarMonitor {
             titleElement.innerHTML = `${meeting.title} <span class="response-status ${this.getResponseStatusClass(meeting.response_status)}">${responseStatusLabel}</span>`;
         }
         
+        this.markRecurring(titleElement, meeting);
+        this.updateMeetingSource('nextMeetingSource', meeting.source);
+        meetingCard.style.borderLeftColor = meeting.color || '';
         document.getElementById('nextMeetingTime').textContent = this.formatTimeRange(meeting.start_time, meeting.end_time);
         document.getElementById('nextMeetingLocation').textContent = meeting.location || 'No location specified';
+        document.getElementById('nextMeetingDescription').textContent = meeting.description || '';
         
         // Check if meeting is not today and show date if needed
         const meetingDate = new Date(meeting.start_time);
@@ -217,9 +501,20 @@ class CalendarMonitor {
         const duration = this.calculateDuration(meeting.start_time, meeting.end_time);
         document.getElementById('nextMeetingDuration').textContent = `Duration: ${duration}`;
         
-        // Calculate time until meeting starts
-        const timeUntilStart = this.calculateTimeUntilStart(meeting.start_time);
-        document.getElementById('nextMeetingTimeUntil').textContent = timeUntilStart;
+        // Prefer the server's countdown; the browser clock may be off
+        const timeUntilStart = startsInSeconds !== undefined && startsInSeconds !== null
+            ? (startsInSeconds > 0 ? this.formatCountdown(startsInSeconds) : 'Starting now')
+            : this.calculateTimeUntilStart(meeting.start_time);
+        const timeUntilElement = document.getElementById('nextMeetingTimeUntil');
+        timeUntilElement.textContent = timeUntilStart;
+
+        const level = this.countdownLevel(startsInSeconds, thresholds);
+        timeUntilElement.classList.toggle('critical', level === 'critical');
+        timeUntilElement.classList.toggle('warning', level === 'warning');
+        if (level === 'critical' && !quiet && this.alertedMeetingId !== meeting.id) {
+            this.alertedMeetingId = meeting.id;
+            this.playAlert();
+        }
     }
 
     updateActiveTimeBlocks(timeBlocks) {
@@ -238,6 +533,8 @@ class CalendarMonitor {
         
         noTimeBlockDiv.style.display = 'none';
         timeBlockInfoDiv.style.display = 'flex';
+        timeBlockInfoDiv.classList.toggle('out-of-office', timeBlock.event_type === 'OutOfOffice');
+        timeBlockInfoDiv.classList.toggle('focus-time', timeBlock.event_type === 'FocusTime');
         
         // Extract time block name (remove brackets)
         const timeBlockName = timeBlock.title.startsWith('[') && timeBlock.title.endsWith(']') 
@@ -254,22 +551,49 @@ class CalendarMonitor {
         const timeLeft = Math.max(0, Math.floor((endTime - now) / 1000));
         const countdownText = this.formatCountdown(timeLeft);
         document.getElementById('activeTimeBlockCountdown').textContent = countdownText;
+
+        // Like the countdown, worked out locally so the bar moves between pushes
+        const startTime = new Date(timeBlock.start_time);
+        const progress = endTime > startTime
+            ? Math.min(100, Math.max(0, (now - startTime) / (endTime - startTime) * 100))
+            : timeBlock.progress_percent;
+        document.getElementById('activeTimeBlockProgress').style.width = `${progress}%`;
+    }
+
+    updateNextTimeBlock(timeBlocks) {
+        const nextElement = document.getElementById('nextTimeBlock');
+        if (!timeBlocks || timeBlocks.length === 0) {
+            nextElement.style.display = 'none';
+            return;
+        }
+
+        const timeBlock = timeBlocks[0];
+        const name = timeBlock.title.startsWith('[') && timeBlock.title.endsWith(']')
+            ? timeBlock.title.slice(1, -1)
+            : timeBlock.title;
+        document.getElementById('nextTimeBlockTitle').textContent = name;
+        document.getElementById('nextTimeBlockTime').textContent = this.formatTimeRange(timeBlock.start_time, timeBlock.end_time);
+        nextElement.style.display = 'flex';
     }
 
     formatTimeRange(startTime, endTime) {
         const start = new Date(startTime);
         const end = new Date(endTime);
         
-        const startStr = start.toLocaleTimeString('en-US', {
-            hour12: false,
+        // In the time zone this display subscribed with, if any
+        const timeZone = this.lastUpdate?.timezone || undefined;
+        const startStr = start.toLocaleTimeString(this.display.locale, {
+            hour12: !this.display.clock_24h,
             hour: '2-digit',
-            minute: '2-digit'
+            minute: '2-digit',
+            timeZone
         });
         
-        const endStr = end.toLocaleTimeString('en-US', {
-            hour12: false,
+        const endStr = end.toLocaleTimeString(this.display.locale, {
+            hour12: !this.display.clock_24h,
             hour: '2-digit',
-            minute: '2-digit'
+            minute: '2-digit',
+            timeZone
         });
         
         return `${startStr} - ${endStr}`;
@@ -306,6 +630,28 @@ class CalendarMonitor {
         }
     }
 
+    markRecurring(titleElement, meeting) {
+        if (!meeting.recurring_event_id) return;
+
+        const indicator = document.createElement('span');
+        indicator.className = 'recurring-indicator';
+        indicator.title = 'Recurring meeting';
+        indicator.textContent = '↻';
+        titleElement.appendChild(indicator);
+    }
+
+    updateMeetingSource(elementId, source) {
+        const sourceElement = document.getElementById(elementId);
+        if (!source) {
+            sourceElement.style.display = 'none';
+            return;
+        }
+
+        sourceElement.textContent = source.name;
+        sourceElement.style.borderLeftColor = source.color || '';
+        sourceElement.style.display = 'block';
+    }
+
     getResponseStatusClass(responseStatus) {
         if (!responseStatus) return '';
         
@@ -352,7 +698,7 @@ class CalendarMonitor {
         
         if (daysDiff >= 2 && daysDiff <= 7) {
             // Show day name for this week
-            return meetingDate.toLocaleDateString('en-US', { 
+            return meetingDate.toLocaleDateString(this.display.locale, { 
                 weekday: 'long',
                 month: 'short',
                 day: 'numeric'
@@ -360,7 +706,7 @@ class CalendarMonitor {
         }
         
         // Show full date for further dates
-        return meetingDate.toLocaleDateString('en-US', {
+        return meetingDate.toLocaleDateString(this.display.locale, {
             weekday: 'short',
             month: 'short',
             day: 'numeric',
@@ -0,0 +1,28 @@
+use calendar_monitor::badge::{render_badge, BUSY_COLOR, FREE_COLOR};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_badge() {
+        let svg = render_badge("calendar", "busy until 15:00", BUSY_COLOR);
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.contains("<title>calendar: busy until 15:00</title>"));
+        assert!(svg.contains(&format!("fill=\"{}\"", BUSY_COLOR)));
+
+        // The message part grows with the text
+        let width = |svg: &str| -> usize {
+            let start = svg.find("width=\"").unwrap() + 7;
+            svg[start..].split('"').next().unwrap().parse().unwrap()
+        };
+        assert!(width(&svg) > width(&render_badge("calendar", "free", FREE_COLOR)));
+    }
+
+    #[test]
+    fn test_render_badge_escapes_text() {
+        let svg = render_badge("R&D <room>", "free", FREE_COLOR);
+        assert!(svg.contains("R&amp;D &lt;room&gt;"));
+        assert!(!svg.contains("<room>"));
+    }
+}
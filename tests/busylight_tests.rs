@@ -0,0 +1,94 @@
+use calendar_monitor::app::MeetingUpdate;
+use calendar_monitor::busylight::{BusyLight, LightState, Rgb};
+use calendar_monitor::config::{BusyLightConfig, BusyLightDevice};
+use calendar_monitor::meeting::Meeting;
+use chrono::{Duration, Utc};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(current: bool, next_in_minutes: Option<i64>, quiet: bool) -> MeetingUpdate {
+        let mut update: MeetingUpdate = serde_json::from_value(serde_json::json!({
+            "current_meeting": null,
+            "next_meeting": null,
+            "countdown_seconds": null,
+            "active_time_blocks": [],
+        }))
+        .unwrap();
+        if current {
+            let start = Utc::now() - Duration::minutes(10);
+            update.current_meeting = Some(Meeting::new("Standup".to_string(), start, start + Duration::minutes(30)));
+        }
+        update.next_meeting_starts_in_seconds = next_in_minutes.map(|minutes| minutes * 60);
+        update.quiet = quiet;
+        update
+    }
+
+    #[test]
+    fn test_light_follows_the_meetings() {
+        let soon = Duration::minutes(5);
+        assert_eq!(LightState::of(&update(true, Some(3), false), soon), LightState::Busy);
+        assert_eq!(LightState::of(&update(false, Some(5), false), soon), LightState::Soon);
+        assert_eq!(LightState::of(&update(false, Some(6), false), soon), LightState::Free);
+        assert_eq!(LightState::of(&update(false, None, false), soon), LightState::Free);
+
+        let light = BusyLight::new(BusyLightConfig { soon_color: "#FF8000".to_string(), ..Default::default() });
+        assert_eq!(light.color(&update(true, None, false)), Rgb(255, 0, 0));
+        assert_eq!(light.color(&update(false, Some(2), false)), Rgb(255, 128, 0));
+        assert_eq!(light.color(&update(false, None, false)), Rgb(0, 255, 0));
+        assert_eq!(light.color(&update(true, None, true)), Rgb::OFF, "off during quiet hours");
+
+        let light = BusyLight::new(BusyLightConfig { off_in_quiet_hours: false, ..Default::default() });
+        assert_eq!(light.color(&update(true, None, true)), Rgb(255, 0, 0));
+    }
+
+    #[test]
+    fn test_device_reports() {
+        let orange = Rgb(255, 128, 0);
+        assert_eq!(BusyLightDevice::Luxafor.report(orange), vec![0, 1, 0xff, 255, 128, 0, 0, 0, 0]);
+        assert_eq!(BusyLightDevice::Blinkstick.report(orange), vec![1, 255, 128, 0]);
+
+        let kuando = BusyLightDevice::Kuando.report(orange);
+        assert_eq!(kuando.len(), 65, "report id and 64 bytes");
+        assert_eq!(kuando[1..9], [0x10, 0, 100, 50, 0, 0, 0, 0]);
+        let checksum: u16 = kuando[1..63].iter().map(|&byte| u16::from(byte)).sum();
+        assert_eq!(kuando[63..], checksum.to_be_bytes());
+
+        assert!(BusyLightDevice::Luxafor.matches(0x04d8, 0xf372));
+        assert!(!BusyLightDevice::Luxafor.matches(0x04d8, 0xf848));
+        assert!(BusyLightDevice::Kuando.matches(0x04d8, 0xf848));
+        assert!(BusyLightDevice::Kuando.matches(0x27bb, 0x3bcd));
+        assert!(BusyLightDevice::Blinkstick.matches(0x20a0, 0x41e5));
+    }
+
+    #[test]
+    fn test_busylight_validation() {
+        assert_eq!(BusyLightConfig::default().validate().is_ok(), cfg!(feature = "busylight"));
+        assert!(Rgb::parse("red").is_err());
+        assert!(Rgb::parse("#12345").is_err());
+        assert!(Rgb::parse("#12345g").is_err());
+
+        let config: BusyLightConfig = toml::from_str("device = \"kuando\"\nbusy_color = \"#00f\"").unwrap();
+        assert_eq!(config.device, BusyLightDevice::Kuando);
+        let error = config.validate().unwrap_err().to_string();
+        assert!(error.contains("busy_color") || !cfg!(feature = "busylight"), "{}", error);
+    }
+
+    #[cfg(not(feature = "busylight"))]
+    #[tokio::test]
+    async fn test_failures_are_reported_once() {
+        use calendar_monitor::events::{Event, NotificationBackend};
+        use std::sync::Arc;
+
+        let light = BusyLight::new(BusyLightConfig::default());
+        let busy = Event::Update(Arc::new(update(true, None, false)));
+        let error = light.handle(&busy).await.unwrap_err();
+        assert!(error.to_string().contains("without busy light support"), "{}", error);
+
+        // Neither the same color again nor a new one with the same error is reported again
+        light.handle(&busy).await.unwrap();
+        light.handle(&Event::Update(Arc::new(update(false, None, false)))).await.unwrap();
+        light.handle(&Event::ConfigChanged { loaded_at: Utc::now() }).await.unwrap();
+    }
+}
@@ -0,0 +1,147 @@
+use calendar_monitor::caldav::{calendar_query, parse_multistatus};
+use calendar_monitor::calendar::CalendarService;
+use calendar_monitor::config::{CaldavConfig, CaldavSourceConfig, Config};
+use calendar_monitor::recurrence::Window;
+use chrono::{NaiveDate, Utc};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn multistatus(calendar_data: &[String]) -> String {
+        let responses: String = calendar_data
+            .iter()
+            .enumerate()
+            .map(|(i, data)| format!(
+                r#"<d:response>
+    <d:href>/dav/calendars/alice/personal/event-{}.ics</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:getetag>"{}"</d:getetag>
+        <cal:calendar-data><![CDATA[{}]]></cal:calendar-data>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>"#,
+                i, i, data
+            ))
+            .collect();
+        format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:" xmlns:cal="urn:ietf:params:xml:ns:caldav">{}</d:multistatus>"#,
+            responses
+        )
+    }
+
+    fn vcalendar(uid: &str, summary: &str, start: chrono::DateTime<Utc>) -> String {
+        let end = start + chrono::Duration::minutes(30);
+        format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Test//EN\r\nBEGIN:VEVENT\r\nUID:{}\r\nSUMMARY:{}\r\nDTSTART:{}\r\nDTEND:{}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+            uid,
+            summary,
+            start.format("%Y%m%dT%H%M%SZ"),
+            end.format("%Y%m%dT%H%M%SZ"),
+        )
+    }
+
+    #[test]
+    fn test_calendar_query_time_range() {
+        let day = NaiveDate::from_ymd_opt(2025, 6, 2).unwrap();
+        let query = calendar_query(&Window::new(day, day.succ_opt().unwrap()));
+
+        assert!(query.contains(r#"<c:comp-filter name="VEVENT">"#));
+        assert!(query.contains(r#"<c:time-range start="20250602T000000Z" end="20250604T000000Z"/>"#));
+    }
+
+    #[test]
+    fn test_parse_multistatus() {
+        let first = vcalendar("a@example.com", "Planning", Utc::now());
+        let second = vcalendar("b@example.com", "Retro", Utc::now());
+        let calendar_data = parse_multistatus(&multistatus(&[first.clone(), second])).unwrap();
+
+        assert_eq!(calendar_data.len(), 2);
+        // XML parsing normalizes line endings, which the ICS parser accepts as well
+        assert_eq!(calendar_data[0], first.replace("\r\n", "\n"));
+
+        // Resources without calendar data (e.g. 404 propstats) are skipped
+        assert!(parse_multistatus(&multistatus(&[])).unwrap().is_empty());
+        assert!(parse_multistatus("not xml").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_caldav_source_feeds_meetings() {
+        use wiremock::matchers::{basic_auth, header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let start = Utc::now() + chrono::Duration::minutes(10);
+        Mock::given(method("REPORT"))
+            .and(path("/dav/calendars/alice/personal/"))
+            .and(header("Depth", "1"))
+            .and(basic_auth("alice", "app-password"))
+            .respond_with(
+                ResponseTemplate::new(207)
+                    .set_body_string(multistatus(&[vcalendar("sync@example.com", "Nextcloud sync", start)])),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = Config {
+            caldav: CaldavConfig {
+                sources: vec![CaldavSourceConfig {
+                    url: format!("{}/dav/calendars/alice/personal/", server.uri()),
+                    username: Some("alice".to_string()),
+                    password: Some("app-password".to_string()),
+                    name: Some("Nextcloud".to_string()),
+                    ..Default::default()
+                }],
+            },
+            ..Default::default()
+        };
+        let service = CalendarService::new_from_config(&config, reqwest::Client::new());
+
+        let meetings = service.get_meetings_for_today_and_tomorrow().await.unwrap();
+        assert_eq!(meetings.len(), 1);
+        assert_eq!(meetings[0].title, "Nextcloud sync");
+        assert_eq!(meetings[0].uid.as_deref(), Some("sync@example.com"));
+        let source = meetings[0].source.as_ref().unwrap();
+        assert_eq!(source.id, "personal");
+        assert_eq!(source.name, "Nextcloud");
+
+        let health = service.source_health().await;
+        assert_eq!(health.len(), 1);
+        assert!(health[0].is_healthy());
+        assert_eq!(health[0].event_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_caldav_auth_failure_is_recorded() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("REPORT"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let config = Config {
+            caldav: CaldavConfig {
+                sources: vec![CaldavSourceConfig {
+                    url: format!("{}/calendars/alice/work/", server.uri()),
+                    username: Some("alice".to_string()),
+                    password: Some("wrong".to_string()),
+                    ..Default::default()
+                }],
+            },
+            ..Default::default()
+        };
+        let service = CalendarService::new_from_config(&config, reqwest::Client::new());
+
+        assert!(service.get_meetings_for_today_and_tomorrow().await.unwrap().is_empty());
+        let health = service.source_health().await;
+        assert!(!health[0].is_healthy());
+        assert!(health[0].last_error.as_ref().unwrap().contains("401"));
+    }
+}
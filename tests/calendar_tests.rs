@@ -1,7 +1,7 @@
 use calendar_monitor::calendar::CalendarService;
 use calendar_monitor::meeting::{Meeting, ResponseStatus};
-use calendar_monitor::config::{Config, ServerConfig, IcsConfig, GoogleConfig};
-use chrono::{NaiveDate, Utc};
+use calendar_monitor::config::{Config, ServerConfig, IcsConfig, IcsSourceConfig, GoogleConfig};
+use chrono::{NaiveDate, TimeZone, Utc};
 
 #[cfg(test)]
 mod tests {
@@ -13,30 +13,34 @@ mod tests {
                 host: "127.0.0.1".to_string(),
                 port: 3000,
                 cache_ttl_seconds: 300,
+                ..Default::default()
             },
             ics: IcsConfig {
                 file_paths: vec![
                     "test_calendar1.ics".to_string(),
                     "test_calendar2.ics".to_string(),
                 ],
+                ..Default::default()
             },
             google: GoogleConfig {
                 client_id: None,
                 client_secret: None,
                 redirect_uri: None,
+                ..Default::default()
             },
+            ..Default::default()
         }
     }
 
     fn create_test_service() -> CalendarService {
         let config = create_test_config();
-        CalendarService::new_from_config(&config)
+        CalendarService::new_from_config(&config, reqwest::Client::new())
     }
 
     #[test]
     fn test_calendar_service_from_config() {
         let config = create_test_config();
-        let _service = CalendarService::new_from_config(&config);
+        let _service = CalendarService::new_from_config(&config, reqwest::Client::new());
         
         // Test that the service is created successfully
         // Since fields are private, we test that the service can be instantiated
@@ -51,18 +55,22 @@ mod tests {
                 host: "127.0.0.1".to_string(),
                 port: 3000,
                 cache_ttl_seconds: 600,
+                ..Default::default()
             },
             ics: IcsConfig {
                 file_paths: vec![], // Empty file paths
+                ..Default::default()
             },
             google: GoogleConfig {
                 client_id: None,
                 client_secret: None,
                 redirect_uri: None,
+                ..Default::default()
             },
+            ..Default::default()
         };
         
-        let _service = CalendarService::new_from_config(&config);
+        let _service = CalendarService::new_from_config(&config, reqwest::Client::new());
         
         // Test that the service is created successfully with empty ICS config
         // Since fields are private, we verify it doesn't panic on creation
@@ -159,6 +167,35 @@ mod tests {
         assert!(countdown < 35 * 60); // But less than 35 minutes due to test execution time
     }
 
+    #[test]
+    fn test_meeting_progress() {
+        let now = Utc::now();
+        let meeting = |start: i64, end: i64| Meeting::new(
+            "[Focus]".to_string(),
+            now + chrono::Duration::minutes(start),
+            now + chrono::Duration::minutes(end)
+        );
+
+        assert_eq!(meeting(10, 70).progress_percent(), 0);
+        assert!((24..=25).contains(&meeting(-30, 90).progress_percent()));
+        assert_eq!(meeting(-70, -10).progress_percent(), 100);
+        assert_eq!(meeting(-10, -10).progress_percent(), 100);
+    }
+
+    #[test]
+    fn test_meeting_local_times() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 15, 11, 0, 0).unwrap();
+        let meeting = Meeting::new("Planning".to_string(), start, start + chrono::Duration::minutes(90))
+            .with_local_times(chrono_tz::Asia::Tokyo);
+        let local = meeting.local.unwrap();
+        assert_eq!(local.date, "2024-01-15");
+        assert_eq!(local.range, "20:00 - 21:30");
+
+        let meeting = Meeting::new("Late".to_string(), start, start + chrono::Duration::hours(1))
+            .with_local_times(chrono_tz::Pacific::Kiritimati);
+        assert_eq!(meeting.local.unwrap().date, "2024-01-16");
+    }
+
     #[test]
     fn test_meeting_builder() {
         let meeting = Meeting::new(
@@ -469,4 +506,205 @@ mod tests {
         assert!(meeting.should_display());
         assert_eq!(meeting.response_status_label(), None);
     }
+
+    #[tokio::test]
+    async fn test_source_health_tracking() {
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+        let now = Utc::now();
+        let good_path = temp_dir.path().join("good.ics");
+        std::fs::write(&good_path, format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nSUMMARY:Healthy Event\r\nDTSTART:{}\r\nDTEND:{}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+            (now + chrono::Duration::minutes(30)).format("%Y%m%dT%H%M%SZ"),
+            (now + chrono::Duration::minutes(60)).format("%Y%m%dT%H%M%SZ"),
+        )).expect("Failed to write ICS file");
+        let good = good_path.to_string_lossy().to_string();
+        let missing = temp_dir.path().join("missing.ics").to_string_lossy().to_string();
+
+        let service = CalendarService::new_with_ics_files(vec![good.clone(), missing.clone()]);
+
+        // Nothing fetched yet
+        let health = service.source_health().await;
+        assert_eq!(health.len(), 2);
+        assert!(health.iter().all(|h| h.last_success.is_none() && h.last_error.is_none()));
+        assert!(health.iter().all(|h| !h.is_healthy()));
+
+        service.get_meetings_for_today_and_tomorrow().await.unwrap();
+
+        let health = service.source_health().await;
+        let good_health = health.iter().find(|h| h.source == good).unwrap();
+        assert!(good_health.is_healthy());
+        assert!(good_health.last_error.is_none());
+        assert_eq!(good_health.event_count, 1);
+
+        let missing_health = health.iter().find(|h| h.source == missing).unwrap();
+        assert!(!missing_health.is_healthy());
+        assert!(missing_health.last_success.is_none());
+        assert!(missing_health.last_error.as_ref().unwrap().contains("not found"));
+        assert_eq!(missing_health.failing_since, missing_health.last_error_at);
+        assert_eq!(missing_health.event_count, 0);
+
+        // Shown on the display by name, without the path
+        let errors = service.source_errors().await;
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].source, "missing");
+        assert_eq!(Some(errors[0].since), missing_health.failing_since);
+        assert!(!errors[0].error.contains(&missing), "{}", errors[0].error);
+        assert_eq!(service.last_updated().await, good_health.last_success);
+    }
+
+    #[tokio::test]
+    async fn test_ics_download_through_configured_proxy() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let now = Utc::now();
+        let proxy = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/team.ics"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+                "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nSUMMARY:Proxied Event\r\nDTSTART:{}\r\nDTEND:{}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+                (now + chrono::Duration::minutes(30)).format("%Y%m%dT%H%M%SZ"),
+                (now + chrono::Duration::minutes(60)).format("%Y%m%dT%H%M%SZ"),
+            )))
+            .expect(1)
+            .mount(&proxy)
+            .await;
+
+        // The calendar host does not resolve; only the proxy can serve it
+        let mut config = create_test_config();
+        config.ics.file_paths = vec!["http://calendar.invalid/team.ics".to_string()];
+        config.http.proxy = Some(proxy.uri());
+        assert!(config.validate().is_ok());
+
+        let service = CalendarService::new_from_config(&config, calendar_monitor::http::build_client(&config.http).unwrap());
+        let meetings = service.get_meetings_for_today_and_tomorrow().await.unwrap();
+
+        assert_eq!(meetings.len(), 1);
+        assert_eq!(meetings[0].title, "Proxied Event");
+    }
+
+
+    #[tokio::test]
+    async fn test_snapshot_used_when_all_sources_fail() {
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+        let now = Utc::now();
+        let ics_path = temp_dir.path().join("calendar.ics");
+        std::fs::write(&ics_path, format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nSUMMARY:Remembered Event\r\nDTSTART:{}\r\nDTEND:{}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+            (now + chrono::Duration::minutes(30)).format("%Y%m%dT%H%M%SZ"),
+            (now + chrono::Duration::minutes(60)).format("%Y%m%dT%H%M%SZ"),
+        )).expect("Failed to write ICS file");
+        let ics = ics_path.to_string_lossy().to_string();
+        let snapshot_path = temp_dir.path().join("meetings_snapshot.json");
+
+        // A good fetch is written to the snapshot file
+        let service = CalendarService::new_with_ics_files(vec![ics.clone()])
+            .with_snapshot_file(snapshot_path.clone());
+        let meetings = service.get_meetings_for_today_and_tomorrow().await.unwrap();
+        assert_eq!(meetings.len(), 1);
+        assert!(service.stale_since().await.is_none());
+        assert!(snapshot_path.exists());
+
+        // After a restart with the source unreachable, the snapshot is served and flagged stale
+        std::fs::remove_file(&ics_path).unwrap();
+        let restarted = CalendarService::new_with_ics_files(vec![ics])
+            .with_snapshot_file(snapshot_path);
+        let meetings = restarted.get_meetings_for_today_and_tomorrow().await.unwrap();
+        assert_eq!(meetings.len(), 1);
+        assert_eq!(meetings[0].title, "Remembered Event");
+        assert!(restarted.stale_since().await.is_some());
+    }
+
+
+    #[tokio::test]
+    async fn test_partial_failure_keeps_snapshot_and_is_stale() {
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+        let now = Utc::now();
+        let write_ics = |name: &str, title: &str| {
+            let path = temp_dir.path().join(name);
+            std::fs::write(&path, format!(
+                "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nSUMMARY:{}\r\nDTSTART:{}\r\nDTEND:{}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+                title,
+                (now + chrono::Duration::minutes(30)).format("%Y%m%dT%H%M%SZ"),
+                (now + chrono::Duration::minutes(60)).format("%Y%m%dT%H%M%SZ"),
+            )).expect("Failed to write ICS file");
+            path
+        };
+        let work = write_ics("work.ics", "Work Event");
+        let home = write_ics("home.ics", "Home Event");
+        let sources = vec![work.to_string_lossy().to_string(), home.to_string_lossy().to_string()];
+        let snapshot_path = temp_dir.path().join("meetings_snapshot.json");
+
+        let service = CalendarService::new_with_ics_files(sources.clone())
+            .with_snapshot_file(snapshot_path.clone());
+        assert_eq!(service.get_meetings_for_today_and_tomorrow().await.unwrap().len(), 2);
+        let complete = std::fs::read(&snapshot_path).unwrap();
+
+        // With one source down the other still shows, flagged stale, and the
+        // snapshot of the complete fetch is not overwritten
+        std::fs::remove_file(&home).unwrap();
+        let restarted = CalendarService::new_with_ics_files(sources)
+            .with_snapshot_file(snapshot_path.clone());
+        let meetings = restarted.get_meetings_for_today_and_tomorrow().await.unwrap();
+        assert_eq!(meetings.len(), 1);
+        assert_eq!(meetings[0].title, "Work Event");
+        assert!(restarted.stale_since().await.is_some());
+        assert_eq!(std::fs::read(&snapshot_path).unwrap(), complete);
+    }
+
+
+    #[tokio::test]
+    async fn test_sources_polled_at_their_own_interval() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let now = Utc::now();
+        let ics = |title: &str| format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nSUMMARY:{}\r\nDTSTART:{}\r\nDTEND:{}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+            title,
+            (now + chrono::Duration::minutes(30)).format("%Y%m%dT%H%M%SZ"),
+            (now + chrono::Duration::minutes(60)).format("%Y%m%dT%H%M%SZ"),
+        );
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/local.ics"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(ics("Local")))
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/company.ics"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(ics("All hands")))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut config = create_test_config();
+        config.ics.file_paths.clear();
+        config.ics.sources = vec![
+            IcsSourceConfig {
+                path: format!("{}/local.ics", server.uri()),
+                poll_interval_seconds: Some(1),
+                ..Default::default()
+            },
+            IcsSourceConfig {
+                path: format!("{}/company.ics", server.uri()),
+                poll_interval_seconds: Some(900),
+                ..Default::default()
+            },
+        ];
+        assert!(config.validate().is_ok());
+
+        let service = CalendarService::new_from_config(&config, reqwest::Client::new());
+        assert_eq!(service.get_meetings_for_today_and_tomorrow().await.unwrap().len(), 2);
+
+        // The local file is due again; the company calendar is reused as it was
+        tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+        let meetings = service.get_meetings_for_today_and_tomorrow().await.unwrap();
+        assert_eq!(meetings.len(), 2);
+        assert!(meetings.iter().any(|m| m.title == "All hands"));
+
+        config.ics.sources[0].poll_interval_seconds = Some(0);
+        assert!(config.validate().is_err());
+    }
 }
@@ -0,0 +1,147 @@
+use calendar_monitor::check::{check_sources, run};
+use calendar_monitor::cli::{Cli, Command};
+use calendar_monitor::config::{Config, GoogleConfig, IcsConfig, IcsSourceConfig};
+use calendar_monitor::google_calendar::GoogleTokens;
+use chrono::{Duration, Utc};
+use clap::Parser;
+use serde_json::json;
+use std::fs;
+use tempfile::TempDir;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write an ICS file with one event starting in an hour
+    fn write_ics(dir: &TempDir) -> String {
+        let start = Utc::now() + Duration::hours(1);
+        let contents = format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:check@test\r\nSUMMARY:Planning\r\nDTSTART:{}\r\nDTEND:{}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+            start.format("%Y%m%dT%H%M%SZ"),
+            (start + Duration::minutes(30)).format("%Y%m%dT%H%M%SZ"),
+        );
+        let path = dir.path().join("work.ics");
+        fs::write(&path, contents).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn test_check_subcommand_is_parsed() {
+        let cli = Cli::try_parse_from(["calendar-monitor", "check", "--ics", "work.ics"]).unwrap();
+        assert_eq!(cli.command, Some(Command::Check));
+        assert_eq!(cli.ics, vec!["work.ics"]);
+
+        let cli = Cli::try_parse_from(["calendar-monitor"]).unwrap();
+        assert_eq!(cli.command, None);
+    }
+
+    #[tokio::test]
+    async fn test_check_reports_each_source() {
+        let temp_dir = TempDir::new().unwrap();
+        let working = write_ics(&temp_dir);
+        let missing = temp_dir.path().join("missing.ics").to_string_lossy().to_string();
+        let config = Config {
+            ics: IcsConfig {
+                sources: vec![
+                    IcsSourceConfig { path: working, name: Some("Work".to_string()), ..Default::default() },
+                    IcsSourceConfig { path: missing.clone(), name: Some("Home".to_string()), ..Default::default() },
+                ],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let report = check_sources(&config, None).await;
+        assert_eq!(report.results.len(), 2);
+        let work = report.results.iter().find(|r| r.name == "Work").unwrap();
+        assert!(work.passed);
+        assert_eq!(work.detail, "1 events today and tomorrow");
+        let home = report.results.iter().find(|r| r.name == "Home").unwrap();
+        assert!(!home.passed);
+        assert!(!home.detail.contains(&missing), "paths are replaced by names: {}", home.detail);
+
+        assert!(!report.passed());
+        assert_eq!(report.exit_code(), 1);
+        let printed = report.to_string();
+        assert!(printed.contains("PASS  Work: 1 events today and tomorrow"));
+        assert!(printed.contains("FAIL  Home: "));
+        assert!(printed.ends_with("1 of 2 checks passed\n"));
+    }
+
+    #[tokio::test]
+    async fn test_check_reaches_google() {
+        let server = MockServer::start().await;
+        let now = Utc::now();
+        Mock::given(method("GET"))
+            .and(path("/calendar/v3/calendars/primary/events"))
+            .and(header("authorization", "Bearer stub-access-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "items": [{
+                    "id": "standup",
+                    "summary": "Standup",
+                    "start": { "dateTime": now.to_rfc3339() },
+                    "end": { "dateTime": (now + Duration::minutes(15)).to_rfc3339() }
+                }]
+            })))
+            .mount(&server)
+            .await;
+        let config = Config {
+            google: GoogleConfig {
+                client_id: Some("stub_client_id".to_string()),
+                client_secret: Some("stub_client_secret".to_string()),
+                redirect_uri: Some("http://localhost:3000/auth/google/callback".to_string()),
+                token_url: Some(format!("{}/token", server.uri())),
+                api_base_url: Some(server.uri()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let tokens = GoogleTokens {
+            access_token: "stub-access-token".to_string(),
+            refresh_token: Some("stub-refresh-token".to_string()),
+            expires_at: Some(now + Duration::hours(1)),
+        };
+
+        let report = check_sources(&config, Some(tokens)).await;
+        assert_eq!(report.results.len(), 1);
+        assert_eq!(report.results[0].name, "google");
+        assert!(report.passed(), "{}", report);
+        assert_eq!(report.exit_code(), 0);
+
+        // Without saved tokens there is nothing to check with
+        let report = check_sources(&config, None).await;
+        assert!(!report.passed());
+        assert!(report.results[0].detail.contains("not signed in"));
+    }
+
+    #[tokio::test]
+    async fn test_check_reports_invalid_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("monitor.toml");
+        fs::write(&config_path, "[server]\nport = \"not a port\"\n").unwrap();
+
+        let cli = Cli::try_parse_from(["calendar-monitor", "check", "--config", config_path.to_str().unwrap()]).unwrap();
+        let report = run(&cli).await;
+        assert_eq!(report.results.len(), 1);
+        assert_eq!(report.results[0].name, "config");
+        assert!(!report.results[0].passed);
+        assert_eq!(report.exit_code(), 1);
+    }
+
+
+    #[tokio::test]
+    async fn test_check_refuses_unknown_settings() {
+        let temp_dir = TempDir::new().unwrap();
+        let ics_path = write_ics(&temp_dir);
+        let config_path = temp_dir.path().join("monitor.toml");
+        fs::write(&config_path, format!("[ics]\nfile_paths = [\"{}\"]\nfile_path = \"typo.ics\"\n", ics_path)).unwrap();
+
+        let cli = Cli::try_parse_from(["calendar-monitor", "check", "--config", config_path.to_str().unwrap()]).unwrap();
+        let report = run(&cli).await;
+        assert_eq!(report.results.len(), 1);
+        assert!(!report.results[0].passed);
+        assert!(report.results[0].detail.contains("`ics.file_path` (line 3)"), "{}", report);
+    }
+}
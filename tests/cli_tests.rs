@@ -0,0 +1,91 @@
+use calendar_monitor::cli::Cli;
+use calendar_monitor::config::Config;
+use clap::error::ErrorKind;
+use clap::Parser;
+use std::fs;
+use tempfile::TempDir;
+use tracing::level_filters::LevelFilter;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_flags() {
+        let cli = Cli::try_parse_from([
+            "calendar-monitor",
+            "--config", "/etc/monitor.toml",
+            "--host", "127.0.0.1",
+            "--port", "8080",
+            "--ics", "work.ics",
+            "--ics", "https://example.com/home.ics",
+            "--log-level", "debug",
+        ])
+        .unwrap();
+
+        assert_eq!(cli.config.as_deref(), Some(std::path::Path::new("/etc/monitor.toml")));
+        assert_eq!(cli.host.as_deref(), Some("127.0.0.1"));
+        assert_eq!(cli.port, Some(8080));
+        assert_eq!(cli.ics, vec!["work.ics", "https://example.com/home.ics"]);
+        assert_eq!(cli.log_level, Some(LevelFilter::DEBUG));
+
+        let none = Cli::try_parse_from(["calendar-monitor"]).unwrap();
+        assert!(none.config.is_none() && none.port.is_none() && none.ics.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_flags_and_version() {
+        assert!(Cli::try_parse_from(["calendar-monitor", "--port", "http"]).is_err());
+        assert!(Cli::try_parse_from(["calendar-monitor", "--log-level", "loud"]).is_err());
+        assert!(Cli::try_parse_from(["calendar-monitor", "--verbose"]).is_err());
+
+        let version = Cli::try_parse_from(["calendar-monitor", "--version"]).unwrap_err();
+        assert_eq!(version.kind(), ErrorKind::DisplayVersion);
+        let help = Cli::try_parse_from(["calendar-monitor", "--help"]).unwrap_err();
+        assert_eq!(help.kind(), ErrorKind::DisplayHelp);
+        assert!(help.to_string().contains("--config <PATH>"));
+    }
+
+    #[test]
+    fn test_flags_override_config_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("monitor.toml");
+        fs::write(&config_path, r#"
+[server]
+host = "0.0.0.0"
+port = 3000
+cache_ttl_seconds = 300
+
+[ics]
+file_paths = ["from-file.ics"]
+
+[google]
+"#).unwrap();
+
+        let cli = Cli::try_parse_from([
+            "calendar-monitor",
+            "--config", config_path.to_str().unwrap(),
+            "--port", "4000",
+            "--ics", "from-flag.ics",
+        ])
+        .unwrap();
+        let config = Config::load_with(&cli).unwrap();
+
+        assert_eq!(config.server.host, "0.0.0.0");
+        assert_eq!(config.server.port, 4000);
+        assert_eq!(config.ics.file_paths, vec!["from-flag.ics"]);
+
+        // Reloading the file keeps the flags
+        let reloaded = Config::reload_with(&config_path, &cli).unwrap();
+        assert_eq!(reloaded.server.port, 4000);
+
+        // A config file given by name has to exist
+        let missing = Cli::try_parse_from([
+            "calendar-monitor",
+            "--config", temp_dir.path().join("missing.toml").to_str().unwrap(),
+        ])
+        .unwrap();
+        let error = Config::load_with(&missing).unwrap_err();
+        assert!(error.to_string().contains("missing.toml"));
+    }
+}
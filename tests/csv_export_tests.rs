@@ -0,0 +1,35 @@
+use calendar_monitor::csv_export::to_csv;
+use calendar_monitor::meeting::{Meeting, MeetingSource, ResponseStatus};
+use chrono::{Local, TimeZone, Utc};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_rows() {
+        let start = Local.with_ymd_and_hms(2025, 6, 2, 9, 0, 0).unwrap().with_timezone(&Utc);
+        let end = Local.with_ymd_and_hms(2025, 6, 2, 9, 30, 0).unwrap().with_timezone(&Utc);
+        let meetings = vec![
+            Meeting::new("Standup".to_string(), start, end)
+                .with_location("Room 1".to_string())
+                .with_source(MeetingSource::google())
+                .with_response_status(ResponseStatus::Tentative),
+            Meeting::new("Budget, \"final\"".to_string(), start, end),
+        ];
+
+        let csv = to_csv(&meetings);
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+        assert_eq!(lines[0], "title,start,end,location,source,response_status");
+        assert_eq!(lines[1], "Standup,2025-06-02 09:00,2025-06-02 09:30,Room 1,Google Calendar,tentative");
+        assert_eq!(lines[2], "\"Budget, \"\"final\"\"\",2025-06-02 09:00,2025-06-02 09:30,,,");
+        assert_eq!(lines[3], "");
+    }
+
+    #[test]
+    fn test_csv_neutralizes_formulas() {
+        let now = Utc::now();
+        let csv = to_csv(&[Meeting::new("=HYPERLINK(\"http://evil\")".to_string(), now, now)]);
+        assert!(csv.contains("\"'=HYPERLINK(\"\"http://evil\"\")\""));
+    }
+}
@@ -0,0 +1,74 @@
+use calendar_monitor::app::{MeetingUpdate, API_VERSION};
+use calendar_monitor::eink::render;
+use calendar_monitor::config::CountdownConfig;
+use calendar_monitor::meeting::Meeting;
+use chrono::{Duration, Utc};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(current: Option<Meeting>, next: Option<Meeting>) -> MeetingUpdate {
+        MeetingUpdate {
+            version: API_VERSION,
+            countdown_seconds: current.as_ref().map(|m| m.time_until_end()),
+            next_meeting_starts_in_seconds: next.as_ref().map(|m| m.time_until_start()),
+            current_meeting: current,
+            next_meeting: next,
+            active_time_blocks: vec![],
+            upcoming_time_blocks: vec![],
+            all_day_events: vec![],
+            holiday: None,
+            agenda_today: vec![],
+            stale: false,
+            stale_since: None,
+            timezone: None,
+            last_updated: None,
+            source_errors: Vec::new(),
+            quiet: false,
+            countdown: CountdownConfig::default(),
+        }
+    }
+
+    fn black_pixels(frame: &calendar_monitor::eink::Framebuffer, width: u32, height: u32) -> usize {
+        (0..height).flat_map(|y| (0..width).map(move |x| (x, y))).filter(|&(x, y)| frame.is_black(x, y)).count()
+    }
+
+    #[test]
+    fn test_render_draws_meetings_as_png() {
+        let now = Utc::now();
+        let current = Meeting::new("Sprint Review".to_string(), now - Duration::minutes(5), now + Duration::minutes(25));
+        let next = Meeting::new("1:1".to_string(), now + Duration::hours(1), now + Duration::hours(2));
+
+        let frame = render(&update(Some(current), Some(next)), 800, 480);
+        let png = frame.to_png().unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+
+        let decoder = png::Decoder::new(png.as_slice());
+        let reader = decoder.read_info().unwrap();
+        let info = reader.info();
+        assert_eq!((info.width, info.height), (800, 480));
+        assert_eq!(info.bit_depth, png::BitDepth::One);
+        assert_eq!(info.color_type, png::ColorType::Grayscale);
+
+        // Mostly white with some text
+        let black = black_pixels(&frame, 800, 480);
+        assert!(black > 1000 && black < 800 * 480 / 4, "{}", black);
+    }
+
+    #[test]
+    fn test_render_scales_to_resolution_and_handles_free_time() {
+        let free = update(None, None);
+        let small = render(&free, 400, 240);
+        let large = render(&free, 800, 480);
+
+        // Same layout at twice the size
+        assert_eq!(black_pixels(&large, 800, 480), 4 * black_pixels(&small, 400, 240));
+
+        // Long titles are cut to fit instead of running off the screen
+        let now = Utc::now();
+        let long = Meeting::new("A".repeat(200), now - Duration::minutes(5), now + Duration::minutes(25));
+        let frame = render(&update(Some(long), None), 400, 240);
+        assert!((0..240).all(|y| !frame.is_black(399, y)));
+    }
+}
@@ -0,0 +1,138 @@
+use calendar_monitor::config::{EmailConfig, SmtpSecurity};
+use calendar_monitor::email::EmailNotifier;
+use calendar_monitor::meeting::Meeting;
+use calendar_monitor::notify::{MeetingEvent, Notifier};
+use chrono::{Duration, Local, NaiveTime, Utc};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meeting(title: &str, minutes_from_now: i64, length_minutes: i64) -> Meeting {
+        let start = Utc::now() + Duration::minutes(minutes_from_now);
+        Meeting::new(title.to_string(), start, start + Duration::minutes(length_minutes))
+    }
+
+    fn clock(time: chrono::DateTime<Utc>) -> String {
+        time.with_timezone(&Local).format("%H:%M").to_string()
+    }
+
+    fn email_config(port: u16) -> EmailConfig {
+        EmailConfig {
+            host: "127.0.0.1".to_string(),
+            port: Some(port),
+            security: SmtpSecurity::None,
+            from: "Calendar Monitor <calendar@example.com>".to_string(),
+            to: vec!["me@example.com".to_string(), "team@example.com".to_string()],
+            agenda_time: Some("07:30".to_string()),
+            remind_minutes_before: 10,
+            ..Default::default()
+        }
+    }
+
+    /// Accepts one SMTP session and returns its envelope commands and message
+    async fn smtp_session(listener: TcpListener) -> (Vec<String>, String) {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        writer.write_all(b"220 localhost ESMTP\r\n").await.unwrap();
+
+        let mut commands = Vec::new();
+        let mut data = String::new();
+        while let Some(line) = lines.next_line().await.unwrap() {
+            let reply: &[u8] = match line.split_whitespace().next().unwrap_or_default().to_uppercase().as_str() {
+                "EHLO" | "HELO" => b"250 localhost\r\n",
+                "DATA" => {
+                    writer.write_all(b"354 Go ahead\r\n").await.unwrap();
+                    while let Some(line) = lines.next_line().await.unwrap() {
+                        if line == "." {
+                            break;
+                        }
+                        data.push_str(&line);
+                        data.push('\n');
+                    }
+                    b"250 Queued\r\n"
+                }
+                "QUIT" => {
+                    writer.write_all(b"221 Bye\r\n").await.unwrap();
+                    break;
+                }
+                _ => b"250 OK\r\n",
+            };
+            commands.push(line);
+            writer.write_all(reply).await.unwrap();
+        }
+        (commands, data)
+    }
+
+    #[tokio::test]
+    async fn test_agenda_mail_is_sent_over_smtp() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(smtp_session(listener));
+
+        let standup = meeting("Standup", 60, 15);
+        let mut review = meeting("Review", 120, 30);
+        review.location = Some("Room 4".to_string());
+        let email = EmailNotifier::new(email_config(port), true);
+        assert_eq!(email.agenda_time(), NaiveTime::from_hms_opt(7, 30, 0));
+        assert_eq!(email.remind_before(), Some(Duration::minutes(10)));
+        email.notify(&MeetingEvent::Agenda(vec![standup.clone(), review.clone()])).await.unwrap();
+
+        let (commands, data) = server.await.unwrap();
+        assert!(commands.contains(&"MAIL FROM:<calendar@example.com>".to_string()), "{:?}", commands);
+        assert!(commands.contains(&"RCPT TO:<me@example.com>".to_string()));
+        assert!(commands.contains(&"RCPT TO:<team@example.com>".to_string()));
+        assert!(data.contains("Subject: Today's meetings"), "{}", data);
+        assert!(data.contains(&format!("{} - {}  Standup", clock(standup.start_time), clock(standup.end_time))), "{}", data);
+        assert!(data.contains(&format!("{} - {}  Review\n    Room 4", clock(review.start_time), clock(review.end_time))), "{}", data);
+    }
+
+    #[test]
+    fn test_reminder_mail() {
+        let email = EmailNotifier::new(email_config(25), true);
+        let standup = meeting("Standup", 10, 15);
+
+        let (subject, body) = email.mail(&MeetingEvent::Reminder(standup.clone())).unwrap();
+        assert_eq!(subject, format!("Standup starts at {}", clock(standup.start_time)));
+        assert_eq!(body, format!("{} - {}  Standup\n", clock(standup.start_time), clock(standup.end_time)));
+
+        assert!(email.mail(&MeetingEvent::Started(standup.clone())).is_none());
+        assert!(email.mail(&MeetingEvent::Agenda(Vec::new())).is_none(), "no digest for an empty day");
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_smtp_server_is_reported() {
+        // Bound and dropped, so nothing listens there
+        let port = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap().port();
+        let email = EmailNotifier::new(email_config(port), true);
+        let error = email.notify(&MeetingEvent::Reminder(meeting("Standup", 5, 15))).await.unwrap_err();
+        assert!(error.to_string().contains("Failed to send mail through 127.0.0.1"), "{}", error);
+    }
+
+    #[test]
+    fn test_email_validation() {
+        let mut config = email_config(25);
+        config.validate().unwrap();
+        assert_eq!(EmailConfig::default().port(), 587);
+        assert_eq!(EmailConfig { security: SmtpSecurity::Tls, ..Default::default() }.port(), 465);
+
+        let invalid = [
+            EmailConfig { host: String::new(), ..config.clone() },
+            EmailConfig { from: "not an address".to_string(), ..config.clone() },
+            EmailConfig { to: Vec::new(), ..config.clone() },
+            EmailConfig { username: Some("me".to_string()), ..config.clone() },
+            EmailConfig { agenda_time: Some("7:30am".to_string()), ..config.clone() },
+            EmailConfig { agenda_time: None, remind_minutes_before: 0, ..config.clone() },
+        ];
+        for email in invalid {
+            assert!(email.validate().is_err(), "{:?}", email);
+        }
+
+        config.username = Some("me".to_string());
+        config.password = Some("secret".to_string());
+        config.validate().unwrap();
+    }
+}
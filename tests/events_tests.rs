@@ -0,0 +1,154 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use calendar_monitor::calendar::SourceError;
+use calendar_monitor::events::{Event, EventBus, NotificationBackend, SourceWatch};
+use calendar_monitor::meeting::Meeting;
+use calendar_monitor::notify::{MeetingEvent, Notifier, NotifierBackend, Schedules};
+use chrono::{Duration, Utc};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Forwards the kind of every event it gets, failing on config changes
+    struct Forward(mpsc::UnboundedSender<&'static str>);
+
+    #[async_trait]
+    impl NotificationBackend for Forward {
+        fn name(&self) -> &str {
+            "Forward"
+        }
+
+        async fn handle(&self, event: &Event) -> Result<()> {
+            self.0.send(event.kind()).unwrap();
+            match event {
+                Event::ConfigChanged { .. } => Err(anyhow!("not interested")),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Records the events it is notified of
+    #[derive(Default)]
+    struct Recorder {
+        events: Mutex<Vec<String>>,
+        silent: bool,
+    }
+
+    #[async_trait]
+    impl Notifier for Recorder {
+        fn name(&self) -> &str {
+            "Recorder"
+        }
+
+        fn remind_before(&self) -> Option<Duration> {
+            Some(Duration::minutes(10))
+        }
+
+        fn silent_in_quiet_hours(&self) -> bool {
+            self.silent
+        }
+
+        async fn notify(&self, event: &MeetingEvent) -> Result<()> {
+            let description = match event {
+                MeetingEvent::Started(meeting) => format!("started {}", meeting.title),
+                MeetingEvent::Ended(meeting) => format!("ended {}", meeting.title),
+                MeetingEvent::Agenda(meetings) => format!("agenda of {}", meetings.len()),
+                MeetingEvent::Reminder(meeting) => format!("reminder {}", meeting.title),
+            };
+            self.events.lock().unwrap().push(description);
+            Ok(())
+        }
+    }
+
+    fn meeting(title: &str, minutes_from_now: i64) -> Meeting {
+        let start = Utc::now() + Duration::minutes(minutes_from_now);
+        Meeting::new(title.to_string(), start, start + Duration::minutes(30))
+    }
+
+    fn source_error(source: &str) -> SourceError {
+        SourceError { source: source.to_string(), error: "timed out".to_string(), since: Utc::now() }
+    }
+
+    fn update_with(agenda_today: Vec<Meeting>, quiet: bool) -> Event {
+        let mut update: calendar_monitor::app::MeetingUpdate = serde_json::from_value(serde_json::json!({
+            "current_meeting": null,
+            "next_meeting": null,
+            "countdown_seconds": null,
+            "active_time_blocks": [],
+            "last_updated": Utc::now(),
+        }))
+        .unwrap();
+        update.agenda_today = agenda_today;
+        update.quiet = quiet;
+        Event::Update(Arc::new(update))
+    }
+
+    #[tokio::test]
+    async fn test_bus_delivers_events_until_unsubscribed() {
+        let bus = EventBus::default();
+        assert!(!bus.has_subscribers());
+        // Published without subscribers, and so dropped
+        bus.publish(Event::SourceRecovered { source: "Work".to_string() });
+
+        let (sender, mut received) = mpsc::unbounded_channel();
+        let subscription = bus.subscribe(Arc::new(Forward(sender)));
+        assert!(bus.has_subscribers());
+
+        // A failure is logged and the backend keeps receiving events
+        bus.publish(Event::ConfigChanged { loaded_at: Utc::now() });
+        bus.publish(Event::SourceFailed { source: "Work".to_string(), error: "timed out".to_string() });
+        assert_eq!(received.recv().await, Some("config change"));
+        assert_eq!(received.recv().await, Some("source failure"));
+
+        drop(subscription);
+        assert_eq!(received.recv().await, None, "the backend is dropped with its subscription");
+    }
+
+    #[test]
+    fn test_source_watch_reports_failures_and_recoveries_once() {
+        let mut watch = SourceWatch::default();
+        assert!(watch.observe(&[]).is_empty());
+
+        let events = watch.observe(&[source_error("Work")]);
+        assert!(matches!(&events[..], [Event::SourceFailed { source, error }] if source == "Work" && error == "timed out"));
+        assert!(watch.observe(&[source_error("Work")]).is_empty(), "still failing");
+
+        let events = watch.observe(&[source_error("Team")]);
+        assert!(matches!(&events[..], [Event::SourceRecovered { source: recovered }, Event::SourceFailed { source: failed, .. }]
+            if recovered == "Work" && failed == "Team"));
+    }
+
+    #[tokio::test]
+    async fn test_notifier_backends_share_their_schedules() {
+        let recorder = Arc::new(Recorder::default());
+        let schedules = Arc::new(Mutex::new(Schedules::default()));
+        let backend = NotifierBackend::new(recorder.clone(), schedules.clone());
+        assert_eq!(backend.name(), "Recorder");
+
+        let standup = meeting("Standup", 5);
+        backend.handle(&Event::Meeting { event: Box::new(MeetingEvent::Started(meeting("Review", -5))), quiet: false }).await.unwrap();
+        backend.handle(&update_with(vec![standup.clone(), meeting("Retro", 60)], false)).await.unwrap();
+        backend.handle(&update_with(vec![standup.clone()], false)).await.unwrap();
+        // As after a configuration reload: the new backend knows the reminder was sent
+        let reloaded = NotifierBackend::new(recorder.clone(), schedules);
+        reloaded.handle(&update_with(vec![standup], false)).await.unwrap();
+        reloaded.handle(&Event::ConfigChanged { loaded_at: Utc::now() }).await.unwrap();
+
+        assert_eq!(*recorder.events.lock().unwrap(), vec!["started Review", "reminder Standup"]);
+    }
+
+    #[tokio::test]
+    async fn test_notifier_backends_keep_quiet_hours() {
+        let recorder = Arc::new(Recorder { silent: true, ..Default::default() });
+        let backend = NotifierBackend::new(recorder.clone(), Arc::default());
+        backend.handle(&Event::Meeting { event: Box::new(MeetingEvent::Started(meeting("Review", -5))), quiet: true }).await.unwrap();
+        backend.handle(&update_with(vec![meeting("Standup", 5)], true)).await.unwrap();
+        assert!(recorder.events.lock().unwrap().is_empty());
+
+        backend.handle(&Event::Meeting { event: Box::new(MeetingEvent::Ended(meeting("Review", -35))), quiet: false }).await.unwrap();
+        assert_eq!(*recorder.events.lock().unwrap(), vec!["ended Review"]);
+    }
+}
@@ -0,0 +1,167 @@
+use calendar_monitor::calendar::CalendarService;
+use calendar_monitor::config::{Config, IcsConfig, IcsSourceConfig, TimeBlocksConfig};
+use calendar_monitor::filter::{TimeBlockMarker, TitleFilter};
+use calendar_monitor::meeting::{EventType, Meeting};
+use chrono::{Duration, Utc};
+use std::fs;
+use tempfile::TempDir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write an ICS file with one event per title, an hour apart from now on
+    fn write_ics(dir: &TempDir, titles: &[&str]) -> String {
+        let mut contents = String::from("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n");
+        for (i, title) in titles.iter().enumerate() {
+            let start = Utc::now() + Duration::hours(i as i64 + 1);
+            contents.push_str(&format!(
+                "BEGIN:VEVENT\r\nUID:filter-{}@test\r\nSUMMARY:{}\r\nDTSTART:{}\r\nDTEND:{}\r\nEND:VEVENT\r\n",
+                i,
+                title,
+                start.format("%Y%m%dT%H%M%SZ"),
+                (start + Duration::minutes(30)).format("%Y%m%dT%H%M%SZ"),
+            ));
+        }
+        contents.push_str("END:VCALENDAR\r\n");
+        let path = dir.path().join("company.ics");
+        fs::write(&path, contents).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn test_title_filter_rules() {
+        let keep_all = TitleFilter::new(&[], &[]).unwrap();
+        assert!(keep_all.is_empty());
+        assert!(keep_all.matches("Anything"));
+
+        let exclude = TitleFilter::new(&[], &["^Blocked".to_string(), "(?i)lunch".to_string()]).unwrap();
+        assert!(exclude.matches("Planning"));
+        assert!(!exclude.matches("Blocked for focus"));
+        assert!(exclude.matches("Not Blocked"));
+        assert!(!exclude.matches("Team LUNCH"));
+
+        // Exclusions win over inclusions
+        let include = TitleFilter::new(&[r"\[Team\]".to_string(), "^All hands$".to_string()], &["cancelled".to_string()]).unwrap();
+        assert!(include.matches("[Team] Retro"));
+        assert!(include.matches("All hands"));
+        assert!(!include.matches("All hands prep"));
+        assert!(!include.matches("[Team] Retro cancelled"));
+
+        let error = TitleFilter::new(&["[Team".to_string()], &[]).unwrap_err();
+        assert!(error.to_string().contains("Invalid include pattern '[Team'"));
+    }
+
+    #[tokio::test]
+    async fn test_ics_source_filters_meetings() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = write_ics(&temp_dir, &["[Team] Sync", "Blocked: focus", "Company townhall", "[Team] Blocked"]);
+        let config = Config {
+            ics: IcsConfig {
+                sources: vec![IcsSourceConfig {
+                    path,
+                    include: vec![r"\[Team\]".to_string(), "townhall".to_string()],
+                    exclude: vec!["Blocked".to_string()],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        let service = CalendarService::new_from_config(&config, reqwest::Client::new());
+        let meetings = service.get_meetings_for_today_and_tomorrow().await.unwrap();
+        let titles: Vec<_> = meetings.iter().map(|m| m.title.as_str()).collect();
+        assert_eq!(titles, vec!["[Team] Sync", "Company townhall"]);
+
+        // Sources added at runtime are filtered too, and bad patterns rejected
+        let other_dir = TempDir::new().unwrap();
+        let other = write_ics(&other_dir, &["Lunch", "Review"]);
+        let invalid = IcsSourceConfig { path: other.clone(), exclude: vec!["(".to_string()], ..Default::default() };
+        assert!(service.add_source(&invalid).await.is_err());
+        service.add_source(&IcsSourceConfig { path: other, exclude: vec!["^Lunch$".to_string()], ..Default::default() })
+            .await
+            .unwrap();
+        let meetings = service.get_meetings_for_today_and_tomorrow().await.unwrap();
+        assert!(meetings.iter().any(|m| m.title == "Review"));
+        assert!(!meetings.iter().any(|m| m.title == "Lunch"));
+    }
+
+    #[test]
+    fn test_invalid_filter_fails_validation() {
+        let toml_content = r#"
+[server]
+host = "127.0.0.1"
+port = 3000
+cache_ttl_seconds = 300
+
+[ics]
+file_paths = []
+
+[[ics.sources]]
+path = "https://calendar.example.com/company.ics"
+exclude = ["^Blocked", "(unclosed"]
+
+[google]
+"#;
+        let config: Config = toml::from_str(toml_content).unwrap();
+        assert_eq!(config.ics.sources[0].exclude.len(), 2);
+        let error = config.validate().unwrap_err().to_string();
+        assert!(error.contains("Invalid exclude pattern '(unclosed'"), "{}", error);
+    }
+
+
+    #[tokio::test]
+    async fn test_time_block_marker() {
+        let now = Utc::now();
+        let meeting = |title: &str| Meeting::new(title.to_string(), now, now + Duration::hours(1));
+        let marker = TimeBlockMarker::new(Some("Focus:"), Some("(?i)^deep work"), Some("focus")).unwrap();
+        assert!(marker.matches(&meeting("Focus: roadmap")));
+        assert!(marker.matches(&meeting("Deep Work")));
+        assert!(marker.matches(&meeting("Writing").with_categories(vec!["Work".to_string(), "FOCUS".to_string()])));
+        assert!(!marker.matches(&meeting("Planning").with_categories(vec!["Work".to_string()])));
+
+        let marked = marker.apply(vec![meeting("Focus: roadmap"), meeting("Planning")]);
+        assert_eq!(marked[0].event_type, Some(EventType::TimeBlock));
+        assert!(marked[0].is_time_block());
+        assert_eq!(marked[0].time_block_name(), Some("Focus: roadmap".to_string()));
+        assert!(!marked[1].is_time_block());
+
+        let error = TimeBlockMarker::new(None, Some("(unclosed"), None).unwrap_err();
+        assert!(error.to_string().contains("Invalid time block pattern"));
+
+        // Configured for ICS sources, by CATEGORIES or for a whole calendar
+        let temp_dir = TempDir::new().unwrap();
+        let start = now + Duration::hours(1);
+        let ics = format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:writing@test\r\nSUMMARY:Writing\r\nCATEGORIES:Work,Focus\r\nDTSTART:{}\r\nDTEND:{}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+            start.format("%Y%m%dT%H%M%SZ"),
+            (start + Duration::minutes(30)).format("%Y%m%dT%H%M%SZ"),
+        );
+        let categorized = temp_dir.path().join("work.ics");
+        fs::write(&categorized, ics).unwrap();
+        let dedicated_dir = TempDir::new().unwrap();
+        let dedicated = write_ics(&dedicated_dir, &["Reading"]);
+        let config = Config {
+            ics: IcsConfig {
+                sources: vec![
+                    IcsSourceConfig { path: categorized.to_string_lossy().to_string(), ..Default::default() },
+                    IcsSourceConfig { path: dedicated, time_blocks: true, ..Default::default() },
+                ],
+                ..Default::default()
+            },
+            time_blocks: TimeBlocksConfig { category: Some("focus".to_string()), ..Default::default() },
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        let meetings = CalendarService::new_from_config(&config, reqwest::Client::new()).get_meetings_for_today_and_tomorrow().await.unwrap();
+        let writing = meetings.iter().find(|m| m.title == "Writing").unwrap();
+        assert_eq!(writing.categories, vec!["Work", "Focus"]);
+        assert!(writing.is_time_block());
+        let reading = meetings.iter().find(|m| m.title == "Reading").unwrap();
+        assert!(reading.is_time_block());
+        assert_eq!(reading.event_type, None);
+    }
+}
@@ -0,0 +1,98 @@
+use calendar_monitor::config::WorkingHoursConfig;
+use calendar_monitor::free_slots::{find_free_slots, parse_duration, FreeSlot};
+use calendar_monitor::meeting::Meeting;
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone, Utc, Weekday};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Monday 2025-06-02
+    fn monday() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, 2).unwrap()
+    }
+
+    fn at(date: NaiveDate, hour: u32, minute: u32) -> DateTime<Utc> {
+        Local
+            .from_local_datetime(&date.and_hms_opt(hour, minute, 0).unwrap())
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn meeting(title: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> Meeting {
+        Meeting::new(title.to_string(), start, end)
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30m"), Some(Duration::minutes(30)));
+        assert_eq!(parse_duration("1h"), Some(Duration::hours(1)));
+        assert_eq!(parse_duration("1h30m"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("45"), Some(Duration::minutes(45)));
+
+        for invalid in ["", "0", "0m", "h", "30s", "1h30", "-5"] {
+            assert_eq!(parse_duration(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_gaps_between_meetings_within_working_hours() {
+        let day = monday();
+        let meetings = vec![
+            meeting("Standup", at(day, 9, 0), at(day, 9, 15)),
+            meeting("Planning", at(day, 10, 0), at(day, 12, 0)),
+            // Overlaps Planning, so no gap before it ends
+            meeting("Lunch", at(day, 11, 30), at(day, 13, 0)),
+            meeting("Too close", at(day, 13, 20), at(day, 16, 45)),
+        ];
+
+        let slots = find_free_slots(&meetings, at(day, 8, 0), &WorkingHoursConfig::default(), Duration::minutes(30), 1, 10);
+        assert_eq!(slots, vec![FreeSlot { start: at(day, 9, 15), end: at(day, 10, 0) }]);
+        assert_eq!(slots[0].duration_minutes(), 45);
+
+        let slots = find_free_slots(&meetings, at(day, 8, 0), &WorkingHoursConfig::default(), Duration::minutes(15), 1, 10);
+        assert_eq!(slots.len(), 3);
+        assert_eq!(slots[1], FreeSlot { start: at(day, 13, 0), end: at(day, 13, 20) });
+        assert_eq!(slots[2], FreeSlot { start: at(day, 16, 45), end: at(day, 17, 0) });
+    }
+
+    #[test]
+    fn test_search_starts_now_and_skips_non_working_days() {
+        // Friday afternoon: the rest of Friday, then straight to Monday
+        let friday = NaiveDate::from_ymd_opt(2025, 6, 6).unwrap();
+        let next_monday = NaiveDate::from_ymd_opt(2025, 6, 9).unwrap();
+        let holiday = Meeting::new_all_day("Holiday".to_string(), friday, next_monday);
+
+        let slots = find_free_slots(&[holiday], at(friday, 16, 0), &WorkingHoursConfig::default(), Duration::minutes(30), 7, 2);
+        assert_eq!(slots, vec![
+            FreeSlot { start: at(friday, 16, 0), end: at(friday, 17, 0) },
+            FreeSlot { start: at(next_monday, 9, 0), end: at(next_monday, 17, 0) },
+        ]);
+
+        let weekends = WorkingHoursConfig {
+            start: "10:00".to_string(),
+            end: "14:00".to_string(),
+            days: vec![Weekday::Sat],
+        };
+        let slots = find_free_slots(&[], at(friday, 16, 0), &weekends, Duration::hours(1), 7, 5);
+        assert_eq!(slots.len(), 1);
+        assert_eq!(slots[0].start, at(NaiveDate::from_ymd_opt(2025, 6, 7).unwrap(), 10, 0));
+    }
+
+    #[test]
+    fn test_working_hours_validation() {
+        assert!(WorkingHoursConfig::default().hours().is_ok());
+
+        let reversed = WorkingHoursConfig { start: "18:00".to_string(), ..Default::default() };
+        assert!(reversed.hours().is_err());
+        let malformed = WorkingHoursConfig { end: "5pm".to_string(), ..Default::default() };
+        assert!(malformed.hours().is_err());
+
+        let config: WorkingHoursConfig = toml::from_str(r#"
+            start = "08:30"
+            end = "16:30"
+            days = ["mon", "Tuesday", "wed"]
+        "#).unwrap();
+        assert_eq!(config.days, vec![Weekday::Mon, Weekday::Tue, Weekday::Wed]);
+    }
+}
@@ -0,0 +1,62 @@
+use calendar_monitor::config::GpioConfig;
+use calendar_monitor::gpio::GpioPins;
+use calendar_monitor::hue::RoomState;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sign() -> GpioConfig {
+        // Red LED on 17 for busy and ending soon, amber on 27, green on 22
+        GpioConfig {
+            busy_pins: vec![17],
+            ending_soon_pins: vec![17, 27],
+            free_pins: vec![22],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_pins_follow_the_meeting_state() {
+        let pins = GpioPins::new(sign());
+        assert_eq!(pins.levels(RoomState::Busy), vec![(17, true), (22, false), (27, false)]);
+        assert_eq!(pins.levels(RoomState::EndingSoon), vec![(17, true), (22, false), (27, true)]);
+        assert_eq!(pins.levels(RoomState::Free), vec![(17, false), (22, true), (27, false)]);
+
+        let relays = GpioPins::new(GpioConfig { active_low: true, ..sign() });
+        assert_eq!(relays.levels(RoomState::Free), vec![(17, true), (22, false), (27, true)]);
+    }
+
+    #[test]
+    fn test_gpio_validation() {
+        assert_eq!(sign().validate().is_ok(), cfg!(feature = "gpio"));
+        assert!(GpioConfig::default().validate().is_err(), "no pins");
+        let error = GpioConfig { free_pins: vec![40], ..sign() }.validate().unwrap_err().to_string();
+        assert!(error.contains("40") || !cfg!(feature = "gpio"), "{}", error);
+
+        let config: GpioConfig = toml::from_str("busy_pins = [17]\nactive_low = true").unwrap();
+        assert_eq!(config.ending_soon_minutes, 5);
+        assert_eq!(config.pins().into_iter().collect::<Vec<_>>(), vec![17]);
+    }
+
+    #[cfg(not(feature = "gpio"))]
+    #[tokio::test]
+    async fn test_failures_are_reported_once() {
+        use calendar_monitor::app::MeetingUpdate;
+        use calendar_monitor::events::{Event, NotificationBackend};
+        use std::sync::Arc;
+
+        let update: MeetingUpdate = serde_json::from_value(serde_json::json!({
+            "current_meeting": null,
+            "next_meeting": null,
+            "countdown_seconds": null,
+            "active_time_blocks": [],
+        }))
+        .unwrap();
+        let free = Event::Update(Arc::new(update));
+        let pins = GpioPins::new(sign());
+        let error = pins.handle(&free).await.unwrap_err();
+        assert!(error.to_string().contains("without GPIO support"), "{}", error);
+        pins.handle(&free).await.unwrap();
+    }
+}
@@ -0,0 +1,48 @@
+use calendar_monitor::hidden_meetings::HiddenMeetings;
+use calendar_monitor::meeting::Meeting;
+use chrono::{Duration, Utc};
+use tempfile::TempDir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meeting(title: &str) -> Meeting {
+        let now = Utc::now();
+        Meeting::new(title.to_string(), now + Duration::minutes(10), now + Duration::minutes(40))
+    }
+
+    #[tokio::test]
+    async fn test_hidden_meetings_persist() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("hidden_meetings.json");
+        let (ghost, standup) = (meeting("Ghost"), meeting("Standup"));
+
+        let hidden = HiddenMeetings::open(path.clone());
+        hidden.hide(&ghost, None).await.unwrap();
+
+        let reopened = HiddenMeetings::open(path.clone());
+        let visible = reopened.retain_visible(vec![ghost.clone(), standup]).await;
+        let titles: Vec<&str> = visible.iter().map(|m| m.title.as_str()).collect();
+        assert_eq!(titles, vec!["Standup"]);
+        assert_eq!(reopened.list().await[0].title, "Ghost");
+
+        assert!(reopened.unhide(&ghost.id).await.unwrap());
+        assert!(!reopened.unhide(&ghost.id).await.unwrap());
+        assert!(HiddenMeetings::open(path).list().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_snoozed_meeting_reappears() {
+        let hidden = HiddenMeetings::in_memory();
+        let skipped = meeting("Skipped");
+
+        hidden.hide(&skipped, Some(Utc::now() + Duration::hours(1))).await.unwrap();
+        assert!(hidden.retain_visible(vec![skipped.clone()]).await.is_empty());
+
+        // Hiding again replaces the earlier entry
+        hidden.hide(&skipped, Some(Utc::now() - Duration::seconds(1))).await.unwrap();
+        assert_eq!(hidden.retain_visible(vec![skipped.clone()]).await.len(), 1);
+        assert!(hidden.list().await.is_empty());
+    }
+}
@@ -0,0 +1,142 @@
+use calendar_monitor::config::{Config, HolidaysConfig};
+use calendar_monitor::holidays::{HolidaySource, PublicHoliday};
+use calendar_monitor::recurrence::Window;
+use calendar_monitor::source::CalendarSource;
+use chrono::{Datelike, Duration, Local};
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holidays_config(api_url: &str, region: Option<&str>) -> Config {
+        Config {
+            holidays: HolidaysConfig {
+                country: Some("de".to_string()),
+                region: region.map(str::to_string),
+                api_url: Some(api_url.to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Stub holiday API listing a nationwide and a Bavarian holiday today,
+    /// and a nationwide one next week
+    async fn start_holiday_stub() -> MockServer {
+        let server = MockServer::start().await;
+        let today = Local::now().date_naive();
+        let holiday = |date: chrono::NaiveDate, local_name: &str, name: &str, counties: Option<Vec<&str>>| json!({
+            "date": date,
+            "localName": local_name,
+            "name": name,
+            "countryCode": "DE",
+            "fixed": false,
+            "global": counties.is_none(),
+            "counties": counties,
+            "launchYear": null,
+            "types": ["Public"]
+        });
+        let next_week = today + Duration::days(7);
+        let holidays = [
+            (today, holiday(today, "Tag der Deutschen Einheit", "German Unity Day", None)),
+            (today, holiday(today, "Heilige Drei Könige", "Epiphany", Some(vec!["DE-BW", "DE-BY"]))),
+            (next_week, holiday(next_week, "Neujahr", "New Year's Day", None)),
+        ];
+
+        // Around New Year the window spans two years, each listed separately
+        let mut years = vec![today.year()];
+        if (today + Duration::days(1)).year() != today.year() {
+            years.push(today.year() + 1);
+        }
+        for year in years {
+            let listed: Vec<_> = holidays.iter()
+                .filter(|(date, _)| date.year() == year)
+                .map(|(_, holiday)| holiday.clone())
+                .collect();
+            Mock::given(method("GET"))
+                .and(path(format!("/api/v3/PublicHolidays/{}/DE", year)))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!(listed)))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+        server
+    }
+
+    #[test]
+    fn test_holiday_observed_in_region() {
+        let holiday: PublicHoliday = serde_json::from_value(json!({
+            "date": "2025-01-06",
+            "localName": "Heilige Drei Könige",
+            "name": "Epiphany",
+            "global": false,
+            "counties": ["DE-BW", "DE-BY", "DE-ST"]
+        }))
+        .unwrap();
+
+        assert!(holiday.observed_in(Some("DE-BY")));
+        assert!(holiday.observed_in(Some("de-by")));
+        assert!(!holiday.observed_in(Some("DE-BE")));
+        assert!(!holiday.observed_in(None));
+    }
+
+    #[tokio::test]
+    async fn test_holiday_source_lists_todays_holidays() {
+        let server = start_holiday_stub().await;
+        let source = HolidaySource::new_from_config(&holidays_config(&server.uri(), None), reqwest::Client::new())
+            .expect("Country is configured");
+
+        let window = Window::today_and_tomorrow();
+        let holidays = source.fetch_events(&window).await.unwrap();
+
+        // Only the nationwide holiday; the regional one needs a region
+        assert_eq!(holidays.len(), 1);
+        let holiday = &holidays[0];
+        assert_eq!(holiday.title, "German Unity Day");
+        assert_eq!(holiday.description.as_deref(), Some("Tag der Deutschen Einheit"));
+        assert!(holiday.all_day);
+        assert!(holiday.is_holiday());
+
+        // Each year is only fetched once
+        source.fetch_events(&window).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_holiday_source_includes_regional_holidays() {
+        let server = start_holiday_stub().await;
+        let config = holidays_config(&server.uri(), Some("DE-BY"));
+        let source = HolidaySource::new_from_config(&config, reqwest::Client::new()).unwrap();
+
+        let holidays = source.fetch_events(&Window::today_and_tomorrow()).await.unwrap();
+        let titles: Vec<_> = holidays.iter().map(|h| h.title.as_str()).collect();
+        assert_eq!(titles, vec!["German Unity Day", "Epiphany"]);
+    }
+
+    #[tokio::test]
+    async fn test_holiday_source_backs_off_after_failure() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let source = HolidaySource::new_from_config(&holidays_config(&server.uri(), None), reqwest::Client::new()).unwrap();
+        let window = Window::today_and_tomorrow();
+
+        assert!(source.fetch_events(&window).await.is_err());
+        let failure = source.failure().await.expect("Expected the failure to be kept");
+        assert!(failure.error.contains("503"), "{}", failure.error);
+        // Not asked again right away, and still reported as failing
+        assert!(source.fetch_events(&window).await.unwrap().is_empty());
+        assert_eq!(source.failure().await, Some(failure));
+    }
+
+    #[test]
+    fn test_holidays_disabled_without_country() {
+        assert!(HolidaySource::new_from_config(&Config::default(), reqwest::Client::new()).is_none());
+    }
+}
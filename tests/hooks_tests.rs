@@ -0,0 +1,98 @@
+use calendar_monitor::config::HooksConfig;
+use calendar_monitor::hooks::{meeting_env, ShellHooks};
+use calendar_monitor::meeting::Meeting;
+use calendar_monitor::notify::{MeetingEvent, Notifier};
+use chrono::{Duration, Utc};
+use std::fs;
+use tempfile::TempDir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn standup() -> Meeting {
+        let start = Utc::now() + Duration::minutes(5);
+        Meeting::new("Standup".to_string(), start, start + Duration::minutes(15))
+            .with_location("https://meet.example.com/standup".to_string())
+    }
+
+    #[test]
+    fn test_meeting_env() {
+        let meeting = standup();
+        let now = meeting.start_time - Duration::seconds(90);
+        let env = meeting_env("reminder", &meeting, now);
+        let var = |name: &str| env.iter().find(|(key, _)| *key == name).map(|(_, value)| value.as_str());
+
+        assert_eq!(var("MEETING_EVENT"), Some("reminder"));
+        assert_eq!(var("MEETING_TITLE"), Some("Standup"));
+        assert_eq!(var("MEETING_URL"), Some("https://meet.example.com/standup"));
+        assert_eq!(var("MEETING_START"), Some(meeting.start_time.to_rfc3339().as_str()));
+        assert_eq!(var("SECONDS_UNTIL_START"), Some("90"));
+        assert_eq!(var("SECONDS_UNTIL_END"), Some("990"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_hooks_run_with_the_meeting_in_the_environment() {
+        let temp_dir = TempDir::new().unwrap();
+        let log = temp_dir.path().join("hooks.log");
+        let command = |event: &str| {
+            Some(format!(
+                "echo \"{} $MEETING_EVENT $MEETING_TITLE $MEETING_URL\" >> '{}'",
+                event,
+                log.display()
+            ))
+        };
+        let hooks = ShellHooks::new(HooksConfig {
+            meeting_start: command("started"),
+            meeting_reminder: command("soon"),
+            remind_minutes_before: 10,
+            ..Default::default()
+        });
+        assert_eq!(hooks.remind_before(), Some(Duration::minutes(10)));
+
+        let meeting = standup();
+        hooks.notify(&MeetingEvent::Reminder(meeting.clone())).await.unwrap();
+        hooks.notify(&MeetingEvent::Started(meeting.clone())).await.unwrap();
+        // No meeting_end command
+        hooks.notify(&MeetingEvent::Ended(meeting)).await.unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&log).unwrap(),
+            "soon reminder Standup https://meet.example.com/standup\n\
+             started start Standup https://meet.example.com/standup\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_failing_and_hanging_hooks_are_reported() {
+        let hooks = ShellHooks::new(HooksConfig {
+            meeting_start: Some("echo 'no speakers found' >&2; exit 3".to_string()),
+            meeting_end: Some("sleep 10".to_string()),
+            timeout_seconds: 1,
+            ..Default::default()
+        });
+        let error = hooks.notify(&MeetingEvent::Started(standup())).await.unwrap_err().to_string();
+        assert!(error.contains("no speakers found"), "{}", error);
+
+        let started = std::time::Instant::now();
+        let error = hooks.notify(&MeetingEvent::Ended(standup())).await.unwrap_err().to_string();
+        assert!(error.contains("did not finish within 1s"), "{}", error);
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_hooks_validation() {
+        let hooks = HooksConfig::default();
+        hooks.validate().unwrap();
+        assert!(!hooks.any());
+
+        assert!(HooksConfig { meeting_start: Some(" ".to_string()), ..Default::default() }.validate().is_err());
+        assert!(HooksConfig { meeting_reminder: Some("notify".to_string()), remind_minutes_before: 0, ..Default::default() }
+            .validate()
+            .is_err());
+        assert!(HooksConfig { timeout_seconds: 0, ..Default::default() }.validate().is_err());
+        assert!(HooksConfig { meeting_end: Some("unmute".to_string()), ..Default::default() }.any());
+    }
+}
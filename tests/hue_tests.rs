@@ -0,0 +1,146 @@
+use calendar_monitor::app::MeetingUpdate;
+use calendar_monitor::busylight::Rgb;
+use calendar_monitor::config::HueConfig;
+use calendar_monitor::events::{Event, NotificationBackend};
+use calendar_monitor::hue::{xy, HueAction, HueLights, RoomState};
+use calendar_monitor::meeting::Meeting;
+use chrono::{Duration, Utc};
+use serde_json::json;
+use std::sync::Arc;
+use wiremock::matchers::{body_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An update with a meeting ending in `ends_in_minutes`, or none
+    fn update(ends_in_minutes: Option<i64>) -> MeetingUpdate {
+        let mut update: MeetingUpdate = serde_json::from_value(json!({
+            "current_meeting": null,
+            "next_meeting": null,
+            "countdown_seconds": null,
+            "active_time_blocks": [],
+        }))
+        .unwrap();
+        if let Some(minutes) = ends_in_minutes {
+            let end = Utc::now() + Duration::minutes(minutes);
+            update.current_meeting = Some(Meeting::new("Standup".to_string(), end - Duration::minutes(30), end));
+            update.countdown_seconds = Some(minutes * 60);
+        }
+        update
+    }
+
+    fn hue_config(bridge: &str) -> HueConfig {
+        HueConfig {
+            bridge: bridge.to_string(),
+            api_key: Some("secret-key".to_string()),
+            light: Some("3".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_hue_actions_follow_the_meeting() {
+        let ending_soon = Duration::minutes(5);
+        assert_eq!(RoomState::of(&update(Some(20)), ending_soon), RoomState::Busy);
+        assert_eq!(RoomState::of(&update(Some(5)), ending_soon), RoomState::EndingSoon);
+        assert_eq!(RoomState::of(&update(None), ending_soon), RoomState::Free);
+        assert_eq!(RoomState::of(&update(Some(1)), Duration::zero()), RoomState::Busy);
+
+        assert_eq!(HueAction::parse("OFF").unwrap(), HueAction::Off);
+        assert_eq!(HueAction::parse("scene:AbC123").unwrap(), HueAction::Scene("AbC123".to_string()));
+        assert_eq!(HueAction::parse("#ffa500").unwrap(), HueAction::Color(Rgb(255, 165, 0)));
+        assert!(HueAction::parse("scene:").is_err());
+        assert!(HueAction::parse("orange").is_err());
+
+        assert_eq!(xy(Rgb(255, 0, 0)), [0.7006, 0.2993]);
+        assert_eq!(xy(Rgb(0, 0, 0)), [0.3127, 0.329]);
+
+        let hue = HueLights::new(reqwest::Client::new(), hue_config("192.168.1.20"));
+        assert_eq!(hue.action(&update(Some(20))), &HueAction::Color(Rgb(255, 0, 0)));
+        assert_eq!(hue.action(&update(None)), &HueAction::Off);
+        assert_eq!(hue.request(&HueAction::Off), ("lights/3/state".to_string(), json!({ "on": false })));
+
+        let room = HueLights::new(
+            reqwest::Client::new(),
+            HueConfig { light: None, group: Some("2".to_string()), ..hue_config("192.168.1.20") },
+        );
+        assert_eq!(
+            room.request(&HueAction::Scene("AbC123".to_string())),
+            ("groups/2/action".to_string(), json!({ "scene": "AbC123" }))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hue_light_is_set_on_state_changes() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/api/secret-key/lights/3/state"))
+            .and(body_json(json!({ "on": true, "xy": [0.7006, 0.2993], "bri": 254 })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{ "success": { "/lights/3/state/on": true } }])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/api/secret-key/lights/3/state"))
+            .and(body_json(json!({ "on": false })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{ "success": { "/lights/3/state/on": false } }])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let hue = HueLights::new(reqwest::Client::new(), hue_config(&server.uri()));
+        hue.handle(&Event::Update(Arc::new(update(Some(20))))).await.unwrap();
+        // Still busy: nothing to send
+        hue.handle(&Event::Update(Arc::new(update(Some(19))))).await.unwrap();
+        hue.handle(&Event::Update(Arc::new(update(None)))).await.unwrap();
+        hue.handle(&Event::ConfigChanged { loaded_at: Utc::now() }).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_hue_errors_are_reported() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                { "error": { "type": 1, "address": "/lights/3/state", "description": "unauthorized user" } }
+            ])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let hue = HueLights::new(reqwest::Client::new(), hue_config(&server.uri()));
+        let busy = Event::Update(Arc::new(update(Some(20))));
+        let error = hue.handle(&busy).await.unwrap_err().to_string();
+        assert!(error.contains("unauthorized user"), "{}", error);
+        // Not retried straight away
+        hue.handle(&busy).await.unwrap();
+
+        // Nothing listens on the bridge address; the API key stays out of the error
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let hue = HueLights::new(reqwest::Client::new(), hue_config(&format!("127.0.0.1:{}", port)));
+        let error = hue.handle(&busy).await.unwrap_err().to_string();
+        assert!(error.contains("Hue bridge request failed"), "{}", error);
+        assert!(!error.contains("secret-key"), "{}", error);
+    }
+
+    #[test]
+    fn test_hue_validation() {
+        hue_config("192.168.1.20").validate().unwrap();
+        let invalid = [
+            HueConfig { bridge: String::new(), ..hue_config("192.168.1.20") },
+            HueConfig { api_key: None, ..hue_config("192.168.1.20") },
+            HueConfig { group: Some("2".to_string()), ..hue_config("192.168.1.20") },
+            HueConfig { light: None, ..hue_config("192.168.1.20") },
+            HueConfig { brightness: 0, ..hue_config("192.168.1.20") },
+            HueConfig { free: "blue".to_string(), ..hue_config("192.168.1.20") },
+            HueConfig { busy: "scene:AbC123".to_string(), ..hue_config("192.168.1.20") },
+        ];
+        for hue in invalid {
+            assert!(hue.validate().is_err(), "{:?}", hue);
+        }
+        HueConfig { light: None, group: Some("2".to_string()), busy: "scene:AbC123".to_string(), ..hue_config("192.168.1.20") }
+            .validate()
+            .unwrap();
+    }
+}
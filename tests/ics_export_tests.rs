@@ -0,0 +1,70 @@
+use calendar_monitor::ics_export::to_ics;
+use calendar_monitor::meeting::{Meeting, MeetingSource, ResponseStatus};
+use chrono::{NaiveDate, TimeZone, Utc};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_writes_events() {
+        let start = Utc.with_ymd_and_hms(2025, 6, 2, 9, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2025, 6, 2, 9, 30, 0).unwrap();
+        let meetings = vec![
+            Meeting::new("Standup; daily, short".to_string(), start, end)
+                .with_uid("standup@example.com".to_string())
+                .with_location("Room 1\nFloor 2".to_string())
+                .with_source(MeetingSource::google())
+                .with_response_status(ResponseStatus::Tentative),
+            Meeting::new_all_day(
+                "Republic Day".to_string(),
+                NaiveDate::from_ymd_opt(2025, 10, 29).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 10, 30).unwrap(),
+            ),
+        ];
+
+        let ics = to_ics(&meetings, "Calendar Monitor");
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert!(ics.contains("UID:standup@example.com\r\n"));
+        assert!(ics.contains("DTSTART:20250602T090000Z\r\nDTEND:20250602T093000Z\r\n"));
+        assert!(ics.contains("SUMMARY:Standup\\; daily\\, short\r\n"));
+        assert!(ics.contains("LOCATION:Room 1\\nFloor 2\r\n"));
+        assert!(ics.contains("CATEGORIES:Google Calendar\r\n"));
+        assert!(ics.contains("STATUS:TENTATIVE\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20251029\r\nDTEND;VALUE=DATE:20251030\r\n"));
+        assert!(ics.lines().all(|line| line.len() <= 75));
+    }
+
+    #[test]
+    fn test_export_keeps_uids_unique_and_parses_back() {
+        let start = Utc.with_ymd_and_hms(2025, 6, 2, 9, 0, 0).unwrap();
+        let next_day = start + chrono::Duration::days(1);
+        let long_title = "Quarterly planning ".repeat(10).trim_end().to_string();
+        let meetings = vec![
+            // Two occurrences of one recurring event
+            Meeting::new("Sync".to_string(), start, start + chrono::Duration::minutes(30)).with_uid("sync@example.com".to_string()),
+            Meeting::new("Sync".to_string(), next_day, next_day + chrono::Duration::minutes(30)).with_uid("sync@example.com".to_string()),
+            Meeting::new(long_title.clone(), start, start + chrono::Duration::hours(2)),
+        ];
+
+        let ics = to_ics(&meetings, "Calendar Monitor");
+        assert!(ics.contains("UID:sync@example.com-20250602T090000Z\r\n"));
+        assert!(ics.contains("UID:sync@example.com-20250603T090000Z\r\n"));
+
+        // Folded lines are read back whole by an ICS parser
+        let reader = ical::IcalParser::new(ics.as_bytes());
+        let calendar = reader.into_iter().next().unwrap().unwrap();
+        assert_eq!(calendar.events.len(), 3);
+        let titles: Vec<String> = calendar.events.into_iter()
+            .flat_map(|event| {
+                event.properties.into_iter()
+                    .filter(|p| p.name == "SUMMARY")
+                    .filter_map(|p| p.value)
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        assert!(titles.contains(&long_title));
+    }
+}
@@ -0,0 +1,2307 @@
+use calendar_monitor::app::{build_router, serve, AgendaEntryKind, AgendaResponse, AppState, ServerInfo, ConfigResponse, Envelope, ServerMessage, WS_PROTOCOL_VERSION, MeetingUpdate, OverrideStatus, ReloadResponse, StatusOverride, GoogleCalendarsResponse, HiddenResponse, NextMeeting, DeckState, OverlayLine, RangeResponse, SearchResponse, SourceChangeResponse, SourcesResponse, API_VERSION};
+use calendar_monitor::config::{AuthFlow, Config, CountdownConfig, DisplayConfig, PrivacyLevel, ProfileConfig, REDACTED, GoogleConfig, IcsConfig, ServerConfig};
+use calendar_monitor::google_calendar::GoogleTokens;
+use calendar_monitor::meeting::Meeting;
+use calendar_monitor::hidden_meetings::HiddenMeeting;
+use calendar_monitor::stats::{MeetingStats, StatsPeriod};
+use chrono::{DateTime, Duration, Timelike, Utc};
+use futures_util::StreamExt;
+use serde_json::json;
+use std::fs;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tempfile::TempDir;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ics_timestamp(time: DateTime<Utc>) -> String {
+        time.format("%Y%m%dT%H%M%SZ").to_string()
+    }
+
+    /// Write a temp ICS file containing a single upcoming event
+    fn write_upcoming_ics(dir: &TempDir, title: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> String {
+        let contents = format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//calendar-monitor//tests//EN\r\nBEGIN:VEVENT\r\nUID:ics-upcoming@test\r\nSUMMARY:{}\r\nDTSTART:{}\r\nDTEND:{}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+            title,
+            ics_timestamp(start),
+            ics_timestamp(end)
+        );
+        let path = dir.path().join("calendar.ics");
+        fs::write(&path, contents).expect("Failed to write ICS file");
+        path.to_string_lossy().to_string()
+    }
+
+    /// Start a stub Google Calendar API that returns a single in-progress event
+    async fn start_google_stub(title: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/calendar/v3/calendars/primary/events"))
+            .and(header("authorization", "Bearer stub-access-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "items": [{
+                    "id": "google-current",
+                    "iCalUID": "google-current@google.com",
+                    "summary": title,
+                    "start": { "dateTime": start.to_rfc3339() },
+                    "end": { "dateTime": end.to_rfc3339() },
+                    "attendees": [
+                        { "email": "me@example.com", "self": true, "responseStatus": "accepted" }
+                    ]
+                }]
+            })))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    fn create_test_config(ics_path: String, google_stub_uri: &str) -> Config {
+        Config {
+            server: ServerConfig {
+                host: "127.0.0.1".to_string(),
+                port: 0,
+                cache_ttl_seconds: 300,
+                ..Default::default()
+            },
+            ics: IcsConfig {
+                file_paths: vec![ics_path],
+                ..Default::default()
+            },
+            google: GoogleConfig {
+                client_id: Some("stub_client_id".to_string()),
+                client_secret: Some("stub_client_secret".to_string()),
+                redirect_uri: Some("http://localhost:3000/auth/google/callback".to_string()),
+                auth_url: Some(format!("{}/o/oauth2/auth", google_stub_uri)),
+                token_url: Some(format!("{}/token", google_stub_uri)),
+                api_base_url: Some(google_stub_uri.to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn stub_tokens() -> GoogleTokens {
+        GoogleTokens {
+            access_token: "stub-access-token".to_string(),
+            refresh_token: Some("stub-refresh-token".to_string()),
+            expires_at: Some(Utc::now() + Duration::hours(1)),
+        }
+    }
+
+    /// Serve the app on an ephemeral port and return its address
+    async fn spawn_app(config: Config) -> SocketAddr {
+        let state = AppState::new(Arc::new(config), Some(stub_tokens()));
+        let app = build_router(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        addr
+    }
+
+    fn assert_merged_payload(update: &MeetingUpdate) {
+        let current = update.current_meeting.as_ref().expect("Expected a current meeting");
+        assert_eq!(current.title, "Google Standup");
+        assert_eq!(current.source.as_ref().map(|s| s.id.as_str()), Some("google"));
+        assert!(update.countdown_seconds.unwrap() > 0);
+        assert!(update.last_updated.is_some());
+        assert!(update.source_errors.is_empty());
+        assert!(!update.quiet);
+        assert_eq!(update.countdown, CountdownConfig::default());
+        assert!(update.agenda_today.iter().any(|m| m.title == "Google Standup"));
+
+        let next = update.next_meeting.as_ref().expect("Expected a next meeting");
+        assert_eq!(next.title, "ICS Planning");
+        let starts_in = update.next_meeting_starts_in_seconds.unwrap();
+        assert!((25 * 60..=30 * 60).contains(&starts_in), "{}", starts_in);
+        assert_eq!(next.source.as_ref().map(|s| s.id.as_str()), Some("calendar"));
+
+        assert!(update.active_time_blocks.is_empty());
+    }
+
+    async fn setup() -> (TempDir, MockServer, SocketAddr) {
+        let now = Utc::now();
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let ics_path = write_upcoming_ics(
+            &temp_dir,
+            "ICS Planning",
+            now + Duration::minutes(30),
+            now + Duration::minutes(90),
+        );
+        let google = start_google_stub(
+            "Google Standup",
+            now - Duration::minutes(10),
+            now + Duration::minutes(20),
+        )
+        .await;
+        let addr = spawn_app(create_test_config(ics_path, &google.uri())).await;
+        (temp_dir, google, addr)
+    }
+
+    #[tokio::test]
+    async fn test_api_meetings_merges_google_and_ics() {
+        let (_temp_dir, _google, addr) = setup().await;
+
+        let response = reqwest::get(format!("http://{}/api/meetings", addr))
+            .await
+            .expect("Request to /api/meetings failed");
+        assert!(response.status().is_success());
+
+        let update: MeetingUpdate = response.json().await.expect("Invalid MeetingUpdate JSON");
+        assert_merged_payload(&update);
+    }
+
+    #[tokio::test]
+    async fn test_websocket_pushes_merged_payload() {
+        let (_temp_dir, _google, addr) = setup().await;
+
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr))
+            .await
+            .expect("WebSocket connection failed");
+
+        let message = tokio::time::timeout(std::time::Duration::from_secs(10), socket.next())
+            .await
+            .expect("Timed out waiting for WebSocket payload")
+            .expect("WebSocket closed before sending a payload")
+            .expect("WebSocket error");
+
+        let update: MeetingUpdate = serde_json::from_str(message.to_text().unwrap())
+            .expect("Invalid MeetingUpdate JSON");
+        assert_merged_payload(&update);
+        assert_eq!(update.version, API_VERSION);
+    }
+
+    #[tokio::test]
+    async fn test_websocket_clients_share_one_update_stream() {
+        let (_temp_dir, _google, addr) = setup().await;
+
+        let mut sockets = Vec::new();
+        for _ in 0..3 {
+            let (socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr))
+                .await
+                .expect("WebSocket connection failed");
+            sockets.push(socket);
+        }
+        let mut payloads = Vec::new();
+        for socket in &mut sockets {
+            let message = tokio::time::timeout(std::time::Duration::from_secs(10), socket.next())
+                .await
+                .expect("Timed out waiting for WebSocket payload")
+                .unwrap()
+                .unwrap();
+            payloads.push(message.into_text().unwrap());
+        }
+        assert!(payloads.iter().all(|p| serde_json::from_str::<MeetingUpdate>(p).is_ok()));
+
+        let info: ServerInfo = reqwest::get(format!("http://{}/api/v1/server-info", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(info.connected_clients, 3);
+
+        drop(sockets);
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(10);
+        loop {
+            let info: ServerInfo = reqwest::get(format!("http://{}/api/v1/server-info", addr))
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            if info.connected_clients == 0 {
+                break;
+            }
+            assert!(tokio::time::Instant::now() < deadline, "Clients were never dropped");
+            tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_websocket_only_pushes_changes() {
+        let (_temp_dir, _google, addr) = setup().await;
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr))
+            .await
+            .expect("WebSocket connection failed");
+        async fn next_update<S>(socket: &mut S) -> MeetingUpdate
+        where
+            S: futures_util::Stream<Item = Result<tokio_tungstenite::tungstenite::Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+        {
+            let message = socket.next().await.unwrap().unwrap();
+            serde_json::from_str(message.to_text().unwrap()).unwrap()
+        }
+
+        let first = tokio::time::timeout(std::time::Duration::from_secs(10), next_update(&mut socket)).await.unwrap();
+        assert_merged_payload(&first);
+        // Only the countdowns change from second to second
+        assert!(tokio::time::timeout(std::time::Duration::from_millis(2500), next_update(&mut socket)).await.is_err());
+
+        let until = (Utc::now() + Duration::hours(1)).to_rfc3339();
+        reqwest::Client::new()
+            .post(format!("http://{}/api/v1/override", addr))
+            .json(&json!({ "status": "busy", "until": until, "title": "Phone call" }))
+            .send()
+            .await
+            .unwrap();
+        let changed = tokio::time::timeout(std::time::Duration::from_secs(5), next_update(&mut socket))
+            .await
+            .expect("Expected the change to be pushed");
+        assert_eq!(changed.current_meeting.unwrap().title, "Phone call");
+    }
+
+    #[tokio::test]
+    async fn test_websocket_interval_holds_back_changes() {
+        let (_temp_dir, _google, addr) = setup().await;
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws?interval=3", addr))
+            .await
+            .expect("WebSocket connection failed");
+        let first = tokio::time::timeout(std::time::Duration::from_secs(10), socket.next()).await.unwrap();
+        assert!(first.is_some());
+
+        let until = (Utc::now() + Duration::hours(1)).to_rfc3339();
+        reqwest::Client::new()
+            .post(format!("http://{}/api/v1/override", addr))
+            .json(&json!({ "status": "busy", "until": until, "title": "Phone call" }))
+            .send()
+            .await
+            .unwrap();
+        // The change waits for the three seconds to pass
+        assert!(tokio::time::timeout(std::time::Duration::from_millis(1500), socket.next()).await.is_err());
+        let message = tokio::time::timeout(std::time::Duration::from_secs(5), socket.next())
+            .await
+            .expect("Expected the change after the interval")
+            .unwrap()
+            .unwrap();
+        let update: MeetingUpdate = serde_json::from_str(message.to_text().unwrap()).unwrap();
+        assert_eq!(update.current_meeting.unwrap().title, "Phone call");
+
+        for interval in ["0", "soon", "3601"] {
+            assert!(tokio_tungstenite::connect_async(format!("ws://{}/ws?interval={}", addr, interval)).await.is_err());
+            let response = reqwest::get(format!("http://{}/events?interval={}", addr, interval)).await.unwrap();
+            assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_websocket_subscription_tailors_updates() {
+        use tokio_tungstenite::tungstenite::Message;
+        use futures_util::SinkExt;
+
+        let (_temp_dir, _google, addr) = setup().await;
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr))
+            .await
+            .expect("WebSocket connection failed");
+        async fn next_json<S>(socket: &mut S) -> serde_json::Value
+        where
+            S: futures_util::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+        {
+            let message = tokio::time::timeout(std::time::Duration::from_secs(10), socket.next())
+                .await
+                .expect("Timed out waiting for a message")
+                .unwrap()
+                .unwrap();
+            serde_json::from_str(message.to_text().unwrap()).unwrap()
+        }
+        assert_merged_payload(&serde_json::from_value(next_json(&mut socket).await).unwrap());
+
+        // Only the ICS calendar: the Google meeting in progress is left out
+        socket.send(Message::Text(json!({ "sources": ["calendar"] }).to_string())).await.unwrap();
+        let update: MeetingUpdate = serde_json::from_value(next_json(&mut socket).await).unwrap();
+        assert!(update.current_meeting.is_none());
+        assert_eq!(update.next_meeting.unwrap().title, "ICS Planning");
+        assert!(update.agenda_today.iter().all(|m| m.title != "Google Standup"));
+
+        socket.send(Message::Text(json!({ "include_time_blocks": false, "timezone": "Europe/Istanbul" }).to_string())).await.unwrap();
+        let update: MeetingUpdate = serde_json::from_value(next_json(&mut socket).await).unwrap();
+        assert_eq!(update.current_meeting.unwrap().title, "Google Standup");
+        assert_eq!(update.timezone.as_deref(), Some("Europe/Istanbul"));
+
+        for invalid in [json!({ "timezone": "Mars/Olympus" }), json!({ "colour": "red" })] {
+            socket.send(Message::Text(invalid.to_string())).await.unwrap();
+            let error = next_json(&mut socket).await;
+            assert!(error["error"].as_str().unwrap().starts_with("Invalid subscription"), "{}", error);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_websocket_typed_protocol() {
+        use tokio_tungstenite::tungstenite::Message;
+        use futures_util::SinkExt;
+
+        let (_temp_dir, _google, addr) = setup().await;
+        let response = reqwest::get(format!("http://{}/ws?protocol=3", addr)).await.unwrap();
+        assert_eq!(response.status(), 400);
+
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws?protocol=2", addr))
+            .await
+            .expect("WebSocket connection failed");
+        async fn next_message<S>(socket: &mut S) -> ServerMessage
+        where
+            S: futures_util::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+        {
+            let message = tokio::time::timeout(std::time::Duration::from_secs(10), socket.next())
+                .await
+                .expect("Timed out waiting for a message")
+                .unwrap()
+                .unwrap();
+            let envelope: Envelope<ServerMessage> = serde_json::from_str(message.to_text().unwrap()).unwrap();
+            assert_eq!(envelope.protocol, WS_PROTOCOL_VERSION);
+            envelope.message
+        }
+
+        match next_message(&mut socket).await {
+            ServerMessage::Display(display) => assert_eq!(display, DisplayConfig::default()),
+            other => panic!("Expected the display settings, got {:?}", other),
+        }
+        match next_message(&mut socket).await {
+            ServerMessage::Update(update) => assert_merged_payload(&update),
+            other => panic!("Expected an update, got {:?}", other),
+        }
+        match next_message(&mut socket).await {
+            ServerMessage::SourceStatus(sources) => assert!(!sources.is_empty() && sources.iter().all(|s| s.is_healthy()), "{:?}", sources),
+            other => panic!("Expected the source status, got {:?}", other),
+        }
+
+        socket.send(Message::Text(json!({ "type": "ping" }).to_string())).await.unwrap();
+        assert!(matches!(next_message(&mut socket).await, ServerMessage::Pong));
+
+        let subscribe = json!({ "type": "subscribe", "data": { "sources": ["calendar"] } });
+        socket.send(Message::Text(subscribe.to_string())).await.unwrap();
+        match next_message(&mut socket).await {
+            ServerMessage::Update(update) => assert_eq!(update.next_meeting.unwrap().title, "ICS Planning"),
+            other => panic!("Expected an update, got {:?}", other),
+        }
+
+        for invalid in [json!({ "type": "shout" }), json!({ "type": "subscribe", "data": { "timezone": "Mars/Olympus" } })] {
+            socket.send(Message::Text(invalid.to_string())).await.unwrap();
+            match next_message(&mut socket).await {
+                ServerMessage::Error { message } => assert!(message.starts_with("Invalid"), "{}", message),
+                other => panic!("Expected an error, got {:?}", other),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timezone_adds_local_times() {
+        let (_temp_dir, _google, addr) = setup().await;
+        let tokyo: chrono_tz::Tz = "Asia/Tokyo".parse().unwrap();
+
+        let update: MeetingUpdate = reqwest::get(format!("http://{}/api/v1/meetings?timezone=Asia/Tokyo", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_merged_payload(&update);
+        assert_eq!(update.timezone.as_deref(), Some("Asia/Tokyo"));
+        let current = update.current_meeting.unwrap();
+        let local = current.local.expect("Expected local times");
+        let start = current.start_time.with_timezone(&tokyo);
+        assert_eq!(local.start, start.format("%H:%M").to_string());
+        assert_eq!(local.date, start.format("%Y-%m-%d").to_string());
+        assert_eq!(local.range, format!("{} - {}", local.start, local.end));
+        assert!(update.agenda_today.iter().all(|m| m.local.is_some()));
+
+        // Only when asked for
+        let update: MeetingUpdate = reqwest::get(format!("http://{}/api/v1/meetings", addr)).await.unwrap().json().await.unwrap();
+        assert!(update.current_meeting.unwrap().local.is_none());
+
+        let response = reqwest::get(format!("http://{}/api/v1/meetings?timezone=Mars/Olympus", addr)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws?timezone=Asia/Tokyo", addr))
+            .await
+            .expect("WebSocket connection failed");
+        let message = tokio::time::timeout(std::time::Duration::from_secs(10), socket.next())
+            .await
+            .expect("Timed out waiting for the first update")
+            .unwrap()
+            .unwrap();
+        let update: MeetingUpdate = serde_json::from_str(message.to_text().unwrap()).unwrap();
+        let next = update.next_meeting.unwrap();
+        assert_eq!(next.local.unwrap().start, next.start_time.with_timezone(&tokyo).format("%H:%M").to_string());
+    }
+
+    #[tokio::test]
+    async fn test_binary_payload_formats() {
+        use calendar_monitor::payload::PayloadFormat;
+
+        let (_temp_dir, _google, addr) = setup().await;
+        let client = reqwest::Client::new();
+
+        for (accept, format) in [("application/msgpack", PayloadFormat::MessagePack), ("application/cbor", PayloadFormat::Cbor)] {
+            let response = client
+                .get(format!("http://{}/api/v1/meetings", addr))
+                .header("accept", accept)
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.headers()["content-type"], accept);
+            let update: MeetingUpdate = format.decode(&response.bytes().await.unwrap()).unwrap();
+            assert_merged_payload(&update);
+        }
+        // Anything else stays JSON
+        let response = client.get(format!("http://{}/api/v1/meetings", addr)).header("accept", "*/*").send().await.unwrap();
+        assert_eq!(response.headers()["content-type"], "application/json");
+
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws?format=msgpack", addr))
+            .await
+            .expect("WebSocket connection failed");
+        let message = tokio::time::timeout(std::time::Duration::from_secs(10), socket.next())
+            .await
+            .expect("Timed out waiting for the first update")
+            .unwrap()
+            .unwrap();
+        assert!(message.is_binary());
+        let update: MeetingUpdate = PayloadFormat::MessagePack.decode(&message.into_data()).unwrap();
+        assert_merged_payload(&update);
+
+        match tokio_tungstenite::connect_async(format!("ws://{}/ws?format=xml", addr)).await {
+            Err(tokio_tungstenite::tungstenite::Error::Http(response)) => {
+                assert_eq!(response.status(), 400);
+                let body = String::from_utf8(response.into_body().unwrap_or_default()).unwrap();
+                assert!(body.contains("Unsupported format"), "{}", body);
+            }
+            other => panic!("Expected the format to be refused, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_event_stream_pushes_merged_payload() {
+        let (_temp_dir, _google, addr) = setup().await;
+
+        let mut response = reqwest::get(format!("http://{}/events", addr)).await.unwrap();
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+
+        // Read until the first complete event carrying data
+        let mut body = String::new();
+        let data = tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            loop {
+                let chunk = response.chunk().await.unwrap().expect("Stream ended before sending a payload");
+                body.push_str(std::str::from_utf8(&chunk).unwrap());
+                if let Some(data) = body.lines().find_map(|line| line.strip_prefix("data: ")) {
+                    if body.contains("\n\n") {
+                        return data.to_string();
+                    }
+                }
+            }
+        })
+        .await
+        .expect("Timed out waiting for an event");
+
+        assert!(body.starts_with("retry:5000\n"), "{}", body);
+        let update: MeetingUpdate = serde_json::from_str(&data).expect("Invalid MeetingUpdate JSON");
+        assert_merged_payload(&update);
+    }
+
+    #[tokio::test]
+    async fn test_api_v1_and_legacy_routes_serve_the_same_api() {
+        let (_temp_dir, _google, addr) = setup().await;
+
+        for path in ["/api/v1/meetings", "/api/meetings"] {
+            let update: MeetingUpdate = reqwest::get(format!("http://{}{}", addr, path))
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            assert_merged_payload(&update);
+            assert_eq!(update.version, 1, "{}", path);
+        }
+
+        for path in ["/api/v1/next", "/api/v1/sources", "/api/v1/server-info"] {
+            let response = reqwest::get(format!("http://{}{}", addr, path)).await.unwrap();
+            assert!(response.status().is_success(), "{}", path);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_api_meetings_without_google_tokens() {
+        let now = Utc::now();
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let ics_path = write_upcoming_ics(
+            &temp_dir,
+            "ICS Planning",
+            now + Duration::minutes(30),
+            now + Duration::minutes(90),
+        );
+        let google = MockServer::start().await;
+        let state = AppState::new(Arc::new(create_test_config(ics_path, &google.uri())), None);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, build_router(state)).await.unwrap();
+        });
+
+        let update: MeetingUpdate = reqwest::get(format!("http://{}/api/meetings", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        assert!(update.current_meeting.is_none());
+        assert_eq!(update.next_meeting.unwrap().title, "ICS Planning");
+        // Without tokens the Google API must never be called
+        assert!(google.received_requests().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_api_sources_reports_source_health() {
+        let (_temp_dir, _google, addr) = setup().await;
+
+        // Trigger a fetch so the source has a recorded outcome
+        reqwest::get(format!("http://{}/api/meetings", addr)).await.unwrap();
+
+        let sources: SourcesResponse = reqwest::get(format!("http://{}/api/sources", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        assert_eq!(sources.sources.len(), 1);
+        let source = &sources.sources[0];
+        assert_eq!(source.source, "calendar");
+        assert!(source.is_healthy());
+        assert_eq!(source.event_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_api_sources_name_feeds_instead_of_urls() {
+        use calendar_monitor::config::IcsSourceConfig;
+
+        let feed = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&feed)
+            .await;
+        let feed_url = feed.uri().replace("http://", "http://me:hunter2@") + "/private-8f3a2c/basic.ics";
+
+        let mut config = create_test_config("./missing.ics".to_string(), &feed.uri());
+        config.ics.file_paths.clear();
+        config.ics.sources = vec![IcsSourceConfig { path: feed_url, name: Some("Team".to_string()), ..Default::default() }];
+        let addr = spawn_app(config).await;
+        reqwest::get(format!("http://{}/api/meetings", addr)).await.unwrap();
+
+        let body = reqwest::get(format!("http://{}/api/sources", addr)).await.unwrap().text().await.unwrap();
+        assert!(!body.contains("hunter2") && !body.contains("private-8f3a2c"), "{}", body);
+        let sources: SourcesResponse = serde_json::from_str(&body).unwrap();
+        assert_eq!(sources.sources[0].source, "Team");
+        assert!(sources.sources[0].last_error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_api_meetings_deduplicates_google_event_exported_as_ics() {
+        // Both APIs report whole seconds
+        let now = Utc::now().with_nanosecond(0).unwrap();
+        let start = now - Duration::minutes(10);
+        let end = now + Duration::minutes(20);
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+
+        // The Google calendar is also subscribed through its ICS export
+        let ics_path = temp_dir.path().join("google-export.ics");
+        fs::write(&ics_path, format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:google-current@google.com\r\nSUMMARY:Google Standup\r\nDTSTART:{}\r\nDTEND:{}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+            ics_timestamp(start),
+            ics_timestamp(end),
+        )).expect("Failed to write ICS file");
+
+        let google = start_google_stub("Google Standup", start, end).await;
+        let config = create_test_config(ics_path.to_string_lossy().to_string(), &google.uri());
+        let state = AppState::new(Arc::new(config), Some(stub_tokens()));
+
+        let update = calendar_monitor::app::build_meeting_update(&state).await;
+
+        let current = update.current_meeting.expect("Expected a current meeting");
+        assert_eq!(current.title, "Google Standup");
+        assert_eq!(current.uid.as_deref(), Some("google-current@google.com"));
+        // The Google copy wins, so the response status survives the merge
+        assert!(current.response_status.is_some());
+        // The ICS copy must not reappear as the next meeting
+        assert!(update.next_meeting.is_none());
+    }
+
+
+    #[tokio::test]
+    async fn test_runtime_source_management_persists_to_config_file() {
+        let (temp_dir, google, _addr) = setup().await;
+        let ics_path = temp_dir.path().join("calendar.ics").to_string_lossy().to_string();
+
+        let config_path = temp_dir.path().join("calendar-monitor.toml");
+        fs::write(&config_path, format!(
+            "# Kiosk configuration\n[server]\nhost = \"127.0.0.1\"\nport = 0\ncache_ttl_seconds = 300\nallow_source_management = true\n\n[ics]\nfile_paths = [\"{}\"]\n\n[google]\n",
+            ics_path
+        )).unwrap();
+
+        let mut config = create_test_config(ics_path, &google.uri());
+        config.server.allow_source_management = true;
+        let state = AppState::new(Arc::new(config), Some(stub_tokens()))
+            .with_config_file(Some(config_path.clone()));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, build_router(state)).await.unwrap();
+        });
+
+        let client = reqwest::Client::new();
+        let feed_url = format!("{}/team.ics", google.uri());
+
+        let response = client
+            .post(format!("http://{}/api/sources", addr))
+            .json(&json!({ "path": feed_url, "alias": "team", "color": "#2e86de" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::CREATED);
+        let added: SourceChangeResponse = response.json().await.unwrap();
+        assert!(added.persisted);
+        assert_eq!(added.sources.len(), 2);
+        assert_eq!(added.sources[1].source, "team");
+
+        // The file keeps its comments and now lists the new source
+        let saved = fs::read_to_string(&config_path).unwrap();
+        assert!(saved.starts_with("# Kiosk configuration"));
+        let reloaded: Config = toml::from_str(&saved).unwrap();
+        assert_eq!(reloaded.ics.sources.len(), 1);
+        assert_eq!(reloaded.ics.sources[0].path, feed_url);
+        assert_eq!(reloaded.ics.sources[0].alias.as_deref(), Some("team"));
+
+        // Adding it again is a conflict
+        let response = client
+            .post(format!("http://{}/api/sources", addr))
+            .json(&json!({ "path": feed_url }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::CONFLICT);
+
+        let response = client
+            .delete(format!("http://{}/api/sources", addr))
+            .query(&[("path", feed_url.as_str())])
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let removed: SourceChangeResponse = response.json().await.unwrap();
+        assert!(removed.persisted);
+        assert_eq!(removed.sources.len(), 1);
+
+        let reloaded: Config = toml::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+        assert!(reloaded.ics.sources.is_empty());
+        assert_eq!(reloaded.ics.file_paths.len(), 1);
+
+        // Removing an unknown source is a 404
+        let response = client
+            .delete(format!("http://{}/api/sources", addr))
+            .query(&[("path", feed_url.as_str())])
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_runtime_source_management_disabled_by_default() {
+        let (_temp_dir, _google, addr) = setup().await;
+        let client = reqwest::Client::new();
+
+        let response = client
+            .post(format!("http://{}/api/sources", addr))
+            .json(&json!({ "path": "https://calendar.example.com/team.ics" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+
+        let response = client
+            .delete(format!("http://{}/api/sources", addr))
+            .query(&[("path", "https://calendar.example.com/team.ics")])
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_api_google_calendars_lists_calendar_list() {
+        let (_temp_dir, google, addr) = setup().await;
+        Mock::given(method("GET"))
+            .and(path("/calendar/v3/users/me/calendarList"))
+            .and(header("authorization", "Bearer stub-access-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "items": [
+                    { "id": "me@example.com", "summary": "me@example.com", "primary": true, "accessRole": "owner", "backgroundColor": "#9fe1e7" },
+                    { "id": "team@group.calendar.google.com", "summary": "Team", "summaryOverride": "My Team", "accessRole": "reader" }
+                ]
+            })))
+            .mount(&google)
+            .await;
+
+        let response = reqwest::get(format!("http://{}/api/google/calendars", addr)).await.unwrap();
+        assert!(response.status().is_success());
+        let body: GoogleCalendarsResponse = response.json().await.unwrap();
+
+        assert_eq!(body.calendars.len(), 2);
+        let primary = &body.calendars[0];
+        assert!(primary.primary);
+        assert_eq!(primary.color.as_deref(), Some("#9fe1e7"));
+        // Only the primary calendar is monitored by default
+        assert!(primary.monitored);
+
+        let team = &body.calendars[1];
+        assert_eq!(team.id, "team@group.calendar.google.com");
+        assert_eq!(team.name, "My Team");
+        assert_eq!(team.access_role.as_deref(), Some("reader"));
+        assert!(!team.monitored);
+    }
+
+    #[tokio::test]
+    async fn test_api_google_calendars_requires_connection() {
+        let google = MockServer::start().await;
+        let config = create_test_config("./missing.ics".to_string(), &google.uri());
+        let state = AppState::new(Arc::new(config), None);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, build_router(state)).await.unwrap();
+        });
+
+        let response = reqwest::get(format!("http://{}/api/google/calendars", addr)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+        assert!(google.received_requests().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_device_authorization_flow_stores_tokens() {
+        use wiremock::matchers::body_string_contains;
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let google = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/device/code"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "device_code": "stub-device-code",
+                "user_code": "GQVQ-JKEC",
+                "verification_url": "https://www.google.com/device",
+                "expires_in": 1800,
+                "interval": 1
+            })))
+            .expect(1)
+            .mount(&google)
+            .await;
+        // The user has not entered the code yet on the first poll
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .and(body_string_contains("device_code=stub-device-code"))
+            .respond_with(ResponseTemplate::new(428).set_body_json(json!({ "error": "authorization_pending" })))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&google)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .and(body_string_contains("device_code=stub-device-code"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "access_token": "device-access-token",
+                "refresh_token": "device-refresh-token",
+                "token_type": "Bearer",
+                "expires_in": 3600
+            })))
+            .mount(&google)
+            .await;
+
+        let mut config = create_test_config("./missing.ics".to_string(), &google.uri());
+        config.google.auth_flow = AuthFlow::Device;
+        config.google.redirect_uri = None;
+        config.google.device_auth_url = Some(format!("{}/device/code", google.uri()));
+        let token_file = temp_dir.path().join("google_tokens.json");
+        config.google.token_file = Some(token_file.to_string_lossy().to_string());
+        assert!(config.validate().is_ok());
+
+        let state = AppState::new(Arc::new(config), None);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app_state = state.clone();
+        tokio::spawn(async move {
+            axum::serve(listener, build_router(app_state)).await.unwrap();
+        });
+
+        let client = reqwest::Client::new();
+        let response = client.post(format!("http://{}/auth/google/device", addr)).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+        let status: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(status["status"], "pending");
+        assert_eq!(status["user_code"], "GQVQ-JKEC");
+        assert_eq!(status["verification_url"], "https://www.google.com/device");
+
+        // Starting again while pending returns the same code
+        let again: serde_json::Value = client.post(format!("http://{}/auth/google/device", addr))
+            .send().await.unwrap().json().await.unwrap();
+        assert_eq!(again["user_code"], "GQVQ-JKEC");
+
+        let connected = tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            loop {
+                let status: serde_json::Value = reqwest::get(format!("http://{}/auth/google/device", addr))
+                    .await.unwrap().json().await.unwrap();
+                if status["status"] != "pending" {
+                    return status;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+            }
+        })
+        .await
+        .expect("Device authorization did not complete");
+        assert_eq!(connected["status"], "connected");
+
+        let tokens = state.google_tokens.read().await.clone().expect("Tokens stored in state");
+        assert_eq!(tokens.access_token, "device-access-token");
+        assert_eq!(tokens.refresh_token.as_deref(), Some("device-refresh-token"));
+        assert!(token_file.exists());
+    }
+
+
+    #[tokio::test]
+    async fn test_device_flow_login_page_needs_auth_token() {
+        let google = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/device/code"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "device_code": "stub-device-code",
+                "user_code": "GQVQ-JKEC",
+                "verification_url": "https://www.google.com/device",
+                "expires_in": 1800,
+                "interval": 60
+            })))
+            .expect(1)
+            .mount(&google)
+            .await;
+
+        let mut config = create_test_config("./missing.ics".to_string(), &google.uri());
+        config.google.auth_flow = AuthFlow::Device;
+        config.google.redirect_uri = None;
+        config.google.device_auth_url = Some(format!("{}/device/code", google.uri()));
+        config.server.auth_token = Some("s3cret-display-token".to_string());
+        let state = AppState::new(Arc::new(config), None);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, build_router(state)).await.unwrap();
+        });
+
+        // Without the token, no device flow is started and no code is shown
+        let response = reqwest::get(format!("http://{}/auth/google/login", addr)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+        assert!(google.received_requests().await.unwrap().is_empty());
+
+        let page = reqwest::get(format!("http://{}/auth/google/login?token=s3cret-display-token", addr))
+            .await.unwrap().text().await.unwrap();
+        assert!(page.contains("GQVQ-JKEC"), "{}", page);
+    }
+
+
+    #[tokio::test]
+    async fn test_oauth_callback_validates_state() {
+        use wiremock::matchers::body_string_contains;
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let google = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .and(body_string_contains("code=good-code"))
+            .and(body_string_contains("code_verifier="))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "access_token": "callback-access-token",
+                "refresh_token": "callback-refresh-token",
+                "token_type": "Bearer",
+                "expires_in": 3600
+            })))
+            .expect(1)
+            .mount(&google)
+            .await;
+
+        let mut config = create_test_config("./missing.ics".to_string(), &google.uri());
+        let token_file = temp_dir.path().join("google_tokens.json");
+        config.google.token_file = Some(token_file.to_string_lossy().to_string());
+        let state = AppState::new(Arc::new(config), None);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app_state = state.clone();
+        tokio::spawn(async move {
+            axum::serve(listener, build_router(app_state)).await.unwrap();
+        });
+
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .unwrap();
+        let login = client.get(format!("http://{}/auth/google/login", addr)).send().await.unwrap();
+        assert!(login.status().is_redirection());
+        let location = reqwest::Url::parse(login.headers()["location"].to_str().unwrap()).unwrap();
+        let oauth_state = location
+            .query_pairs()
+            .find(|(key, _)| key == "state")
+            .map(|(_, value)| value.to_string())
+            .expect("Expected a state parameter");
+        let cookie = login.headers()["set-cookie"].to_str().unwrap().split(';').next().unwrap().to_string();
+        assert_eq!(cookie, format!("calendar_monitor_google_sign_in={}", oauth_state));
+
+        // A forged state is rejected before anything is sent to Google
+        let forged = client
+            .get(format!("http://{}/auth/google/callback?code=evil-code&state=forged", addr))
+            .header("cookie", "calendar_monitor_google_sign_in=forged")
+            .send().await.unwrap();
+        assert_eq!(forged.status(), reqwest::StatusCode::BAD_REQUEST);
+
+        // So is a real state from a browser that did not start the sign-in
+        let callback_url = format!("http://{}/auth/google/callback?code=good-code&state={}", addr, oauth_state);
+        let elsewhere = client.get(&callback_url).send().await.unwrap();
+        assert_eq!(elsewhere.status(), reqwest::StatusCode::BAD_REQUEST);
+        assert!(google.received_requests().await.unwrap().is_empty());
+        assert!(state.google_tokens.read().await.is_none());
+
+        let accepted = client.get(&callback_url).header("cookie", &cookie).send().await.unwrap();
+        assert_eq!(accepted.status(), reqwest::StatusCode::OK);
+        assert_eq!(
+            state.google_tokens.read().await.as_ref().map(|t| t.access_token.clone()),
+            Some("callback-access-token".to_string())
+        );
+        assert!(token_file.exists());
+
+        // The state can't be replayed
+        let replayed = client.get(&callback_url).header("cookie", &cookie).send().await.unwrap();
+        assert_eq!(replayed.status(), reqwest::StatusCode::BAD_REQUEST);
+
+        // Errors passed back in the link are shown as text
+        let page = client
+            .get(format!("http://{}/auth/google/callback?error=%3Cscript%3Ealert(1)%3C/script%3E", addr))
+            .send().await.unwrap().text().await.unwrap();
+        assert!(page.contains("&lt;script&gt;") && !page.contains("<script>alert"), "{}", page);
+    }
+
+
+    #[tokio::test]
+    async fn test_declined_google_invitation_hides_ics_copy() {
+        let now = Utc::now().with_nanosecond(0).unwrap();
+        let start = now - Duration::minutes(10);
+        let end = now + Duration::minutes(20);
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+
+        // The ICS export of the same calendar has no response status
+        let ics_path = temp_dir.path().join("google-export.ics");
+        fs::write(&ics_path, format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:all-hands@google.com\r\nSUMMARY:All hands\r\nDTSTART:{}\r\nDTEND:{}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+            ics_timestamp(start),
+            ics_timestamp(end),
+        )).expect("Failed to write ICS file");
+
+        let google = MockServer::start().await;
+        let event = |id: &str, title: &str, response: &str, start: DateTime<Utc>, end: DateTime<Utc>| json!({
+            "id": id,
+            "iCalUID": format!("{}@google.com", id),
+            "summary": title,
+            "start": { "dateTime": start.to_rfc3339() },
+            "end": { "dateTime": end.to_rfc3339() },
+            "attendees": [
+                { "email": "organizer@example.com", "responseStatus": "accepted" },
+                { "email": "me@example.com", "self": true, "responseStatus": response }
+            ]
+        });
+        Mock::given(method("GET"))
+            .and(path("/calendar/v3/calendars/primary/events"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "items": [
+                    event("all-hands", "All hands", "declined", start, end),
+                    event("review", "Design review", "tentative", now + Duration::hours(1), now + Duration::hours(2))
+                ]
+            })))
+            .mount(&google)
+            .await;
+
+        let config = create_test_config(ics_path.to_string_lossy().to_string(), &google.uri());
+        let state = AppState::new(Arc::new(config), Some(stub_tokens()));
+
+        let update = calendar_monitor::app::build_meeting_update(&state).await;
+
+        // Neither the declined Google event nor its ICS copy is current
+        assert!(update.current_meeting.is_none());
+        let next = update.next_meeting.expect("Expected a next meeting");
+        assert_eq!(next.title, "Design review");
+        assert_eq!(next.response_status, Some(calendar_monitor::meeting::ResponseStatus::Tentative));
+    }
+
+
+    #[tokio::test]
+    async fn test_google_failure_keeps_previous_meetings() {
+        let now = Utc::now();
+        let google = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/calendar/v3/calendars/primary/events"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "items": [{
+                    "id": "google-current",
+                    "summary": "Google Standup",
+                    "start": { "dateTime": (now - Duration::minutes(10)).to_rfc3339() },
+                    "end": { "dateTime": (now + Duration::minutes(20)).to_rfc3339() }
+                }]
+            })))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&google)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/calendar/v3/calendars/primary/events"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&google)
+            .await;
+
+        let mut config = create_test_config("./missing.ics".to_string(), &google.uri());
+        // Ask Google on every update
+        config.google.cache_ttl_seconds = Some(0);
+        let state = AppState::new(Arc::new(config), Some(stub_tokens()));
+
+        let first = calendar_monitor::app::build_meeting_update(&state).await;
+        assert_eq!(first.current_meeting.unwrap().title, "Google Standup");
+
+        // Google is now unavailable; the display keeps the last good meetings
+        let second = calendar_monitor::app::build_meeting_update(&state).await;
+        assert_eq!(second.current_meeting.unwrap().title, "Google Standup");
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_updates_share_one_google_request() {
+        let now = Utc::now();
+        let google = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/calendar/v3/calendars/primary/events"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                        "items": [{
+                            "id": "google-current",
+                            "summary": "Google Standup",
+                            "start": { "dateTime": (now - Duration::minutes(10)).to_rfc3339() },
+                            "end": { "dateTime": (now + Duration::minutes(20)).to_rfc3339() }
+                        }]
+                    }))
+                    .set_delay(std::time::Duration::from_millis(300)),
+            )
+            .expect(1)
+            .mount(&google)
+            .await;
+
+        let config = create_test_config("./missing.ics".to_string(), &google.uri());
+        let state = AppState::new(Arc::new(config), Some(stub_tokens()));
+
+        let first = calendar_monitor::app::build_meeting_update(&state);
+        let second = async {
+            // Start while the first request is in flight
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            calendar_monitor::app::build_meeting_update(&state).await
+        };
+        let (first, second) = tokio::join!(first, second);
+
+        assert_eq!(first.current_meeting.unwrap().title, "Google Standup");
+        assert_eq!(second.current_meeting.unwrap().title, "Google Standup");
+    }
+
+
+    #[tokio::test]
+    async fn test_google_meetings_served_from_cache_within_ttl() {
+        let now = Utc::now();
+        let google = start_google_stub(
+            "Google Standup",
+            now - Duration::minutes(10),
+            now + Duration::minutes(20),
+        )
+        .await;
+
+        let config = create_test_config("./missing.ics".to_string(), &google.uri());
+        assert_eq!(config.google.cache_ttl_seconds(), 60);
+        let state = AppState::new(Arc::new(config), Some(stub_tokens()));
+        let event_requests = || async {
+            google.received_requests().await.unwrap().iter()
+                .filter(|r| r.url.path().ends_with("/events"))
+                .count()
+        };
+
+        for _ in 0..3 {
+            let update = calendar_monitor::app::build_meeting_update(&state).await;
+            assert_eq!(update.current_meeting.unwrap().title, "Google Standup");
+        }
+        assert_eq!(event_requests().await, 1);
+
+        // Signing in again drops the cached meetings
+        state.google_meetings.lock().await.invalidate();
+        calendar_monitor::app::build_meeting_update(&state).await;
+        assert_eq!(event_requests().await, 2);
+    }
+
+
+    #[tokio::test]
+    async fn test_google_cache_covers_all_of_today_and_tomorrow() {
+        use calendar_monitor::recurrence::Window;
+        use wiremock::matchers::query_param;
+
+        let window = Window::today_and_tomorrow();
+        let google = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/calendar/v3/calendars/primary/events"))
+            .and(query_param("timeMin", window.start().format("%Y-%m-%dT%H:%M:%SZ").to_string()))
+            .and(query_param("timeMax", window.end().format("%Y-%m-%dT%H:%M:%SZ").to_string()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "items": [
+                    {
+                        "id": "early",
+                        "summary": "Early Sync",
+                        "start": { "dateTime": window.start().to_rfc3339() },
+                        "end": { "dateTime": (window.start() + Duration::minutes(1)).to_rfc3339() }
+                    },
+                    {
+                        "id": "late",
+                        "summary": "Late Review",
+                        "start": { "dateTime": (window.end() - Duration::minutes(30)).to_rfc3339() },
+                        "end": { "dateTime": window.end().to_rfc3339() }
+                    }
+                ]
+            })))
+            .mount(&google)
+            .await;
+        let addr = spawn_app(create_test_config("./missing.ics".to_string(), &google.uri())).await;
+
+        let range: RangeResponse = reqwest::Client::new()
+            .get(format!("http://{}/api/meetings/range", addr))
+            .query(&[("from", window.start().to_rfc3339()), ("to", window.end().to_rfc3339())])
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let titles: Vec<&str> = range.meetings.iter().map(|m| m.title.as_str()).collect();
+        assert_eq!(titles, vec!["Early Sync", "Late Review"]);
+    }
+
+
+    #[tokio::test]
+    async fn test_failing_google_fetch_is_listed_in_source_errors() {
+        let google = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/calendar/v3/calendars/primary/events"))
+            .respond_with(ResponseTemplate::new(401).set_body_json(json!({ "error": "invalid_credentials" })))
+            .mount(&google)
+            .await;
+        let mut config = create_test_config("./missing.ics".to_string(), &google.uri());
+        config.ics.file_paths.clear();
+        let mut tokens = stub_tokens();
+        tokens.refresh_token = None;
+        let state = AppState::new(Arc::new(config), Some(tokens));
+
+        let update = calendar_monitor::app::build_meeting_update(&state).await;
+        assert_eq!(update.source_errors.len(), 1);
+        let failure = &update.source_errors[0];
+        assert_eq!(failure.source, "Google Calendar");
+        assert!(failure.error.contains("401"), "{}", failure.error);
+
+        // Still failing since the first attempt
+        state.google_meetings.lock().await.invalidate();
+        let update = calendar_monitor::app::build_meeting_update(&state).await;
+        assert_eq!(update.source_errors[0].since, failure.since);
+    }
+
+
+    #[tokio::test]
+    async fn test_google_service_is_shared_between_updates() {
+        let now = Utc::now();
+        let google = start_google_stub(
+            "Google Standup",
+            now - Duration::minutes(10),
+            now + Duration::minutes(20),
+        )
+        .await;
+        Mock::given(method("GET"))
+            .and(path("/calendar/v3/calendars/primary/events"))
+            .and(header("authorization", "Bearer new-access-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "items": [] })))
+            .mount(&google)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/calendar/v3/colors"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "event": {} })))
+            .expect(1)
+            .mount(&google)
+            .await;
+
+        let mut config = create_test_config("./missing.ics".to_string(), &google.uri());
+        config.google.cache_ttl_seconds = Some(0);
+        let state = AppState::new(Arc::new(config), Some(stub_tokens()));
+
+        // The color palette is loaded by the first update only
+        for _ in 0..2 {
+            let update = calendar_monitor::app::build_meeting_update(&state).await;
+            assert_eq!(update.current_meeting.unwrap().title, "Google Standup");
+        }
+
+        // New tokens (e.g. after signing in again) are picked up by the shared service
+        *state.google_tokens.write().await = Some(GoogleTokens {
+            access_token: "new-access-token".to_string(),
+            ..stub_tokens()
+        });
+        let update = calendar_monitor::app::build_meeting_update(&state).await;
+        assert!(update.current_meeting.is_none());
+    }
+
+
+    #[tokio::test]
+    async fn test_google_logout_revokes_and_forgets_tokens() {
+        use wiremock::matchers::body_string_contains;
+
+        let now = Utc::now();
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let google = start_google_stub(
+            "Google Standup",
+            now - Duration::minutes(10),
+            now + Duration::minutes(20),
+        )
+        .await;
+        Mock::given(method("POST"))
+            .and(path("/revoke"))
+            .and(body_string_contains("token=stub-refresh-token"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&google)
+            .await;
+
+        let mut config = create_test_config("./missing.ics".to_string(), &google.uri());
+        config.google.revoke_url = Some(format!("{}/revoke", google.uri()));
+        let token_file = temp_dir.path().join("google_tokens.json");
+        config.google.token_file = Some(token_file.to_string_lossy().to_string());
+        config.save_google_tokens(&stub_tokens()).unwrap();
+
+        let state = AppState::new(Arc::new(config), Some(stub_tokens()));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app_state = state.clone();
+        tokio::spawn(async move {
+            axum::serve(listener, build_router(app_state)).await.unwrap();
+        });
+
+        let update = calendar_monitor::app::build_meeting_update(&state).await;
+        assert_eq!(update.current_meeting.unwrap().title, "Google Standup");
+
+        // A plain link only shows the confirmation
+        let page = reqwest::get(format!("http://{}/auth/google/logout", addr)).await.unwrap();
+        assert!(page.status().is_success());
+        assert!(state.google_tokens.read().await.is_some());
+        let page = page.text().await.unwrap();
+        let form_token = page.split("name='form_token' value='").nth(1).unwrap().split('\'').next().unwrap();
+
+        // Other sites can't submit the form
+        let client = reqwest::Client::new();
+        let response = client.post(format!("http://{}/auth/google/logout", addr)).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+        let response = client
+            .post(format!("http://{}/auth/google/logout", addr))
+            .header("origin", "https://elsewhere.example")
+            .form(&[("form_token", form_token)])
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+        assert!(state.google_tokens.read().await.is_some());
+
+        let response = client
+            .post(format!("http://{}/auth/google/logout", addr))
+            .header("origin", format!("http://{}", addr))
+            .form(&[("form_token", form_token)])
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert!(state.google_tokens.read().await.is_none());
+        assert!(!token_file.exists());
+
+        // Google meetings disappear right away, without another API call
+        let requests_before = google.received_requests().await.unwrap().len();
+        let update = calendar_monitor::app::build_meeting_update(&state).await;
+        assert!(update.current_meeting.is_none());
+        assert_eq!(google.received_requests().await.unwrap().len(), requests_before);
+    }
+
+
+    #[tokio::test]
+    async fn test_public_holiday_explains_empty_day() {
+        let now = Utc::now();
+        let google = start_google_stub("Later", now + Duration::hours(30), now + Duration::hours(31)).await;
+        let today = chrono::Local::now().date_naive();
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v3/PublicHolidays/{}/TR", today.format("%Y"))))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "date": today,
+                "localName": "Cumhuriyet Bayramı",
+                "name": "Republic Day",
+                "global": true,
+                "counties": null
+            }])))
+            .mount(&google)
+            .await;
+
+        let mut config = create_test_config("./missing.ics".to_string(), &google.uri());
+        config.holidays.country = Some("TR".to_string());
+        config.holidays.api_url = Some(google.uri());
+        let state = AppState::new(Arc::new(config), Some(stub_tokens()));
+
+        let update = calendar_monitor::app::build_meeting_update(&state).await;
+        assert!(update.current_meeting.is_none());
+        assert_eq!(update.holiday.as_deref(), Some("Republic Day"));
+        assert_eq!(update.all_day_events.len(), 1);
+        assert_eq!(update.all_day_events[0].source.as_ref().unwrap().id, "holidays");
+    }
+
+
+    #[tokio::test]
+    async fn test_api_agenda_lists_the_day_in_order() {
+        let (_temp_dir, _google, addr) = setup().await;
+        let today = chrono::Local::now().date_naive();
+
+        let response = reqwest::get(format!("http://{}/api/agenda?date={}", addr, today))
+            .await
+            .expect("Request to /api/agenda failed");
+        assert!(response.status().is_success());
+
+        let agenda: AgendaResponse = response.json().await.expect("Invalid AgendaResponse JSON");
+        assert_eq!(agenda.date, today);
+        let standup = agenda.events.iter()
+            .find(|e| e.meeting.title == "Google Standup")
+            .expect("Expected the Google meeting in today's agenda");
+        assert_eq!(standup.kind, AgendaEntryKind::Meeting);
+        assert!(agenda.events.windows(2).all(|pair| pair[0].meeting.start_time <= pair[1].meeting.start_time));
+    }
+
+    #[tokio::test]
+    async fn test_api_agenda_reads_other_days_from_sources() {
+        let now = Utc::now();
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let start = now + Duration::days(7);
+        let ics_path = write_upcoming_ics(&temp_dir, "Next Week Review", start, start + Duration::hours(1));
+        let google = start_google_stub("Google Standup", now - Duration::minutes(10), now + Duration::minutes(20)).await;
+        let addr = spawn_app(create_test_config(ics_path, &google.uri())).await;
+
+        let date = start.with_timezone(&chrono::Local).date_naive();
+        let agenda: AgendaResponse = reqwest::get(format!("http://{}/api/agenda?date={}", addr, date))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let titles: Vec<&str> = agenda.events.iter().map(|e| e.meeting.title.as_str()).collect();
+        assert_eq!(titles, vec!["Next Week Review"]);
+
+        let response = reqwest::get(format!("http://{}/api/agenda?date=next-week", addr)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_api_stats_sums_meeting_time() {
+        let (_temp_dir, _google, addr) = setup().await;
+
+        let stats: MeetingStats = reqwest::get(format!("http://{}/api/stats", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .expect("Invalid MeetingStats JSON");
+        assert_eq!(stats.period, StatsPeriod::Day);
+        assert_eq!((stats.from, stats.to), StatsPeriod::Day.range(chrono::Local::now().date_naive()));
+        // The Google meeting in progress counts whatever the time of day
+        assert!(stats.meeting_count >= 1);
+        assert!(stats.meeting_minutes >= 1);
+
+        let week: MeetingStats = reqwest::get(format!("http://{}/api/stats?period=week", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert!(week.from <= stats.from && stats.to <= week.to);
+        assert!(week.meeting_minutes >= stats.meeting_minutes);
+
+        let response = reqwest::get(format!("http://{}/api/stats?period=month", addr)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    }
+
+
+    #[tokio::test]
+    async fn test_api_meetings_range_returns_overlapping_meetings() {
+        let (_temp_dir, _google, addr) = setup().await;
+        let client = reqwest::Client::new();
+        let now = Utc::now();
+
+        // The Google meeting is in progress, the ICS one starts in 30 minutes
+        let range: RangeResponse = client
+            .get(format!("http://{}/api/meetings/range", addr))
+            .query(&[("from", now.to_rfc3339()), ("to", (now + Duration::minutes(15)).to_rfc3339())])
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let titles: Vec<&str> = range.meetings.iter().map(|m| m.title.as_str()).collect();
+        assert_eq!(titles, vec!["Google Standup"]);
+
+        let range: RangeResponse = client
+            .get(format!("http://{}/api/meetings/range", addr))
+            .query(&[("from", now.to_rfc3339()), ("to", (now + Duration::hours(4)).to_rfc3339())])
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let titles: Vec<&str> = range.meetings.iter().map(|m| m.title.as_str()).collect();
+        assert_eq!(titles, vec!["Google Standup", "ICS Planning"]);
+
+        for query in ["from=2025-06-02T10:00:00Z", "from=yesterday&to=2025-06-02T10:00:00Z", "from=2025-06-02T10:00:00Z&to=2025-06-02T09:00:00Z", "from=2025-01-01T00:00:00Z&to=2025-06-01T00:00:00Z"] {
+            let response = reqwest::get(format!("http://{}/api/meetings/range?{}", addr, query)).await.unwrap();
+            assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST, "{}", query);
+        }
+    }
+
+
+    #[tokio::test]
+    async fn test_api_next_returns_flat_current_or_next_meeting() {
+        let (_temp_dir, _google, addr) = setup().await;
+
+        let body: serde_json::Value = reqwest::get(format!("http://{}/api/next", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert!(body.as_object().unwrap().values().all(|v| !v.is_object() && !v.is_array()));
+
+        let next: NextMeeting = serde_json::from_value(body).unwrap();
+        assert_eq!(next.title.as_deref(), Some("Google Standup"));
+        assert_eq!(next.starts_in_seconds, Some(0));
+        assert!(next.ends_in_seconds.unwrap() > 0);
+        assert_eq!(next.location, None);
+        // Counts down to ICS Planning, the meeting after the current one
+        assert!(next.next_meeting_starts_in_seconds.unwrap() > 25 * 60);
+    }
+
+
+    #[tokio::test]
+    async fn test_api_status_text_summarizes_current_and_next() {
+        let (_temp_dir, _google, addr) = setup().await;
+
+        let response = reqwest::get(format!("http://{}/api/status.txt", addr)).await.unwrap();
+        assert!(response.headers()["content-type"].to_str().unwrap().starts_with("text/plain"));
+        let line = response.text().await.unwrap();
+        assert!(line.starts_with("In: Google Standup (ends "), "{}", line);
+        assert!(line.contains(" — Next: ICS Planning at "), "{}", line);
+        assert!(line.ends_with('\n') && line.lines().count() == 1);
+
+        let idle = MeetingUpdate {
+            version: API_VERSION,
+            current_meeting: None,
+            next_meeting: None,
+            countdown_seconds: None,
+            next_meeting_starts_in_seconds: None,
+            active_time_blocks: vec![],
+            upcoming_time_blocks: vec![],
+            all_day_events: vec![],
+            holiday: None,
+            agenda_today: vec![],
+            stale: false,
+            stale_since: None,
+            timezone: None,
+            last_updated: None,
+            source_errors: Vec::new(),
+            quiet: false,
+            countdown: CountdownConfig::default(),
+        };
+        assert_eq!(calendar_monitor::app::status_line(&idle), "Free");
+    }
+
+
+    #[tokio::test]
+    async fn test_api_deck_returns_compact_state() {
+        let (_temp_dir, _google, addr) = setup().await;
+
+        let deck: DeckState = reqwest::get(format!("http://{}/api/deck", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(deck.state, "busy");
+        assert_eq!(deck.title.as_deref(), Some("Google Standup"));
+        assert!((19..=20).contains(&deck.minutes.unwrap()), "{:?}", deck.minutes);
+        assert_eq!(deck.icon, "meeting");
+        assert_eq!(deck.join_url, None);
+
+        let now = Utc::now();
+        let mut update: MeetingUpdate = serde_json::from_value(json!({
+            "current_meeting": null,
+            "next_meeting": null,
+            "countdown_seconds": null,
+            "active_time_blocks": [],
+        }))
+        .unwrap();
+        let free = DeckState::from(&update);
+        assert_eq!((free.state.as_str(), free.icon.as_str(), free.title, free.minutes), ("free", "free", None, None));
+
+        update.next_meeting = Some(
+            Meeting::new("Quarterly planning with the platform team".to_string(), now + Duration::seconds(150), now + Duration::hours(1))
+                .with_location("https://meet.example.com/abc-defg".to_string()),
+        );
+        let soon = DeckState::from(&update);
+        assert_eq!(soon.state, "soon");
+        assert_eq!(soon.title.as_deref(), Some("Quarterly plann…"));
+        assert_eq!(soon.minutes, Some(3));
+        assert_eq!(soon.icon, "video");
+        assert_eq!(soon.join_url.as_deref(), Some("https://meet.example.com/abc-defg"));
+
+        // Later meetings are named, but not offered to join yet
+        update.countdown.warning_threshold_seconds = 60;
+        let later = DeckState::from(&update);
+        assert_eq!((later.state.as_str(), later.icon.as_str(), later.join_url), ("free", "free", None));
+        assert_eq!(later.minutes, Some(3));
+    }
+
+
+    #[tokio::test]
+    async fn test_overlay_page_and_line() {
+        let (_temp_dir, _google, addr) = setup().await;
+
+        let response = reqwest::get(format!("http://{}/overlay", addr)).await.unwrap();
+        assert!(response.headers()["content-type"].to_str().unwrap().starts_with("text/html"));
+        assert!(response.text().await.unwrap().contains("/api/v1/overlay"));
+
+        let line: OverlayLine = reqwest::get(format!("http://{}/api/v1/overlay", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert!(line.text.starts_with("Now: Google Standup, "), "{}", line.text);
+        assert!(line.text.ends_with(" left"), "{}", line.text);
+
+        let now = Utc::now();
+        let mut update: MeetingUpdate = serde_json::from_value(json!({
+            "current_meeting": null,
+            "next_meeting": null,
+            "countdown_seconds": null,
+            "active_time_blocks": [],
+        }))
+        .unwrap();
+        assert_eq!(calendar_monitor::app::overlay_line(&update), "");
+        update.next_meeting = Some(Meeting::new("Standup".to_string(), now + Duration::seconds(253), now + Duration::minutes(30)));
+        let text = calendar_monitor::app::overlay_line(&update);
+        assert!(["Next: Standup in 04:12", "Next: Standup in 04:13"].contains(&text.as_str()), "{}", text);
+    }
+
+
+    #[tokio::test]
+    async fn test_https_with_configured_certificate() {
+        let now = Utc::now();
+        let temp_dir = TempDir::new().unwrap();
+        let ics_path = write_upcoming_ics(&temp_dir, "ICS Planning", now + Duration::minutes(30), now + Duration::minutes(90));
+        let google = start_google_stub("Google Standup", now - Duration::minutes(10), now + Duration::minutes(20)).await;
+        let mut config = create_test_config(ics_path, &google.uri());
+        let fixture = |name: &str| format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+        config.server.tls_cert_file = Some(fixture("tls-cert.pem"));
+        config.server.tls_key_file = Some(fixture("tls-key.pem"));
+        config.validate().unwrap();
+        let mut half = config.clone();
+        half.server.tls_key_file = None;
+        assert!(half.validate().unwrap_err().to_string().contains("must be set together"));
+
+        let server = config.server.clone();
+        let app = build_router(AppState::new(Arc::new(config), Some(stub_tokens())));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { serve(listener, app, &server).await.unwrap() });
+
+        let cert = reqwest::Certificate::from_pem(&fs::read(fixture("tls-cert.pem")).unwrap()).unwrap();
+        let client = reqwest::Client::builder().add_root_certificate(cert).build().unwrap();
+        let next: NextMeeting = client
+            .get(format!("https://localhost:{}/api/next", addr.port()))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(next.title.as_deref(), Some("Google Standup"));
+
+        // Plain HTTP is not answered
+        assert!(reqwest::get(format!("http://{}/api/next", addr)).await.is_err());
+
+        let missing = ServerConfig {
+            tls_cert_file: Some(fixture("missing.pem")),
+            tls_key_file: Some(fixture("tls-key.pem")),
+            ..Default::default()
+        };
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let error = serve(listener, build_router(AppState::new(Arc::new(Config::default()), None)), &missing)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("missing.pem"), "{}", error);
+    }
+
+
+    #[tokio::test]
+    async fn test_badge_shows_busy_until_end_of_meeting() {
+        let (_temp_dir, _google, addr) = setup().await;
+
+        let response = reqwest::get(format!("http://{}/badge.svg?label=Room%201", addr)).await.unwrap();
+        assert_eq!(response.headers()["content-type"], "image/svg+xml");
+        let svg = response.text().await.unwrap();
+        assert!(svg.contains("<title>Room 1: busy until "), "{}", svg);
+    }
+
+
+    #[tokio::test]
+    async fn test_eink_png_at_requested_resolution() {
+        let (_temp_dir, _google, addr) = setup().await;
+
+        let response = reqwest::get(format!("http://{}/render/eink.png?width=600&height=448", addr)).await.unwrap();
+        assert_eq!(response.headers()["content-type"], "image/png");
+        let png = response.bytes().await.unwrap();
+        let reader = png::Decoder::new(png.as_ref()).read_info().unwrap();
+        assert_eq!((reader.info().width, reader.info().height), (600, 448));
+
+        let response = reqwest::get(format!("http://{}/render/eink.png?width=100000", addr)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    }
+
+
+    #[tokio::test]
+    async fn test_calendar_ics_exports_merged_view_with_token() {
+        let now = Utc::now();
+        let temp_dir = TempDir::new().unwrap();
+        let ics_path = write_upcoming_ics(&temp_dir, "ICS Planning", now + Duration::minutes(30), now + Duration::minutes(90));
+        let google = start_google_stub("Google Standup", now - Duration::minutes(10), now + Duration::minutes(20)).await;
+        let mut config = create_test_config(ics_path, &google.uri());
+        config.export.token = Some("s3cret-export-token".to_string());
+        let addr = spawn_app(config).await;
+
+        let response = reqwest::get(format!("http://{}/calendar.ics?token=s3cret-export-token", addr)).await.unwrap();
+        assert!(response.headers()["content-type"].to_str().unwrap().starts_with("text/calendar"));
+        let ics = response.text().await.unwrap();
+        assert!(ics.contains("SUMMARY:Google Standup\r\n"));
+        assert!(ics.contains("SUMMARY:ICS Planning\r\n"));
+
+        let response = reqwest::get(format!("http://{}/calendar.ics?token=guess", addr)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        // Disabled without a configured token
+        let (_temp_dir, _google, addr) = setup().await;
+        let response = reqwest::get(format!("http://{}/calendar.ics", addr)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+    }
+
+
+    #[tokio::test]
+    async fn test_api_meetings_reports_time_block_progress() {
+        let now = Utc::now();
+        let temp_dir = TempDir::new().unwrap();
+        let ics_path = write_upcoming_ics(&temp_dir, "ICS Planning", now + Duration::minutes(30), now + Duration::minutes(90));
+        let google = start_google_stub("Google Standup", now - Duration::minutes(10), now + Duration::minutes(20)).await;
+        let blocks_path = temp_dir.path().join("blocks.ics");
+        let event = |uid: &str, title: &str, start: DateTime<Utc>, end: DateTime<Utc>| format!(
+            "BEGIN:VEVENT\r\nUID:{}\r\nSUMMARY:{}\r\nDTSTART:{}\r\nDTEND:{}\r\nEND:VEVENT\r\n",
+            uid, title, ics_timestamp(start), ics_timestamp(end)
+        );
+        fs::write(&blocks_path, format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{}{}END:VCALENDAR\r\n",
+            event("focus@test", "[Focus]", now - Duration::minutes(30), now + Duration::minutes(90)),
+            event("deep-work@test", "[Deep Work]", now + Duration::minutes(120), now + Duration::minutes(180)),
+        )).unwrap();
+        let mut config = create_test_config(ics_path, &google.uri());
+        config.ics.file_paths.push(blocks_path.to_string_lossy().to_string());
+        let addr = spawn_app(config).await;
+
+        let update: MeetingUpdate = reqwest::get(format!("http://{}/api/v1/meetings", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(update.current_meeting.unwrap().title, "Google Standup");
+        assert_eq!(update.active_time_blocks.len(), 1);
+        assert_eq!(update.active_time_blocks[0].meeting.title, "[Focus]");
+        assert!((24..=26).contains(&update.active_time_blocks[0].progress_percent));
+        let upcoming: Vec<&str> = update.upcoming_time_blocks.iter().map(|m| m.title.as_str()).collect();
+        assert_eq!(upcoming, ["[Deep Work]"]);
+        assert!(update.agenda_today.iter().any(|m| m.title == "[Focus]"));
+    }
+
+    #[tokio::test]
+    async fn test_auth_token_protects_api_and_websocket() {
+        let now = Utc::now();
+        let temp_dir = TempDir::new().unwrap();
+        let ics_path = write_upcoming_ics(&temp_dir, "ICS Planning", now + Duration::minutes(30), now + Duration::minutes(90));
+        let google = start_google_stub("Google Standup", now - Duration::minutes(10), now + Duration::minutes(20)).await;
+        let mut config = create_test_config(ics_path, &google.uri());
+        config.server.auth_token = Some("s3cret-display-token".to_string());
+        let addr = spawn_app(config).await;
+        let client = reqwest::Client::new();
+
+        for path in ["/api/v1/meetings", "/api/meetings", "/events", "/badge.svg", "/auth/google/login", "/auth/google/device"] {
+            let response = client.get(format!("http://{}{}", addr, path)).send().await.unwrap();
+            assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED, "{}", path);
+            assert_eq!(response.headers()["www-authenticate"], "Bearer");
+        }
+        for path in ["/auth/google/device", "/auth/google/logout"] {
+            let response = client.post(format!("http://{}{}", addr, path)).send().await.unwrap();
+            assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED, "{}", path);
+        }
+        let page = client
+            .get(format!("http://{}/auth/google/logout?token=s3cret-display-token", addr))
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert!(page.contains("action='/auth/google/logout?token=s3cret-display-token'"), "{}", page);
+        let response = client
+            .get(format!("http://{}/api/v1/meetings", addr))
+            .bearer_auth("guess")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let update: MeetingUpdate = client
+            .get(format!("http://{}/api/v1/meetings", addr))
+            .bearer_auth("s3cret-display-token")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_merged_payload(&update);
+
+        // The page itself is public; it passes the token on
+        let response = client.get(format!("http://{}/", addr)).send().await.unwrap();
+        assert!(response.status().is_success());
+
+        assert!(tokio_tungstenite::connect_async(format!("ws://{}/ws", addr)).await.is_err());
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws?token=s3cret-display-token", addr))
+            .await
+            .expect("WebSocket connection failed");
+        let message = tokio::time::timeout(std::time::Duration::from_secs(10), socket.next())
+            .await
+            .expect("Timed out waiting for the first update")
+            .unwrap()
+            .unwrap();
+        assert_merged_payload(&serde_json::from_str(message.to_text().unwrap()).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_basic_auth_protects_dashboard_and_api() {
+        let now = Utc::now();
+        let temp_dir = TempDir::new().unwrap();
+        let ics_path = write_upcoming_ics(&temp_dir, "ICS Planning", now + Duration::minutes(30), now + Duration::minutes(90));
+        let google = start_google_stub("Google Standup", now - Duration::minutes(10), now + Duration::minutes(20)).await;
+        let mut config = create_test_config(ics_path, &google.uri());
+        config.server.auth_token = Some("s3cret-display-token".to_string());
+        config.server.auth_username = Some("family".to_string());
+        config.server.auth_password = Some("kitchen-display-pw".to_string());
+        let addr = spawn_app(config).await;
+        let client = reqwest::Client::new();
+
+        for path in ["/", "/overlay", "/api/v1/meetings", "/events", "/auth/google/login"] {
+            let response = client.get(format!("http://{}{}", addr, path)).send().await.unwrap();
+            assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED, "{}", path);
+            assert!(response.headers()["www-authenticate"].to_str().unwrap().starts_with("Basic realm="));
+        }
+        let response = client
+            .get(format!("http://{}/", addr))
+            .basic_auth("family", Some("wrong-password"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let response = client
+            .get(format!("http://{}/", addr))
+            .basic_auth("family", Some("kitchen-display-pw"))
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+        let update: MeetingUpdate = client
+            .get(format!("http://{}/api/v1/meetings", addr))
+            .basic_auth("family", Some("kitchen-display-pw"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_merged_payload(&update);
+
+        // The token still opens everything, for displays that can't log in
+        let response = client.get(format!("http://{}/?token=s3cret-display-token", addr)).send().await.unwrap();
+        assert!(response.status().is_success());
+        let response = client
+            .get(format!("http://{}/api/v1/meetings", addr))
+            .bearer_auth("s3cret-display-token")
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+
+        // Static assets stay public so the login prompt isn't repeated for them
+        let response = client.get(format!("http://{}/static/style.css", addr)).send().await.unwrap();
+        assert!(response.status().is_success());
+    }
+
+    #[tokio::test]
+    async fn test_export_csv_lists_meetings_in_range() {
+        let (_temp_dir, _google, addr) = setup().await;
+        let today = chrono::Local::now().date_naive();
+        let tomorrow = today.succ_opt().unwrap();
+
+        let response = reqwest::get(format!("http://{}/api/export.csv?from={}&to={}", addr, today, tomorrow)).await.unwrap();
+        assert!(response.headers()["content-type"].to_str().unwrap().starts_with("text/csv"));
+        assert!(response.headers()["content-disposition"].to_str().unwrap().contains("agenda.csv"));
+        let csv = response.text().await.unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("title,start,end,location,source,response_status"));
+        assert!(csv.contains("Google Standup,"));
+        assert!(csv.lines().any(|line| line.starts_with("Google Standup,") && line.ends_with(",Google Calendar,accepted")));
+
+        let response = reqwest::get(format!("http://{}/api/export.csv?from={}", addr, today)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    }
+
+
+    #[tokio::test]
+    async fn test_api_search_matches_cached_meetings() {
+        let (_temp_dir, _google, addr) = setup().await;
+
+        let search: SearchResponse = reqwest::get(format!("http://{}/api/search?q=planning", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let titles: Vec<&str> = search.meetings.iter().map(|m| m.title.as_str()).collect();
+        assert_eq!(titles, vec!["ICS Planning"]);
+
+        let search: SearchResponse = reqwest::get(format!("http://{}/api/search?q=o", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let titles: Vec<&str> = search.meetings.iter().map(|m| m.title.as_str()).collect();
+        assert_eq!(titles, vec!["Google Standup"]);
+
+        let response = reqwest::get(format!("http://{}/api/search?q=%20", addr)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    }
+
+
+    #[tokio::test]
+    async fn test_api_meeting_details_by_id() {
+        let (_temp_dir, _google, addr) = setup().await;
+        let update: MeetingUpdate = reqwest::get(format!("http://{}/api/meetings", addr)).await.unwrap().json().await.unwrap();
+        let current = update.current_meeting.unwrap();
+        assert!(!current.id.is_empty());
+
+        let meeting: calendar_monitor::meeting::Meeting = reqwest::get(format!("http://{}/api/meetings/{}", addr, current.id))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(meeting.title, "Google Standup");
+        assert_eq!(meeting.attendees, current.attendees);
+
+        let response = reqwest::get(format!("http://{}/api/meetings/does-not-exist", addr)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+        // Static routes still win over the id
+        let response = reqwest::get(format!("http://{}/api/meetings/range?from=2025-06-02", addr)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    }
+
+
+    #[tokio::test]
+    async fn test_graphql_returns_only_requested_fields() {
+        let (_temp_dir, _google, addr) = setup().await;
+        let client = reqwest::Client::new();
+
+        let query = "{ meetings { title kind source { id } } currentMeeting { title } freeBusy { busy busyUntil } }";
+        let body: serde_json::Value = client
+            .post(format!("http://{}/graphql", addr))
+            .json(&serde_json::json!({ "query": query }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert!(body.get("errors").is_none(), "{}", body);
+
+        let data = &body["data"];
+        let titles: Vec<&str> = data["meetings"].as_array().unwrap().iter().map(|m| m["title"].as_str().unwrap()).collect();
+        assert_eq!(titles, vec!["Google Standup", "ICS Planning"]);
+        assert_eq!(data["meetings"][0]["kind"], "MEETING");
+        assert_eq!(data["meetings"][0].as_object().unwrap().len(), 3);
+        assert_eq!(data["currentMeeting"]["title"], "Google Standup");
+        assert_eq!(data["freeBusy"]["busy"], true);
+
+        // Top-level fields resolve concurrently, so sources are read once the
+        // query above has fetched them
+        let body: serde_json::Value = client
+            .post(format!("http://{}/graphql", addr))
+            .json(&serde_json::json!({ "query": "{ sources { healthy eventCount } }" }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(body["data"]["sources"], serde_json::json!([{ "healthy": true, "eventCount": 1 }]));
+
+        let body: serde_json::Value = client
+            .post(format!("http://{}/graphql", addr))
+            .json(&serde_json::json!({ "query": "{ freeBusy { freeSlots(duration: \"soon\") { start } } }" }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert!(body["errors"][0]["message"].as_str().unwrap().contains("Invalid duration"), "{}", body);
+    }
+
+
+    #[tokio::test]
+    async fn test_api_config_redacts_secrets() {
+        let (_temp_dir, _google, addr) = setup().await;
+
+        let response = reqwest::get(format!("http://{}/api/v1/config", addr)).await.unwrap();
+        let body = response.text().await.unwrap();
+        assert!(!body.contains("stub_client_secret"), "{}", body);
+
+        let config: ConfigResponse = serde_json::from_str(&body).unwrap();
+        assert_eq!(config.config.google.client_secret.as_deref(), Some(REDACTED));
+        assert_eq!(config.config.google.client_id.as_deref(), Some("stub_client_id"));
+        assert!(config.config.ics.file_paths[0].ends_with("calendar.ics"));
+        assert_eq!(config.config_file, None);
+    }
+
+
+    #[tokio::test]
+    async fn test_admin_reload_switches_to_edited_config() {
+        let now = Utc::now();
+        let first_dir = TempDir::new().expect("Failed to create temp dir");
+        let second_dir = TempDir::new().expect("Failed to create temp dir");
+        let first_ics = write_upcoming_ics(&first_dir, "Before Reload", now + Duration::minutes(30), now + Duration::minutes(60));
+        let second_ics = write_upcoming_ics(&second_dir, "After Reload", now + Duration::minutes(30), now + Duration::minutes(60));
+        let config_path = first_dir.path().join("calendar-monitor.toml");
+        let write_config = |ics_path: &str| {
+            let contents = format!("[server]\nhost = \"127.0.0.1\"\nport = 0\ncache_ttl_seconds = 300\n\n[ics]\nfile_paths = [{:?}]\n\n[google]\n", ics_path);
+            fs::write(&config_path, contents).expect("Failed to write config file");
+        };
+
+        write_config(&first_ics);
+        let config = Config::reload(&config_path).expect("Invalid config");
+        let state = AppState::new(Arc::new(config), None).with_config_file(Some(config_path.clone()));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, build_router(state)).await.unwrap();
+        });
+
+        let next_title = || async {
+            let update: MeetingUpdate = reqwest::get(format!("http://{}/api/meetings", addr))
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            update.next_meeting.map(|m| m.title)
+        };
+        assert_eq!(next_title().await.as_deref(), Some("Before Reload"));
+
+        write_config(&second_ics);
+        let client = reqwest::Client::new();
+        let response = client.post(format!("http://{}/api/v1/admin/reload", addr)).send().await.unwrap();
+        assert!(response.status().is_success());
+        let reload: ReloadResponse = response.json().await.unwrap();
+        assert_eq!(reload.config_file, config_path);
+        assert!(reload.restart_required.is_empty());
+        assert_eq!(next_title().await.as_deref(), Some("After Reload"));
+
+        // A broken file is rejected and the running configuration kept
+        fs::write(&config_path, "[server]\nport = \"not a number\"\n").unwrap();
+        let response = client.post(format!("http://{}/api/v1/admin/reload", addr)).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+        assert_eq!(next_title().await.as_deref(), Some("After Reload"));
+    }
+
+
+    #[tokio::test]
+    async fn test_status_override_replaces_current_meeting() {
+        let (_temp_dir, _google, addr) = setup().await;
+        let client = reqwest::Client::new();
+        let url = format!("http://{}/api/v1/override", addr);
+        let current = || async {
+            let update: MeetingUpdate = reqwest::get(format!("http://{}/api/meetings", addr))
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            update.current_meeting
+        };
+        let until = (Utc::now() + Duration::hours(1)).to_rfc3339();
+
+        let response = client.post(&url).json(&json!({ "status": "free", "until": until })).send().await.unwrap();
+        assert!(response.status().is_success());
+        assert!(current().await.is_none());
+        let active: Option<StatusOverride> = reqwest::get(&url).await.unwrap().json().await.unwrap();
+        assert_eq!(active.unwrap().status, OverrideStatus::Free);
+
+        client.post(&url).json(&json!({ "status": "busy", "until": until, "title": "Phone call" })).send().await.unwrap();
+        let meeting = current().await.expect("Busy override shows a current meeting");
+        assert_eq!(meeting.title, "Phone call");
+        assert_eq!(meeting.source.unwrap().id, "override");
+
+        let response = client.delete(&url).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NO_CONTENT);
+        assert_eq!(current().await.unwrap().title, "Google Standup");
+        let active: Option<StatusOverride> = reqwest::get(&url).await.unwrap().json().await.unwrap();
+        assert!(active.is_none());
+
+        let past = (Utc::now() - Duration::minutes(5)).to_rfc3339();
+        let response = client.post(&url).json(&json!({ "status": "busy", "until": past })).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    }
+
+
+    #[tokio::test]
+    async fn test_created_meeting_counts_as_current() {
+        let (_temp_dir, _google, addr) = setup().await;
+        let client = reqwest::Client::new();
+        let now = Utc::now();
+
+        let response = client
+            .post(format!("http://{}/api/v1/meetings", addr))
+            .json(&json!({
+                "title": "Walk-in booking",
+                "start": (now + Duration::minutes(10)).to_rfc3339(),
+                "end": (now + Duration::minutes(20)).to_rfc3339(),
+                "location": "Room 2",
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::CREATED);
+        let created: Meeting = response.json().await.unwrap();
+        assert_eq!(created.location.as_deref(), Some("Room 2"));
+
+        let update: MeetingUpdate = reqwest::get(format!("http://{}/api/meetings", addr)).await.unwrap().json().await.unwrap();
+        assert_eq!(update.next_meeting.unwrap().id, created.id);
+
+        let response = client.delete(format!("http://{}/api/v1/meetings/{}", addr, created.id)).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NO_CONTENT);
+        let update: MeetingUpdate = reqwest::get(format!("http://{}/api/meetings", addr)).await.unwrap().json().await.unwrap();
+        assert_eq!(update.next_meeting.unwrap().title, "ICS Planning");
+
+        let response = client
+            .post(format!("http://{}/api/v1/meetings", addr))
+            .json(&json!({ "title": "Backwards", "start": now.to_rfc3339(), "end": (now - Duration::minutes(5)).to_rfc3339() }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    }
+
+
+    #[tokio::test]
+    async fn test_hidden_meeting_is_left_off_the_display() {
+        let (_temp_dir, _google, addr) = setup().await;
+        let client = reqwest::Client::new();
+        let update = || async {
+            let update: MeetingUpdate = reqwest::get(format!("http://{}/api/meetings", addr)).await.unwrap().json().await.unwrap();
+            update
+        };
+        let standup = update().await.current_meeting.expect("Google Standup is in progress");
+
+        let response = client.post(format!("http://{}/api/v1/meetings/{}/hide", addr, standup.id)).send().await.unwrap();
+        assert!(response.status().is_success());
+        assert!(update().await.current_meeting.is_none());
+        let hidden: HiddenResponse = reqwest::get(format!("http://{}/api/v1/hidden", addr)).await.unwrap().json().await.unwrap();
+        assert_eq!(hidden.hidden.len(), 1);
+        assert_eq!(hidden.hidden[0].until, None);
+
+        let response = client.delete(format!("http://{}/api/v1/meetings/{}/hide", addr, standup.id)).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NO_CONTENT);
+        assert_eq!(update().await.current_meeting.unwrap().id, standup.id);
+
+        let response = client
+            .post(format!("http://{}/api/v1/meetings/{}/hide", addr, standup.id))
+            .json(&json!({ "duration": "1h" }))
+            .send()
+            .await
+            .unwrap();
+        let snoozed: HiddenMeeting = response.json().await.unwrap();
+        assert!(snoozed.until.unwrap() > Utc::now() + Duration::minutes(59));
+
+        let response = client
+            .post(format!("http://{}/api/v1/meetings/{}/hide", addr, standup.id))
+            .json(&json!({ "duration": "a while" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+        let response = client.post(format!("http://{}/api/v1/meetings/unknown/hide", addr)).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+    }
+
+
+    #[tokio::test]
+    async fn test_private_sources_are_redacted() {
+        let now = Utc::now();
+        let temp_dir = TempDir::new().unwrap();
+        let ics_path = write_upcoming_ics(&temp_dir, "ICS Planning", now + Duration::minutes(30), now + Duration::minutes(90));
+        let google = start_google_stub("Google Standup", now - Duration::minutes(10), now + Duration::minutes(20)).await;
+        let mut config = create_test_config(ics_path, &google.uri());
+        config.google.private = true;
+        config.privacy.title = "Do not disturb".to_string();
+        let addr = spawn_app(config).await;
+
+        let update: MeetingUpdate = reqwest::get(format!("http://{}/api/v1/meetings", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let current = update.current_meeting.unwrap();
+        assert_eq!(current.title, "Do not disturb");
+        assert!(current.attendees.is_empty() && current.description.is_none() && current.uid.is_none());
+        assert!(current.source.unwrap().private);
+        // Other sources are shown as they are
+        assert_eq!(update.next_meeting.unwrap().title, "ICS Planning");
+
+        // Nor can the title be found through the other endpoints
+        for endpoint in ["agenda", "export.csv", "search?q=standup"] {
+            let body = reqwest::get(format!("http://{}/api/v1/{}", addr, endpoint)).await.unwrap().text().await.unwrap();
+            assert!(!body.contains("Standup"), "{}: {}", endpoint, body);
+        }
+    }
+
+
+    #[tokio::test]
+    async fn test_countdown_thresholds_come_from_config() {
+        let now = Utc::now();
+        let temp_dir = TempDir::new().unwrap();
+        let ics_path = write_upcoming_ics(&temp_dir, "ICS Planning", now + Duration::minutes(30), now + Duration::minutes(90));
+        let google = start_google_stub("Google Standup", now - Duration::minutes(10), now + Duration::minutes(20)).await;
+        let mut config = create_test_config(ics_path, &google.uri());
+        config.countdown = CountdownConfig { warning_threshold_seconds: 600, critical_threshold_seconds: 120 };
+        let addr = spawn_app(config).await;
+
+        let update: serde_json::Value = reqwest::get(format!("http://{}/api/v1/meetings", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(update["countdown"], json!({ "warning_threshold_seconds": 600, "critical_threshold_seconds": 120 }));
+    }
+
+
+    #[tokio::test]
+    async fn test_api_display_config_serves_display_settings() {
+        let now = Utc::now();
+        let temp_dir = TempDir::new().unwrap();
+        let ics_path = write_upcoming_ics(&temp_dir, "ICS Planning", now + Duration::minutes(30), now + Duration::minutes(90));
+        let google = start_google_stub("Google Standup", now - Duration::minutes(10), now + Duration::minutes(20)).await;
+        let mut config = create_test_config(ics_path, &google.uri());
+        config.display = DisplayConfig {
+            clock_24h: false,
+            locale: "de-DE".to_string(),
+            theme: "dark".to_string(),
+            show_descriptions: true,
+            show_locations: false,
+        };
+        assert!(config.validate().is_ok());
+        let addr = spawn_app(config.clone()).await;
+
+        let display: DisplayConfig = reqwest::get(format!("http://{}/api/v1/display-config", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(display, config.display);
+
+        config.display.theme = "dark mode".to_string();
+        let error = config.validate().unwrap_err().to_string();
+        assert!(error.contains("display.theme"), "{}", error);
+    }
+
+
+    #[tokio::test]
+    async fn test_profiles_filter_and_redact_updates() {
+        let now = Utc::now();
+        let temp_dir = TempDir::new().unwrap();
+        let ics_path = write_upcoming_ics(&temp_dir, "ICS Planning", now + Duration::minutes(30), now + Duration::minutes(90));
+        let google = start_google_stub("Google Standup", now - Duration::minutes(10), now + Duration::minutes(20)).await;
+        let mut config = create_test_config(ics_path, &google.uri());
+        config.profile.insert(
+            "lobby".to_string(),
+            ProfileConfig { privacy: PrivacyLevel::BusyFree, ..Default::default() },
+        );
+        config.profile.insert(
+            "team".to_string(),
+            ProfileConfig { exclude: vec!["Standup".to_string()], ..Default::default() },
+        );
+        let addr = spawn_app(config).await;
+
+        let lobby: MeetingUpdate = reqwest::get(format!("http://{}/api/v1/meetings?profile=lobby", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let current = lobby.current_meeting.expect("busy/free still shows the current meeting");
+        assert_eq!(current.title, "Busy");
+        assert!(current.source.is_none());
+        assert_eq!(lobby.next_meeting.unwrap().title, "Busy");
+        assert!(lobby.agenda_today.is_empty());
+
+        let team: MeetingUpdate = reqwest::get(format!("http://{}/api/v1/meetings?profile=team", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert!(team.current_meeting.is_none());
+        assert_eq!(team.next_meeting.unwrap().title, "ICS Planning");
+        assert!(team.agenda_today.iter().all(|m| m.title != "Google Standup"));
+
+        let response = reqwest::get(format!("http://{}/api/v1/meetings?profile=kiosk", addr)).await.unwrap();
+        assert_eq!(response.status(), 400);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["error"], "Unknown profile 'kiosk'");
+    }
+}
@@ -0,0 +1,128 @@
+use calendar_monitor::config::{IssueProvider, IssueSourceConfig};
+use calendar_monitor::issues::IssueSource;
+use calendar_monitor::recurrence::Window;
+use calendar_monitor::source::CalendarSource;
+use chrono::{Duration, Local, Utc};
+use serde_json::json;
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source_config(provider: IssueProvider, repository: &str, api_url: &str) -> IssueSourceConfig {
+        IssueSourceConfig {
+            provider,
+            repository: repository.to_string(),
+            token: Some("secret-token".to_string()),
+            api_url: Some(api_url.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_github_milestones_become_all_day_events() {
+        let server = MockServer::start().await;
+        let today = Local::now().date_naive();
+        Mock::given(method("GET"))
+            .and(path("/repos/acme/widgets/milestones"))
+            .and(query_param("state", "open"))
+            .and(header("authorization", "Bearer secret-token"))
+            .and(header("user-agent", "calendar-monitor"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {
+                    "number": 3,
+                    "title": "v1.0",
+                    "html_url": "https://github.com/acme/widgets/milestone/3",
+                    "due_on": format!("{}T07:00:00Z", today)
+                },
+                {
+                    "number": 4,
+                    "title": "v2.0",
+                    "html_url": "https://github.com/acme/widgets/milestone/4",
+                    "due_on": (Utc::now() + Duration::days(30)).to_rfc3339()
+                },
+                { "number": 5, "title": "Someday", "html_url": "https://github.com/acme/widgets/milestone/5", "due_on": null }
+            ])))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let source = IssueSource::new(reqwest::Client::new(), source_config(IssueProvider::GitHub, "acme/widgets", &server.uri()));
+        let items = source.fetch_due_items().await.unwrap();
+        assert_eq!(items.len(), 2);
+
+        // Only what is due in the window; the events are then cached, so
+        // the repository is asked once more in total
+        let window = Window::today_and_tomorrow();
+        let meetings = source.fetch_events(&window).await.unwrap();
+        source.fetch_events(&window).await.unwrap();
+        server.verify().await;
+
+        assert_eq!(meetings.len(), 1);
+        assert_eq!(meetings[0].title, "Milestone: v1.0");
+        assert!(meetings[0].all_day);
+        assert_eq!(meetings[0].description.as_deref(), Some("https://github.com/acme/widgets/milestone/3"));
+        assert_eq!(meetings[0].source.as_ref().unwrap().id, "acme/widgets");
+    }
+
+    #[tokio::test]
+    async fn test_gitlab_issues_and_milestones_with_due_dates() {
+        let server = MockServer::start().await;
+        let today = Local::now().date_naive();
+        Mock::given(method("GET"))
+            .and(path("/projects/acme%2Fplatform%2Fapi/milestones"))
+            .and(header("private-token", "secret-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                { "id": 1, "title": "Sprint 12", "due_date": today, "web_url": "https://gitlab.com/acme/platform/api/-/milestones/1" }
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/projects/acme%2Fplatform%2Fapi/issues"))
+            .and(query_param("due_date", "any"))
+            .and(header("private-token", "secret-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                { "iid": 42, "title": "Renew certificates", "due_date": today, "web_url": "https://gitlab.com/acme/platform/api/-/issues/42" },
+                { "iid": 43, "title": "No deadline", "due_date": null, "web_url": "https://gitlab.com/acme/platform/api/-/issues/43" }
+            ])))
+            .mount(&server)
+            .await;
+
+        let source = IssueSource::new(reqwest::Client::new(), source_config(IssueProvider::GitLab, "acme/platform/api", &server.uri()));
+        let meetings = source.fetch_events(&Window::today_and_tomorrow()).await.unwrap();
+
+        let titles: Vec<_> = meetings.iter().map(|m| m.title.as_str()).collect();
+        assert_eq!(titles, vec!["Milestone: Sprint 12", "#42 Renew certificates"]);
+    }
+
+    #[tokio::test]
+    async fn test_issue_source_failure_yields_no_events() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let source = IssueSource::new(reqwest::Client::new(), source_config(IssueProvider::GitHub, "acme/private", &server.uri()));
+        assert!(source.fetch_due_items().await.unwrap_err().to_string().contains("401"));
+        assert!(source.fetch_events(&Window::today_and_tomorrow()).await.unwrap().is_empty());
+        assert!(source.failure().await.unwrap().error.contains("401"));
+    }
+
+    #[test]
+    fn test_issue_source_validation() {
+        assert!(source_config(IssueProvider::GitHub, "acme/widgets", "https://api.github.com").validate().is_ok());
+        assert!(source_config(IssueProvider::GitLab, "acme/platform/api", "https://gitlab.example.com/api/v4").validate().is_ok());
+
+        // GitHub has no nested groups
+        assert!(source_config(IssueProvider::GitHub, "acme/platform/api", "https://api.github.com").validate().is_err());
+        assert!(source_config(IssueProvider::GitHub, "widgets", "https://api.github.com").validate().is_err());
+        assert!(source_config(IssueProvider::GitLab, "acme/", "https://gitlab.com/api/v4").validate().is_err());
+        assert!(source_config(IssueProvider::GitLab, "acme/api", "gitlab.example.com").validate().is_err());
+
+        let parsed: IssueSourceConfig = toml::from_str("provider = \"gitlab\"\nrepository = \"acme/api\"").unwrap();
+        assert_eq!(parsed.provider, IssueProvider::GitLab);
+    }
+}
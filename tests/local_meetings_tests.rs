@@ -0,0 +1,55 @@
+use calendar_monitor::local_meetings::LocalMeetingStore;
+use calendar_monitor::meeting::Meeting;
+use calendar_monitor::recurrence::Window;
+use calendar_monitor::source::CalendarSource;
+use chrono::{Duration, Utc};
+use tempfile::TempDir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_local_meetings_survive_restart() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("local_meetings.json");
+        let now = Utc::now();
+
+        let store = LocalMeetingStore::open(path.clone());
+        let walk_in = store
+            .add(Meeting::new("Walk-in".to_string(), now + Duration::minutes(5), now + Duration::minutes(35)))
+            .await
+            .unwrap();
+        assert_eq!(walk_in.source.as_ref().unwrap().id, "local");
+        store
+            .add(Meeting::new("Last week".to_string(), now - Duration::days(10), now - Duration::days(10) + Duration::hours(1)))
+            .await
+            .unwrap();
+        // Adding drops meetings that ended over a week ago
+        store
+            .add(Meeting::new("Later".to_string(), now + Duration::hours(2), now + Duration::hours(3)))
+            .await
+            .unwrap();
+
+        let reopened = LocalMeetingStore::open(path.clone());
+        let titles: Vec<String> = reopened.meetings().await.into_iter().map(|m| m.title).collect();
+        assert_eq!(titles, vec!["Walk-in", "Later"]);
+
+        let today = reopened.fetch_events(&Window::today_and_tomorrow()).await.unwrap();
+        assert!(today.iter().any(|m| m.id == walk_in.id));
+
+        assert!(reopened.remove(&walk_in.id).await.unwrap());
+        assert!(!reopened.remove(&walk_in.id).await.unwrap());
+        assert_eq!(LocalMeetingStore::open(path).meetings().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unreadable_local_meetings_start_empty() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("local_meetings.json");
+        std::fs::write(&path, "not json").unwrap();
+
+        assert!(LocalMeetingStore::open(path).meetings().await.is_empty());
+        assert!(LocalMeetingStore::in_memory().meetings().await.is_empty());
+    }
+}
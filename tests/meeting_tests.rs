@@ -1,4 +1,5 @@
-use calendar_monitor::meeting::{Meeting, ResponseStatus};
+use calendar_monitor::meeting::{highest_priority, merge_meetings, Meeting, MeetingSource, ResponseStatus};
+use calendar_monitor::config::PrivacyConfig;
 use chrono::Utc;
 
 #[cfg(test)]
@@ -235,4 +236,214 @@ mod tests {
         );
         assert_eq!(no_status.response_status_label(), None);
     }
+
+    #[test]
+    fn test_cross_source_duplicate_detection() {
+        let now = Utc::now();
+        let start = now + chrono::Duration::hours(1);
+        let end = now + chrono::Duration::hours(2);
+
+        // Same UID and start time -> duplicate, even if titles differ slightly
+        let google = Meeting::new("Planning".to_string(), start, end).with_uid("abc@google.com".to_string());
+        let ics = Meeting::new("Planning (team)".to_string(), start, end).with_uid("abc@google.com".to_string());
+        assert!(google.is_duplicate_of(&ics));
+
+        // Different UIDs -> distinct events, even with the same title
+        let other = Meeting::new("Planning".to_string(), start, end).with_uid("xyz@example.com".to_string());
+        assert!(!google.is_duplicate_of(&other));
+
+        // Missing UID falls back to case-insensitive title + start time
+        let no_uid = Meeting::new(" planning ".to_string(), start, end);
+        assert!(google.is_duplicate_of(&no_uid));
+
+        // Recurring instances share a UID but start at different times
+        let next_day = Meeting::new("Planning".to_string(), start + chrono::Duration::days(1), end + chrono::Duration::days(1))
+            .with_uid("abc@google.com".to_string());
+        assert!(!google.is_duplicate_of(&next_day));
+    }
+
+    #[test]
+    fn test_merge_meetings_prefers_earlier_source() {
+        let now = Utc::now();
+        let start = now + chrono::Duration::hours(1);
+        let end = now + chrono::Duration::hours(2);
+
+        let google = vec![
+            Meeting::new("Planning".to_string(), start, end)
+                .with_uid("abc@google.com".to_string())
+                .with_response_status(ResponseStatus::Tentative),
+        ];
+        let ics = vec![
+            Meeting::new("Lunch".to_string(), now + chrono::Duration::minutes(30), now + chrono::Duration::minutes(45)),
+            Meeting::new("Planning".to_string(), start, end).with_uid("abc@google.com".to_string()),
+        ];
+
+        let merged = merge_meetings(vec![google, ics]);
+
+        assert_eq!(merged.len(), 2);
+        // Sorted by start time
+        assert_eq!(merged[0].title, "Lunch");
+        // Google copy kept (it carries the response status)
+        assert_eq!(merged[1].title, "Planning");
+        assert_eq!(merged[1].response_status, Some(ResponseStatus::Tentative));
+    }
+
+
+    #[test]
+    fn test_meeting_source_from_ics_path() {
+        let source = MeetingSource::from_ics_path("https://calendar.example.com/feeds/team.ics?key=secret");
+        assert_eq!(source.id, "team");
+        assert_eq!(source.name, "team");
+        assert_eq!(source.color, None);
+
+        assert_eq!(MeetingSource::from_ics_path("./calendars/personal.ics").id, "personal");
+        assert_eq!(MeetingSource::from_ics_path("https://calendar.example.com/").id, "calendar.example.com");
+
+        let now = Utc::now();
+        let meeting = Meeting::new("Standup".to_string(), now, now + chrono::Duration::minutes(15))
+            .with_source(MeetingSource::google());
+        let json = serde_json::to_value(&meeting).unwrap();
+        assert_eq!(json["source"]["id"], "google");
+        assert_eq!(json["source"]["name"], "Google Calendar");
+    }
+
+
+    #[test]
+    fn test_all_day_meeting() {
+        let day = chrono::NaiveDate::from_ymd_opt(2025, 12, 24).unwrap();
+        let meeting = Meeting::new_all_day("Christmas Eve".to_string(), day, day.succ_opt().unwrap());
+
+        assert!(meeting.all_day);
+        assert_eq!(meeting.start_time.with_timezone(&chrono::Local).date_naive(), day);
+        assert!(!Meeting::new("Standup".to_string(), meeting.start_time, meeting.end_time).all_day);
+
+        let json = serde_json::to_value(&meeting).unwrap();
+        assert_eq!(json["all_day"], true);
+    }
+
+
+    #[test]
+    fn test_meeting_matches_query() {
+        let now = Utc::now();
+        let meeting = Meeting::new("Sprint Review".to_string(), now, now)
+            .with_description("Demo of the new Dashboard".to_string())
+            .with_location("Room Ankara".to_string());
+
+        assert!(meeting.matches("sprint"));
+        assert!(meeting.matches("DASHBOARD"));
+        assert!(meeting.matches("ankara"));
+        assert!(!meeting.matches("retro"));
+
+        // Missing description and location just don't match
+        assert!(!Meeting::new("Lunch".to_string(), now, now).matches("room"));
+    }
+
+    #[test]
+    fn test_meeting_url() {
+        let now = Utc::now();
+        let meeting = Meeting::new("Sync".to_string(), now, now)
+            .with_location("Room 4".to_string())
+            .with_description("Join: <https://meet.example.com/abc-def>.\nOr call in".to_string());
+        assert_eq!(meeting.url().as_deref(), Some("https://meet.example.com/abc-def"));
+
+        // The location wins
+        let meeting = meeting.with_location("https://zoom.example.com/j/123, room 4".to_string());
+        assert_eq!(meeting.url().as_deref(), Some("https://zoom.example.com/j/123"));
+        assert_eq!(Meeting::new("Lunch".to_string(), now, now).with_location("Cafe".to_string()).url(), None);
+    }
+
+
+    #[test]
+    fn test_meeting_ids() {
+        let start = chrono::TimeZone::with_ymd_and_hms(&Utc, 2025, 6, 2, 9, 0, 0).unwrap();
+        let end = start + chrono::Duration::minutes(30);
+
+        // Derived from UID and start: copies from different sources share it,
+        // occurrences of a series don't
+        let google = Meeting::new("Sync".to_string(), start, end)
+            .with_uid("sync@example.com".to_string())
+            .with_source(MeetingSource::google());
+        let ics = Meeting::new("Sync (copy)".to_string(), start, end)
+            .with_source(MeetingSource::from_ics_path("work.ics"))
+            .with_uid("sync@example.com".to_string());
+        let next_week = Meeting::new("Sync".to_string(), start + chrono::Duration::days(7), end + chrono::Duration::days(7))
+            .with_uid("sync@example.com".to_string());
+        assert_eq!(google.id, ics.id);
+        assert_ne!(google.id, next_week.id);
+
+        // Without a UID, from source, start and title
+        let lunch = Meeting::new("Lunch".to_string(), start, end);
+        assert_eq!(lunch.id, Meeting::new("Lunch".to_string(), start, end).id);
+        assert_ne!(lunch.id, lunch.clone().with_source(MeetingSource::google()).id);
+        assert_ne!(lunch.id, Meeting::new("Dinner".to_string(), start, end).id);
+
+        // Stable across releases
+        assert_eq!(lunch.id.len(), 16);
+        assert_eq!(lunch.id, lunch.compute_id());
+    }
+
+
+    #[test]
+    fn test_source_priority_resolves_conflicts() {
+        let now = Utc::now();
+        let start = now + chrono::Duration::hours(1);
+        let end = now + chrono::Duration::hours(2);
+        let team = MeetingSource::new("team".to_string(), "Team".to_string(), None);
+        let personal = MeetingSource::new("personal".to_string(), "Personal".to_string(), None).with_priority(10);
+
+        // The copy from the higher-priority source wins, even when listed later
+        let merged = merge_meetings(vec![
+            vec![Meeting::new("Planning".to_string(), start, end).with_uid("abc".to_string()).with_source(team.clone())],
+            vec![Meeting::new("Planning".to_string(), start, end).with_uid("abc".to_string()).with_source(personal.clone())],
+        ]);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].source.as_ref().unwrap().id, "personal");
+        assert_eq!(merged[0].priority(), 10);
+
+        // Different meetings in the same slot: the highest priority is picked, the first on ties
+        let review = Meeting::new("Review".to_string(), start, end).with_source(team.clone());
+        let dentist = Meeting::new("Dentist".to_string(), start, end).with_source(personal);
+        let sync = Meeting::new("Sync".to_string(), start, end).with_source(team);
+        assert_eq!(highest_priority([&review, &dentist, &sync]).unwrap().title, "Dentist");
+        assert_eq!(highest_priority([&review, &sync]).unwrap().title, "Review");
+        assert!(highest_priority(std::iter::empty()).is_none());
+
+        // Only non-default priorities are serialized
+        let json = serde_json::to_value(&dentist).unwrap();
+        assert_eq!(json["source"]["priority"], 10);
+        assert!(serde_json::to_value(&review).unwrap()["source"].get("priority").is_none());
+    }
+
+
+    #[test]
+    fn test_privacy_redacts_meetings() {
+        let now = Utc::now();
+        let later = now + chrono::Duration::hours(1);
+        let meeting = Meeting::new("Interview with Jane".to_string(), now, later)
+            .with_location("Room 4".to_string())
+            .with_uid("interview@example.com".to_string());
+        let focus = Meeting::new("[Focus]".to_string(), now, later);
+
+        let privacy = PrivacyConfig { enabled: true, ..Default::default() };
+        let redacted = privacy.apply(meeting.clone());
+        assert_eq!(redacted.title, "Busy");
+        assert_eq!(redacted.location, None);
+        assert_eq!(redacted.uid, None);
+        assert_eq!(redacted.id, meeting.id);
+        // Time blocks stay time blocks, named only when asked for
+        let focus_redacted = privacy.apply(focus.clone());
+        assert_eq!(focus_redacted.title, "[Busy]");
+        assert!(focus_redacted.is_time_block());
+        let keep_blocks = PrivacyConfig { enabled: true, keep_time_blocks: true, ..Default::default() };
+        assert_eq!(keep_blocks.apply(focus.clone()).title, "[Focus]");
+        assert_eq!(keep_blocks.apply(meeting.clone()).title, "Busy");
+
+        // Without the global switch, only private sources are redacted
+        let per_source = PrivacyConfig::default();
+        assert_eq!(per_source.apply(meeting.clone()).title, "Interview with Jane");
+        let private = MeetingSource::new("personal".to_string(), "Personal".to_string(), None).with_private(true);
+        assert_eq!(per_source.apply(meeting.with_source(private)).title, "Busy");
+        let holiday = Meeting::new("New Year".to_string(), now, later).with_source(MeetingSource::holidays());
+        assert_eq!(privacy.apply(holiday).title, "New Year");
+    }
 }
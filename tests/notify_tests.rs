@@ -0,0 +1,506 @@
+use calendar_monitor::app::{MeetingUpdate, API_VERSION};
+use calendar_monitor::config::{CountdownConfig, DesktopConfig, DiscordConfig, SlackConfig, TeamsConfig, TelegramConfig};
+use calendar_monitor::desktop::DesktopNotifier;
+use calendar_monitor::discord::DiscordWebhook;
+use calendar_monitor::meeting::Meeting;
+use calendar_monitor::notify::{render_template, AgendaSchedule, MeetingEvent, MeetingTracker, Notifier, ReminderSchedule};
+use calendar_monitor::slack::SlackStatus;
+use calendar_monitor::teams::TeamsPresence;
+use calendar_monitor::telegram::TelegramBot;
+use chrono::{Duration, Local, NaiveDate, NaiveTime, Utc};
+use serde_json::json;
+use wiremock::matchers::{body_json, body_partial_json, body_string_contains, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meeting(title: &str, minutes_from_now: i64, length_minutes: i64) -> Meeting {
+        let start = Utc::now() + Duration::minutes(minutes_from_now);
+        Meeting::new(title.to_string(), start, start + Duration::minutes(length_minutes))
+    }
+
+    #[test]
+    fn test_tracker_reports_meetings_starting_and_ending() {
+        let mut tracker = MeetingTracker::default();
+        let standup = meeting("Standup", -5, 15);
+        let review = meeting("Review", 10, 30);
+
+        assert!(tracker.observe(None).is_empty());
+        let events = tracker.observe(Some(&standup));
+        assert!(matches!(&events[..], [MeetingEvent::Started(m)] if m.title == "Standup"));
+        assert!(tracker.observe(Some(&standup)).is_empty(), "nothing new while it goes on");
+
+        // Back to back: the first ends before the next starts
+        let events = tracker.observe(Some(&review));
+        assert!(matches!(
+            &events[..],
+            [MeetingEvent::Ended(a), MeetingEvent::Started(b)] if a.title == "Standup" && b.title == "Review"
+        ));
+
+        // Extended while on: started again with the new end
+        let mut extended = review.clone();
+        extended.end_time += Duration::minutes(15);
+        let events = tracker.observe(Some(&extended));
+        assert!(matches!(&events[..], [MeetingEvent::Ended(_), MeetingEvent::Started(m)] if m.end_time == extended.end_time));
+
+        let events = tracker.observe(None);
+        assert!(matches!(&events[..], [MeetingEvent::Ended(m)] if m.title == "Review"));
+        assert!(tracker.observe(None).is_empty());
+    }
+
+    #[test]
+    fn test_render_template() {
+        let mut planning = meeting("Planning", 0, 60);
+        planning.location = Some("Room 4".to_string());
+        let end = planning.end_time.with_timezone(&Local);
+
+        assert_eq!(
+            render_template("{title} in {location} until {end}", &planning, true),
+            format!("Planning in Room 4 until {}", end.format("%H:%M"))
+        );
+        assert_eq!(render_template("until {end}", &planning, false), format!("until {}", end.format("%-I:%M %p")));
+    }
+
+    #[tokio::test]
+    async fn test_slack_status_follows_the_meeting() {
+        let server = MockServer::start().await;
+        let standup = meeting("Standup", -5, 30);
+        let ok = ResponseTemplate::new(200).set_body_json(json!({ "ok": true }));
+
+        Mock::given(method("POST"))
+            .and(path("/users.profile.set"))
+            .and(header("authorization", "Bearer xoxp-test"))
+            .and(body_partial_json(json!({
+                "profile": {
+                    "status_text": "Busy: Standup",
+                    "status_emoji": ":spiral_calendar_pad:",
+                    "status_expiration": standup.end_time.timestamp()
+                }
+            })))
+            .respond_with(ok.clone())
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/dnd.setSnooze"))
+            .and(header("authorization", "Bearer xoxp-test"))
+            .and(body_string_contains("num_minutes="))
+            .respond_with(ok.clone())
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/users.profile.set"))
+            .and(body_partial_json(json!({
+                "profile": { "status_text": "", "status_emoji": "", "status_expiration": 0 }
+            })))
+            .respond_with(ok.clone())
+            .expect(1)
+            .mount(&server)
+            .await;
+        // Snooze already ended by hand
+        Mock::given(method("POST"))
+            .and(path("/dnd.endSnooze"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "ok": false, "error": "snooze_not_active" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let slack = SlackStatus::new(
+            reqwest::Client::new(),
+            SlackConfig {
+                token: Some("xoxp-test".to_string()),
+                status_text: "Busy: {title}".to_string(),
+                status_emoji: ":spiral_calendar_pad:".to_string(),
+                api_base_url: Some(server.uri()),
+                ..Default::default()
+            },
+            true,
+        );
+        slack.notify(&MeetingEvent::Started(standup.clone())).await.unwrap();
+        slack.notify(&MeetingEvent::Ended(standup)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_slack_errors_are_reported() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/users.profile.set"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "ok": false, "error": "invalid_auth" })))
+            .mount(&server)
+            .await;
+
+        let slack = SlackStatus::new(
+            reqwest::Client::new(),
+            SlackConfig {
+                token: Some("expired".to_string()),
+                api_base_url: Some(server.uri()),
+                ..Default::default()
+            },
+            true,
+        );
+        let error = slack.notify(&MeetingEvent::Started(meeting("Standup", 0, 30))).await.unwrap_err();
+        assert!(error.to_string().contains("invalid_auth"), "{}", error);
+    }
+
+    fn teams_config(server: &MockServer) -> TeamsConfig {
+        TeamsConfig {
+            client_id: "app-id".to_string(),
+            tenant: "contoso.com".to_string(),
+            refresh_token: Some("refresh-1".to_string()),
+            status_message: "In {title}".to_string(),
+            login_base_url: Some(server.uri()),
+            api_base_url: Some(server.uri()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_teams_presence_follows_the_meeting() {
+        let server = MockServer::start().await;
+        let standup = meeting("Standup", -5, 30);
+        let ok = ResponseTemplate::new(200);
+
+        // One token for both events; the rotated refresh token is kept
+        Mock::given(method("POST"))
+            .and(path("/contoso.com/oauth2/v2.0/token"))
+            .and(body_string_contains("grant_type=refresh_token"))
+            .and(body_string_contains("refresh_token=refresh-1"))
+            .and(body_string_contains("client_id=app-id"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "access_token": "access-1",
+                "expires_in": 3600,
+                "refresh_token": "refresh-2",
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/me/presence/setUserPreferredPresence"))
+            .and(header("authorization", "Bearer access-1"))
+            .and(body_partial_json(json!({ "availability": "Busy", "activity": "Busy" })))
+            .respond_with(ok.clone())
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/me/presence/setStatusMessage"))
+            .and(body_partial_json(json!({
+                "statusMessage": {
+                    "message": { "content": "In Standup", "contentType": "text" },
+                    "expiryDateTime": { "dateTime": standup.end_time.format("%Y-%m-%dT%H:%M:%S").to_string(), "timeZone": "UTC" }
+                }
+            })))
+            .respond_with(ok.clone())
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/me/presence/clearUserPreferredPresence"))
+            .and(header("authorization", "Bearer access-1"))
+            .respond_with(ok.clone())
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/me/presence/setStatusMessage"))
+            .and(body_json(json!({ "statusMessage": { "message": { "content": "", "contentType": "text" } } })))
+            .respond_with(ok.clone())
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let teams = TeamsPresence::new(reqwest::Client::new(), teams_config(&server), true);
+        assert!(!teams.silent_in_quiet_hours());
+        teams.notify(&MeetingEvent::Started(standup.clone())).await.unwrap();
+        teams.notify(&MeetingEvent::Reminder(standup.clone())).await.unwrap();
+        teams.notify(&MeetingEvent::Ended(standup)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_teams_errors_are_reported() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/contoso.com/oauth2/v2.0/token"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "error": "invalid_grant",
+                "error_description": "AADSTS70008: The refresh token has expired.",
+            })))
+            .mount(&server)
+            .await;
+
+        let teams = TeamsPresence::new(reqwest::Client::new(), teams_config(&server), true);
+        let error = teams.notify(&MeetingEvent::Started(meeting("Standup", 0, 30))).await.unwrap_err();
+        assert!(error.to_string().contains("AADSTS70008"), "{}", error);
+
+        teams_config(&server).validate().unwrap();
+        let invalid = [
+            TeamsConfig { client_id: String::new(), ..teams_config(&server) },
+            TeamsConfig { refresh_token: None, ..teams_config(&server) },
+            TeamsConfig { availability: "Away".to_string(), ..teams_config(&server) },
+            TeamsConfig { status_message: "x".repeat(281), ..teams_config(&server) },
+        ];
+        for teams in invalid {
+            assert!(teams.validate().is_err(), "{:?}", teams);
+        }
+    }
+
+    #[test]
+    fn test_agenda_schedule_is_due_once_a_day() {
+        let mut schedule = AgendaSchedule::default();
+        let at = NaiveTime::from_hms_opt(8, 0, 0).unwrap();
+        let day = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let time = |day: NaiveDate, hour: u32, minute: u32| day.and_hms_opt(hour, minute, 0).unwrap();
+
+        assert!(!schedule.due("Discord", at, time(day, 7, 59)));
+        assert!(schedule.due("Discord", at, time(day, 8, 0)));
+        assert!(!schedule.due("Discord", at, time(day, 8, 1)), "only once a day");
+        assert!(!schedule.due("Discord", at, time(day.succ_opt().unwrap(), 7, 0)));
+        assert!(schedule.due("Discord", at, time(day.succ_opt().unwrap(), 9, 30)));
+
+        // Started after the agenda time: nothing until the next day
+        assert!(!schedule.due("Other", at, time(day, 10, 0)));
+        assert!(schedule.due("Other", at, time(day.succ_opt().unwrap(), 8, 0)));
+    }
+
+    #[tokio::test]
+    async fn test_discord_posts_meeting_starts_and_agenda() {
+        let server = MockServer::start().await;
+        let standup = meeting("Standup", 0, 15);
+        let review = meeting("Review", 60, 30);
+        let clock = |time: chrono::DateTime<Utc>| time.with_timezone(&Local).format("%H:%M").to_string();
+
+        Mock::given(method("POST"))
+            .and(path("/api/webhooks/1/secret"))
+            .and(body_partial_json(json!({
+                "content": format!("Standup starts, ends at {}", clock(standup.end_time)),
+                "username": "Calendar",
+                "allowed_mentions": { "parse": [] }
+            })))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/webhooks/1/secret"))
+            .and(body_partial_json(json!({
+                "content": format!(
+                    "Today:\n{} Standup\n{} Review",
+                    clock(standup.start_time),
+                    clock(review.start_time)
+                )
+            })))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let discord = DiscordWebhook::new(
+            reqwest::Client::new(),
+            DiscordConfig {
+                webhook_url: Some(format!("{}/api/webhooks/1/secret", server.uri())),
+                start_message: "{title} starts, ends at {end}".to_string(),
+                agenda_time: Some("08:00".to_string()),
+                agenda_header: "Today:".to_string(),
+                agenda_line: "{start} {title}".to_string(),
+                username: Some("Calendar".to_string()),
+            },
+            true,
+        );
+        assert_eq!(discord.agenda_time(), NaiveTime::from_hms_opt(8, 0, 0));
+
+        discord.notify(&MeetingEvent::Started(standup.clone())).await.unwrap();
+        // Extended while on: not announced again
+        discord.notify(&MeetingEvent::Ended(standup.clone())).await.unwrap();
+        discord.notify(&MeetingEvent::Started(standup.clone())).await.unwrap();
+        discord.notify(&MeetingEvent::Agenda(vec![standup, review])).await.unwrap();
+        // Nothing to post for an empty day
+        discord.notify(&MeetingEvent::Agenda(Vec::new())).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_discord_errors_are_reported() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(json!({ "message": "Unknown Webhook" })))
+            .mount(&server)
+            .await;
+
+        let discord = DiscordWebhook::new(
+            reqwest::Client::new(),
+            DiscordConfig { webhook_url: Some(server.uri()), ..Default::default() },
+            true,
+        );
+        let error = discord.notify(&MeetingEvent::Started(meeting("Standup", 0, 30))).await.unwrap_err();
+        assert!(error.to_string().contains("Unknown Webhook"), "{}", error);
+    }
+
+    fn telegram(api_base_url: &str) -> TelegramBot {
+        TelegramBot::new(
+            reqwest::Client::new(),
+            TelegramConfig {
+                bot_token: Some("123:abc".to_string()),
+                chat_id: Some(42),
+                api_base_url: Some(api_base_url.to_string()),
+                ..Default::default()
+            },
+            true,
+        )
+    }
+
+    #[test]
+    fn test_reminders_are_due_once_before_the_start() {
+        let mut schedule = ReminderSchedule::default();
+        let now = Utc::now();
+        let soon = meeting("Standup", 3, 15);
+        let later = meeting("Review", 30, 30);
+        let started = meeting("Planning", -1, 60);
+        let mut vacation = meeting("Vacation", 2, 60 * 24);
+        vacation.all_day = true;
+        let candidates = vec![soon.clone(), later.clone(), started, vacation];
+
+        let due = schedule.due("Telegram", Duration::minutes(5), &candidates, now);
+        assert_eq!(due.iter().map(|m| m.title.as_str()).collect::<Vec<_>>(), vec!["Standup"]);
+        assert!(schedule.due("Telegram", Duration::minutes(5), &candidates, now).is_empty(), "reminded once");
+        assert_eq!(schedule.due("Other", Duration::minutes(5), &candidates, now).len(), 1, "per notifier");
+
+        let due = schedule.due("Telegram", Duration::minutes(5), &candidates, now + Duration::minutes(26));
+        assert_eq!(due.iter().map(|m| m.title.as_str()).collect::<Vec<_>>(), vec!["Review"]);
+    }
+
+    #[tokio::test]
+    async fn test_telegram_sends_reminders() {
+        let server = MockServer::start().await;
+        let standup = meeting("Standup", 5, 15);
+        Mock::given(method("POST"))
+            .and(path("/bot123:abc/sendMessage"))
+            .and(body_partial_json(json!({
+                "chat_id": 42,
+                "text": format!("Standup starts at {}", standup.start_time.with_timezone(&Local).format("%H:%M"))
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "ok": true, "result": {} })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let bot = telegram(&server.uri());
+        assert_eq!(bot.remind_before(), Some(Duration::minutes(5)));
+        bot.notify(&MeetingEvent::Reminder(standup.clone())).await.unwrap();
+        bot.notify(&MeetingEvent::Started(standup)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_telegram_commands_from_the_configured_chat() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/bot123:abc/getUpdates"))
+            .and(query_param("offset", "7"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "ok": true,
+                "result": [
+                    { "update_id": 7, "message": { "chat": { "id": 42 }, "text": "/next" } },
+                    { "update_id": 8, "message": { "chat": { "id": 99 }, "text": "/agenda" } },
+                    { "update_id": 9, "message": { "chat": { "id": 42 }, "text": "hello" } },
+                    { "update_id": 10, "message": { "chat": { "id": 42 }, "text": "/agenda@CalendarBot" } },
+                    { "update_id": 11 }
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        let bot = telegram(&server.uri());
+        let mut offset = Some(7);
+        let commands = bot.commands(&mut offset).await.unwrap();
+        assert_eq!(commands, vec!["/next", "/agenda@CalendarBot"]);
+        assert_eq!(offset, Some(12));
+    }
+
+    #[tokio::test]
+    async fn test_telegram_errors_do_not_leak_the_token() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(401).set_body_json(json!({ "ok": false, "description": "Unauthorized" })))
+            .mount(&server)
+            .await;
+
+        let error = telegram(&server.uri()).commands(&mut None).await.unwrap_err().to_string();
+        assert!(error.contains("Unauthorized"), "{}", error);
+        let error = telegram("http://127.0.0.1:1").send("hi").await.unwrap_err().to_string();
+        assert!(!error.contains("123:abc"), "{}", error);
+    }
+
+    #[test]
+    fn test_telegram_answers() {
+        let bot = telegram("http://localhost");
+        let standup = meeting("Standup", -5, 15);
+        let review = meeting("Review", 1, 30);
+        let mut vacation = meeting("Vacation", -60, 60 * 24);
+        vacation.all_day = true;
+        let clock = |time: chrono::DateTime<Utc>| time.with_timezone(&Local).format("%H:%M").to_string();
+        let mut update = MeetingUpdate {
+            version: API_VERSION,
+            current_meeting: Some(standup.clone()),
+            next_meeting: Some(review.clone()),
+            countdown_seconds: None,
+            next_meeting_starts_in_seconds: None,
+            active_time_blocks: vec![],
+            upcoming_time_blocks: vec![],
+            all_day_events: vec![],
+            holiday: None,
+            agenda_today: vec![vacation, standup.clone(), review.clone()],
+            stale: false,
+            stale_since: None,
+            timezone: None,
+            last_updated: None,
+            source_errors: Vec::new(),
+            quiet: false,
+            countdown: CountdownConfig::default(),
+        };
+
+        let next = bot.answer("/next", &update);
+        assert!(next.starts_with(&format!("Now: Standup until {}\nNext: Review ", clock(standup.end_time))), "{}", next);
+        assert_eq!(
+            bot.answer("/agenda@CalendarBot", &update),
+            format!(
+                "Today's meetings:\nAll day  Vacation\n{} - {}  Standup\n{} - {}  Review",
+                clock(standup.start_time),
+                clock(standup.end_time),
+                clock(review.start_time),
+                clock(review.end_time)
+            )
+        );
+        assert!(bot.answer("/start", &update).contains("/next"));
+
+        update.current_meeting = None;
+        update.next_meeting = None;
+        update.agenda_today.clear();
+        assert_eq!(bot.answer("/next", &update), "Nothing coming up");
+        assert_eq!(bot.answer("/agenda", &update), "No meetings today");
+    }
+
+    #[test]
+    fn test_desktop_notifications() {
+        let config = DesktopConfig {
+            remind_minutes_before: 10,
+            body: "{start} - {end} in {location}".to_string(),
+            ..Default::default()
+        };
+        // Only accepted when the D-Bus backend is compiled in
+        assert_eq!(config.validate().is_ok(), cfg!(feature = "desktop-notifications"));
+        if cfg!(feature = "desktop-notifications") {
+            assert!(DesktopConfig { remind_minutes_before: 0, ..config.clone() }.validate().is_err());
+        }
+
+        let desktop = DesktopNotifier::new(config, true);
+        assert_eq!(desktop.remind_before(), Some(Duration::minutes(10)));
+        let mut standup = meeting("Standup", 10, 15);
+        standup.location = Some("Room 4".to_string());
+        let (summary, body) = desktop.notification(&MeetingEvent::Reminder(standup.clone())).unwrap();
+        assert_eq!(summary, "Standup");
+        let clock = |time: chrono::DateTime<Utc>| time.with_timezone(&Local).format("%H:%M").to_string();
+        assert_eq!(body, format!("{} - {} in Room 4", clock(standup.start_time), clock(standup.end_time)));
+        assert!(desktop.notification(&MeetingEvent::Started(standup)).is_none());
+    }
+}
@@ -41,7 +41,7 @@ fn test_oauth_url_generation() {
     };
     
     let service = GoogleCalendarService::new(config).unwrap();
-    let (auth_url, _csrf_token) = service.get_auth_url();
+    let (auth_url, _csrf_token, _pkce_verifier) = service.get_auth_url();
     
     let auth_url_string = auth_url.to_string();
     assert!(auth_url_string.contains("accounts.google.com"));
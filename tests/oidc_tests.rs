@@ -0,0 +1,277 @@
+use base64::Engine;
+use calendar_monitor::app::{build_router, AppState};
+use calendar_monitor::config::{Config, IcsConfig, OidcConfig, ServerConfig, REDACTED};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use serde_json::json;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tempfile::TempDir;
+use wiremock::matchers::{body_string_contains, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIGNING_KEY: &[u8] = include_bytes!("fixtures/oidc-key.pem");
+
+    /// An OpenID provider publishing the fixture's signing key
+    async fn start_provider() -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/.well-known/openid-configuration"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "issuer": server.uri(),
+                "authorization_endpoint": format!("{}/authorize", server.uri()),
+                "token_endpoint": format!("{}/token", server.uri()),
+                "jwks_uri": format!("{}/jwks", server.uri()),
+            })))
+            .mount(&server)
+            .await;
+        let jwks: serde_json::Value = serde_json::from_str(include_str!("fixtures/oidc-jwks.json")).unwrap();
+        Mock::given(method("GET"))
+            .and(path("/jwks"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(jwks))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    /// The provider answers `code` with an ID token for `email`, issued for `nonce`
+    async fn issue_token(provider: &MockServer, code: &str, email: &str, nonce: &str) {
+        let claims = json!({
+            "iss": provider.uri(),
+            "aud": "dashboard",
+            "sub": format!("user-{}", code),
+            "email": email,
+            "email_verified": true,
+            "nonce": nonce,
+            "iat": Utc::now().timestamp(),
+            "exp": (Utc::now() + Duration::minutes(5)).timestamp(),
+        });
+        let header_ = Header { kid: Some("test-key".to_string()), ..Header::new(Algorithm::ES256) };
+        let id_token = jsonwebtoken::encode(&header_, &claims, &EncodingKey::from_ec_pem(SIGNING_KEY).unwrap()).unwrap();
+        let credentials = base64::engine::general_purpose::STANDARD.encode("dashboard:client-secret");
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .and(header("authorization", format!("Basic {}", credentials).as_str()))
+            .and(body_string_contains(format!("code={}", code)))
+            .and(body_string_contains("code_verifier="))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "access_token": "provider-access-token",
+                "token_type": "Bearer",
+                "id_token": id_token,
+            })))
+            .mount(provider)
+            .await;
+    }
+
+    fn oidc_config(issuer: &str) -> OidcConfig {
+        OidcConfig {
+            issuer: issuer.to_string(),
+            client_id: "dashboard".to_string(),
+            client_secret: Some("client-secret".to_string()),
+            redirect_uri: "http://localhost:3000/auth/oidc/callback".to_string(),
+            allowed_users: vec!["@example.com".to_string()],
+            ..Default::default()
+        }
+    }
+
+    async fn spawn_app(temp_dir: &TempDir, oidc: OidcConfig) -> SocketAddr {
+        let ics_path = temp_dir.path().join("calendar.ics");
+        std::fs::write(&ics_path, "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nEND:VCALENDAR\r\n").unwrap();
+        let config = Config {
+            server: ServerConfig { host: "127.0.0.1".to_string(), port: 0, cache_ttl_seconds: 300, ..Default::default() },
+            ics: IcsConfig { file_paths: vec![ics_path.to_string_lossy().to_string()], ..Default::default() },
+            oidc: Some(oidc),
+            ..Default::default()
+        };
+        let app = build_router(AppState::new(Arc::new(config), None));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        addr
+    }
+
+    /// Start signing in and return the `state` and `nonce` sent to the provider
+    async fn start_login(client: &reqwest::Client, addr: SocketAddr, provider: &MockServer) -> (String, String) {
+        let response = client.get(format!("http://{}/auth/oidc/login?return_to=/overlay", addr)).send().await.unwrap();
+        assert!(response.status().is_redirection());
+        let location = url::Url::parse(response.headers()["location"].to_str().unwrap()).unwrap();
+        assert!(location.as_str().starts_with(&format!("{}/authorize?", provider.uri())), "{}", location);
+        let query: HashMap<String, String> = location.query_pairs().into_owned().collect();
+        assert_eq!(query["client_id"], "dashboard");
+        assert_eq!(query["redirect_uri"], "http://localhost:3000/auth/oidc/callback");
+        assert_eq!(query["scope"], "openid email profile");
+        assert_eq!(query["code_challenge_method"], "S256");
+        (query["state"].clone(), query["nonce"].clone())
+    }
+
+    #[tokio::test]
+    async fn test_oidc_sign_in_gates_dashboard_and_api() {
+        let provider = start_provider().await;
+        let temp_dir = TempDir::new().unwrap();
+        let addr = spawn_app(&temp_dir, oidc_config(&provider.uri())).await;
+        let client = reqwest::Client::builder().redirect(reqwest::redirect::Policy::none()).build().unwrap();
+
+        // Pages go to sign in, the API answers 401; static assets stay public
+        let response = client.get(format!("http://{}/?profile=kitchen", addr)).send().await.unwrap();
+        assert!(response.status().is_redirection());
+        assert_eq!(response.headers()["location"], "/auth/oidc/login?return_to=%2F%3Fprofile%3Dkitchen");
+        for path in ["/api/v1/meetings", "/events"] {
+            let response = client.get(format!("http://{}{}", addr, path)).send().await.unwrap();
+            assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED, "{}", path);
+        }
+        let response = client.get(format!("http://{}/static/app.js", addr)).send().await.unwrap();
+        assert!(response.status().is_success());
+
+        let (state, nonce) = start_login(&client, addr, &provider).await;
+        issue_token(&provider, "good", "Ada@Example.com", &nonce).await;
+        let response = client
+            .get(format!("http://{}/auth/oidc/callback?code=good&state={}", addr, state))
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_redirection(), "{}", response.status());
+        assert_eq!(response.headers()["location"], "/overlay");
+        let cookie = response.headers()["set-cookie"].to_str().unwrap().to_string();
+        assert!(cookie.contains("HttpOnly") && cookie.contains("SameSite=Lax") && !cookie.contains("Secure"), "{}", cookie);
+        let session = cookie.split(';').next().unwrap().to_string();
+
+        for path in ["/", "/overlay", "/api/v1/meetings"] {
+            let response = client.get(format!("http://{}{}", addr, path)).header("cookie", &session).send().await.unwrap();
+            assert!(response.status().is_success(), "{}: {}", path, response.status());
+        }
+
+        // Each sign-in can be completed once
+        let response = client
+            .get(format!("http://{}/auth/oidc/callback?code=good&state={}", addr, state))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+        let response = client.post(format!("http://{}/auth/oidc/logout", addr)).header("cookie", &session).send().await.unwrap();
+        assert!(response.headers()["set-cookie"].to_str().unwrap().contains("Max-Age=0"));
+        let response = client.get(format!("http://{}/api/v1/meetings", addr)).header("cookie", &session).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_oidc_rejects_other_users_and_tokens() {
+        let provider = start_provider().await;
+        let temp_dir = TempDir::new().unwrap();
+        let addr = spawn_app(&temp_dir, oidc_config(&provider.uri())).await;
+        let client = reqwest::Client::builder().redirect(reqwest::redirect::Policy::none()).build().unwrap();
+
+        // Signed in at the provider, but not in allowed_users
+        let (state, nonce) = start_login(&client, addr, &provider).await;
+        issue_token(&provider, "outsider", "eve@example.org", &nonce).await;
+        let response = client
+            .get(format!("http://{}/auth/oidc/callback?code=outsider&state={}", addr, state))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+        assert!(response.headers().get("set-cookie").is_none());
+
+        // A token issued for another sign-in
+        let (state, _) = start_login(&client, addr, &provider).await;
+        issue_token(&provider, "replayed", "ada@example.com", "someone-elses-nonce").await;
+        let response = client
+            .get(format!("http://{}/auth/oidc/callback?code=replayed&state={}", addr, state))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_GATEWAY);
+        assert!(response.text().await.unwrap().contains("not issued for this sign-in"));
+
+        // Provider errors are shown, escaped
+        let response = client
+            .get(format!("http://{}/auth/oidc/callback?error=access_denied&error_description=%3Cscript%3E", addr))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+        assert!(!response.text().await.unwrap().contains("<script>"));
+
+        // Sign-in never sends people to other sites
+        let response = client.get(format!("http://{}/auth/oidc/login?return_to=//evil.example", addr)).send().await.unwrap();
+        let location = url::Url::parse(response.headers()["location"].to_str().unwrap()).unwrap();
+        let state = location.query_pairs().find(|(name, _)| name == "state").unwrap().1.into_owned();
+        let nonce = location.query_pairs().find(|(name, _)| name == "nonce").unwrap().1.into_owned();
+        issue_token(&provider, "returning", "ada@example.com", &nonce).await;
+        let response = client
+            .get(format!("http://{}/auth/oidc/callback?code=returning&state={}", addr, state))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.headers()["location"], "/");
+
+        // Nor anywhere that can't be put in a header
+        let response = client.get(format!("http://{}/auth/oidc/login?return_to=/%0A", addr)).send().await.unwrap();
+        let location = url::Url::parse(response.headers()["location"].to_str().unwrap()).unwrap();
+        let state = location.query_pairs().find(|(name, _)| name == "state").unwrap().1.into_owned();
+        let nonce = location.query_pairs().find(|(name, _)| name == "nonce").unwrap().1.into_owned();
+        issue_token(&provider, "newline", "ada@example.com", &nonce).await;
+        let response = client
+            .get(format!("http://{}/auth/oidc/callback?code=newline&state={}", addr, state))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.headers()["location"], "/");
+    }
+
+    #[test]
+    fn test_oidc_config_validation() {
+        let config = oidc_config("https://login.example.com");
+        config.validate().unwrap();
+        assert!(config.allows(Some("ada@example.com")));
+        assert!(config.allows(Some("ADA@EXAMPLE.COM")));
+        assert!(!config.allows(Some("ada@example.com.evil.org")));
+        assert!(!config.allows(None));
+        assert!(OidcConfig { allowed_users: vec![], ..config.clone() }.allows(None));
+
+        let invalid = [
+            OidcConfig { issuer: "login.example.com".to_string(), ..config.clone() },
+            OidcConfig { client_id: String::new(), ..config.clone() },
+            OidcConfig { redirect_uri: "http://localhost:3000/callback".to_string(), ..config.clone() },
+            OidcConfig { scopes: vec!["email".to_string()], ..config.clone() },
+            OidcConfig { allowed_users: vec!["example.com".to_string()], ..config.clone() },
+            OidcConfig { session_hours: 0, ..config.clone() },
+        ];
+        for oidc in invalid {
+            assert!(oidc.validate().is_err(), "{:?}", oidc);
+        }
+
+        let config: Config = toml::from_str(
+            r#"
+            [server]
+            host = "0.0.0.0"
+            port = 3000
+            cache_ttl_seconds = 300
+
+            [ics]
+            file_paths = []
+
+            [google]
+
+            [oidc]
+            issuer = "https://login.example.com"
+            client_id = "dashboard"
+            client_secret = "client-secret"
+            redirect_uri = "https://calendar.example.com/auth/oidc/callback"
+            "#,
+        )
+        .unwrap();
+        let oidc = config.oidc.as_ref().unwrap();
+        assert_eq!(oidc.scopes, ["openid", "email", "profile"]);
+        assert_eq!(oidc.session_hours, 12);
+        assert_eq!(config.redacted().oidc.unwrap().client_secret.as_deref(), Some(REDACTED));
+        assert!(calendar_monitor::oidc::set_session_cookie(oidc, "abc").ends_with("; Secure"));
+    }
+}
@@ -0,0 +1,70 @@
+use calendar_monitor::app::{Envelope, ServerMessage};
+use calendar_monitor::config::CountdownConfig;
+use calendar_monitor::meeting::{Meeting, MeetingSource};
+use calendar_monitor::payload::PayloadFormat;
+use chrono::{Duration, Utc};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_negotiation() {
+        assert_eq!(PayloadFormat::parse("msgpack"), Some(PayloadFormat::MessagePack));
+        assert_eq!(PayloadFormat::parse("cbor"), Some(PayloadFormat::Cbor));
+        assert_eq!(PayloadFormat::parse("xml"), None);
+
+        assert_eq!(PayloadFormat::from_accept("application/cbor"), Some(PayloadFormat::Cbor));
+        assert_eq!(
+            PayloadFormat::from_accept("text/html, application/x-msgpack;q=0.9, application/json"),
+            Some(PayloadFormat::MessagePack)
+        );
+        assert_eq!(PayloadFormat::from_accept("application/json, application/cbor"), Some(PayloadFormat::Json));
+        assert_eq!(PayloadFormat::from_accept("*/*"), None);
+    }
+
+    #[test]
+    fn test_binary_formats_round_trip_typed_messages() {
+        let now = Utc::now();
+        let meeting = Meeting::new("Standup".to_string(), now, now + Duration::minutes(15))
+            .with_source(MeetingSource::google());
+        let message = Envelope {
+            protocol: 2,
+            message: ServerMessage::Update(Box::new(calendar_monitor::app::MeetingUpdate {
+                version: 1,
+                current_meeting: Some(meeting),
+                next_meeting: None,
+                countdown_seconds: Some(900),
+                next_meeting_starts_in_seconds: None,
+                active_time_blocks: vec![],
+                upcoming_time_blocks: vec![],
+                all_day_events: vec![],
+                holiday: None,
+                agenda_today: vec![],
+                stale: false,
+                stale_since: None,
+                timezone: None,
+                last_updated: None,
+                source_errors: Vec::new(),
+                quiet: false,
+                countdown: CountdownConfig::default(),
+            })),
+        };
+
+        for format in [PayloadFormat::MessagePack, PayloadFormat::Cbor] {
+            let encoded = format.encode(&message).unwrap();
+            let json = serde_json::to_vec(&message).unwrap();
+            assert!(encoded.len() < json.len(), "{:?} is not smaller than JSON", format);
+
+            let decoded: Envelope<ServerMessage> = format.decode(&encoded).unwrap();
+            assert_eq!(decoded.protocol, 2);
+            let ServerMessage::Update(update) = decoded.message else {
+                panic!("Expected an update");
+            };
+            let current = update.current_meeting.unwrap();
+            assert_eq!(current.title, "Standup");
+            assert_eq!(current.start_time, now);
+            assert_eq!(update.countdown_seconds, Some(900));
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use calendar_monitor::recurrence::{expand, parse_until, Frequency, RecurrenceRule, RecurringEvent, Window};
+use chrono::{NaiveDate, TimeZone, Utc, Weekday};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    /// Weekly event first held on Monday 2025-06-02, 09:00-09:30 UTC
+    fn weekly_event(rrule: &str) -> RecurringEvent {
+        RecurringEvent {
+            start: Utc.with_ymd_and_hms(2025, 6, 2, 9, 0, 0).unwrap(),
+            end: Utc.with_ymd_and_hms(2025, 6, 2, 9, 30, 0).unwrap(),
+            rrule: rrule.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_rule() {
+        let rule = RecurrenceRule::parse("FREQ=WEEKLY;WKST=MO;UNTIL=20250620T235959Z;BYDAY=MO,WE,FR").unwrap();
+        assert_eq!(rule.frequency, Frequency::Weekly);
+        assert_eq!(rule.until, Some(date(2025, 6, 20)));
+        assert_eq!(rule.by_day, vec![Weekday::Mon, Weekday::Wed, Weekday::Fri]);
+
+        // Ordinal BYDAY prefixes are tolerated
+        let rule = RecurrenceRule::parse("FREQ=WEEKLY;BYDAY=1MO,-1FR").unwrap();
+        assert_eq!(rule.by_day, vec![Weekday::Mon, Weekday::Fri]);
+
+        // Unsupported frequencies are not parsed
+        assert!(RecurrenceRule::parse("FREQ=MONTHLY;BYMONTHDAY=1").is_none());
+        assert!(RecurrenceRule::parse("BYDAY=MO").is_none());
+    }
+
+    #[test]
+    fn test_parse_until_formats() {
+        assert_eq!(parse_until("FREQ=WEEKLY;UNTIL=20250620T235959Z"), Some(date(2025, 6, 20)));
+        assert_eq!(parse_until("FREQ=WEEKLY;UNTIL=20250620T235959"), Some(date(2025, 6, 20)));
+        assert_eq!(parse_until("UNTIL=20251225;FREQ=WEEKLY"), Some(date(2025, 12, 25)));
+        assert_eq!(parse_until("FREQ=WEEKLY"), None);
+        assert_eq!(parse_until("FREQ=WEEKLY;UNTIL=invalid-date"), None);
+    }
+
+    #[test]
+    fn test_expand_weekly_on_original_weekday() {
+        let event = weekly_event("FREQ=WEEKLY");
+
+        // Monday 2025-06-09 and Tuesday 2025-06-10
+        let occurrences = expand(&event, &Window::new(date(2025, 6, 9), date(2025, 6, 10)));
+        assert_eq!(occurrences.len(), 1);
+        assert_eq!(occurrences[0].start, Utc.with_ymd_and_hms(2025, 6, 9, 9, 0, 0).unwrap());
+        assert_eq!(occurrences[0].end, Utc.with_ymd_and_hms(2025, 6, 9, 9, 30, 0).unwrap());
+    }
+
+    #[test]
+    fn test_expand_with_byday() {
+        let event = weekly_event("FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR");
+
+        // Friday to Monday: the weekend is skipped
+        let occurrences = expand(&event, &Window::new(date(2025, 6, 13), date(2025, 6, 16)));
+        let days: Vec<_> = occurrences.iter().map(|o| o.start.date_naive()).collect();
+        assert_eq!(days, vec![date(2025, 6, 13), date(2025, 6, 16)]);
+    }
+
+    #[test]
+    fn test_expand_respects_until_and_series_start() {
+        let event = weekly_event("FREQ=WEEKLY;UNTIL=20250609T235959Z;BYDAY=MO,TU");
+
+        // The UNTIL day itself is still included, the day after is not
+        let occurrences = expand(&event, &Window::new(date(2025, 6, 9), date(2025, 6, 10)));
+        assert_eq!(occurrences.len(), 1);
+        assert_eq!(occurrences[0].start.date_naive(), date(2025, 6, 9));
+
+        // Nothing before the first occurrence
+        let occurrences = expand(&event, &Window::new(date(2025, 5, 26), date(2025, 5, 27)));
+        assert!(occurrences.is_empty());
+    }
+
+    #[test]
+    fn test_expand_event_crossing_midnight() {
+        let event = RecurringEvent {
+            start: Utc.with_ymd_and_hms(2025, 6, 2, 23, 0, 0).unwrap(),
+            end: Utc.with_ymd_and_hms(2025, 6, 3, 1, 0, 0).unwrap(),
+            rrule: "FREQ=WEEKLY".to_string(),
+        };
+
+        let occurrences = expand(&event, &Window::new(date(2025, 6, 9), date(2025, 6, 9)));
+        assert_eq!(occurrences.len(), 1);
+        assert_eq!(occurrences[0].end, Utc.with_ymd_and_hms(2025, 6, 10, 1, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_expand_unsupported_rule_yields_nothing() {
+        let event = weekly_event("FREQ=DAILY;COUNT=5");
+        assert!(expand(&event, &Window::new(date(2025, 6, 2), date(2025, 6, 8))).is_empty());
+    }
+
+
+    #[test]
+    fn test_window_overlaps() {
+        let window = Window::new(date(2025, 6, 2), date(2025, 6, 3));
+
+        assert!(window.overlaps(
+            Utc.with_ymd_and_hms(2025, 6, 3, 23, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 6, 4, 1, 0, 0).unwrap(),
+        ));
+        // Multi-day events that started before the window
+        assert!(window.overlaps(
+            Utc.with_ymd_and_hms(2025, 5, 30, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 6, 5, 0, 0, 0).unwrap(),
+        ));
+        assert!(!window.overlaps(
+            Utc.with_ymd_and_hms(2025, 6, 1, 9, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 6, 2, 0, 0, 0).unwrap(),
+        ));
+        assert!(!window.overlaps(
+            Utc.with_ymd_and_hms(2025, 6, 4, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 6, 4, 1, 0, 0).unwrap(),
+        ));
+    }
+}
@@ -0,0 +1,93 @@
+use calendar_monitor::meeting::{Meeting, MeetingSource};
+use calendar_monitor::notify::{MeetingEvent, Notifier};
+use calendar_monitor::scripting::Scripts;
+use chrono::{Duration, Utc};
+use std::fs;
+use tempfile::TempDir;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meeting(title: &str) -> Meeting {
+        let start = Utc::now() + Duration::hours(1);
+        Meeting::new(title.to_string(), start, start + Duration::minutes(30))
+            .with_source(MeetingSource::new("work".to_string(), "Work".to_string(), None))
+    }
+
+    #[test]
+    fn test_scripts_rewrite_and_hide_meetings() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("10-interviews.rhai"),
+            r#"
+            fn transform(meeting) {
+                if meeting.title.contains("Interview") {
+                    meeting.priority = 10;
+                    meeting.categories.push("hiring");
+                }
+                meeting
+            }
+            "#,
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("20-cleanup.rhai"),
+            r#"
+            fn transform(meeting) {
+                if meeting.title == "Lunch" {
+                    return ();
+                }
+                meeting.title.replace("FW: ", "");
+                meeting.start = "ignored";
+                meeting
+            }
+            "#,
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("notes.txt"), "not a script").unwrap();
+
+        let scripts = Scripts::load(temp_dir.path()).unwrap();
+        assert_eq!(scripts.names(), vec!["10-interviews.rhai", "20-cleanup.rhai"]);
+        assert!(!scripts.reacts_to_events());
+
+        let interview = meeting("FW: Interview with Sam");
+        let meetings = scripts.transform(vec![interview.clone(), meeting("Lunch"), meeting("Standup")]);
+        assert_eq!(meetings.len(), 2);
+        assert_eq!(meetings[0].title, "Interview with Sam");
+        assert_eq!(meetings[0].priority(), 10);
+        assert_eq!(meetings[0].categories, vec!["hiring"]);
+        assert_eq!(meetings[0].start_time, interview.start_time, "times are read-only");
+        assert_eq!(meetings[1].title, "Standup");
+        assert_eq!(meetings[1].priority(), 0);
+    }
+
+    #[test]
+    fn test_failing_scripts() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("broken.rhai"), "fn transform(meeting) {").unwrap();
+        let error = Scripts::load(temp_dir.path()).err().unwrap().to_string();
+        assert!(error.contains("broken.rhai"), "{}", error);
+
+        // Errors and endless loops at runtime leave the meeting as it was
+        let scripts = Scripts::from_source("loop.rhai", "fn transform(meeting) { if meeting.title == \"Standup\" { loop {} } meeting.missing.field }").unwrap();
+        let meetings = scripts.transform(vec![meeting("Standup"), meeting("Review")]);
+        assert_eq!(meetings.iter().map(|m| m.title.as_str()).collect::<Vec<_>>(), vec!["Standup", "Review"]);
+    }
+
+    #[tokio::test]
+    async fn test_scripts_react_to_meeting_events() {
+        let scripts = Scripts::from_source(
+            "events.rhai",
+            r#"fn on_event(event, meeting) { if event == "start" { throw `no room for ${meeting.title}`; } }"#,
+        )
+        .unwrap();
+        assert!(scripts.reacts_to_events());
+        assert!(!scripts.silent_in_quiet_hours());
+
+        // A failing script is logged rather than failing the notification
+        scripts.notify(&MeetingEvent::Started(meeting("Standup"))).await.unwrap();
+        scripts.notify(&MeetingEvent::Ended(meeting("Standup"))).await.unwrap();
+        scripts.notify(&MeetingEvent::Agenda(vec![meeting("Standup")])).await.unwrap();
+    }
+}
@@ -0,0 +1,83 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use calendar_monitor::meeting::{Meeting, ResponseStatus};
+use calendar_monitor::recurrence::Window;
+use calendar_monitor::source::{Aggregator, CalendarSource};
+use chrono::{Duration, Utc};
+
+/// Source serving a fixed list of meetings, or failing
+struct StubSource {
+    name: &'static str,
+    meetings: Option<Vec<Meeting>>,
+}
+
+#[async_trait]
+impl CalendarSource for StubSource {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    async fn fetch_events(&self, _window: &Window) -> Result<Vec<Meeting>> {
+        self.meetings.clone().ok_or_else(|| anyhow!("{} is unreachable", self.name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_aggregator_merges_sources_in_priority_order() {
+        let start = Utc::now() + Duration::hours(1);
+        let end = start + Duration::hours(1);
+
+        let primary = StubSource {
+            name: "primary",
+            meetings: Some(vec![
+                Meeting::new("Planning".to_string(), start, end)
+                    .with_uid("planning@example.com".to_string())
+                    .with_response_status(ResponseStatus::Tentative),
+                Meeting::new("Offsite".to_string(), start + Duration::hours(2), end + Duration::hours(2))
+                    .with_uid("offsite@example.com".to_string())
+                    .with_response_status(ResponseStatus::Declined),
+            ]),
+        };
+        let secondary = StubSource {
+            name: "secondary",
+            meetings: Some(vec![
+                Meeting::new("Planning".to_string(), start, end).with_uid("planning@example.com".to_string()),
+                Meeting::new("Offsite".to_string(), start + Duration::hours(2), end + Duration::hours(2))
+                    .with_uid("offsite@example.com".to_string()),
+                Meeting::new("Lunch".to_string(), start - Duration::minutes(30), start),
+            ]),
+        };
+
+        let meetings = Aggregator::new()
+            .with_source(primary)
+            .with_source(secondary)
+            .fetch_meetings(&Window::today_and_tomorrow())
+            .await;
+
+        // Sorted by start time, duplicates collapsed onto the primary copy,
+        // and the declined invitation hidden together with its copy
+        let titles: Vec<_> = meetings.iter().map(|m| m.title.as_str()).collect();
+        assert_eq!(titles, vec!["Lunch", "Planning"]);
+        assert_eq!(meetings[1].response_status, Some(ResponseStatus::Tentative));
+    }
+
+    #[tokio::test]
+    async fn test_aggregator_skips_failing_sources() {
+        let now = Utc::now();
+        let meetings = Aggregator::new()
+            .with_source(StubSource { name: "down", meetings: None })
+            .with_source(StubSource {
+                name: "up",
+                meetings: Some(vec![Meeting::new("Standup".to_string(), now, now + Duration::minutes(15))]),
+            })
+            .fetch_meetings(&Window::today_and_tomorrow())
+            .await;
+
+        assert_eq!(meetings.len(), 1);
+        assert_eq!(meetings[0].title, "Standup");
+    }
+}
@@ -0,0 +1,80 @@
+use calendar_monitor::meeting::{Meeting, ResponseStatus};
+use calendar_monitor::stats::{meeting_stats, StatsPeriod};
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone, Utc};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wednesday 2025-06-04
+    fn wednesday() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, 4).unwrap()
+    }
+
+    fn at(date: NaiveDate, hour: u32, minute: u32) -> DateTime<Utc> {
+        Local
+            .from_local_datetime(&date.and_hms_opt(hour, minute, 0).unwrap())
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn meeting(title: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> Meeting {
+        Meeting::new(title.to_string(), start, end)
+    }
+
+    #[test]
+    fn test_period_ranges() {
+        let day = wednesday();
+        assert_eq!(StatsPeriod::parse("day"), Some(StatsPeriod::Day));
+        assert_eq!(StatsPeriod::parse("month"), None);
+
+        assert_eq!(StatsPeriod::Day.range(day), (at(day, 0, 0), at(day + Duration::days(1), 0, 0)));
+        let monday = day - Duration::days(2);
+        assert_eq!(StatsPeriod::Week.range(day), (at(monday, 0, 0), at(monday + Duration::days(7), 0, 0)));
+        assert_eq!(StatsPeriod::Week.range(monday), StatsPeriod::Week.range(day));
+    }
+
+    #[test]
+    fn test_day_stats() {
+        let day = wednesday();
+        let meetings = vec![
+            meeting("Standup", at(day, 9, 0), at(day, 9, 30)),
+            // Straight after standup
+            meeting("Planning", at(day, 9, 30), at(day, 10, 30)),
+            // Overlaps planning, only its last half hour adds up
+            meeting("Sync", at(day, 10, 0), at(day, 11, 0)),
+            meeting("Lunch talk", at(day, 13, 0), at(day, 14, 0)),
+            meeting("1:1", at(day, 14, 3), at(day, 14, 33)),
+            meeting("Declined", at(day, 16, 0), at(day, 17, 0)).with_response_status(ResponseStatus::Declined),
+            meeting("[Focus]", at(day, 15, 0), at(day, 16, 0)),
+            Meeting::new_all_day("Company offsite".to_string(), day, day + Duration::days(1)),
+            meeting("Yesterday", at(day, 0, 0) - Duration::hours(2), at(day, 0, 0) - Duration::hours(1)),
+        ];
+
+        let (from, to) = StatsPeriod::Day.range(day);
+        let stats = meeting_stats(&meetings, StatsPeriod::Day, from, to);
+        assert_eq!(stats.meeting_count, 5);
+        assert_eq!(stats.meeting_minutes, 120 + 60 + 30);
+        assert_eq!(stats.longest_gap_minutes, Some(120));
+        assert_eq!(stats.back_to_back, 3);
+    }
+
+    #[test]
+    fn test_week_gaps_stay_within_a_day() {
+        let day = wednesday();
+        let meetings = vec![
+            meeting("Morning", at(day, 9, 0), at(day, 10, 0)),
+            meeting("Next morning", at(day + Duration::days(1), 9, 0), at(day + Duration::days(1), 10, 0)),
+        ];
+
+        let (from, to) = StatsPeriod::Week.range(day);
+        let stats = meeting_stats(&meetings, StatsPeriod::Week, from, to);
+        assert_eq!(stats.meeting_count, 2);
+        assert_eq!(stats.meeting_minutes, 120);
+        assert_eq!(stats.longest_gap_minutes, None);
+        assert_eq!(stats.back_to_back, 0);
+
+        let empty = meeting_stats(&[], StatsPeriod::Week, from, to);
+        assert_eq!((empty.meeting_count, empty.meeting_minutes), (0, 0));
+    }
+}
@@ -0,0 +1,45 @@
+use calendar_monitor::systemd;
+use std::time::Duration;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // One test, as it sets the process environment systemd would
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_systemd_notifications_and_socket_activation() {
+        use std::os::unix::net::UnixDatagram;
+
+        // Not started by systemd: nothing passed, nothing sent
+        assert!(systemd::activated_listener().unwrap().is_none());
+        assert_eq!(systemd::watchdog_interval(), None);
+        systemd::ready();
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let socket_path = temp_dir.path().join("notify.sock");
+        let socket = UnixDatagram::bind(&socket_path).unwrap();
+        socket.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        std::env::set_var("NOTIFY_SOCKET", &socket_path);
+        std::env::set_var("WATCHDOG_USEC", "30000000");
+        std::env::set_var("WATCHDOG_PID", std::process::id().to_string());
+
+        let interval = systemd::watchdog_interval();
+        systemd::ready();
+        systemd::watchdog();
+        std::env::set_var("WATCHDOG_PID", "1");
+        let other_process = systemd::watchdog_interval();
+        for name in ["NOTIFY_SOCKET", "WATCHDOG_USEC", "WATCHDOG_PID"] {
+            std::env::remove_var(name);
+        }
+
+        assert_eq!(interval, Some(Duration::from_secs(15)));
+        assert_eq!(other_process, None);
+        let mut buffer = [0; 64];
+        let received = socket.recv(&mut buffer).unwrap();
+        assert_eq!(&buffer[..received], b"READY=1\n");
+        let received = socket.recv(&mut buffer).unwrap();
+        assert_eq!(&buffer[..received], b"WATCHDOG=1\n");
+    }
+}
@@ -0,0 +1,158 @@
+use calendar_monitor::config::{Config, NotificationsConfig, WebhookConfig, WebhookEvent, REDACTED};
+use calendar_monitor::meeting::Meeting;
+use calendar_monitor::notify::{MeetingEvent, Notifier};
+use calendar_monitor::webhook::Webhook;
+use chrono::{Duration, Utc};
+use serde_json::json;
+use std::collections::BTreeMap;
+use wiremock::matchers::{body_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meeting() -> Meeting {
+        let start = Utc::now();
+        Meeting::new("\"Quick\" sync".to_string(), start, start + Duration::minutes(30))
+            .with_location("https://meet.example.com/abc".to_string())
+    }
+
+    fn webhook_config(url: &str) -> WebhookConfig {
+        WebhookConfig {
+            name: "chat".to_string(),
+            url: format!("{}/hooks/{{{{event}}}}", url),
+            method: "put".to_string(),
+            headers: BTreeMap::from([("X-Title".to_string(), "Meeting: {{meeting.title}}".to_string())]),
+            body: r#"{"text": {{json meeting.title}}, "join": {{json join_url}}, "location": {{json meeting.location}}}"#
+                .to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_webhook_request_is_rendered() {
+        let webhook = Webhook::new(reqwest::Client::new(), webhook_config("https://chat.example.com"), true);
+        let request = webhook.request(&MeetingEvent::Started(meeting())).unwrap().unwrap();
+        assert_eq!(request.url, "https://chat.example.com/hooks/start");
+        assert_eq!(request.headers, vec![("X-Title".to_string(), "Meeting: \"Quick\" sync".to_string())]);
+        let body: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+        assert_eq!(
+            body,
+            json!({
+                "text": "\"Quick\" sync",
+                "join": "https://meet.example.com/abc",
+                "location": "https://meet.example.com/abc",
+            })
+        );
+
+        // Only the configured events
+        assert_eq!(webhook.request(&MeetingEvent::Ended(meeting())).unwrap(), None);
+        assert_eq!(webhook.request(&MeetingEvent::Agenda(vec![meeting()])).unwrap(), None);
+        assert_eq!(webhook.remind_before(), None);
+
+        let reminders = Webhook::new(
+            reqwest::Client::new(),
+            WebhookConfig {
+                events: vec![WebhookEvent::Reminder],
+                remind_minutes_before: 10,
+                body: "{{meeting.title}} at {{start}}".to_string(),
+                ..webhook_config("https://chat.example.com")
+            },
+            true,
+        );
+        assert_eq!(reminders.remind_before(), Some(Duration::minutes(10)));
+        let request = reminders.request(&MeetingEvent::Reminder(meeting())).unwrap().unwrap();
+        assert_eq!(request.url, "https://chat.example.com/hooks/reminder");
+        assert!(request.body.starts_with("\"Quick\" sync at "), "{}", request.body);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_is_sent() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/hooks/start"))
+            .and(header("x-title", "Meeting: \"Quick\" sync"))
+            .and(header("content-type", "application/json"))
+            .and(body_json(json!({
+                "text": "\"Quick\" sync",
+                "join": "https://meet.example.com/abc",
+                "location": "https://meet.example.com/abc",
+            })))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let webhook = Webhook::new(reqwest::Client::new(), webhook_config(&server.uri()), true);
+        webhook.notify(&MeetingEvent::Started(meeting())).await.unwrap();
+        webhook.notify(&MeetingEvent::Ended(meeting())).await.unwrap();
+
+        let failing = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .respond_with(ResponseTemplate::new(500).set_body_string("upstream down"))
+            .mount(&failing)
+            .await;
+        let webhook = Webhook::new(reqwest::Client::new(), webhook_config(&failing.uri()), true);
+        let error = webhook.notify(&MeetingEvent::Started(meeting())).await.unwrap_err().to_string();
+        assert!(error.contains("HTTP 500") && error.contains("upstream down"), "{}", error);
+    }
+
+    #[test]
+    fn test_webhook_validation() {
+        webhook_config("https://chat.example.com").validate().unwrap();
+        let invalid = [
+            WebhookConfig { name: " ".to_string(), ..webhook_config("https://chat.example.com") },
+            WebhookConfig { url: String::new(), ..Default::default() },
+            WebhookConfig { method: "SEND IT".to_string(), ..webhook_config("https://chat.example.com") },
+            WebhookConfig { events: vec![], ..webhook_config("https://chat.example.com") },
+            WebhookConfig { body: "{{#if meeting}}".to_string(), ..webhook_config("https://chat.example.com") },
+            WebhookConfig {
+                headers: BTreeMap::from([("Bad Header".to_string(), "x".to_string())]),
+                ..webhook_config("https://chat.example.com")
+            },
+            WebhookConfig {
+                events: vec![WebhookEvent::Reminder],
+                remind_minutes_before: 0,
+                ..webhook_config("https://chat.example.com")
+            },
+        ];
+        for webhook in invalid {
+            assert!(webhook.validate().is_err(), "{:?}", webhook);
+        }
+
+        let notifications = NotificationsConfig {
+            webhooks: vec![webhook_config("https://a.example.com"), webhook_config("https://b.example.com")],
+            ..Default::default()
+        };
+        let error = notifications.validate().unwrap_err().to_string();
+        assert!(error.contains("'chat' is used by more than one webhook"), "{}", error);
+
+        let config: Config = toml::from_str(
+            r#"
+            [server]
+            host = "0.0.0.0"
+            port = 3000
+            cache_ttl_seconds = 300
+
+            [ics]
+            file_paths = []
+
+            [google]
+
+            [[notifications.webhooks]]
+            name = "ntfy"
+            url = "https://ntfy.sh/my-meetings"
+            headers = { Authorization = "Bearer tk_secret", Title = "{{meeting.title}}" }
+            body = "Starts at {{start}}"
+            events = ["start", "end"]
+            "#,
+        )
+        .unwrap();
+        let webhook = &config.notifications.webhooks[0];
+        assert_eq!(webhook.method, "POST");
+        assert_eq!(webhook.events, vec![WebhookEvent::Start, WebhookEvent::End]);
+        let redacted = config.redacted();
+        assert_eq!(redacted.notifications.webhooks[0].headers["Authorization"], REDACTED);
+    }
+}